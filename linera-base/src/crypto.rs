@@ -13,6 +13,7 @@ use linera_witty::{
     WitLoad, WitStore, WitType,
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
 use thiserror::Error;
 #[cfg(with_testing)]
 use {
@@ -72,6 +73,11 @@ pub enum CryptoError {
         expected = dalek::PUBLIC_KEY_LENGTH,
     )]
     IncorrectPublicKeySize(usize),
+    #[error(
+        "Byte slice has length {0} but a `Signature` requires exactly {expected} bytes",
+        expected = dalek::SIGNATURE_LENGTH,
+    )]
+    IncorrectSignatureSize(usize),
     #[error("Could not parse integer: {0}")]
     ParseIntError(#[from] ParseIntError),
 }
@@ -453,6 +459,11 @@ impl CryptoHash {
     }
 }
 
+/// Computes the SHA-256 hash of some raw bytes.
+pub fn sha256(bytes: &[u8]) -> [u8; 32] {
+    Sha256::digest(bytes).into()
+}
+
 impl Signature {
     /// Computes a signature.
     pub fn new<'de, T>(value: &T, secret: &KeyPair) -> Self
@@ -465,6 +476,23 @@ impl Signature {
         Signature(signature)
     }
 
+    /// Computes a signature of some raw bytes, rather than of a [`BcsSignable`] value.
+    pub fn new_for_bytes(message: &[u8], secret: &KeyPair) -> Self {
+        Signature(secret.0.sign(message))
+    }
+
+    /// Reads a signature from its raw bytes.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, CryptoError> {
+        let signature = dalek::Signature::from_slice(bytes)
+            .map_err(|_| CryptoError::IncorrectSignatureSize(bytes.len()))?;
+        Ok(Signature(signature))
+    }
+
+    /// Returns the raw bytes of this signature.
+    pub fn to_bytes(&self) -> [u8; dalek::SIGNATURE_LENGTH] {
+        self.0.to_bytes()
+    }
+
     fn check_internal<'de, T>(
         &self,
         value: &T,
@@ -491,6 +519,16 @@ impl Signature {
             })
     }
 
+    /// Checks a signature of some raw bytes, rather than of a [`BcsSignable`] value.
+    pub fn check_bytes(&self, message: &[u8], author: PublicKey) -> Result<(), CryptoError> {
+        dalek::VerifyingKey::from_bytes(&author.0)
+            .and_then(|public_key| public_key.verify(message, &self.0))
+            .map_err(|error| CryptoError::InvalidSignature {
+                error: error.to_string(),
+                type_name: "raw bytes".to_string(),
+            })
+    }
+
     /// Checks an optional signature.
     pub fn check_optional_signature<'de, T>(
         signature: Option<&Self>,
@@ -839,4 +877,12 @@ mod tests {
         assert_eq!(output, expected_output);
         assert_eq!(input, le_bytes_to_u64_array(&u64_array_to_le_bytes(input)));
     }
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            hex::encode(sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
 }
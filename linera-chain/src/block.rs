@@ -9,7 +9,7 @@ use linera_base::{
     crypto::{BcsHashable, CryptoHash},
     data_types::{BlockHeight, OracleResponse, Timestamp},
     hashed::Hashed,
-    identifiers::{BlobId, BlobType, ChainId, MessageId, Owner},
+    identifiers::{BlobId, BlobType, ChainId, GenericApplicationId, MessageId, Owner},
 };
 use linera_execution::{committee::Epoch, Operation, SystemOperation};
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
@@ -17,8 +17,8 @@ use thiserror::Error;
 
 use crate::{
     data_types::{
-        BlockExecutionOutcome, EventRecord, ExecutedBlock, IncomingBundle, Medium, MessageBundle,
-        OutgoingMessage, ProposedBlock,
+        BlockExecutionOutcome, EventRecord, ExecutedBlock, IncomingBundle, Medium, MessageAction,
+        MessageBundle, OutgoingMessage, ProposedBlock,
     },
     types::CertificateValue,
     ChainError,
@@ -526,6 +526,78 @@ impl Block {
     pub fn messages(&self) -> &Vec<Vec<OutgoingMessage>> {
         &self.body.messages
     }
+
+    /// Returns a receipt for each transaction in the block: first the incoming bundles,
+    /// then the operations.
+    pub fn receipts(&self) -> Vec<TransactionReceipt> {
+        let bundles = self.body.incoming_bundles.iter().map(|bundle| {
+            let success = bundle.action == MessageAction::Accept;
+            (None, None, success)
+        });
+        let operations = (0u32..)
+            .zip(&self.body.operations)
+            .map(|(index, operation)| (Some(index), Some(operation.application_id()), true));
+        (0u32..)
+            .zip(bundles.chain(operations))
+            .map(
+                |(transaction_index, (operation_index, application_id, success))| {
+                    let index = transaction_index as usize;
+                    TransactionReceipt {
+                        transaction_index,
+                        operation_index,
+                        application_id,
+                        success,
+                        message_count: self.body.messages.get(index).map_or(0, Vec::len) as u32,
+                        events: self.body.events.get(index).cloned().unwrap_or_default(),
+                    }
+                },
+            )
+            .collect()
+    }
+}
+
+/// A compact summary of the outcome of one transaction in a block.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct TransactionReceipt {
+    /// The index of the transaction in the block.
+    pub transaction_index: u32,
+    /// The index of the operation, or `None` if the transaction received incoming messages.
+    pub operation_index: Option<u32>,
+    /// The application of the operation, or `None` if the transaction received incoming
+    /// messages.
+    pub application_id: Option<GenericApplicationId>,
+    /// Whether the transaction took effect. This is `false` for rejected incoming bundles.
+    /// Operations are always successful: a failing operation cannot be part of a block.
+    pub success: bool,
+    /// The number of outgoing messages produced by the transaction.
+    pub message_count: u32,
+    /// The events produced by the transaction.
+    pub events: Vec<EventRecord>,
+}
+
+/// The transaction receipts of a confirmed block.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct BlockReceipts {
+    /// The chain the block belongs to.
+    pub chain_id: ChainId,
+    /// The height of the block.
+    pub height: BlockHeight,
+    /// The hash of the confirmed block.
+    pub hash: CryptoHash,
+    /// The receipts of the block's transactions.
+    pub transactions: Vec<TransactionReceipt>,
+}
+
+impl BlockReceipts {
+    /// Returns the receipts of a confirmed block with the given hash.
+    pub fn new(hash: CryptoHash, block: &Block) -> Self {
+        BlockReceipts {
+            chain_id: block.header.chain_id,
+            height: block.header.height,
+            hash,
+            transactions: block.receipts(),
+        }
+    }
 }
 
 impl From<Block> for ExecutedBlock {
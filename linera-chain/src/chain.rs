@@ -4,7 +4,8 @@
 #[cfg(with_metrics)]
 use std::sync::LazyLock;
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    ops::Range,
     sync::Arc,
 };
 
@@ -28,7 +29,7 @@ use linera_execution::{
     ExecutionOutcome, ExecutionRuntimeContext, ExecutionStateView, Message, MessageContext,
     Operation, OperationContext, Query, QueryContext, QueryOutcome, RawExecutionOutcome,
     RawOutgoingMessage, ResourceController, ResourceTracker, ServiceRuntimeEndpoint,
-    TransactionTracker,
+    TransactionOutcome, TransactionTracker,
 };
 use linera_views::{
     context::Context,
@@ -42,6 +43,7 @@ use linera_views::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    block::BlockReceipts,
     data_types::{
         BlockExecutionOutcome, ChainAndHeight, ChannelFullName, EventRecord, IncomingBundle,
         MessageAction, MessageBundle, Origin, OutgoingMessage, PostedMessage, ProposedBlock,
@@ -228,6 +230,9 @@ where
     pub outbox_counters: RegisterView<C, BTreeMap<BlockHeight, u32>>,
     /// Channels able to multicast messages to subscribers.
     pub channels: ReentrantCollectionView<C, ChannelFullName, ChannelStateView<C>>,
+    /// Transaction receipts of all certified blocks for this sender.
+    /// Like `confirmed_log`, this has length `usize::from(next_block_height)`.
+    pub receipts: LogView<C, BlockReceipts>,
 }
 
 /// Block-chaining state.
@@ -482,6 +487,7 @@ where
         bundle: MessageBundle,
         local_time: Timestamp,
         add_to_received_log: bool,
+        max_pending_bundles: Option<usize>,
     ) -> Result<bool, ChainError> {
         assert!(!bundle.messages.is_empty());
         let chain_id = self.chain_id();
@@ -520,21 +526,30 @@ where
         if bundle.goes_to_inbox() {
             // Process the inbox bundle and update the inbox state.
             let mut inbox = self.inboxes.try_load_entry_mut(origin).await?;
-            let entry = BundleInInbox::new(origin.clone(), &bundle);
-            let skippable = bundle.is_skippable();
-            let newly_added = inbox
-                .add_bundle(bundle)
+            let map_inbox_error = |error| match error {
+                InboxError::ViewError(error) => ChainError::ViewError(error),
+                error => ChainError::InternalError(format!(
+                    "while processing messages in certified block: {error}"
+                )),
+            };
+            let mut newly_added_bundles = inbox
+                .admit_parked_bundles(max_pending_bundles)
+                .await
+                .map_err(map_inbox_error)?;
+            if inbox
+                .add_bundle(bundle.clone(), max_pending_bundles)
                 .await
-                .map_err(|error| match error {
-                    InboxError::ViewError(error) => ChainError::ViewError(error),
-                    error => ChainError::InternalError(format!(
-                        "while processing messages in certified block: {error}"
-                    )),
-                })?;
-            if newly_added && !skippable {
-                let seen = local_time;
-                self.unskippable_bundles
-                    .push_back(TimestampedBundleInInbox { entry, seen });
+                .map_err(map_inbox_error)?
+            {
+                newly_added_bundles.push(bundle);
+            }
+            for bundle in newly_added_bundles {
+                if !bundle.is_skippable() {
+                    let entry = BundleInInbox::new(origin.clone(), &bundle);
+                    let seen = local_time;
+                    self.unskippable_bundles
+                        .push_back(TimestampedBundleInInbox { entry, seen });
+                }
             }
         }
 
@@ -565,6 +580,32 @@ where
         }
     }
 
+    /// Returns up to `limit` entries of the `received_log`, newest first. The first entry is
+    /// the one just before index `before`, or the newest one if `before` is `None`.
+    pub async fn read_received_log_backward(
+        &self,
+        before: Option<u64>,
+        limit: u64,
+    ) -> Result<Vec<ChainAndHeight>, ChainError> {
+        let range = backward_range(self.received_log.count(), before, limit);
+        let mut entries = self.received_log.read(range).await?;
+        entries.reverse();
+        Ok(entries)
+    }
+
+    /// Returns up to `limit` hashes of the `confirmed_log`, newest first. The first hash is
+    /// the one just before index `before`, or the newest one if `before` is `None`.
+    pub async fn read_confirmed_log_backward(
+        &self,
+        before: Option<u64>,
+        limit: u64,
+    ) -> Result<Vec<CryptoHash>, ChainError> {
+        let range = backward_range(self.confirmed_log.count(), before, limit);
+        let mut hashes = self.confirmed_log.read(range).await?;
+        hashes.reverse();
+        Ok(hashes)
+    }
+
     pub async fn execute_init_message(
         &mut self,
         message_id: MessageId,
@@ -601,6 +642,46 @@ where
         self.execution_state.system.ownership.get()
     }
 
+    /// Moves the parked bundles from the given origins into their inboxes, as long as the
+    /// inboxes hold fewer than `max_pending_bundles` bundles.
+    pub async fn admit_parked_bundles<'a>(
+        &mut self,
+        origins: impl IntoIterator<Item = &'a Origin>,
+        max_pending_bundles: Option<usize>,
+        local_time: Timestamp,
+    ) -> Result<(), ChainError> {
+        let chain_id = self.chain_id();
+        let origins = origins.into_iter().collect::<BTreeSet<_>>();
+        let inboxes = self.inboxes.try_load_entries_mut(origins.clone()).await?;
+        for (origin, mut inbox) in origins.into_iter().zip(inboxes) {
+            let admitted = inbox
+                .admit_parked_bundles(max_pending_bundles)
+                .await
+                .map_err(|error| ChainError::from((chain_id, origin.clone(), error)))?;
+            for bundle in admitted {
+                if !bundle.is_skippable() {
+                    let entry = BundleInInbox::new(origin.clone(), &bundle);
+                    let seen = local_time;
+                    self.unskippable_bundles
+                        .push_back(TimestampedBundleInInbox { entry, seen });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of parked bundles for each origin that has any.
+    pub async fn parked_bundle_counts(&self) -> Result<Vec<(Origin, u64)>, ChainError> {
+        let mut counts = Vec::new();
+        for (origin, inbox) in self.inboxes.try_load_all_entries().await? {
+            let count = inbox.parked_bundles.count();
+            if count > 0 {
+                counts.push((origin, count as u64));
+            }
+        }
+        Ok(counts)
+    }
+
     /// Removes the incoming message bundles in the block from the inboxes.
     pub async fn remove_bundles_from_inboxes(
         &mut self,
@@ -680,6 +761,42 @@ where
         local_time: Timestamp,
         round: Option<u32>,
         replaying_oracle_responses: Option<Vec<Vec<OracleResponse>>>,
+    ) -> Result<BlockExecutionOutcome, ChainError> {
+        self.execute_block_and_trace(block, local_time, round, replaying_oracle_responses, None)
+            .await
+    }
+
+    /// Executes a block like [`Self::execute_block`], and also returns, for each transaction,
+    /// the state hashes recorded after each of its operations or messages. These are only
+    /// recorded if the chain was loaded with
+    /// [`linera_execution::ExecutionRuntimeConfig::trace_state_hashes`] set.
+    pub async fn execute_block_with_state_hash_trace(
+        &mut self,
+        block: &ProposedBlock,
+        local_time: Timestamp,
+        round: Option<u32>,
+        replaying_oracle_responses: Option<Vec<Vec<OracleResponse>>>,
+    ) -> Result<(BlockExecutionOutcome, Vec<Vec<CryptoHash>>), ChainError> {
+        let mut trace = Vec::new();
+        let outcome = self
+            .execute_block_and_trace(
+                block,
+                local_time,
+                round,
+                replaying_oracle_responses,
+                Some(&mut trace),
+            )
+            .await?;
+        Ok((outcome, trace))
+    }
+
+    async fn execute_block_and_trace(
+        &mut self,
+        block: &ProposedBlock,
+        local_time: Timestamp,
+        round: Option<u32>,
+        replaying_oracle_responses: Option<Vec<Vec<OracleResponse>>>,
+        mut state_hash_trace: Option<&mut Vec<Vec<CryptoHash>>>,
     ) -> Result<BlockExecutionOutcome, ChainError> {
         #[cfg(with_metrics)]
         let _execution_latency = BLOCK_EXECUTION_LATENCY.measure_latency();
@@ -774,6 +891,7 @@ where
         let mut oracle_responses = Vec::new();
         let mut events = Vec::new();
         let mut messages = Vec::new();
+        let mut recipients = HashSet::new();
         for (txn_index, transaction) in block.transactions() {
             let chain_execution_context = match transaction {
                 Transaction::ReceiveMessages(_) => ChainExecutionContext::IncomingBundle(txn_index),
@@ -840,7 +958,17 @@ where
                 .update_execution_outcomes_with_app_registrations(&mut txn_tracker)
                 .await
                 .with_execution_context(chain_execution_context)?;
-            let (txn_outcomes, txn_oracle_responses, new_next_message_index) = txn_tracker
+            recipients.extend(txn_tracker.destinations().cloned());
+            resource_controller
+                .track_recipients(recipients.len())
+                .with_execution_context(chain_execution_context)?;
+            let TransactionOutcome {
+                outcomes: txn_outcomes,
+                oracle_responses: txn_oracle_responses,
+                next_message_index: new_next_message_index,
+                state_hashes,
+                ..
+            } = txn_tracker
                 .destructure()
                 .with_execution_context(chain_execution_context)?;
             next_message_index = new_next_message_index;
@@ -878,6 +1006,9 @@ where
             oracle_responses.push(txn_oracle_responses);
             messages.push(txn_messages);
             events.push(txn_events);
+            if let Some(trace) = state_hash_trace.as_mut() {
+                trace.push(state_hashes);
+            }
         }
 
         // Finally, charge for the block fee, except if the chain is closed. Closed chains should
@@ -1259,6 +1390,16 @@ where
     }
 }
 
+/// Returns the range of the last `limit` indices of a log of length `count` that are lower
+/// than `before`.
+fn backward_range(count: usize, before: Option<u64>, limit: u64) -> Range<usize> {
+    let end = before.map_or(count, |before| {
+        usize::try_from(before).unwrap_or(usize::MAX).min(count)
+    });
+    let start = end.saturating_sub(usize::try_from(limit).unwrap_or(usize::MAX));
+    start..end
+}
+
 #[test]
 fn empty_block_size() {
     let executed_block = crate::data_types::ExecutedBlock {
@@ -24,7 +24,7 @@ use linera_base::{
 use linera_execution::{
     committee::{Committee, Epoch, ValidatorName},
     system::OpenChainConfig,
-    Message, MessageKind, Operation, SystemMessage, SystemOperation,
+    Message, MessageKind, Operation, ResourceControlPolicy, SystemMessage, SystemOperation,
 };
 use serde::{Deserialize, Serialize};
 
@@ -153,6 +153,32 @@ impl ProposedBlock {
         );
         Ok(())
     }
+
+    /// Checks the number of operations, the number of incoming messages and the size of
+    /// the block against the limits of the given policy.
+    pub fn check_resource_limits(&self, policy: &ResourceControlPolicy) -> Result<(), ChainError> {
+        let operations = self.operations.len() as u64;
+        ensure!(
+            operations <= policy.maximum_operations_per_block,
+            ChainError::TooManyOperations {
+                count: operations,
+                max: policy.maximum_operations_per_block,
+            }
+        );
+        let incoming_messages = self
+            .incoming_bundles
+            .iter()
+            .map(|bundle| bundle.bundle.messages.len() as u64)
+            .sum::<u64>();
+        ensure!(
+            incoming_messages <= policy.maximum_incoming_messages_per_block,
+            ChainError::TooManyIncomingMessages {
+                count: incoming_messages,
+                max: policy.maximum_incoming_messages_per_block,
+            }
+        );
+        self.check_proposal_size(policy.maximum_block_proposal_size)
+    }
 }
 
 /// A transaction in a block: incoming messages or an operation.
@@ -36,6 +36,9 @@ mod inbox_tests;
 /// * The cursors of added bundles (resp. removed bundles) must be increasing over time.
 /// * Reconciliation of added and removed bundles is allowed to skip some added bundles.
 ///   However, the opposite is not true: every removed bundle must be eventually added.
+/// * The number of added bundles may be capped. Beyond the cap, new bundles are parked in a
+///   separate queue and admitted in order as the inbox is drained. Parked bundles come after
+///   all added bundles, and can also be removed directly.
 #[derive(Debug, ClonableView, View, async_graphql::SimpleObject)]
 pub struct InboxStateView<C>
 where
@@ -50,6 +53,9 @@ where
     /// These bundles have been removed by anticipation and are waiting to be added.
     /// At least one of `added_bundles` and `removed_bundles` should be empty.
     pub removed_bundles: QueueView<C, MessageBundle>,
+    /// These bundles have been received but are waiting for room in `added_bundles`.
+    /// If this is not empty, then `removed_bundles` is empty.
+    pub parked_bundles: QueueView<C, MessageBundle>,
 }
 
 #[derive(
@@ -166,6 +172,7 @@ where
     /// Consumes a bundle from the inbox.
     ///
     /// Returns `true` if the bundle was already known, i.e. it was present in `added_bundles`.
+    /// Bundles consumed from `parked_bundles` were never admitted, so this returns `false`.
     pub(crate) async fn remove_bundle(
         &mut self,
         bundle: &MessageBundle,
@@ -193,7 +200,23 @@ where
             self.added_bundles.delete_front();
             tracing::trace!("Skipping previously received bundle {:?}", previous_bundle);
         }
-        // Reconcile the bundle with the next added bundle, or mark it as removed.
+        // The parked bundles come after all the added bundles.
+        if self.added_bundles.count() == 0 {
+            while let Some(previous_bundle) = self.parked_bundles.front().await? {
+                if Cursor::from(&previous_bundle) >= cursor {
+                    break;
+                }
+                ensure!(
+                    previous_bundle.is_skippable(),
+                    InboxError::UnskippableBundle {
+                        bundle: previous_bundle
+                    }
+                );
+                self.parked_bundles.delete_front();
+                tracing::trace!("Skipping previously parked bundle {:?}", previous_bundle);
+            }
+        }
+        // Reconcile the bundle with the next added or parked bundle, or mark it as removed.
         let already_known = match self.added_bundles.front().await? {
             Some(previous_bundle) => {
                 // Rationale: If the two cursors are equal, then the bundles should match.
@@ -212,11 +235,25 @@ where
                 tracing::trace!("Consuming bundle {:?}", bundle);
                 true
             }
-            None => {
-                tracing::trace!("Marking bundle as expected: {:?}", bundle);
-                self.removed_bundles.push_back(bundle.clone());
-                false
-            }
+            None => match self.parked_bundles.front().await? {
+                Some(previous_bundle) => {
+                    ensure!(
+                        bundle == &previous_bundle,
+                        InboxError::UnexpectedBundle {
+                            previous_bundle,
+                            bundle: bundle.clone(),
+                        }
+                    );
+                    self.parked_bundles.delete_front();
+                    tracing::trace!("Consuming parked bundle {:?}", bundle);
+                    false
+                }
+                None => {
+                    tracing::trace!("Marking bundle as expected: {:?}", bundle);
+                    self.removed_bundles.push_back(bundle.clone());
+                    false
+                }
+            },
         };
         self.next_cursor_to_remove.set(cursor.try_add_one()?);
         Ok(already_known)
@@ -225,8 +262,16 @@ where
     /// Pushes a bundle to the inbox. The verifications should not fail in production unless
     /// many validators are faulty.
     ///
-    /// Returns `true` if the bundle was new, `false` if it was already in `removed_bundles`.
-    pub(crate) async fn add_bundle(&mut self, bundle: MessageBundle) -> Result<bool, InboxError> {
+    /// If `added_bundles` already holds `max_pending_bundles` bundles, or if earlier bundles
+    /// are parked, the bundle is parked instead.
+    ///
+    /// Returns `true` if the bundle was new, `false` if it was already in `removed_bundles`
+    /// or if it was parked.
+    pub(crate) async fn add_bundle(
+        &mut self,
+        bundle: MessageBundle,
+        max_pending_bundles: Option<usize>,
+    ) -> Result<bool, InboxError> {
         // Record the latest cursor.
         let cursor = Cursor::from(&bundle);
         ensure!(
@@ -263,6 +308,12 @@ where
                 }
                 false
             }
+            None if self.parked_bundles.count() > 0 || self.is_full(max_pending_bundles) => {
+                // Wait until there is room in the inbox.
+                tracing::trace!("Parking bundle {:?}", bundle);
+                self.parked_bundles.push_back(bundle);
+                false
+            }
             None => {
                 // Otherwise, schedule the messages for execution.
                 self.added_bundles.push_back(bundle);
@@ -272,6 +323,29 @@ where
         self.next_cursor_to_add.set(cursor.try_add_one()?);
         Ok(newly_added)
     }
+
+    /// Moves parked bundles to `added_bundles`, in order, until it holds
+    /// `max_pending_bundles` bundles. Returns the admitted bundles.
+    pub(crate) async fn admit_parked_bundles(
+        &mut self,
+        max_pending_bundles: Option<usize>,
+    ) -> Result<Vec<MessageBundle>, InboxError> {
+        let mut admitted = Vec::new();
+        while !self.is_full(max_pending_bundles) {
+            let Some(bundle) = self.parked_bundles.front().await? else {
+                break;
+            };
+            self.parked_bundles.delete_front();
+            tracing::trace!("Admitting parked bundle {:?}", bundle);
+            self.added_bundles.push_back(bundle.clone());
+            admitted.push(bundle);
+        }
+        Ok(admitted)
+    }
+
+    fn is_full(&self, max_pending_bundles: Option<usize>) -> bool {
+        max_pending_bundles.is_some_and(|max| self.added_bundles.count() >= max)
+    }
 }
 
 #[cfg(with_testing)]
@@ -144,6 +144,10 @@ pub enum ChainError {
     InternalError(String),
     #[error("Block proposal is too large")]
     BlockProposalTooLarge,
+    #[error("Block has {count} operations, but at most {max} are allowed")]
+    TooManyOperations { count: u64, max: u64 },
+    #[error("Block receives {count} incoming messages, but at most {max} are allowed")]
+    TooManyIncomingMessages { count: u64, max: u64 },
     #[error(transparent)]
     BcsError(#[from] bcs::Error),
     #[error("Insufficient balance to pay the fees")]
@@ -27,12 +27,14 @@ use linera_views::{
     context::{Context as _, MemoryContext},
     memory::TEST_MEMORY_MAX_STREAM_QUERIES,
     random::generate_test_namespace,
-    views::{View, ViewError},
+    views::{RootView, View, ViewError},
 };
 
 use crate::{
     block::{Block, ConfirmedBlock},
-    data_types::{IncomingBundle, MessageAction, MessageBundle, Origin},
+    data_types::{
+        BlockExecutionOutcome, ChainAndHeight, IncomingBundle, MessageAction, MessageBundle, Origin,
+    },
     test::{make_child_block, make_first_block, BlockTestExt, MessageTestExt},
     ChainError, ChainExecutionContext, ChainStateView,
 };
@@ -101,6 +103,7 @@ fn make_open_chain_config() -> OpenChainConfig {
         committees: iter::once((Epoch::ZERO, committee)).collect(),
         balance: Amount::from_tokens(10),
         application_permissions: Default::default(),
+        bytecode_publishers: None,
     }
 }
 
@@ -183,6 +186,81 @@ async fn test_block_size_limit() {
     );
 }
 
+/// Tests that blocks sending messages to more distinct destinations than the policy allows are
+/// rejected, and that repeated destinations are counted once.
+#[tokio::test]
+async fn test_recipients_limit() {
+    // Exactly at the limit.
+    assert_matches!(execute_block_with_recipients(&[1, 2]).await, Ok(_));
+    // Repeated recipients are only counted once.
+    assert_matches!(execute_block_with_recipients(&[1, 2, 1, 2]).await, Ok(_));
+    // The third recipient exceeds the limit.
+    assert_matches!(
+        execute_block_with_recipients(&[1, 2, 3]).await,
+        Err(ChainError::ExecutionError(
+            execution_error,
+            ChainExecutionContext::Operation(3),
+        )) if matches!(
+            *execution_error,
+            ExecutionError::TooManyRecipients { count: 3, max: 2 }
+        )
+    );
+}
+
+/// Executes a block on a new chain that allows at most two message destinations per block,
+/// with one transfer to each of the root chains with the given `recipients` indices.
+async fn execute_block_with_recipients(
+    recipients: &[u32],
+) -> Result<BlockExecutionOutcome, ChainError> {
+    let time = Timestamp::from(0);
+    let message_id = make_admin_message_id(BlockHeight(3));
+    let chain_id = ChainId::child(message_id);
+    let mut chain = ChainStateView::new(chain_id).await;
+
+    let mut config = make_open_chain_config();
+    config.committees.insert(
+        Epoch(0),
+        Committee::new(
+            BTreeMap::from([(
+                ValidatorName(PublicKey::test_key(1)),
+                ValidatorState {
+                    network_address: PublicKey::test_key(1).to_string(),
+                    votes: 1,
+                },
+            )]),
+            ResourceControlPolicy {
+                maximum_recipients_per_block: 2,
+                ..ResourceControlPolicy::default()
+            },
+        ),
+    );
+
+    chain
+        .execute_init_message(message_id, &config, time, time)
+        .await
+        .unwrap();
+
+    let mut block = make_first_block(chain_id)
+        .with_authenticated_signer(Some(PublicKey::test_key(0).into()))
+        .with_incoming_bundle(IncomingBundle {
+            origin: Origin::chain(admin_id()),
+            bundle: MessageBundle {
+                certificate_hash: CryptoHash::test_hash("certificate"),
+                height: BlockHeight(1),
+                transaction_index: 0,
+                timestamp: time,
+                messages: vec![Message::System(SystemMessage::OpenChain(config))
+                    .to_posted(0, MessageKind::Protected)],
+            },
+            action: MessageAction::Accept,
+        });
+    for index in recipients {
+        block = block.with_transfer(None, Recipient::root(*index), Amount::ONE);
+    }
+
+    chain.execute_block(&block, time, None, None).await
+}
+
 #[tokio::test]
 async fn test_application_permissions() -> anyhow::Result<()> {
     let time = Timestamp::from(0);
@@ -279,3 +357,63 @@ async fn test_application_permissions() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_read_logs_backward() -> anyhow::Result<()> {
+    let mut chain = ChainStateView::new(ChainId::root(0)).await;
+    let received = (0..200u64)
+        .map(|index| ChainAndHeight {
+            chain_id: ChainId::root((index % 5) as u32 + 1),
+            height: BlockHeight(index),
+        })
+        .collect::<Vec<_>>();
+    let hashes = (0..200u64)
+        .map(|index| CryptoHash::test_hash(index.to_string()))
+        .collect::<Vec<_>>();
+    for (chain_and_height, hash) in received.iter().zip(&hashes) {
+        chain.received_log.push(*chain_and_height);
+        chain.confirmed_log.push(*hash);
+    }
+    chain.save().await?;
+
+    // Page through the received log, newest first. The last page is incomplete.
+    let mut pages = Vec::new();
+    let mut before = None;
+    loop {
+        let page = chain.read_received_log_backward(before, 60).await?;
+        let start = before.unwrap_or(200) - page.len() as u64;
+        pages.push(page);
+        if start == 0 {
+            break;
+        }
+        before = Some(start);
+    }
+    assert_eq!(
+        pages.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![60, 60, 60, 20]
+    );
+    let mut expected = received.clone();
+    expected.reverse();
+    assert_eq!(pages.concat(), expected);
+
+    // Pages can start anywhere, and a cursor past the end starts with the newest entry.
+    assert_eq!(
+        chain.read_received_log_backward(Some(100), 2).await?,
+        vec![received[99], received[98]]
+    );
+    assert_eq!(
+        chain.read_received_log_backward(Some(1000), 1).await?,
+        vec![received[199]]
+    );
+    assert!(chain
+        .read_received_log_backward(Some(0), 50)
+        .await?
+        .is_empty());
+    assert!(chain.read_received_log_backward(None, 0).await?.is_empty());
+
+    assert_eq!(
+        chain.read_confirmed_log_backward(Some(50), 50).await?,
+        hashes[..50].iter().rev().copied().collect::<Vec<_>>()
+    );
+    Ok(())
+}
@@ -2,12 +2,13 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use assert_matches::assert_matches;
 use linera_base::data_types::Amount;
 
 use super::*;
 use crate::{
     block::{ConfirmedBlock, ValidatedBlock},
-    test::{make_first_block, BlockTestExt},
+    test::{make_first_block, BlockTestExt, MessageTestExt},
 };
 
 #[test]
@@ -121,3 +122,76 @@ fn test_certificates() {
         .is_none());
     assert!(builder.append(v3.validator, v3.signature).is_err());
 }
+
+fn make_incoming_bundle(message_count: u32) -> IncomingBundle {
+    let messages = (0..message_count)
+        .map(|index| {
+            Message::System(SystemMessage::Credit {
+                target: None,
+                amount: Amount::ONE,
+                source: None,
+            })
+            .to_posted(index, MessageKind::Tracked)
+        })
+        .collect();
+    IncomingBundle {
+        origin: Origin::chain(ChainId::root(2)),
+        bundle: MessageBundle {
+            certificate_hash: CryptoHash::test_hash("certificate"),
+            height: BlockHeight(1),
+            transaction_index: 0,
+            timestamp: Timestamp::default(),
+            messages,
+        },
+        action: MessageAction::Accept,
+    }
+}
+
+#[test]
+fn test_check_resource_limits() {
+    let policy = ResourceControlPolicy {
+        maximum_operations_per_block: 2,
+        maximum_incoming_messages_per_block: 3,
+        ..ResourceControlPolicy::default()
+    };
+
+    // Operations: the limit is inclusive.
+    let block = make_first_block(ChainId::root(1))
+        .with_simple_transfer(ChainId::root(2), Amount::ONE)
+        .with_simple_transfer(ChainId::root(2), Amount::ONE);
+    assert!(block.check_resource_limits(&policy).is_ok());
+    let block = block.with_simple_transfer(ChainId::root(2), Amount::ONE);
+    assert_matches!(
+        block.check_resource_limits(&policy),
+        Err(ChainError::TooManyOperations { count: 3, max: 2 })
+    );
+
+    // Incoming messages are counted across all bundles.
+    let block = make_first_block(ChainId::root(1))
+        .with_incoming_bundle(make_incoming_bundle(2))
+        .with_incoming_bundle(make_incoming_bundle(1));
+    assert!(block.check_resource_limits(&policy).is_ok());
+    let block = block.with_incoming_bundle(make_incoming_bundle(1));
+    assert_matches!(
+        block.check_resource_limits(&policy),
+        Err(ChainError::TooManyIncomingMessages { count: 4, max: 3 })
+    );
+
+    // Serialized size: a block of exactly the maximum size is accepted.
+    let block =
+        make_first_block(ChainId::root(1)).with_simple_transfer(ChainId::root(2), Amount::ONE);
+    let size = bcs::serialized_size(&block).unwrap() as u64;
+    let policy = ResourceControlPolicy {
+        maximum_block_proposal_size: size,
+        ..ResourceControlPolicy::default()
+    };
+    assert!(block.check_resource_limits(&policy).is_ok());
+    let policy = ResourceControlPolicy {
+        maximum_block_proposal_size: size - 1,
+        ..ResourceControlPolicy::default()
+    };
+    assert_matches!(
+        block.check_resource_limits(&policy),
+        Err(ChainError::BlockProposalTooLarge)
+    );
+}
@@ -44,7 +44,10 @@ async fn test_inbox_add_then_remove_skippable() {
     let hash = CryptoHash::test_hash("1");
     let mut view = InboxStateView::new().await;
     // Add one bundle.
-    assert!(view.add_bundle(make_bundle(hash, 0, 0, [0])).await.unwrap());
+    assert!(view
+        .add_bundle(make_bundle(hash, 0, 0, [0]), None)
+        .await
+        .unwrap());
     // Remove the same bundle
     assert!(view
         .remove_bundle(&make_bundle(hash, 0, 0, [0]))
@@ -52,7 +55,7 @@ async fn test_inbox_add_then_remove_skippable() {
         .unwrap());
     // Fail to add an old bundle.
     assert_matches!(
-        view.add_bundle(make_bundle(hash, 0, 0, [0])).await,
+        view.add_bundle(make_bundle(hash, 0, 0, [0]), None).await,
         Err(InboxError::IncorrectOrder { .. })
     );
     // Fail to remove an old bundle.
@@ -61,8 +64,14 @@ async fn test_inbox_add_then_remove_skippable() {
         Err(InboxError::IncorrectOrder { .. })
     );
     // Add two more bundles.
-    assert!(view.add_bundle(make_bundle(hash, 0, 1, [1])).await.unwrap());
-    assert!(view.add_bundle(make_bundle(hash, 1, 0, [2])).await.unwrap());
+    assert!(view
+        .add_bundle(make_bundle(hash, 0, 1, [1]), None)
+        .await
+        .unwrap());
+    assert!(view
+        .add_bundle(make_bundle(hash, 1, 0, [2]), None)
+        .await
+        .unwrap());
     // Fail to remove non-matching bundle.
     assert_matches!(
         view.remove_bundle(&make_bundle(hash, 0, 1, [0])).await,
@@ -94,7 +103,10 @@ async fn test_inbox_remove_then_add_skippable() {
         .await
         .unwrap());
     // Add the same bundle
-    assert!(!view.add_bundle(make_bundle(hash, 0, 0, [0])).await.unwrap());
+    assert!(!view
+        .add_bundle(make_bundle(hash, 0, 0, [0]), None)
+        .await
+        .unwrap());
     // Fail to remove an old bundle.
     assert_matches!(
         view.remove_bundle(&make_bundle(hash, 0, 0, [0])).await,
@@ -102,7 +114,7 @@ async fn test_inbox_remove_then_add_skippable() {
     );
     // Fail to add an old bundle.
     assert_matches!(
-        view.add_bundle(make_bundle(hash, 0, 0, [0])).await,
+        view.add_bundle(make_bundle(hash, 0, 0, [0]), None).await,
         Err(InboxError::IncorrectOrder { .. })
     );
     // Remove two more bundles.
@@ -116,30 +128,39 @@ async fn test_inbox_remove_then_add_skippable() {
         .unwrap());
     // Fail to add non-matching bundle.
     assert_matches!(
-        view.add_bundle(make_bundle(hash, 0, 1, [0])).await,
+        view.add_bundle(make_bundle(hash, 0, 1, [0]), None).await,
         Err(InboxError::UnexpectedBundle { .. })
     );
     // Fail to add non-matching bundle (hash).
     assert_matches!(
-        view.add_bundle(make_bundle(CryptoHash::test_hash("2"), 0, 1, [1]))
+        view.add_bundle(make_bundle(CryptoHash::test_hash("2"), 0, 1, [1]), None)
             .await,
         Err(InboxError::UnexpectedBundle { .. })
     );
     // NOT OK to forget about previous consumed bundles while backfilling.
     assert_matches!(
-        view.add_bundle(make_bundle(hash, 1, 0, [2])).await,
+        view.add_bundle(make_bundle(hash, 1, 0, [2]), None).await,
         Err(InboxError::UnexpectedBundle { .. })
     );
     // OK to backfill the two consumed bundles, with one skippable bundle in the middle.
-    assert!(!view.add_bundle(make_bundle(hash, 0, 1, [1])).await.unwrap());
+    assert!(!view
+        .add_bundle(make_bundle(hash, 0, 1, [1]), None)
+        .await
+        .unwrap());
     // Cannot add an unskippable bundle that was visibly skipped already.
     assert_matches!(
-        view.add_bundle(make_unskippable_bundle(hash, 1, 0, [2]))
+        view.add_bundle(make_unskippable_bundle(hash, 1, 0, [2]), None)
             .await,
         Err(InboxError::UnexpectedBundle { .. })
     );
-    assert!(!view.add_bundle(make_bundle(hash, 1, 0, [2])).await.unwrap());
-    assert!(!view.add_bundle(make_bundle(hash, 1, 1, [3])).await.unwrap());
+    assert!(!view
+        .add_bundle(make_bundle(hash, 1, 0, [2]), None)
+        .await
+        .unwrap());
+    assert!(!view
+        .add_bundle(make_bundle(hash, 1, 1, [3]), None)
+        .await
+        .unwrap());
     // Inbox is empty again.
     assert_eq!(view.added_bundles.count(), 0);
     assert_eq!(view.removed_bundles.count(), 0);
@@ -151,7 +172,7 @@ async fn test_inbox_add_then_remove_unskippable() {
     let mut view = InboxStateView::new().await;
     // Add one bundle.
     assert!(view
-        .add_bundle(make_unskippable_bundle(hash, 0, 0, [0]))
+        .add_bundle(make_unskippable_bundle(hash, 0, 0, [0]), None)
         .await
         .unwrap());
     // Remove the same bundle
@@ -161,7 +182,7 @@ async fn test_inbox_add_then_remove_unskippable() {
         .unwrap());
     // Fail to add an old bundle.
     assert_matches!(
-        view.add_bundle(make_unskippable_bundle(hash, 0, 0, [0]))
+        view.add_bundle(make_unskippable_bundle(hash, 0, 0, [0]), None)
             .await,
         Err(InboxError::IncorrectOrder { .. })
     );
@@ -173,11 +194,11 @@ async fn test_inbox_add_then_remove_unskippable() {
     );
     // Add two more bundles.
     assert!(view
-        .add_bundle(make_unskippable_bundle(hash, 0, 1, [1]))
+        .add_bundle(make_unskippable_bundle(hash, 0, 1, [1]), None)
         .await
         .unwrap());
     assert!(view
-        .add_bundle(make_unskippable_bundle(hash, 1, 0, [2]))
+        .add_bundle(make_unskippable_bundle(hash, 1, 0, [2]), None)
         .await
         .unwrap());
     // Fail to remove non-matching bundle.
@@ -227,7 +248,7 @@ async fn test_inbox_remove_then_add_unskippable() {
         .unwrap());
     // Add the same bundle
     assert!(!view
-        .add_bundle(make_unskippable_bundle(hash, 0, 0, [0]))
+        .add_bundle(make_unskippable_bundle(hash, 0, 0, [0]), None)
         .await
         .unwrap());
     // Fail to remove an old bundle.
@@ -238,7 +259,7 @@ async fn test_inbox_remove_then_add_unskippable() {
     );
     // Fail to add an old bundle.
     assert_matches!(
-        view.add_bundle(make_unskippable_bundle(hash, 0, 0, [0]))
+        view.add_bundle(make_unskippable_bundle(hash, 0, 0, [0]), None)
             .await,
         Err(InboxError::IncorrectOrder { .. })
     );
@@ -253,40 +274,38 @@ async fn test_inbox_remove_then_add_unskippable() {
         .unwrap());
     // Fail to add non-matching bundle.
     assert_matches!(
-        view.add_bundle(make_unskippable_bundle(hash, 0, 1, [0]))
+        view.add_bundle(make_unskippable_bundle(hash, 0, 1, [0]), None)
             .await,
         Err(InboxError::UnexpectedBundle { .. })
     );
     // Fail to add non-matching bundle (hash).
     assert_matches!(
-        view.add_bundle(make_unskippable_bundle(
-            CryptoHash::test_hash("2"),
-            0,
-            1,
-            [1]
-        ))
+        view.add_bundle(
+            make_unskippable_bundle(CryptoHash::test_hash("2"), 0, 1, [1]),
+            None
+        )
         .await,
         Err(InboxError::UnexpectedBundle { .. })
     );
     // NOT OK to forget about previous consumed bundles while backfilling.
     assert_matches!(
-        view.add_bundle(make_unskippable_bundle(hash, 1, 1, [3]))
+        view.add_bundle(make_unskippable_bundle(hash, 1, 1, [3]), None)
             .await,
         Err(InboxError::UnexpectedBundle { .. })
     );
     // OK to add the two bundles.
     assert!(!view
-        .add_bundle(make_unskippable_bundle(hash, 0, 1, [1]))
+        .add_bundle(make_unskippable_bundle(hash, 0, 1, [1]), None)
         .await
         .unwrap());
     // Cannot add an unskippable bundle that was visibly skipped already.
     assert_matches!(
-        view.add_bundle(make_unskippable_bundle(hash, 1, 0, [2]))
+        view.add_bundle(make_unskippable_bundle(hash, 1, 0, [2]), None)
             .await,
         Err(InboxError::UnexpectedBundle { .. })
     );
     assert!(!view
-        .add_bundle(make_unskippable_bundle(hash, 1, 1, [3]))
+        .add_bundle(make_unskippable_bundle(hash, 1, 1, [3]), None)
         .await
         .unwrap());
     // Inbox is empty again.
@@ -300,10 +319,13 @@ async fn test_inbox_add_then_remove_mixed() {
     let mut view = InboxStateView::new().await;
     // Add two bundles.
     assert!(view
-        .add_bundle(make_unskippable_bundle(hash, 0, 1, [1]))
+        .add_bundle(make_unskippable_bundle(hash, 0, 1, [1]), None)
+        .await
+        .unwrap());
+    assert!(view
+        .add_bundle(make_bundle(hash, 1, 0, [2]), None)
         .await
         .unwrap());
-    assert!(view.add_bundle(make_bundle(hash, 1, 0, [2])).await.unwrap());
     // Fail to remove non-matching bundle (skippability).
     assert_matches!(
         view.remove_bundle(&make_bundle(hash, 0, 1, [1])).await,
@@ -338,3 +360,73 @@ async fn test_inbox_add_then_remove_mixed() {
     assert_eq!(view.added_bundles.count(), 0);
     assert_eq!(view.removed_bundles.count(), 0);
 }
+
+#[tokio::test]
+async fn test_inbox_park_bundles() {
+    let hash = CryptoHash::test_hash("1");
+    let mut view = InboxStateView::new().await;
+    let max = Some(2);
+    // The first two bundles are added, the next ones are parked.
+    assert!(view
+        .add_bundle(make_bundle(hash, 0, 0, [0]), max)
+        .await
+        .unwrap());
+    assert!(view
+        .add_bundle(make_bundle(hash, 0, 1, [1]), max)
+        .await
+        .unwrap());
+    assert!(!view
+        .add_bundle(make_unskippable_bundle(hash, 1, 0, [2]), max)
+        .await
+        .unwrap());
+    assert!(!view
+        .add_bundle(make_bundle(hash, 1, 1, [3]), max)
+        .await
+        .unwrap());
+    assert_eq!(view.added_bundles.count(), 2);
+    assert_eq!(view.parked_bundles.count(), 2);
+    // Parked bundles are not received twice.
+    assert_matches!(
+        view.add_bundle(make_bundle(hash, 1, 1, [3]), max).await,
+        Err(InboxError::IncorrectOrder { .. })
+    );
+    assert_eq!(view.next_block_height_to_receive().unwrap(), BlockHeight(2));
+    // Nothing is admitted while the inbox is full.
+    assert!(view.admit_parked_bundles(max).await.unwrap().is_empty());
+    // Draining the inbox makes room for the parked bundles, in order.
+    assert!(view
+        .remove_bundle(&make_bundle(hash, 0, 0, [0]))
+        .await
+        .unwrap());
+    assert_eq!(
+        view.admit_parked_bundles(max).await.unwrap(),
+        vec![make_unskippable_bundle(hash, 1, 0, [2])]
+    );
+    assert_eq!(view.added_bundles.count(), 2);
+    assert_eq!(view.parked_bundles.count(), 1);
+    // Parked bundles can be removed directly, but unskippable ones cannot be skipped.
+    assert!(view
+        .remove_bundle(&make_bundle(hash, 0, 1, [1]))
+        .await
+        .unwrap());
+    assert_matches!(
+        view.remove_bundle(&make_bundle(hash, 1, 1, [3])).await,
+        Err(InboxError::UnskippableBundle { .. })
+    );
+    assert!(view
+        .remove_bundle(&make_unskippable_bundle(hash, 1, 0, [2]))
+        .await
+        .unwrap());
+    assert!(!view
+        .remove_bundle(&make_bundle(hash, 1, 1, [3]))
+        .await
+        .unwrap());
+    assert_eq!(view.added_bundles.count(), 0);
+    assert_eq!(view.parked_bundles.count(), 0);
+    assert_eq!(view.removed_bundles.count(), 0);
+    // New bundles are added again.
+    assert!(view
+        .add_bundle(make_bundle(hash, 2, 0, [4]), max)
+        .await
+        .unwrap());
+}
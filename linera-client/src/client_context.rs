@@ -736,6 +736,7 @@ where
             epoch,
             balance,
             application_permissions: Default::default(),
+            bytecode_publishers: None,
         };
         let operations = iter::repeat(Operation::System(SystemOperation::OpenChain(config)))
             .take(num_new_chains)
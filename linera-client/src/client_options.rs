@@ -580,6 +580,50 @@ pub enum ClientCommand {
         /// Set the maximum write data per block.
         #[arg(long)]
         maximum_bytes_written_per_block: Option<u64>,
+
+        /// Set the maximum size of the argument of an outgoing message, in bytes.
+        #[arg(long)]
+        maximum_message_size: Option<u64>,
+
+        /// Set the maximum number of outgoing messages per transaction.
+        #[arg(long)]
+        maximum_messages_per_transaction: Option<u64>,
+
+        /// Set the maximum depth of nested cross-application calls.
+        #[arg(long)]
+        maximum_call_depth: Option<u64>,
+
+        /// Set the maximum size of the value of an event, in bytes.
+        #[arg(long)]
+        maximum_event_value_size: Option<u64>,
+
+        /// Set the maximum number of events emitted per transaction.
+        #[arg(long)]
+        maximum_events_per_transaction: Option<u64>,
+
+        /// Set the maximum number of distinct message destinations per block.
+        #[arg(long)]
+        maximum_recipients_per_block: Option<u64>,
+
+        /// Set the maximum number of operations per block.
+        #[arg(long)]
+        maximum_operations_per_block: Option<u64>,
+
+        /// Set the maximum number of incoming messages received per block.
+        #[arg(long)]
+        maximum_incoming_messages_per_block: Option<u64>,
+
+        /// Set the fuel charged for each call to the `sha256` host function.
+        #[arg(long)]
+        sha256_fuel: Option<u64>,
+
+        /// Set the fuel charged for each call to the `verify_ed25519` host function.
+        #[arg(long)]
+        verify_ed25519_fuel: Option<u64>,
+
+        /// Set the fuel charged per byte hashed by the cryptographic host functions.
+        #[arg(long)]
+        hashing_fuel_per_byte: Option<u64>,
     },
 
     /// Send one transfer per chain in bulk mode
@@ -708,6 +752,50 @@ pub enum ClientCommand {
         #[arg(long)]
         maximum_bytes_written_per_block: Option<u64>,
 
+        /// Set the maximum size of the argument of an outgoing message, in bytes.
+        #[arg(long)]
+        maximum_message_size: Option<u64>,
+
+        /// Set the maximum number of outgoing messages per transaction.
+        #[arg(long)]
+        maximum_messages_per_transaction: Option<u64>,
+
+        /// Set the maximum depth of nested cross-application calls.
+        #[arg(long)]
+        maximum_call_depth: Option<u64>,
+
+        /// Set the maximum size of the value of an event, in bytes.
+        #[arg(long)]
+        maximum_event_value_size: Option<u64>,
+
+        /// Set the maximum number of events emitted per transaction.
+        #[arg(long)]
+        maximum_events_per_transaction: Option<u64>,
+
+        /// Set the maximum number of distinct message destinations per block.
+        #[arg(long)]
+        maximum_recipients_per_block: Option<u64>,
+
+        /// Set the maximum number of operations per block.
+        #[arg(long)]
+        maximum_operations_per_block: Option<u64>,
+
+        /// Set the maximum number of incoming messages received per block.
+        #[arg(long)]
+        maximum_incoming_messages_per_block: Option<u64>,
+
+        /// Set the fuel charged for each call to the `sha256` host function.
+        #[arg(long)]
+        sha256_fuel: Option<u64>,
+
+        /// Set the fuel charged for each call to the `verify_ed25519` host function.
+        #[arg(long)]
+        verify_ed25519_fuel: Option<u64>,
+
+        /// Set the fuel charged per byte hashed by the cryptographic host functions.
+        #[arg(long)]
+        hashing_fuel_per_byte: Option<u64>,
+
         /// Force this wallet to generate keys using a PRNG and a given seed. USE FOR
         /// TESTING ONLY.
         #[arg(long)]
@@ -912,6 +1000,17 @@ pub enum ClientCommand {
     #[command(subcommand)]
     Storage(DatabaseToolCommand),
 
+    /// Compare two block replay reports, e.g. from two validators that disagree on the
+    /// outcome of a block, and print the first operation or message after which their
+    /// execution states differ.
+    CompareReplayReports {
+        /// The JSON report of the first validator, as printed by `linera storage replay_block`.
+        first: PathBuf,
+
+        /// The JSON report of the second validator.
+        second: PathBuf,
+    },
+
     /// Print CLI help in Markdown format, and exit.
     #[command(hide = true)]
     HelpMarkdown,
@@ -1002,6 +1101,89 @@ pub enum DatabaseToolCommand {
         #[arg(long = "storage")]
         storage_config: String,
     },
+
+    /// List the keys of the state of a chain that belong to no entry of its collections
+    #[command(name = "verify_chain_integrity")]
+    VerifyChainIntegrity {
+        /// Storage configuration for the blockchain history.
+        #[arg(long = "storage")]
+        storage_config: String,
+
+        /// The chain whose state is checked.
+        chain_id: ChainId,
+
+        /// Delete the keys that belong to no entry.
+        #[arg(long)]
+        repair: bool,
+    },
+
+    /// Re-execute a confirmed block against the state of its chain before that block,
+    /// without persisting any changes, and print the hash of the execution state after
+    /// each operation and message as JSON. The chain must not have executed the block yet.
+    #[command(name = "replay_block")]
+    ReplayBlock {
+        /// Storage configuration for the blockchain history.
+        #[arg(long = "storage")]
+        storage_config: String,
+
+        /// The hash of the certificate of the block.
+        certificate_hash: CryptoHash,
+    },
+
+    /// Delete the old certificates of a chain. The latest certificate, the certificates of
+    /// blocks that create or remove a committee and the certificates of blocks with
+    /// messages that are still in flight or waiting in an inbox are always retained.
+    #[command(name = "prune_certificates")]
+    PruneCertificates {
+        /// Storage configuration for the blockchain history.
+        #[arg(long = "storage")]
+        storage_config: String,
+
+        /// The chain whose certificates are pruned.
+        chain_id: ChainId,
+
+        /// Retain the certificates of this many of the latest blocks.
+        #[arg(
+            long,
+            conflicts_with = "max_age_secs",
+            required_unless_present = "max_age_secs"
+        )]
+        keep_latest_blocks: Option<u64>,
+
+        /// Retain the certificates of the blocks that are at most this many seconds old.
+        #[arg(long)]
+        max_age_secs: Option<u64>,
+    },
+
+    /// Export the state of a chain, including its inboxes, outboxes and certificates, to
+    /// a file
+    #[command(name = "export_chain")]
+    ExportChain {
+        /// Storage configuration for the blockchain history.
+        #[arg(long = "storage")]
+        storage_config: String,
+
+        /// The chain to export.
+        chain_id: ChainId,
+
+        /// The file to write the chain to.
+        path: PathBuf,
+    },
+
+    /// Import a chain from a file written by `export_chain`
+    #[command(name = "import_chain")]
+    ImportChain {
+        /// Storage configuration for the blockchain history.
+        #[arg(long = "storage")]
+        storage_config: String,
+
+        /// The file to read the chain from.
+        path: PathBuf,
+
+        /// Replace the state of the chain if it already exists.
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 impl DatabaseToolCommand {
@@ -1015,6 +1197,11 @@ impl DatabaseToolCommand {
             DatabaseToolCommand::ListNamespaces { storage_config } => storage_config,
             DatabaseToolCommand::ListBlobIds { storage_config } => storage_config,
             DatabaseToolCommand::ListRootKeys { storage_config } => storage_config,
+            DatabaseToolCommand::VerifyChainIntegrity { storage_config, .. } => storage_config,
+            DatabaseToolCommand::ReplayBlock { storage_config, .. } => storage_config,
+            DatabaseToolCommand::PruneCertificates { storage_config, .. } => storage_config,
+            DatabaseToolCommand::ExportChain { storage_config, .. } => storage_config,
+            DatabaseToolCommand::ImportChain { storage_config, .. } => storage_config,
         };
         Ok(storage_config.parse::<StorageConfigNamespace>()?)
     }
@@ -1,14 +1,18 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{fmt, str::FromStr};
+use std::{fmt, io, str::FromStr};
 
 use async_trait::async_trait;
-use linera_base::identifiers::BlobId;
+use linera_base::{
+    crypto::CryptoHash,
+    identifiers::{BlobId, ChainId},
+};
+use linera_core::data_types::BlockReplayReport;
 use linera_execution::WasmRuntime;
 #[cfg(with_storage)]
 use linera_storage::list_all_blob_ids;
-use linera_storage::{DbStorage, Storage};
+use linera_storage::{ChainExportError, DbStorage, PruningPolicy, Storage};
 #[cfg(feature = "storage-service")]
 use linera_storage_service::{
     client::ServiceStoreClient,
@@ -52,6 +56,9 @@ pub enum Error {
     Config(#[from] crate::config::Error),
 }
 
+util::impl_from_dynamic!(Error:Backend, ViewError);
+util::impl_from_dynamic!(Error:Backend, linera_core::worker::WorkerError);
+util::impl_from_dynamic!(Error:Backend, ChainExportError);
 util::impl_from_dynamic!(Error:Backend, linera_views::memory::MemoryStoreError);
 #[cfg(feature = "storage-service")]
 util::impl_from_dynamic!(Error:Backend, linera_storage_service::common::ServiceStoreError);
@@ -618,6 +625,230 @@ impl StoreConfig {
             }
         }
     }
+
+    /// Returns the keys of the state of a chain that belong to no entry of its
+    /// collections, and deletes them if `repair` is set.
+    pub async fn verify_chain_integrity(
+        self,
+        chain_id: ChainId,
+        repair: bool,
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        match self {
+            StoreConfig::Memory(_, _) => Err(ViewError::StoreError {
+                backend: "memory".to_string(),
+                error: "verify_chain_integrity is not supported for the memory storage".to_string(),
+            }),
+            #[cfg(feature = "storage-service")]
+            StoreConfig::Service(config, namespace) => {
+                let storage =
+                    DbStorage::<ServiceStoreClient, _>::new(config, &namespace, ROOT_KEY, None)
+                        .await?;
+                storage.verify_chain_integrity(chain_id, repair).await
+            }
+            #[cfg(feature = "rocksdb")]
+            StoreConfig::RocksDb(config, namespace) => {
+                let storage =
+                    DbStorage::<RocksDbStore, _>::new(config, &namespace, ROOT_KEY, None).await?;
+                storage.verify_chain_integrity(chain_id, repair).await
+            }
+            #[cfg(feature = "dynamodb")]
+            StoreConfig::DynamoDb(config, namespace) => {
+                let storage =
+                    DbStorage::<DynamoDbStore, _>::new(config, &namespace, ROOT_KEY, None).await?;
+                storage.verify_chain_integrity(chain_id, repair).await
+            }
+            #[cfg(feature = "scylladb")]
+            StoreConfig::ScyllaDb(config, namespace) => {
+                let storage =
+                    DbStorage::<ScyllaDbStore, _>::new(config, &namespace, ROOT_KEY, None).await?;
+                storage.verify_chain_integrity(chain_id, repair).await
+            }
+        }
+    }
+
+    /// Deletes the certificates of a chain that are older than allowed by the `policy`,
+    /// and returns their hashes.
+    pub async fn prune_certificates(
+        self,
+        chain_id: ChainId,
+        policy: PruningPolicy,
+    ) -> Result<Vec<CryptoHash>, ViewError> {
+        match self {
+            StoreConfig::Memory(_, _) => Err(ViewError::StoreError {
+                backend: "memory".to_string(),
+                error: "prune_certificates is not supported for the memory storage".to_string(),
+            }),
+            #[cfg(feature = "storage-service")]
+            StoreConfig::Service(config, namespace) => {
+                let storage =
+                    DbStorage::<ServiceStoreClient, _>::new(config, &namespace, ROOT_KEY, None)
+                        .await?;
+                storage.prune_certificates(chain_id, policy).await
+            }
+            #[cfg(feature = "rocksdb")]
+            StoreConfig::RocksDb(config, namespace) => {
+                let storage =
+                    DbStorage::<RocksDbStore, _>::new(config, &namespace, ROOT_KEY, None).await?;
+                storage.prune_certificates(chain_id, policy).await
+            }
+            #[cfg(feature = "dynamodb")]
+            StoreConfig::DynamoDb(config, namespace) => {
+                let storage =
+                    DbStorage::<DynamoDbStore, _>::new(config, &namespace, ROOT_KEY, None).await?;
+                storage.prune_certificates(chain_id, policy).await
+            }
+            #[cfg(feature = "scylladb")]
+            StoreConfig::ScyllaDb(config, namespace) => {
+                let storage =
+                    DbStorage::<ScyllaDbStore, _>::new(config, &namespace, ROOT_KEY, None).await?;
+                storage.prune_certificates(chain_id, policy).await
+            }
+        }
+    }
+
+    /// Writes the state and the certificates of a chain to `writer`.
+    #[allow(unused_variables)]
+    pub async fn export_chain(
+        self,
+        chain_id: ChainId,
+        writer: impl io::Write + Send,
+    ) -> Result<(), Error> {
+        match self {
+            StoreConfig::Memory(_, _) => Err(Error::InvalidOperation(
+                "export_chain is not supported for the memory storage".to_string(),
+            )),
+            #[cfg(feature = "storage-service")]
+            StoreConfig::Service(config, namespace) => {
+                let storage =
+                    DbStorage::<ServiceStoreClient, _>::new(config, &namespace, ROOT_KEY, None)
+                        .await?;
+                Ok(storage.export_chain(chain_id, writer).await?)
+            }
+            #[cfg(feature = "rocksdb")]
+            StoreConfig::RocksDb(config, namespace) => {
+                let storage =
+                    DbStorage::<RocksDbStore, _>::new(config, &namespace, ROOT_KEY, None).await?;
+                Ok(storage.export_chain(chain_id, writer).await?)
+            }
+            #[cfg(feature = "dynamodb")]
+            StoreConfig::DynamoDb(config, namespace) => {
+                let storage =
+                    DbStorage::<DynamoDbStore, _>::new(config, &namespace, ROOT_KEY, None).await?;
+                Ok(storage.export_chain(chain_id, writer).await?)
+            }
+            #[cfg(feature = "scylladb")]
+            StoreConfig::ScyllaDb(config, namespace) => {
+                let storage =
+                    DbStorage::<ScyllaDbStore, _>::new(config, &namespace, ROOT_KEY, None).await?;
+                Ok(storage.export_chain(chain_id, writer).await?)
+            }
+        }
+    }
+
+    /// Reads a chain written by [`StoreConfig::export_chain`] from `reader`, replacing an
+    /// existing chain only if `force` is set, and returns its ID.
+    #[allow(unused_variables)]
+    pub async fn import_chain(
+        self,
+        reader: impl io::Read + Send,
+        force: bool,
+    ) -> Result<ChainId, Error> {
+        match self {
+            StoreConfig::Memory(_, _) => Err(Error::InvalidOperation(
+                "import_chain is not supported for the memory storage".to_string(),
+            )),
+            #[cfg(feature = "storage-service")]
+            StoreConfig::Service(config, namespace) => {
+                let storage =
+                    DbStorage::<ServiceStoreClient, _>::new(config, &namespace, ROOT_KEY, None)
+                        .await?;
+                Ok(storage.import_chain(reader, force).await?)
+            }
+            #[cfg(feature = "rocksdb")]
+            StoreConfig::RocksDb(config, namespace) => {
+                let storage =
+                    DbStorage::<RocksDbStore, _>::new(config, &namespace, ROOT_KEY, None).await?;
+                Ok(storage.import_chain(reader, force).await?)
+            }
+            #[cfg(feature = "dynamodb")]
+            StoreConfig::DynamoDb(config, namespace) => {
+                let storage =
+                    DbStorage::<DynamoDbStore, _>::new(config, &namespace, ROOT_KEY, None).await?;
+                Ok(storage.import_chain(reader, force).await?)
+            }
+            #[cfg(feature = "scylladb")]
+            StoreConfig::ScyllaDb(config, namespace) => {
+                let storage =
+                    DbStorage::<ScyllaDbStore, _>::new(config, &namespace, ROOT_KEY, None).await?;
+                Ok(storage.import_chain(reader, force).await?)
+            }
+        }
+    }
+
+    /// Re-executes the block of the given certificate against the current state of its
+    /// chain, without persisting any changes.
+    #[allow(unused_variables)]
+    pub async fn replay_block(
+        self,
+        certificate_hash: CryptoHash,
+        wasm_runtime: Option<WasmRuntime>,
+    ) -> Result<BlockReplayReport, Error> {
+        match self {
+            StoreConfig::Memory(_, _) => Err(Error::InvalidOperation(
+                "replay_block is not supported for the memory storage".to_string(),
+            )),
+            #[cfg(feature = "storage-service")]
+            StoreConfig::Service(config, namespace) => {
+                let storage = DbStorage::<ServiceStoreClient, _>::new(
+                    config,
+                    &namespace,
+                    ROOT_KEY,
+                    wasm_runtime,
+                )
+                .await?;
+                replay_block(storage, certificate_hash).await
+            }
+            #[cfg(feature = "rocksdb")]
+            StoreConfig::RocksDb(config, namespace) => {
+                let storage =
+                    DbStorage::<RocksDbStore, _>::new(config, &namespace, ROOT_KEY, wasm_runtime)
+                        .await?;
+                replay_block(storage, certificate_hash).await
+            }
+            #[cfg(feature = "dynamodb")]
+            StoreConfig::DynamoDb(config, namespace) => {
+                let storage =
+                    DbStorage::<DynamoDbStore, _>::new(config, &namespace, ROOT_KEY, wasm_runtime)
+                        .await?;
+                replay_block(storage, certificate_hash).await
+            }
+            #[cfg(feature = "scylladb")]
+            StoreConfig::ScyllaDb(config, namespace) => {
+                let storage =
+                    DbStorage::<ScyllaDbStore, _>::new(config, &namespace, ROOT_KEY, wasm_runtime)
+                        .await?;
+                replay_block(storage, certificate_hash).await
+            }
+        }
+    }
+}
+
+#[cfg(with_storage)]
+async fn replay_block<S>(
+    storage: S,
+    certificate_hash: CryptoHash,
+) -> Result<BlockReplayReport, Error>
+where
+    S: Storage + Clone + Send + Sync + 'static,
+{
+    let certificate = storage.read_certificate(certificate_hash).await?;
+    let worker = linera_core::worker::WorkerState::new(
+        "Replay".to_string(),
+        None,
+        storage,
+        std::num::NonZeroUsize::new(1).expect("1 is not zero"),
+    );
+    Ok(worker.replay_block(certificate).await?)
 }
 
 #[async_trait]
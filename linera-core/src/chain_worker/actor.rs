@@ -31,7 +31,7 @@ use tracing::{instrument, trace, warn};
 
 use super::{config::ChainWorkerConfig, state::ChainWorkerState, DeliveryNotifier};
 use crate::{
-    data_types::{ChainInfoQuery, ChainInfoResponse},
+    data_types::{BlockReplayReport, ChainInfoQuery, ChainInfoResponse},
     value_cache::ValueCache,
     worker::{NetworkActions, WorkerError},
 };
@@ -90,6 +90,13 @@ where
         callback: oneshot::Sender<Result<(ExecutedBlock, ChainInfoResponse), WorkerError>>,
     },
 
+    /// Re-execute a confirmed block but discard any changes to the chain state.
+    ReplayBlock {
+        certificate: ConfirmedBlockCertificate,
+        #[debug(skip)]
+        callback: oneshot::Sender<Result<BlockReplayReport, WorkerError>>,
+    },
+
     /// Process a leader timeout issued for this multi-owner chain.
     ProcessTimeout {
         certificate: TimeoutCertificate,
@@ -294,6 +301,12 @@ where
                 } => callback
                     .send(self.worker.stage_block_execution(block, round).await)
                     .is_ok(),
+                ChainWorkerRequest::ReplayBlock {
+                    certificate,
+                    callback,
+                } => callback
+                    .send(self.worker.replay_block(certificate).await)
+                    .is_ok(),
                 ChainWorkerRequest::ProcessTimeout {
                     certificate,
                     callback,
@@ -22,6 +22,9 @@ pub struct ChainWorkerConfig {
     /// Blocks with a timestamp this far in the future will still be accepted, but the validator
     /// will wait until that timestamp before voting.
     pub grace_period: Duration,
+    /// The maximum number of bundles waiting in the inbox of each origin. Further bundles
+    /// are parked until the inbox is drained.
+    pub max_pending_bundles_per_origin: Option<usize>,
 }
 
 impl ChainWorkerConfig {
@@ -12,6 +12,7 @@ use linera_base::{
     identifiers::{ChainId, MessageId},
 };
 use linera_chain::{
+    block::BlockReceipts,
     data_types::{
         BlockExecutionOutcome, BlockProposal, ExecutedBlock, MessageBundle, Origin,
         ProposalContent, Target,
@@ -142,7 +143,7 @@ where
         let (epoch, committee) = chain.current_committee()?;
         check_block_epoch(epoch, block.chain_id, block.epoch)?;
         let policy = committee.policy().clone();
-        block.check_proposal_size(policy.maximum_block_proposal_size)?;
+        block.check_resource_limits(&policy)?;
         // Check the authentication of the block.
         ensure!(
             chain.manager.verify_owner(proposal),
@@ -406,6 +407,19 @@ where
                 computed: Box::new(verified_outcome),
             }
         );
+        // The block may have made room in the inboxes for parked bundles.
+        self.state
+            .chain
+            .admit_parked_bundles(
+                executed_block
+                    .block
+                    .incoming_bundles
+                    .iter()
+                    .map(|incoming_bundle| &incoming_bundle.origin),
+                self.state.config.max_pending_bundles_per_origin,
+                local_time,
+            )
+            .await?;
         // Advance to next block height.
         let tip = self.state.chain.tip_state.get_mut();
         tip.block_hash = Some(certificate.hash());
@@ -414,6 +428,10 @@ where
         tip.num_operations += executed_block.block.operations.len() as u32;
         tip.num_outgoing_messages += executed_block.outcome.messages.len() as u32;
         self.state.chain.confirmed_log.push(certificate.hash());
+        self.state
+            .chain
+            .receipts
+            .push(BlockReceipts::new(certificate.hash(), certificate.block()));
         let info = ChainInfoResponse::new(&self.state.chain, self.state.config.key_pair());
         self.state.track_newly_created_chains(&executed_block);
         let mut actions = self.state.create_network_actions().await?;
@@ -508,7 +526,13 @@ where
             if self
                 .state
                 .chain
-                .receive_message_bundle(&origin, bundle, local_time, add_to_received_log)
+                .receive_message_bundle(
+                    &origin,
+                    bundle,
+                    local_time,
+                    add_to_received_log,
+                    self.state.config.max_pending_bundles_per_origin,
+                )
                 .await?
             {
                 new_outbox_entries = true;
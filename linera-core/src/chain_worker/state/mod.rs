@@ -42,7 +42,7 @@ use self::{
 };
 use super::{ChainWorkerConfig, DeliveryNotifier};
 use crate::{
-    data_types::{ChainInfoQuery, ChainInfoResponse, CrossChainRequest},
+    data_types::{BlockReplayReport, ChainInfoQuery, ChainInfoResponse, CrossChainRequest},
     value_cache::ValueCache,
     worker::{NetworkActions, WorkerError},
 };
@@ -188,6 +188,18 @@ where
             .await
     }
 
+    /// Re-executes a confirmed block against the current state of the chain, without
+    /// persisting any changes.
+    pub(super) async fn replay_block(
+        &mut self,
+        certificate: ConfirmedBlockCertificate,
+    ) -> Result<BlockReplayReport, WorkerError> {
+        ChainWorkerStateWithTemporaryChanges::new(self)
+            .await
+            .replay_block(certificate)
+            .await
+    }
+
     /// Processes a leader timeout issued for this multi-owner chain.
     pub(super) async fn process_timeout(
         &mut self,
@@ -6,27 +6,29 @@
 use linera_base::{
     data_types::{ArithmeticError, Timestamp, UserApplicationDescription},
     ensure,
-    identifiers::{AccountOwner, GenericApplicationId, UserApplicationId},
+    identifiers::{AccountOwner, GenericApplicationId, MessageId, UserApplicationId},
 };
-use linera_chain::data_types::{
-    BlockExecutionOutcome, ChannelFullName, ExecutedBlock, IncomingBundle, Medium, MessageAction,
-    ProposalContent, ProposedBlock,
+use linera_chain::{
+    data_types::{
+        BlockExecutionOutcome, ChannelFullName, ExecutedBlock, IncomingBundle, Medium,
+        MessageAction, ProposalContent, ProposedBlock,
+    },
+    types::ConfirmedBlockCertificate,
 };
-use linera_execution::{ChannelSubscription, Query, QueryOutcome};
+use linera_execution::{ChannelSubscription, ExecutionRuntimeConfig, Query, QueryOutcome};
 use linera_storage::{Clock as _, Storage};
 use linera_views::views::View;
 #[cfg(with_testing)]
 use {
     linera_base::{crypto::CryptoHash, data_types::BlockHeight},
-    linera_chain::{
-        data_types::{MessageBundle, Origin},
-        types::ConfirmedBlockCertificate,
-    },
+    linera_chain::data_types::{MessageBundle, Origin},
 };
 
 use super::ChainWorkerState;
 use crate::{
-    data_types::{ChainInfo, ChainInfoQuery, ChainInfoResponse},
+    data_types::{
+        BlockReplayReport, ChainInfo, ChainInfoQuery, ChainInfoResponse, MAX_LOG_PAGE_LEN,
+    },
     worker::WorkerError,
 };
 
@@ -143,6 +145,71 @@ where
         Ok((executed_block, response))
     }
 
+    /// Re-executes a confirmed block against the current state of the chain, which must be
+    /// the state before the block, without persisting any changes.
+    pub(super) async fn replay_block(
+        &mut self,
+        certificate: ConfirmedBlockCertificate,
+    ) -> Result<BlockReplayReport, WorkerError> {
+        let certificate_hash = certificate.hash();
+        let executed_block: ExecutedBlock = certificate.block().clone().into();
+        let block = &executed_block.block;
+        let next_block_height = self.0.chain.tip_state.get().next_block_height;
+        ensure!(
+            block.height == next_block_height,
+            WorkerError::CannotReplayBlock {
+                height: block.height,
+                next_block_height,
+            }
+        );
+        let local_time = self.0.storage.clock().current_time();
+        // The replay uses its own view of the chain, loaded with state hash tracing enabled.
+        // It is never saved.
+        let mut chain = self
+            .0
+            .storage
+            .with_execution_runtime_config(ExecutionRuntimeConfig {
+                trace_state_hashes: true,
+            })
+            .load_chain(block.chain_id)
+            .await?;
+        if !chain.is_active() {
+            if let Some((incoming_bundle, posted_message, config)) =
+                block.starts_with_open_chain_message()
+            {
+                let message_id = MessageId {
+                    chain_id: incoming_bundle.origin.sender,
+                    height: incoming_bundle.bundle.height,
+                    index: posted_message.index,
+                };
+                chain
+                    .execute_init_message(
+                        message_id,
+                        config,
+                        incoming_bundle.bundle.timestamp,
+                        local_time,
+                    )
+                    .await?;
+            }
+        }
+        let (outcome, state_hash_trace) = Box::pin(chain.execute_block_with_state_hash_trace(
+            block,
+            local_time,
+            None,
+            Some(executed_block.outcome.oracle_responses.clone()),
+        ))
+        .await?;
+        Ok(BlockReplayReport {
+            chain_id: block.chain_id,
+            height: block.height,
+            certificate_hash,
+            incoming_bundle_count: block.incoming_bundles.len() as u32,
+            expected_state_hash: executed_block.outcome.state_hash,
+            state_hash: outcome.state_hash,
+            state_hash_trace,
+        })
+    }
+
     /// Validates a block proposed to extend this chain.
     pub(super) async fn validate_proposal_content(
         &mut self,
@@ -256,10 +323,26 @@ where
             let keys = chain.confirmed_log.read(start..end).await?;
             info.requested_sent_certificate_hashes = keys;
         }
+        if let Some(range) = query.request_receipts_in_range {
+            let start: usize = range.start.try_into()?;
+            let limit = range
+                .limit
+                .map_or(MAX_LOG_PAGE_LEN, |limit| limit.min(MAX_LOG_PAGE_LEN));
+            let end = start.saturating_add(limit as usize);
+            info.requested_receipts = chain.receipts.read(start..end).await?;
+        }
         if let Some(start) = query.request_received_log_excluding_first_n {
             let start = usize::try_from(start).map_err(|_| ArithmeticError::Overflow)?;
             info.requested_received_log = chain.received_log.read(start..).await?;
         }
+        if let Some(page) = query.request_received_log_page {
+            info.requested_received_log_page = chain
+                .read_received_log_backward(page.before, page.limit.min(MAX_LOG_PAGE_LEN))
+                .await?;
+        }
+        if query.request_parked_bundle_counts {
+            info.requested_parked_bundle_counts = chain.parked_bundle_counts().await?;
+        }
         if query.request_manager_values {
             info.manager.add_values(&chain.manager);
         }
@@ -802,7 +802,7 @@ where
     }
 
     /// Obtains up to `self.options.max_pending_message_bundles` pending message bundles for the
-    /// local chain.
+    /// local chain, with at most `maximum_incoming_messages_per_block` messages in total.
     #[instrument(level = "trace")]
     async fn pending_message_bundles(&self) -> Result<Vec<IncomingBundle>, ChainClientError> {
         let query = ChainInfoQuery::new(self.chain_id).with_pending_message_bundles();
@@ -850,6 +850,14 @@ where
             }
         }
 
+        // A child chain only learns about its committees from the `OpenChain` message.
+        let (epoch, committees) = self.epoch_and_committees(self.chain_id).await?;
+        let maximum_incoming_messages = epoch
+            .and_then(|epoch| committees.get(&epoch))
+            .map_or(u64::MAX, |committee| {
+                committee.policy().maximum_incoming_messages_per_block
+            });
+        let mut incoming_messages = 0u64;
         Ok(pending_message_bundles
             .into_iter()
             .filter_map(|mut bundle| {
@@ -859,6 +867,10 @@ where
                     .then_some(bundle)
             })
             .take(self.options.max_pending_message_bundles)
+            .take_while(|bundle| {
+                incoming_messages += bundle.bundle.messages.len() as u64;
+                incoming_messages <= maximum_incoming_messages
+            })
             .collect())
     }
 
@@ -2150,8 +2162,7 @@ where
             .await?;
         let block = &executed_block.block;
         let committee = self.local_committee().await?;
-        let max_size = committee.policy().maximum_block_proposal_size;
-        block.check_proposal_size(max_size)?;
+        block.check_resource_limits(committee.policy())?;
         for blob in &blobs {
             if published_blob_ids.contains(&blob.id()) {
                 committee
@@ -2797,6 +2808,7 @@ where
                 epoch,
                 balance,
                 application_permissions: application_permissions.clone(),
+                bytecode_publishers: None,
             };
             let operation = Operation::System(SystemOperation::OpenChain(config));
             let certificate = match self.execute_block(vec![operation], vec![]).await? {
@@ -11,13 +11,14 @@ use linera_base::{
     identifiers::{AccountOwner, ChainDescription, ChainId},
 };
 use linera_chain::{
-    data_types::{ChainAndHeight, IncomingBundle, Medium, MessageBundle},
+    block::BlockReceipts,
+    data_types::{ChainAndHeight, IncomingBundle, Medium, MessageBundle, Origin},
     manager::ChainManagerInfo,
     ChainStateView,
 };
 use linera_execution::{
     committee::{Committee, Epoch, ValidatorName},
-    ExecutionRuntimeContext,
+    first_state_hash_mismatch, ExecutionRuntimeContext,
 };
 use linera_storage::ChainRuntimeContext;
 use linera_views::context::Context;
@@ -58,6 +59,21 @@ impl BlockHeightRange {
     }
 }
 
+/// The maximum number of entries returned for a [`LogPage`].
+pub const MAX_LOG_PAGE_LEN: u64 = 1000;
+
+/// A page of a log, read backward from the newest entries, as used in ChainInfoQuery.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(with_testing, derive(test_strategy::Arbitrary, Eq, PartialEq))]
+pub struct LogPage {
+    /// Only the entries at lower indices are returned. If `None`, the page starts with the
+    /// newest entry.
+    #[debug(skip_if = Option::is_none)]
+    pub before: Option<u64>,
+    /// The maximum number of entries. At most [`MAX_LOG_PAGE_LEN`] entries are returned.
+    pub limit: u64,
+}
+
 /// Request information about a chain.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[cfg_attr(with_testing, derive(test_strategy::Arbitrary, Eq, PartialEq))]
@@ -79,9 +95,17 @@ pub struct ChainInfoQuery {
     /// Query a range of certificate hashes sent from the chain.
     #[debug(skip_if = Option::is_none)]
     pub request_sent_certificate_hashes_in_range: Option<BlockHeightRange>,
+    /// Query the transaction receipts of a range of blocks of the chain, at most
+    /// [`MAX_LOG_PAGE_LEN`] at a time.
+    #[debug(skip_if = Option::is_none)]
+    pub request_receipts_in_range: Option<BlockHeightRange>,
     /// Query new certificate sender chain IDs and block heights received from the chain.
     #[debug(skip_if = Option::is_none)]
     pub request_received_log_excluding_first_n: Option<u64>,
+    /// Query a page of the sender chain IDs and block heights received from the chain,
+    /// newest first.
+    #[debug(skip_if = Option::is_none)]
+    pub request_received_log_page: Option<LogPage>,
     /// Query values from the chain manager, not just votes.
     #[debug(skip_if = Not::not)]
     pub request_manager_values: bool,
@@ -91,6 +115,9 @@ pub struct ChainInfoQuery {
     /// Include a vote to switch to fallback mode, if appropriate.
     #[debug(skip_if = Not::not)]
     pub request_fallback: bool,
+    /// Query the number of received bundles that are parked until the inbox is drained.
+    #[debug(skip_if = Not::not)]
+    pub request_parked_bundle_counts: bool,
 }
 
 impl ChainInfoQuery {
@@ -102,10 +129,13 @@ impl ChainInfoQuery {
             request_owner_balance: None,
             request_pending_message_bundles: false,
             request_sent_certificate_hashes_in_range: None,
+            request_receipts_in_range: None,
             request_received_log_excluding_first_n: None,
+            request_received_log_page: None,
             request_manager_values: false,
             request_leader_timeout: false,
             request_fallback: false,
+            request_parked_bundle_counts: false,
         }
     }
 
@@ -134,11 +164,21 @@ impl ChainInfoQuery {
         self
     }
 
+    pub fn with_receipts_in_range(mut self, range: BlockHeightRange) -> Self {
+        self.request_receipts_in_range = Some(range);
+        self
+    }
+
     pub fn with_received_log_excluding_first_n(mut self, n: u64) -> Self {
         self.request_received_log_excluding_first_n = Some(n);
         self
     }
 
+    pub fn with_received_log_page(mut self, page: LogPage) -> Self {
+        self.request_received_log_page = Some(page);
+        self
+    }
+
     pub fn with_manager_values(mut self) -> Self {
         self.request_manager_values = true;
         self
@@ -153,6 +193,11 @@ impl ChainInfoQuery {
         self.request_fallback = true;
         self
     }
+
+    pub fn with_parked_bundle_counts(mut self) -> Self {
+        self.request_parked_bundle_counts = true;
+        self
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -192,11 +237,21 @@ pub struct ChainInfo {
     /// The response to `request_sent_certificate_hashes_in_range`
     #[debug(skip_if = Vec::is_empty)]
     pub requested_sent_certificate_hashes: Vec<CryptoHash>,
+    /// The response to `request_receipts_in_range`. This ends early if the range extends
+    /// beyond the latest block.
+    #[debug(skip_if = Vec::is_empty)]
+    pub requested_receipts: Vec<BlockReceipts>,
     /// The current number of received certificates (useful for `request_received_log_excluding_first_n`)
     pub count_received_log: usize,
     /// The response to `request_received_certificates_excluding_first_n`
     #[debug(skip_if = Vec::is_empty)]
     pub requested_received_log: Vec<ChainAndHeight>,
+    /// The response to `request_received_log_page`, newest first.
+    #[debug(skip_if = Vec::is_empty)]
+    pub requested_received_log_page: Vec<ChainAndHeight>,
+    /// The number of parked bundles for each origin that has any (if requested).
+    #[debug(skip_if = Vec::is_empty)]
+    pub requested_parked_bundle_counts: Vec<(Origin, u64)>,
 }
 
 impl ChainInfo {
@@ -285,12 +340,60 @@ where
             requested_owner_balance: None,
             requested_pending_message_bundles: Vec::new(),
             requested_sent_certificate_hashes: Vec::new(),
+            requested_receipts: Vec::new(),
             count_received_log: view.received_log.count(),
             requested_received_log: Vec::new(),
+            requested_received_log_page: Vec::new(),
+            requested_parked_bundle_counts: Vec::new(),
         }
     }
 }
 
+/// The result of re-executing a confirmed block, to compare between validators that
+/// disagree on its outcome.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlockReplayReport {
+    /// The chain the block belongs to.
+    pub chain_id: ChainId,
+    /// The height of the block.
+    pub height: BlockHeight,
+    /// The hash of the certificate of the block.
+    pub certificate_hash: CryptoHash,
+    /// The number of incoming bundles in the block. The transactions with a higher index
+    /// are operations.
+    pub incoming_bundle_count: u32,
+    /// The state hash in the certificate.
+    pub expected_state_hash: CryptoHash,
+    /// The state hash computed by the re-execution.
+    pub state_hash: CryptoHash,
+    /// For each transaction, the hash of the execution state after each of its executed
+    /// messages, or after its operation.
+    pub state_hash_trace: Vec<Vec<CryptoHash>>,
+}
+
+impl BlockReplayReport {
+    /// Returns the index of the first transaction, and the index of the step within it, after
+    /// which the execution state differs between the two reports, or `None` if the traces
+    /// agree.
+    pub fn first_divergence(&self, other: &Self) -> Option<(usize, usize)> {
+        let transaction_count = self
+            .state_hash_trace
+            .len()
+            .max(other.state_hash_trace.len());
+        (0..transaction_count).find_map(|index| {
+            let ours = self
+                .state_hash_trace
+                .get(index)
+                .map_or(&[][..], Vec::as_slice);
+            let theirs = other
+                .state_hash_trace
+                .get(index)
+                .map_or(&[][..], Vec::as_slice);
+            first_state_hash_mismatch(ours, theirs).map(|step| (index, step))
+        })
+    }
+}
+
 impl ChainInfoResponse {
     pub fn new(info: impl Into<ChainInfo>, key_pair: Option<&KeyPair>) -> Self {
         let info = Box::new(info.into());
@@ -12,6 +12,7 @@ use linera_base::{
     identifiers::{BlobId, ChainId},
 };
 use linera_chain::{
+    block::BlockReceipts,
     data_types::BlockProposal,
     types::{
         CertificateValue, ConfirmedBlockCertificate, GenericCertificate, LiteCertificate,
@@ -308,6 +309,40 @@ impl<N: ValidatorNode> RemoteNode<N> {
         self.node.download_certificates(hashes).await
     }
 
+    /// Returns the transaction receipts of the blocks on the given chain in the given range of
+    /// heights. Fewer receipts are returned if the range extends beyond the latest block, or
+    /// if it is longer than [`MAX_LOG_PAGE_LEN`][crate::data_types::MAX_LOG_PAGE_LEN].
+    #[instrument(level = "trace")]
+    pub async fn download_receipts(
+        &self,
+        chain_id: ChainId,
+        range: BlockHeightRange,
+    ) -> Result<Vec<BlockReceipts>, NodeError> {
+        let query = ChainInfoQuery::new(chain_id).with_receipts_in_range(range.clone());
+        let response = self.handle_chain_info_query(query).await?;
+        let receipts = response.requested_receipts;
+        let mut expected_height = range.start;
+        for block_receipts in &receipts {
+            ensure!(
+                block_receipts.chain_id == chain_id && block_receipts.height == expected_height,
+                NodeError::InvalidChainInfoResponse
+            );
+            expected_height = expected_height.try_add_one()?;
+        }
+        if range
+            .limit
+            .is_some_and(|limit| receipts.len() as u64 > limit)
+        {
+            warn!(
+                ?range,
+                received_num = receipts.len(),
+                "Validator sent too many receipts."
+            );
+            return Err(NodeError::InvalidChainInfoResponse);
+        }
+        Ok(receipts)
+    }
+
     #[instrument(level = "trace", skip(validators))]
     async fn download_blob(
         validators: &[Self],
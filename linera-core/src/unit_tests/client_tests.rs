@@ -10,7 +10,7 @@ use futures::StreamExt;
 use linera_base::{
     crypto::*,
     data_types::*,
-    identifiers::{Account, AccountOwner, ChainId, MessageId, Owner},
+    identifiers::{Account, AccountOwner, ChainId, GenericApplicationId, MessageId, Owner},
     ownership::{ChainOwnership, TimeoutConfig},
 };
 use linera_chain::{
@@ -43,12 +43,14 @@ use crate::{
         BlanketMessagePolicy, ChainClient, ChainClientError, ClientOutcome, MessageAction,
         MessagePolicy,
     },
+    data_types::BlockHeightRange,
     local_node::LocalNodeError,
     node::{
         CrossChainMessageDelivery,
         NodeError::{self, ClientIoError},
         ValidatorNode,
     },
+    remote_node::RemoteNode,
     test_utils::{FaultType, MemoryStorageBuilder, NodeProvider, StorageBuilder, TestBuilder},
     updater::CommunicationError,
     worker::{Notification, Reason, WorkerError},
@@ -2323,3 +2325,141 @@ where
 
     Ok(())
 }
+
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new().await; "storage_service"))]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "dynamodb", test_case(DynamoDbStorageBuilder::default(); "dynamo_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_process_inbox_with_incoming_message_limit<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let policy = ResourceControlPolicy {
+        maximum_incoming_messages_per_block: 2,
+        ..ResourceControlPolicy::default()
+    };
+    let mut builder = TestBuilder::new(storage_builder, 4, 1)
+        .await?
+        .with_policy(policy);
+    let sender = builder.add_root_chain(1, Amount::from_tokens(3)).await?;
+    let receiver = builder.add_root_chain(2, Amount::ZERO).await?;
+    let receiver_id = receiver.chain_id();
+
+    for _ in 0..3 {
+        sender
+            .transfer(None, Amount::ONE, Recipient::chain(receiver_id))
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    receiver.synchronize_from_validators().await?;
+
+    // The three messages do not fit in a single block.
+    let (certificates, _) = receiver.process_inbox().await?;
+    let message_counts = certificates
+        .iter()
+        .map(|certificate| {
+            certificate
+                .block()
+                .body
+                .incoming_bundles
+                .iter()
+                .map(|bundle| bundle.bundle.messages.len())
+                .sum::<usize>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(message_counts, vec![2, 1]);
+    assert_eq!(receiver.local_balance().await?, Amount::from_tokens(3));
+
+    Ok(())
+}
+
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new().await; "storage_service"))]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "dynamodb", test_case(DynamoDbStorageBuilder::default(); "dynamo_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_download_receipts<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let mut builder = TestBuilder::new(storage_builder, 1, 0).await?;
+    let sender = builder.add_root_chain(1, Amount::from_tokens(4)).await?;
+    let receiver = builder.add_root_chain(2, Amount::ZERO).await?;
+    let receiver_id = receiver.chain_id();
+
+    // A block with two operations, each sending a message to the receiver.
+    let transfer = Operation::System(SystemOperation::Transfer {
+        owner: None,
+        recipient: Recipient::chain(receiver_id),
+        amount: Amount::ONE,
+    });
+    let certificate = sender
+        .execute_operations(vec![transfer.clone(), transfer], vec![])
+        .await?
+        .unwrap();
+    receiver.synchronize_from_validators().await?;
+    let (certificates, _) = receiver.process_inbox().await?;
+    assert_eq!(certificates.len(), 1);
+
+    let validator = builder.node(0);
+    let remote_node = RemoteNode {
+        name: validator.name(),
+        node: validator.clone(),
+    };
+    let range = BlockHeightRange {
+        start: BlockHeight::ZERO,
+        limit: Some(1),
+    };
+
+    let receipts = remote_node
+        .download_receipts(sender.chain_id(), range.clone())
+        .await?;
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].hash, certificate.hash());
+    assert_eq!(receipts[0].height, BlockHeight::ZERO);
+    let transactions = &receipts[0].transactions;
+    assert_eq!(transactions.len(), 2);
+    for (index, receipt) in (0..).zip(transactions) {
+        assert_eq!(receipt.transaction_index, index);
+        assert_eq!(receipt.operation_index, Some(index));
+        assert_eq!(receipt.application_id, Some(GenericApplicationId::System));
+        assert!(receipt.success);
+        assert_eq!(receipt.message_count, 1);
+        assert!(receipt.events.is_empty());
+    }
+
+    // The receiver's block only receives the messages: one bundle per sending transaction.
+    let receipts = remote_node.download_receipts(receiver_id, range).await?;
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].hash, certificates[0].hash());
+    let transactions = &receipts[0].transactions;
+    assert_eq!(transactions.len(), 2);
+    for receipt in transactions {
+        assert_eq!(receipt.operation_index, None);
+        assert_eq!(receipt.application_id, None);
+        assert!(receipt.success);
+        assert_eq!(receipt.message_count, 0);
+    }
+
+    // A range beyond the latest block returns the receipts that exist.
+    let range = BlockHeightRange {
+        start: BlockHeight::ZERO,
+        limit: Some(2),
+    };
+    let receipts = remote_node.download_receipts(receiver_id, range).await?;
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].hash, certificates[0].hash());
+    let range = BlockHeightRange {
+        start: BlockHeight::from(1),
+        limit: None,
+    };
+    assert!(remote_node
+        .download_receipts(receiver_id, range)
+        .await?
+        .is_empty());
+    Ok(())
+}
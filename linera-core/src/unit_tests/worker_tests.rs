@@ -46,11 +46,13 @@ use linera_execution::{
         AdminOperation, OpenChainConfig, Recipient, SystemChannel, SystemMessage, SystemOperation,
     },
     test_utils::{ExpectedCall, RegisterMockApplication, SystemExecutionState},
-    ChannelSubscription, ExecutionError, Message, MessageKind, Query, QueryContext, QueryOutcome,
-    QueryResponse, SystemExecutionError, SystemQuery, SystemResponse,
+    ChannelSubscription, ContractRuntime as _, ExecutionError, Message, MessageKind, Operation,
+    Query, QueryContext, QueryOutcome, QueryResponse, SystemExecutionError, SystemQuery,
+    SystemResponse,
 };
-use linera_storage::{DbStorage, Storage, TestClock};
+use linera_storage::{ChainExportError, DbStorage, PruningPolicy, Storage, TestClock};
 use linera_views::{
+    batch::Batch,
     memory::MemoryStore,
     random::generate_test_namespace,
     store::TestKeyValueStore as _,
@@ -1350,6 +1352,7 @@ where
                 committees,
                 balance,
                 application_permissions: Default::default(),
+                bytecode_publishers: None,
             }))
             .to_posted(0, MessageKind::Protected)],
         },
@@ -2036,6 +2039,22 @@ where
             height: BlockHeight::ZERO
         }
     );
+    let query = ChainInfoQuery::new(ChainId::root(2)).with_received_log_page(LogPage {
+        before: None,
+        limit: 10,
+    });
+    let (response, _actions) = worker.handle_chain_info_query(query).await?;
+    assert_eq!(
+        response.info.requested_received_log_page,
+        response.info.requested_received_log
+    );
+    // Validators clamp the size of the page.
+    let query = ChainInfoQuery::new(ChainId::root(2)).with_received_log_page(LogPage {
+        before: None,
+        limit: u64::MAX,
+    });
+    let (response, _actions) = worker.handle_chain_info_query(query).await?;
+    assert_eq!(response.info.requested_received_log_page.len(), 1);
     Ok(())
 }
 
@@ -2381,6 +2400,7 @@ where
                             admin_id,
                             balance: Amount::ZERO,
                             application_permissions: Default::default(),
+                            bytecode_publishers: None,
                         }),
                     ),
                     direct_outgoing_message(
@@ -2412,6 +2432,7 @@ where
                         admin_id,
                         balance: Amount::ZERO,
                         application_permissions: Default::default(),
+                        bytecode_publishers: None,
                     }))
                     .with_authenticated_signer(Some(key_pair.public().into())),
             ),
@@ -2595,6 +2616,7 @@ where
                                     admin_id,
                                     balance: Amount::ZERO,
                                     application_permissions: Default::default(),
+                                    bytecode_publishers: None,
                                 },
                             ))
                             .to_posted(0, MessageKind::Protected)],
@@ -4013,3 +4035,436 @@ where
 
     Ok(())
 }
+
+/// Tests that replaying a block reports the state hash after each operation, so that two
+/// replays by validators that disagree can be compared.
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "dynamodb", test_case(DynamoDbStorageBuilder::default(); "dynamo_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_replay_block<B>(mut storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let storage = storage_builder.build().await?;
+    let chain_description = ChainDescription::Root(1);
+    let chain_id = ChainId::from(chain_description);
+    let key_pair = KeyPair::generate();
+    let (committee, worker) = init_worker_with_chain(
+        storage.clone(),
+        chain_description,
+        key_pair.public().into(),
+        Amount::ZERO,
+    )
+    .await;
+
+    let (application_id, application);
+    {
+        let mut chain = storage.load_chain(chain_id).await?;
+        (application_id, application) = chain.execution_state.register_mock_application().await?;
+        chain.save().await?;
+    }
+
+    // The application writes a value of the test's choosing in its second operation, to
+    // simulate a non-deterministic execution.
+    let expect_block_execution = |value: u8| {
+        for written_value in [0, value] {
+            application.expect_call(ExpectedCall::execute_operation(
+                move |runtime, _context, _operation| {
+                    let mut batch = Batch::new();
+                    batch.put_key_value_bytes(vec![0], vec![written_value]);
+                    runtime.write_batch(batch)?;
+                    Ok(vec![])
+                },
+            ));
+            application.expect_call(ExpectedCall::default_finalize());
+        }
+    };
+    let operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+    let block = make_first_block(chain_id)
+        .with_operation(operation.clone())
+        .with_operation(operation);
+
+    expect_block_execution(1);
+    let (executed_block, _) = worker.stage_block_execution(block, None).await?;
+    let value = Hashed::new(ConfirmedBlock::new(executed_block));
+    let certificate = make_certificate(&committee, &worker, value);
+
+    expect_block_execution(1);
+    let report = worker.replay_block(certificate.clone()).await?;
+    assert_eq!(report.certificate_hash, certificate.hash());
+    assert_eq!(report.incoming_bundle_count, 0);
+    assert_eq!(report.state_hash_trace.len(), 2);
+    assert!(report
+        .state_hash_trace
+        .iter()
+        .all(|state_hashes| state_hashes.len() == 1));
+    assert_eq!(report.state_hash, report.expected_state_hash);
+
+    expect_block_execution(2);
+    let divergent_report = worker.replay_block(certificate.clone()).await?;
+    assert_ne!(
+        divergent_report.state_hash,
+        divergent_report.expected_state_hash
+    );
+    assert_eq!(report.first_divergence(&divergent_report), Some((1, 0)));
+    assert_eq!(report.first_divergence(&report), None);
+
+    // Replaying does not change the chain.
+    let chain = worker.chain_state_view(chain_id).await?;
+    assert_eq!(chain.tip_state.get().next_block_height, BlockHeight::ZERO);
+    drop(chain);
+
+    // Once the block is executed, the state before it is no longer available.
+    expect_block_execution(1);
+    worker
+        .handle_confirmed_certificate(certificate.clone(), None)
+        .await?;
+    assert_matches!(
+        worker.replay_block(certificate).await,
+        Err(WorkerError::CannotReplayBlock { height, next_block_height })
+            if height == BlockHeight::ZERO && next_block_height == BlockHeight::from(1)
+    );
+    application.assert_no_more_expected_calls();
+    Ok(())
+}
+
+/// Tests that the inbox of a flooded chain stays bounded, and that the parked bundles are
+/// admitted as the inbox is drained, without losing any message.
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "dynamodb", test_case(DynamoDbStorageBuilder::default(); "dynamo_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_inbox_back_pressure<B>(mut storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    const MAX_PENDING_BUNDLES: usize = 2;
+    const NUM_TRANSFERS: u128 = 5;
+
+    let storage = storage_builder.build().await?;
+    let sender_key_pair = KeyPair::generate();
+    let recipient_key_pair = KeyPair::generate();
+    let sender_id = ChainId::root(1);
+    let recipient_id = ChainId::root(2);
+    let (committee, worker) = init_worker_with_chains(
+        storage,
+        vec![
+            (
+                ChainDescription::Root(1),
+                sender_key_pair.public().into(),
+                Amount::from_tokens(NUM_TRANSFERS),
+            ),
+            (
+                ChainDescription::Root(2),
+                recipient_key_pair.public().into(),
+                Amount::ZERO,
+            ),
+        ],
+    )
+    .await;
+    let worker = worker.with_max_pending_bundles_per_origin(Some(MAX_PENDING_BUNDLES));
+
+    // The sender floods the recipient with one transfer per block.
+    let mut certificates = Vec::<ConfirmedBlockCertificate>::new();
+    for balance in (0..NUM_TRANSFERS).rev() {
+        let certificate = make_simple_transfer_certificate(
+            ChainDescription::Root(1),
+            &sender_key_pair,
+            recipient_id,
+            Amount::ONE,
+            Vec::new(),
+            &committee,
+            Amount::from_tokens(balance),
+            &worker,
+            certificates.last(),
+        )
+        .await;
+        worker
+            .fully_handle_certificate_with_notifications(certificate.clone(), &())
+            .await?;
+        certificates.push(certificate);
+    }
+
+    let query = ChainInfoQuery::new(recipient_id)
+        .with_pending_message_bundles()
+        .with_parked_bundle_counts();
+    let (response, _) = worker.handle_chain_info_query(query.clone()).await?;
+    assert_eq!(
+        response.info.requested_pending_message_bundles.len(),
+        MAX_PENDING_BUNDLES
+    );
+    assert_eq!(
+        response.info.requested_parked_bundle_counts,
+        vec![(
+            Origin::chain(sender_id),
+            NUM_TRANSFERS as u64 - MAX_PENDING_BUNDLES as u64
+        )]
+    );
+
+    // The recipient drains its inbox, and the parked bundles are admitted.
+    let mut info = response.info;
+    let mut previous_block = None::<Hashed<ConfirmedBlock>>;
+    while !info.requested_pending_message_bundles.is_empty() {
+        assert!(info.requested_pending_message_bundles.len() <= MAX_PENDING_BUNDLES);
+        let mut block = match &previous_block {
+            None => make_first_block(recipient_id),
+            Some(previous_block) => make_child_block(previous_block),
+        };
+        for incoming_bundle in info.requested_pending_message_bundles {
+            block = block.with_incoming_bundle(incoming_bundle);
+        }
+        let (executed_block, _) = worker.stage_block_execution(block, None).await?;
+        let value = Hashed::new(ConfirmedBlock::new(executed_block));
+        let certificate = make_certificate(&committee, &worker, value.clone());
+        worker
+            .fully_handle_certificate_with_notifications(certificate, &())
+            .await?;
+        previous_block = Some(value);
+        info = worker.handle_chain_info_query(query.clone()).await?.0.info;
+    }
+    assert!(info.requested_parked_bundle_counts.is_empty());
+    assert_eq!(info.chain_balance, Amount::from_tokens(NUM_TRANSFERS));
+    Ok(())
+}
+
+/// Tests that pruning only deletes certificates that are no longer needed, and that the
+/// chain still accepts new blocks afterwards.
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "dynamodb", test_case(DynamoDbStorageBuilder::default(); "dynamo_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_prune_certificates<B>(mut storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let storage = storage_builder.build().await?;
+    let clock = storage_builder.clock();
+    let sender_key_pair = KeyPair::generate();
+    let sender_id = ChainId::root(1);
+    let recipient_id = ChainId::root(2);
+    let (committee, worker) = init_worker_with_chains(
+        storage.clone(),
+        vec![
+            (
+                ChainDescription::Root(1),
+                sender_key_pair.public().into(),
+                Amount::from_tokens(5),
+            ),
+            (
+                ChainDescription::Root(2),
+                KeyPair::generate().public().into(),
+                Amount::ZERO,
+            ),
+        ],
+    )
+    .await;
+
+    let mut certificates = Vec::<ConfirmedBlockCertificate>::new();
+    for balance in (1..5).rev() {
+        let certificate = make_simple_transfer_certificate(
+            ChainDescription::Root(1),
+            &sender_key_pair,
+            recipient_id,
+            Amount::ONE,
+            Vec::new(),
+            &committee,
+            Amount::from_tokens(balance),
+            &worker,
+            certificates.last(),
+        )
+        .await;
+        worker
+            .fully_handle_certificate_with_notifications(certificate.clone(), &())
+            .await?;
+        certificates.push(certificate);
+    }
+
+    // The recipient only receives the transfers of the first two blocks.
+    let query = ChainInfoQuery::new(recipient_id).with_pending_message_bundles();
+    let (response, _) = worker.handle_chain_info_query(query).await?;
+    let mut block = make_first_block(recipient_id);
+    for incoming_bundle in response
+        .info
+        .requested_pending_message_bundles
+        .into_iter()
+        .take(2)
+    {
+        block = block.with_incoming_bundle(incoming_bundle);
+    }
+    let (executed_block, _) = worker.stage_block_execution(block, None).await?;
+    let value = Hashed::new(ConfirmedBlock::new(executed_block));
+    let certificate = make_certificate(&committee, &worker, value);
+    worker
+        .fully_handle_certificate_with_notifications(certificate, &())
+        .await?;
+
+    // No block is old enough yet.
+    let max_age = PruningPolicy::MaxAge(TimeDelta::from_secs(3600));
+    assert!(storage
+        .prune_certificates(sender_id, max_age)
+        .await?
+        .is_empty());
+
+    clock.set(Timestamp::from(7_200_000_000));
+    let pruned = storage.prune_certificates(sender_id, max_age).await?;
+    assert_eq!(pruned, vec![certificates[0].hash(), certificates[1].hash()]);
+    for (certificate, is_retained) in certificates.iter().zip([false, false, true, true]) {
+        assert_eq!(
+            storage.contains_certificate(certificate.hash()).await?,
+            is_retained
+        );
+    }
+
+    // The third block has a message in an inbox, and the fourth is the latest one.
+    let pruned = storage
+        .prune_certificates(sender_id, PruningPolicy::KeepLatestBlocks(0))
+        .await?;
+    assert!(pruned.is_empty());
+
+    // The pruned chain still accepts new blocks.
+    let certificate = make_simple_transfer_certificate(
+        ChainDescription::Root(1),
+        &sender_key_pair,
+        recipient_id,
+        Amount::ONE,
+        Vec::new(),
+        &committee,
+        Amount::ZERO,
+        &worker,
+        certificates.last(),
+    )
+    .await;
+    worker
+        .fully_handle_certificate_with_notifications(certificate, &())
+        .await?;
+    let (response, _) = worker
+        .handle_chain_info_query(ChainInfoQuery::new(sender_id))
+        .await?;
+    assert_eq!(response.info.next_block_height, BlockHeight::from(5));
+    Ok(())
+}
+
+/// Tests that chains exported from one storage are imported into a memory storage with the
+/// same state, inboxes and certificates.
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "dynamodb", test_case(DynamoDbStorageBuilder::default(); "dynamo_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_export_and_import_chain<B>(mut storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let storage = storage_builder.build().await?;
+    let sender_key_pair = KeyPair::generate();
+    let sender_id = ChainId::root(1);
+    let recipient_id = ChainId::root(2);
+    let (committee, worker) = init_worker_with_chains(
+        storage.clone(),
+        vec![
+            (
+                ChainDescription::Root(1),
+                sender_key_pair.public().into(),
+                Amount::from_tokens(5),
+            ),
+            (
+                ChainDescription::Root(2),
+                KeyPair::generate().public().into(),
+                Amount::ZERO,
+            ),
+        ],
+    )
+    .await;
+
+    // The transfers are left in the inbox of the recipient.
+    let mut certificates = Vec::<ConfirmedBlockCertificate>::new();
+    for balance in (2..5).rev() {
+        let certificate = make_simple_transfer_certificate(
+            ChainDescription::Root(1),
+            &sender_key_pair,
+            recipient_id,
+            Amount::ONE,
+            Vec::new(),
+            &committee,
+            Amount::from_tokens(balance),
+            &worker,
+            certificates.last(),
+        )
+        .await;
+        worker
+            .fully_handle_certificate_with_notifications(certificate.clone(), &())
+            .await?;
+        certificates.push(certificate);
+    }
+
+    let target = MemoryStorageBuilder::default().build().await?;
+    for chain_id in [sender_id, recipient_id] {
+        let mut bytes = Vec::new();
+        storage.export_chain(chain_id, &mut bytes).await?;
+        assert_eq!(
+            target.import_chain(bytes.as_slice(), false).await?,
+            chain_id
+        );
+        let chain = storage.load_chain(chain_id).await?;
+        let imported_chain = target.load_chain(chain_id).await?;
+        assert_eq!(
+            imported_chain.execution_state.crypto_hash().await?,
+            chain.execution_state.crypto_hash().await?
+        );
+        assert_eq!(imported_chain.tip_state.get(), chain.tip_state.get());
+        assert_eq!(
+            imported_chain.confirmed_log.read(..).await?,
+            chain.confirmed_log.read(..).await?
+        );
+    }
+    for certificate in &certificates {
+        assert!(target.contains_certificate(certificate.hash()).await?);
+    }
+    let recipient = target.load_chain(recipient_id).await?;
+    let inbox = recipient
+        .inboxes
+        .try_load_entry(&Origin::chain(sender_id))
+        .await?
+        .expect("the inbox should have been imported");
+    assert_eq!(inbox.added_bundles.count(), certificates.len());
+
+    // An existing chain is only replaced if forced.
+    let mut bytes = Vec::new();
+    storage.export_chain(sender_id, &mut bytes).await?;
+    assert_matches!(
+        target.import_chain(bytes.as_slice(), false).await,
+        Err(ChainExportError::ChainAlreadyExists(chain_id)) if chain_id == sender_id
+    );
+    // A truncated stream leaves the existing chain unchanged.
+    let hash = target
+        .load_chain(sender_id)
+        .await?
+        .execution_state
+        .crypto_hash()
+        .await?;
+    assert!(target
+        .import_chain(&bytes[..bytes.len() - 1], true)
+        .await
+        .is_err());
+    assert_eq!(
+        target
+            .load_chain(sender_id)
+            .await?
+            .execution_state
+            .crypto_hash()
+            .await?,
+        hash
+    );
+    assert_eq!(
+        target.import_chain(bytes.as_slice(), true).await?,
+        sender_id
+    );
+    Ok(())
+}
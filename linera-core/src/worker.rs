@@ -55,7 +55,7 @@ use {
 
 use crate::{
     chain_worker::{ChainWorkerActor, ChainWorkerConfig, ChainWorkerRequest, DeliveryNotifier},
-    data_types::{ChainInfoQuery, ChainInfoResponse, CrossChainRequest},
+    data_types::{BlockReplayReport, ChainInfoQuery, ChainInfoResponse, CrossChainRequest},
     join_set_ext::{JoinSet, JoinSetExt},
     notifier::Notifier,
     value_cache::ValueCache,
@@ -205,6 +205,14 @@ pub enum WorkerError {
     },
     #[error("The timestamp of a Tick operation is in the future.")]
     InvalidTimestamp,
+    #[error(
+        "Cannot replay the block at height {height}: the state before it is not available, \
+        the next block height of the chain is {next_block_height}"
+    )]
+    CannotReplayBlock {
+        height: BlockHeight,
+        next_block_height: BlockHeight,
+    },
     #[error("We don't have the value for the certificate.")]
     MissingCertificateValue,
     #[error("The hash certificate doesn't match its value.")]
@@ -347,6 +355,14 @@ where
         self
     }
 
+    /// Configures the maximum number of bundles waiting in the inbox of each origin. Further
+    /// bundles are parked until the inbox is drained.
+    #[instrument(level = "trace", skip(self, value))]
+    pub fn with_max_pending_bundles_per_origin(mut self, value: Option<usize>) -> Self {
+        self.chain_worker_config.max_pending_bundles_per_origin = value;
+        self
+    }
+
     #[instrument(level = "trace", skip(self, tracked_chains))]
     /// Configures the subset of chains that this worker is tracking.
     pub fn with_tracked_chains(
@@ -512,6 +528,22 @@ where
         .await
     }
 
+    /// Re-executes a confirmed block against the current state of its chain, and returns the
+    /// hash of the execution state after each transaction. The chain must not have executed
+    /// the block yet, e.g. because this validator computed a different outcome.
+    #[instrument(level = "trace", skip(self, certificate))]
+    pub async fn replay_block(
+        &self,
+        certificate: ConfirmedBlockCertificate,
+    ) -> Result<BlockReplayReport, WorkerError> {
+        let chain_id = certificate.block().header.chain_id;
+        self.query_chain_worker(chain_id, move |callback| ChainWorkerRequest::ReplayBlock {
+            certificate,
+            callback,
+        })
+        .await
+    }
+
     /// Executes a [`Query`] for an application's state on a specific chain.
     #[instrument(level = "trace", skip(self, chain_id, query))]
     pub async fn query_application(
@@ -0,0 +1,110 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compares executing the operations of a block with `ExecutionStateView::execute_operations`,
+//! which reads each application description from the registry only once, to executing them
+//! one at a time.
+
+use std::time::{Duration, Instant};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use linera_base::{data_types::Timestamp, identifiers::ChainDescription};
+use linera_execution::{
+    test_utils::{
+        create_dummy_operation_context, ExpectedCall, RegisterMockApplication, SystemExecutionState,
+    },
+    ExecutionStateView, Operation, ResourceController, TestExecutionRuntimeContext,
+    TransactionTracker,
+};
+use linera_views::context::MemoryContext;
+use tokio::runtime::Runtime;
+
+/// The number of operations in a block.
+const N_OPERATIONS: usize = 100;
+
+/// Creates a view with an application that expects [`N_OPERATIONS`] operations, and returns
+/// it with these operations.
+async fn prepare_block() -> (
+    ExecutionStateView<MemoryContext<TestExecutionRuntimeContext>>,
+    Vec<Operation>,
+) {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+    let (application_id, application) = view.register_mock_application().await.unwrap();
+    for _ in 0..N_OPERATIONS {
+        application.expect_call(ExpectedCall::execute_operation(
+            |_runtime, _context, _operation| Ok(vec![]),
+        ));
+        application.expect_call(ExpectedCall::default_finalize());
+    }
+    let operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+    (view, vec![operation; N_OPERATIONS])
+}
+
+async fn execute_operations_together(iterations: u64) -> Duration {
+    let mut total_time = Duration::ZERO;
+    for _ in 0..iterations {
+        let (mut view, operations) = prepare_block().await;
+        let measurement = Instant::now();
+        let outcomes = view
+            .execute_operations(
+                create_dummy_operation_context(),
+                Timestamp::from(0),
+                operations,
+                &mut ResourceController::default(),
+            )
+            .await
+            .unwrap();
+        total_time += measurement.elapsed();
+        black_box(outcomes);
+    }
+    total_time
+}
+
+async fn execute_operations_one_by_one(iterations: u64) -> Duration {
+    let mut total_time = Duration::ZERO;
+    for _ in 0..iterations {
+        let (mut view, operations) = prepare_block().await;
+        let mut controller = ResourceController::default();
+        let measurement = Instant::now();
+        for operation in operations {
+            let mut txn_tracker = TransactionTracker::new(0, None);
+            view.execute_operation(
+                create_dummy_operation_context(),
+                Timestamp::from(0),
+                operation,
+                &mut txn_tracker,
+                &mut controller,
+            )
+            .await
+            .unwrap();
+            view.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
+                .await
+                .unwrap();
+            black_box(txn_tracker.destructure().unwrap());
+        }
+        total_time += measurement.elapsed();
+    }
+    total_time
+}
+
+fn bench_execute_operations(criterion: &mut Criterion) {
+    criterion.bench_function("execute_operations_together", |bencher| {
+        bencher
+            .to_async(Runtime::new().expect("Failed to create Tokio runtime"))
+            .iter_custom(execute_operations_together)
+    });
+
+    criterion.bench_function("execute_operations_one_by_one", |bencher| {
+        bencher
+            .to_async(Runtime::new().expect("Failed to create Tokio runtime"))
+            .iter_custom(execute_operations_one_by_one)
+    });
+}
+
+criterion_group!(benches, bench_execute_operations);
+criterion_main!(benches);
@@ -58,7 +58,16 @@ where
             self.describe_application(*required_id).await?;
         }
         let id = UserApplicationId::from(&application);
+        let previous = self.known_applications.get(&id).await?;
         self.known_applications.insert(&id, application)?;
+        // Make sure that the new description does not introduce a dependency cycle.
+        if let Err(error) = self.find_dependencies(vec![id]).await {
+            match previous {
+                Some(description) => self.known_applications.insert(&id, description)?,
+                None => self.known_applications.remove(&id)?,
+            }
+            return Err(error);
+        }
         Ok(id)
     }
 
@@ -68,7 +77,7 @@ where
         application_id: UserApplicationId,
         parameters: Vec<u8>,
         required_application_ids: Vec<UserApplicationId>,
-    ) -> Result<(), SystemExecutionError> {
+    ) -> Result<UserApplicationDescription, SystemExecutionError> {
         // Make sure that referenced applications ids have been registered.
         for required_id in &required_application_ids {
             self.describe_application(*required_id).await?;
@@ -85,8 +94,8 @@ where
             required_application_ids,
         };
         self.known_applications
-            .insert(&application_id, description)?;
-        Ok(())
+            .insert(&application_id, description.clone())?;
+        Ok(description)
     }
 
     /// Retrieves an application's description.
@@ -100,7 +109,28 @@ where
             .ok_or_else(|| SystemExecutionError::UnknownApplicationId(Box::new(id)))
     }
 
+    /// Returns a registered application that requires the given one, if there is any.
+    pub async fn find_dependent(
+        &self,
+        id: UserApplicationId,
+    ) -> Result<Option<UserApplicationId>, SystemExecutionError> {
+        let mut dependent = None;
+        self.known_applications
+            .for_each_index_value_while(|index, description| {
+                if description.required_application_ids.contains(&id) {
+                    dependent = Some(index);
+                    return Ok(false);
+                }
+                Ok(true)
+            })
+            .await?;
+        Ok(dependent)
+    }
+
     /// Retrieves the recursive dependencies of applications and apply a topological sort.
+    ///
+    /// Fails with [`SystemExecutionError::CyclicApplicationDependency`] if the dependencies
+    /// contain a cycle.
     pub async fn find_dependencies(
         &self,
         mut stack: Vec<UserApplicationId>,
@@ -111,6 +141,8 @@ where
         let mut sorted = HashSet::new();
         // The entries for which dependencies have already been pushed once to the stack.
         let mut seen = HashSet::new();
+        // The entries that are seen but not sorted yet, i.e. the current path in the graph.
+        let mut path = Vec::new();
 
         while let Some(id) = stack.pop() {
             if sorted.contains(&id) {
@@ -121,23 +153,58 @@ where
                 // dependencies -- which are now fully sorted.
                 sorted.insert(id);
                 result.push(id);
+                path.pop();
                 continue;
             }
             // First time we see this entry:
             // 1. Mark it so that its dependencies are no longer pushed to the stack.
             seen.insert(id);
+            path.push(id);
             // 2. Schedule all the (yet unseen) dependencies, then this entry for a second visit.
             stack.push(id);
             let app = self.describe_application(id).await?;
             for child in app.required_application_ids.iter().rev() {
                 if !seen.contains(child) {
                     stack.push(*child);
+                } else if !sorted.contains(child) {
+                    // The child is an ancestor of this entry.
+                    let start = path.iter().position(|id| id == child).unwrap_or_default();
+                    let mut cycle = path[start..].to_vec();
+                    cycle.push(*child);
+                    return Err(SystemExecutionError::CyclicApplicationDependency { path: cycle });
                 }
             }
         }
         Ok(result)
     }
 
+    /// Removes all applications except the given ones and their recursive dependencies.
+    /// Unknown IDs are ignored. Returns the number of removed applications.
+    pub async fn retain_with_dependencies(
+        &mut self,
+        ids: Vec<UserApplicationId>,
+    ) -> Result<usize, SystemExecutionError> {
+        let mut known_ids = Vec::new();
+        for id in ids {
+            if self.known_applications.contains_key(&id).await? {
+                known_ids.push(id);
+            }
+        }
+        let retained = self
+            .find_dependencies(known_ids)
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let mut removed = 0;
+        for id in self.known_applications.indices().await? {
+            if !retained.contains(&id) {
+                self.known_applications.remove(&id)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
     /// Retrieves applications' descriptions preceded by their recursive dependencies.
     pub async fn describe_applications_with_dependencies(
         &self,
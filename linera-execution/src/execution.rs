@@ -1,23 +1,45 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(with_metrics)]
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::HashSet,
+    sync::{LazyLock, Mutex},
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    io::{self, Read as _},
     mem, vec,
 };
 
-use futures::{stream::FuturesOrdered, FutureExt, StreamExt, TryStreamExt};
+use futures::{FutureExt, StreamExt};
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{Amount, BlockHeight, Timestamp},
+    ensure,
     identifiers::{Account, AccountOwner, ChainId, Destination, Owner},
+    time::Instant,
+};
+#[cfg(with_metrics)]
+use linera_base::{
+    identifiers::GenericApplicationId,
+    prometheus_util::{
+        bucket_latencies, register_histogram_vec, register_int_counter_vec, MeasureLatency as _,
+    },
 };
 use linera_views::{
+    batch::Batch,
     context::Context,
     key_value_store_view::KeyValueStoreView,
     reentrant_collection_view::HashedReentrantCollectionView,
-    views::{ClonableView, View},
+    store::KeyIterable,
+    views::{ClonableView, CryptoHashView as _, View, ViewError},
 };
 use linera_views_derive::CryptoHashView;
+#[cfg(with_metrics)]
+use prometheus::{HistogramVec, IntCounterVec};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::instrument;
 #[cfg(with_testing)]
 use {
     crate::{
@@ -28,15 +50,100 @@ use {
     std::sync::Arc,
 };
 
-use super::{runtime::ServiceRuntimeRequest, ExecutionRequest};
+use super::{
+    runtime::{RandomSeed, ServiceRuntimeRequest},
+    ExecutionRequest,
+};
 use crate::{
     resources::ResourceController, system::SystemExecutionStateView, ContractSyncRuntime,
     ExecutionError, ExecutionOutcome, ExecutionRuntimeConfig, ExecutionRuntimeContext, Message,
     MessageContext, MessageKind, Operation, OperationContext, Query, QueryContext, QueryOutcome,
-    RawExecutionOutcome, RawOutgoingMessage, ServiceSyncRuntime, SystemMessage, TransactionTracker,
-    UserApplicationDescription, UserApplicationId,
+    RawExecutionOutcome, RawOutgoingMessage, ServiceSyncRuntime, SystemMessage, SystemOperation,
+    TransactionOutcome, TransactionTracker, UserApplicationDescription, UserApplicationId,
 };
 
+#[cfg(with_metrics)]
+/// The number of operations executed, per application.
+static EXECUTED_OPERATIONS_COUNT: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec(
+        "executed_operations_count",
+        "The number of operations executed",
+        &["application_id"],
+    )
+});
+
+#[cfg(with_metrics)]
+/// The number of messages executed, per application.
+static EXECUTED_MESSAGES_COUNT: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec(
+        "executed_messages_count",
+        "The number of messages executed",
+        &["application_id"],
+    )
+});
+
+#[cfg(with_metrics)]
+/// The number of queries handled, per application.
+static HANDLED_QUERIES_COUNT: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec(
+        "handled_queries_count",
+        "The number of queries handled",
+        &["application_id"],
+    )
+});
+
+#[cfg(with_metrics)]
+/// The number of failed operations, messages and queries, per application and error variant.
+static EXECUTION_ERRORS_COUNT: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec(
+        "execution_errors_count",
+        "The number of failed operations, messages and queries",
+        &["application_id", "error"],
+    )
+});
+
+#[cfg(with_metrics)]
+/// The fuel consumed by user applications, per application.
+static CONSUMED_FUEL_COUNT: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec(
+        "consumed_fuel_count",
+        "The fuel consumed by user applications",
+        &["application_id"],
+    )
+});
+
+#[cfg(with_metrics)]
+/// The maximum number of user applications with their own label in the execution metrics.
+const MAX_APPLICATION_LABELS: usize = 100;
+
+#[cfg(with_metrics)]
+/// The user applications that have their own label in the execution metrics.
+static LABELED_APPLICATIONS: LazyLock<Mutex<HashSet<UserApplicationId>>> =
+    LazyLock::new(Mutex::default);
+
+#[cfg(with_metrics)]
+/// Histogram of the latency to run an action of a user application.
+static RUN_USER_ACTION_LATENCY: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec(
+        "run_user_action_latency",
+        "Run user action latency",
+        &[],
+        bucket_latencies(5000.0),
+    )
+});
+
+/// The version of the format written by [`ExecutionStateView::export_state`].
+pub const STATE_EXPORT_VERSION: u32 = 1;
+
+/// The maximum number of stored entries read at once by [`ExecutionStateView::export_state`].
+pub const STATE_EXPORT_CHUNK_LEN: usize = 1000;
+
+/// The maximum size in bytes of a frame read by [`read_frame`].
+pub const MAX_FRAME_LEN: u64 = 1 << 30;
+
+/// The size in bytes above which [`ExecutionStateView::import_state`] writes its pending batch.
+pub const STATE_IMPORT_BATCH_SIZE: usize = 1 << 20;
+
 /// A view accessing the execution state of a chain.
 #[derive(Debug, ClonableView, CryptoHashView)]
 pub struct ExecutionStateView<C> {
@@ -44,6 +151,10 @@ pub struct ExecutionStateView<C> {
     pub system: SystemExecutionStateView<C>,
     /// User applications.
     pub users: HashedReentrantCollectionView<C, UserApplicationId, KeyValueStoreView<C>>,
+    /// The application descriptions read during [`Self::execute_operations`], so that they are
+    /// read from the registry only once for all the operations.
+    #[view(skip)]
+    description_cache: Option<HashMap<UserApplicationId, UserApplicationDescription>>,
 }
 
 /// How to interact with a long-lived service runtime.
@@ -126,6 +237,55 @@ where
     }
 }
 
+/// Compares two traces of state hashes, e.g. from different validators executing the same
+/// block, and returns the index of the first step where they differ.
+///
+/// If one trace is a strict prefix of the other, the length of the shorter one is returned.
+pub fn first_state_hash_mismatch(ours: &[CryptoHash], theirs: &[CryptoHash]) -> Option<usize> {
+    ours.iter()
+        .zip(theirs)
+        .position(|(our_hash, their_hash)| our_hash != their_hash)
+        .or_else(|| (ours.len() != theirs.len()).then_some(ours.len().min(theirs.len())))
+}
+
+/// Counts an executed operation, message or query and, if it failed, its error.
+#[cfg(with_metrics)]
+fn track_execution<T>(
+    counter: &IntCounterVec,
+    application_id: GenericApplicationId,
+    result: &Result<T, ExecutionError>,
+) {
+    let application_id = application_label(application_id);
+    counter.with_label_values(&[&application_id]).inc();
+    if let Err(error) = result {
+        EXECUTION_ERRORS_COUNT
+            .with_label_values(&[&application_id, error.variant_name()])
+            .inc();
+    }
+}
+
+/// Returns the label of an application in the execution metrics.
+///
+/// Only the first [`MAX_APPLICATION_LABELS`] user applications get their own label, so that
+/// the number of time series stays bounded. The others share the label `other`.
+#[cfg(with_metrics)]
+fn application_label(application_id: GenericApplicationId) -> String {
+    let GenericApplicationId::User(application_id) = application_id else {
+        return "system".to_owned();
+    };
+    let mut labeled_applications = LABELED_APPLICATIONS
+        .lock()
+        .expect("metrics lock should not be poisoned");
+    if labeled_applications.contains(&application_id)
+        || labeled_applications.len() < MAX_APPLICATION_LABELS
+    {
+        labeled_applications.insert(application_id);
+        application_id.to_string()
+    } else {
+        "other".to_owned()
+    }
+}
+
 pub enum UserAction {
     Instantiate(OperationContext, Vec<u8>),
     Operation(OperationContext, Vec<u8>),
@@ -157,6 +317,41 @@ impl UserAction {
             UserAction::Message(context, _) => context.round,
         }
     }
+
+    /// The seed of the pseudo-random bytes available to the application, which is the same on
+    /// every validator.
+    fn random_seed(&self, chain_id: ChainId, application_id: UserApplicationId) -> RandomSeed {
+        let (operation_index, message_id) = match self {
+            UserAction::Instantiate(context, _) => (context.index, None),
+            UserAction::Operation(context, _) => (context.index, None),
+            UserAction::Message(context, _) => (None, Some(context.message_id)),
+        };
+        RandomSeed::Action {
+            chain_id,
+            height: self.height(),
+            operation_index,
+            message_id,
+            application_id,
+        }
+    }
+
+    /// The name of this kind of action, for diagnostics.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            UserAction::Instantiate(..) => "instantiation",
+            UserAction::Operation(..) => "operation",
+            UserAction::Message(..) => "message",
+        }
+    }
+
+    /// The size of the argument passed to the application, in bytes.
+    pub(crate) fn argument_size(&self) -> usize {
+        match self {
+            UserAction::Instantiate(_, argument) => argument.len(),
+            UserAction::Operation(_, argument) => argument.len(),
+            UserAction::Message(_, argument) => argument.len(),
+        }
+    }
 }
 
 impl<C> ExecutionStateView<C>
@@ -164,6 +359,135 @@ where
     C: Context + Clone + Send + Sync + 'static,
     C::Extra: ExecutionRuntimeContext,
 {
+    /// Creates a speculative copy of this view, for instance to execute a pending block while
+    /// this view keeps serving queries.
+    ///
+    /// The fork reads the committed state through the same storage context, but its changes stay
+    /// in memory: they are discarded when the fork is dropped, and can be written to a batch with
+    /// [`View::flush`]. The fork must not be used anymore once this view has been saved, since
+    /// the committed state it is based on will have changed.
+    pub fn fork(&mut self) -> Result<Self, ViewError> {
+        self.clone_unchecked()
+    }
+
+    /// Writes the saved execution state, including all user application states, to `writer`.
+    ///
+    /// The output is deterministic and starts with the format version and the state hash,
+    /// followed by the stored entries in the order of their keys. The keys are listed one
+    /// prefix at a time, and prefixes with more than [`STATE_EXPORT_CHUNK_LEN`] keys are split
+    /// further, so that at most that many values are read at once. The view must not have
+    /// unsaved changes.
+    pub async fn export_state(&self, mut writer: impl io::Write) -> Result<(), ExecutionError> {
+        ensure!(
+            !self.has_pending_changes().await,
+            ExecutionError::UnsavedStateExport
+        );
+        let state_hash = self.crypto_hash().await?;
+        write_frame(&mut writer, &(STATE_EXPORT_VERSION, state_hash))?;
+        let context = self.context();
+        // The prefixes to export, relative to the base key, with the next one at the end.
+        let mut prefixes = vec![Vec::new()];
+        while let Some(prefix) = prefixes.pop() {
+            let full_prefix = context.base_index(&prefix);
+            let (chunk, next_bytes) = {
+                let keys = context
+                    .find_keys_by_prefix(&full_prefix)
+                    .await
+                    .map_err(ViewError::from)?;
+                split_export_prefix(&prefix, &keys)?
+            };
+            prefixes.extend(next_bytes.into_iter().rev().map(|byte| {
+                let mut next_prefix = prefix.clone();
+                next_prefix.push(byte);
+                next_prefix
+            }));
+            let full_keys = chunk.iter().map(|key| context.base_index(key)).collect();
+            let values = context
+                .read_multi_values_bytes(full_keys)
+                .await
+                .map_err(ViewError::from)?;
+            for (key, value) in chunk.iter().zip(values) {
+                if let Some(value) = value {
+                    write_frame(&mut writer, &Some((key, value)))?;
+                }
+            }
+        }
+        write_frame(&mut writer, &None::<(Vec<u8>, Vec<u8>)>)?;
+        Ok(())
+    }
+
+    /// Reconstructs an execution state written by [`ExecutionStateView::export_state`] in the
+    /// given `context`, which must be empty, and checks that its hash matches the exported one.
+    ///
+    /// Entries are written to storage in batches of about [`STATE_IMPORT_BATCH_SIZE`] bytes.
+    /// If the import fails, e.g. because the hash does not match, the entries that were
+    /// written are deleted again.
+    pub async fn import_state(
+        context: C,
+        mut reader: impl io::Read,
+    ) -> Result<Self, ExecutionError> {
+        let (version, expected): (u32, CryptoHash) = read_frame(&mut reader)?;
+        ensure!(
+            version == STATE_EXPORT_VERSION,
+            ExecutionError::UnsupportedStateExportVersion(version)
+        );
+        let base_key = context.base_key();
+        let is_empty = context
+            .find_keys_by_prefix(&base_key)
+            .await
+            .map_err(ViewError::from)?
+            .iterator()
+            .next()
+            .is_none();
+        ensure!(is_empty, ExecutionError::StateImportIntoNonEmptyContext);
+        match Self::import_entries(context.clone(), reader, expected).await {
+            Ok(view) => Ok(view),
+            Err(error) => {
+                let mut batch = Batch::new();
+                batch.delete_key_prefix(base_key);
+                context.write_batch(batch).await.map_err(ViewError::from)?;
+                Err(error)
+            }
+        }
+    }
+
+    /// Writes the entries read from `reader` to the empty `context`, then loads the view and
+    /// checks its hash.
+    async fn import_entries(
+        context: C,
+        mut reader: impl io::Read,
+        expected: CryptoHash,
+    ) -> Result<Self, ExecutionError> {
+        let mut batch = Batch::new();
+        while let Some((key, value)) = read_frame::<Option<(Vec<u8>, Vec<u8>)>>(&mut reader)? {
+            batch.put_key_value_bytes(context.base_index(&key), value);
+            if batch.size() >= STATE_IMPORT_BATCH_SIZE {
+                context
+                    .write_batch(mem::take(&mut batch))
+                    .await
+                    .map_err(ViewError::from)?;
+            }
+        }
+        context.write_batch(batch).await.map_err(ViewError::from)?;
+        let view = Self::load(context).await?;
+        let actual = view.crypto_hash().await?;
+        ensure!(
+            actual == expected,
+            ExecutionError::StateImportHashMismatch { expected, actual }
+        );
+        Ok(view)
+    }
+
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            %chain_id,
+            %application_id,
+            kind = action.kind(),
+            argument_size = action.argument_size(),
+        )
+    )]
     #[expect(clippy::too_many_arguments)]
     async fn run_user_action(
         &mut self,
@@ -176,7 +500,15 @@ where
         txn_tracker: &mut TransactionTracker,
         resource_controller: &mut ResourceController<Option<Owner>>,
     ) -> Result<(), ExecutionError> {
-        let ExecutionRuntimeConfig {} = self.context().extra().execution_runtime_config();
+        let ExecutionRuntimeConfig {
+            trace_state_hashes: _,
+        } = self.context().extra().execution_runtime_config();
+        let kind = action.kind();
+        let argument_size = action.argument_size();
+        #[cfg(with_metrics)]
+        let _latency = RUN_USER_ACTION_LATENCY.measure_latency();
+        #[cfg(with_metrics)]
+        let initial_fuel = resource_controller.tracker.fuel;
         self.run_user_action_with_runtime(
             application_id,
             chain_id,
@@ -187,7 +519,27 @@ where
             txn_tracker,
             resource_controller,
         )
-        .await?;
+        .await
+        .map_err(|error| match error {
+            // Missing blobs are reported unchanged so that callers can fetch them and retry.
+            ExecutionError::BlobsNotFound(_) => error,
+            error => ExecutionError::FailedUserAction {
+                application_id,
+                kind,
+                argument_size,
+                error: Box::new(error),
+            },
+        })?;
+        #[cfg(with_metrics)]
+        {
+            let consumed_fuel = resource_controller
+                .tracker
+                .fuel
+                .saturating_sub(initial_fuel);
+            CONSUMED_FUEL_COUNT
+                .with_label_values(&[&application_label(application_id.into())])
+                .inc_by(consumed_fuel);
+        }
         Ok(())
     }
 
@@ -217,6 +569,7 @@ where
             futures::channel::mpsc::unbounded();
         let txn_tracker_moved = mem::take(txn_tracker);
         let (code, description) = self.load_contract(application_id).await?;
+        let random_seed = CryptoHash::new(&action.random_seed(chain_id, application_id));
         let contract_runtime_task = linera_base::task::Blocking::spawn(move |mut codes| {
             let runtime = ContractSyncRuntime::new(
                 execution_state_sender,
@@ -226,6 +579,7 @@ where
                 controller,
                 &action,
                 txn_tracker_moved,
+                random_seed,
             );
 
             async move {
@@ -252,14 +606,69 @@ where
         Ok(())
     }
 
+    /// Retrieves an application's description, from the description cache if it is enabled.
+    pub(crate) async fn describe_application(
+        &mut self,
+        id: UserApplicationId,
+    ) -> Result<UserApplicationDescription, ExecutionError> {
+        if let Some(description) = self
+            .description_cache
+            .as_ref()
+            .and_then(|cache| cache.get(&id))
+        {
+            return Ok(description.clone());
+        }
+        let description = self.system.registry.describe_application(id).await?;
+        if let Some(cache) = &mut self.description_cache {
+            cache.insert(id, description.clone());
+        }
+        Ok(description)
+    }
+
+    /// Returns an error if `chain_id` is not the ID of the chain this view belongs to.
+    fn check_chain_id(&self, chain_id: ChainId) -> Result<(), ExecutionError> {
+        let expected = self.context().extra().chain_id();
+        ensure!(
+            chain_id == expected,
+            ExecutionError::IncorrectChainId {
+                expected,
+                actual: chain_id,
+            }
+        );
+        Ok(())
+    }
+
+    /// Records the current state hash in `txn_tracker` if state hashes are being traced.
+    async fn trace_state_hash(
+        &mut self,
+        txn_tracker: &mut TransactionTracker,
+    ) -> Result<(), ExecutionError> {
+        let ExecutionRuntimeConfig { trace_state_hashes } =
+            self.context().extra().execution_runtime_config();
+        if trace_state_hashes {
+            txn_tracker.add_state_hash(self.crypto_hash_mut().await?);
+        }
+        Ok(())
+    }
+
     /// Schedules application registration messages when needed.
     ///
-    /// Ensures that the outgoing messages in `results` are preceded by a system message that
-    /// registers the application that will handle the messages.
+    /// Ensures that the outgoing messages of the user outcomes in `txn_tracker` are preceded by
+    /// a system message that registers the applications that will handle the messages. At most
+    /// one registration message is sent to each destination, and none to the current chain,
+    /// which already knows about its applications.
+    ///
+    /// The order of the resulting outcomes is part of the chain state and must not depend on
+    /// anything but the existing outcomes: the registration messages are added in a single system
+    /// outcome right before the first user outcome, sorted by destination, and each of them
+    /// describes the applications returned by
+    /// [`find_dependencies`][crate::ApplicationRegistryView::find_dependencies] for the sorted
+    /// list of application IDs, i.e. every application after its dependencies.
     pub async fn update_execution_outcomes_with_app_registrations(
-        &self,
+        &mut self,
         txn_tracker: &mut TransactionTracker,
     ) -> Result<(), ExecutionError> {
+        let local_destination = Destination::Recipient(self.context().extra().chain_id());
         let results = txn_tracker.outcomes_mut();
         let user_application_outcomes = results.iter().filter_map(|outcome| match outcome {
             ExecutionOutcome::User(application_id, result) => Some((application_id, result)),
@@ -270,6 +679,9 @@ where
 
         for (application_id, result) in user_application_outcomes {
             for message in &result.messages {
+                if message.destination == local_destination {
+                    continue;
+                }
                 applications_to_register_per_destination
                     .entry(&message.destination)
                     .or_default()
@@ -281,28 +693,25 @@ where
             return Ok(());
         }
 
-        let messages = applications_to_register_per_destination
-            .into_iter()
-            .map(|(destination, applications_to_describe)| async {
-                let applications = self
-                    .system
-                    .registry
-                    .describe_applications_with_dependencies(
-                        applications_to_describe.into_iter().collect(),
-                    )
-                    .await?;
-
-                Ok::<_, ExecutionError>(RawOutgoingMessage {
-                    destination: destination.clone(),
-                    authenticated: false,
-                    grant: Amount::ZERO,
-                    kind: MessageKind::Simple,
-                    message: SystemMessage::RegisterApplications { applications },
-                })
-            })
-            .collect::<FuturesOrdered<_>>()
-            .try_collect::<Vec<_>>()
-            .await?;
+        let mut messages = Vec::new();
+        for (destination, applications_to_describe) in applications_to_register_per_destination {
+            let application_ids = self
+                .system
+                .registry
+                .find_dependencies(applications_to_describe.into_iter().collect())
+                .await?;
+            let mut applications = Vec::with_capacity(application_ids.len());
+            for id in application_ids {
+                applications.push(self.describe_application(id).await?);
+            }
+            messages.push(RawOutgoingMessage {
+                destination: destination.clone(),
+                authenticated: false,
+                grant: Amount::ZERO,
+                kind: MessageKind::Simple,
+                message: SystemMessage::RegisterApplications { applications },
+            });
+        }
 
         let system_outcome = RawExecutionOutcome {
             messages,
@@ -320,6 +729,20 @@ where
         Ok(())
     }
 
+    /// Executes an operation, leaving this view unchanged if it fails.
+    ///
+    /// The operation runs on a copy of this view, which replaces it only on success. This view is
+    /// therefore also unchanged if the returned future is dropped before completion.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            chain_id = %context.chain_id,
+            height = %context.height,
+            application_id = ?operation.application_id(),
+        ),
+        err(level = "debug")
+    )]
     pub async fn execute_operation(
         &mut self,
         context: OperationContext,
@@ -328,13 +751,167 @@ where
         txn_tracker: &mut TransactionTracker,
         resource_controller: &mut ResourceController<Option<Owner>>,
     ) -> Result<(), ExecutionError> {
-        assert_eq!(context.chain_id, self.context().extra().chain_id());
+        self.check_chain_id(context.chain_id)?;
+        #[cfg(with_metrics)]
+        let application_id = operation.application_id();
+        let mut view = self.clone_unchecked()?;
+        let tracker_before = resource_controller.tracker;
+        let start = Instant::now();
+        let result = Box::pin(view.execute_operation_without_rollback(
+            context,
+            local_time,
+            operation,
+            txn_tracker,
+            resource_controller,
+        ))
+        .await;
+        #[cfg(with_metrics)]
+        track_execution(&EXECUTED_OPERATIONS_COUNT, application_id, &result);
+        result?;
+        txn_tracker.add_resource_usage(
+            &tracker_before,
+            &resource_controller.tracker,
+            start.elapsed(),
+        );
+        *self = view;
+        self.trace_state_hash(txn_tracker).await
+    }
+
+    /// Executes a sequence of operations, each in its own transaction, and returns the
+    /// outcomes of each operation in order.
+    ///
+    /// The operations are numbered starting from the index in `context`. Execution stops at
+    /// the first failing operation, and the error contains its index. The operations before it
+    /// remain executed.
+    pub async fn execute_operations(
+        &mut self,
+        context: OperationContext,
+        local_time: Timestamp,
+        operations: Vec<Operation>,
+        resource_controller: &mut ResourceController<Option<Owner>>,
+    ) -> Result<Vec<TransactionOutcome>, ExecutionError> {
+        self.description_cache = Some(HashMap::new());
+        let result = Box::pin(self.execute_operations_with_description_cache(
+            context,
+            local_time,
+            operations,
+            resource_controller,
+        ))
+        .await;
+        self.description_cache = None;
+        result
+    }
+
+    async fn execute_operations_with_description_cache(
+        &mut self,
+        context: OperationContext,
+        local_time: Timestamp,
+        operations: Vec<Operation>,
+        resource_controller: &mut ResourceController<Option<Owner>>,
+    ) -> Result<Vec<TransactionOutcome>, ExecutionError> {
+        let first_index = context.index.unwrap_or(0);
+        let mut next_message_index = 0;
+        let mut outcomes = Vec::with_capacity(operations.len());
+        for (index, operation) in (first_index..).zip(operations) {
+            let context = OperationContext {
+                index: Some(index),
+                ..context
+            };
+            let mut txn_tracker = TransactionTracker::new(next_message_index, None);
+            let result = async {
+                Box::pin(self.execute_operation(
+                    context,
+                    local_time,
+                    operation.clone(),
+                    &mut txn_tracker,
+                    resource_controller,
+                ))
+                .await?;
+                resource_controller
+                    .with_state(self)
+                    .await?
+                    .track_operation(&operation)?;
+                self.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
+                    .await?;
+                Ok::<_, ExecutionError>(())
+            }
+            .await;
+            if let Err(error) = result {
+                return Err(ExecutionError::FailedOperation {
+                    index,
+                    error: Box::new(error),
+                });
+            }
+            if matches!(operation, Operation::System(_)) {
+                // System operations can replace or remove registered descriptions.
+                if let Some(cache) = &mut self.description_cache {
+                    cache.clear();
+                }
+            }
+            let txn_outcome = txn_tracker.destructure()?;
+            next_message_index = txn_outcome.next_message_index;
+            outcomes.push(txn_outcome);
+        }
+        Ok(outcomes)
+    }
+
+    /// Executes an operation on a temporary copy of this view, without modifying it.
+    ///
+    /// Returns the outcomes of the operation, including the application registrations, and the
+    /// state hash that the view would have after executing it.
+    pub async fn simulate_operation(
+        &mut self,
+        context: OperationContext,
+        local_time: Timestamp,
+        operation: Operation,
+        resource_controller: &mut ResourceController<Option<Owner>>,
+    ) -> Result<(TransactionOutcome, CryptoHash), ExecutionError> {
+        let mut view = self.clone_unchecked()?;
+        let mut txn_tracker = TransactionTracker::new(0, None);
+        Box::pin(view.execute_operation(
+            context,
+            local_time,
+            operation,
+            &mut txn_tracker,
+            resource_controller,
+        ))
+        .await?;
+        view.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
+            .await?;
+        let outcome = txn_tracker.destructure()?;
+        let state_hash = view.crypto_hash_mut().await?;
+        Ok((outcome, state_hash))
+    }
+
+    async fn execute_operation_without_rollback(
+        &mut self,
+        context: OperationContext,
+        local_time: Timestamp,
+        operation: Operation,
+        txn_tracker: &mut TransactionTracker,
+        resource_controller: &mut ResourceController<Option<Owner>>,
+    ) -> Result<(), ExecutionError> {
         match operation {
             Operation::System(op) => {
+                let removed_application = match &op {
+                    SystemOperation::RemoveApplicationState { application_id } => {
+                        Some(*application_id)
+                    }
+                    _ => None,
+                };
+                let prune_registry = op == SystemOperation::PruneRegistry;
                 let new_application = self
                     .system
                     .execute_operation(context, op, txn_tracker)
                     .await?;
+                if let Some(application_id) = removed_application {
+                    self.remove_application_state(application_id, txn_tracker, resource_controller)
+                        .await?;
+                }
+                if prune_registry {
+                    let count = self.prune_registry().await?;
+                    txn_tracker.add_pruned_application_count(count);
+                }
                 if let Some((application_id, argument)) = new_application {
                     let user_action = UserAction::Instantiate(context, argument);
                     self.run_user_action(
@@ -370,6 +947,59 @@ where
         Ok(())
     }
 
+    /// Removes the stored state of a user application, recording the freed storage in the
+    /// resource tracker and in the transaction outcome.
+    async fn remove_application_state(
+        &mut self,
+        application_id: UserApplicationId,
+        txn_tracker: &mut TransactionTracker,
+        resource_controller: &mut ResourceController<Option<Owner>>,
+    ) -> Result<(), ExecutionError> {
+        let Some(view) = self.users.take_entry(&application_id).await? else {
+            return Ok(());
+        };
+        let freed_bytes = view.total_size().sum_i32()?;
+        resource_controller.track_stored_bytes(-freed_bytes)?;
+        txn_tracker.add_freed_bytes(freed_bytes.unsigned_abs().into());
+        Ok(())
+    }
+
+    /// Removes the descriptions of applications that have no state on this chain, are not
+    /// referenced by the application permissions, and are not required by any application
+    /// that is kept. Returns the number of removed descriptions.
+    async fn prune_registry(&mut self) -> Result<usize, ExecutionError> {
+        let mut retained = self.users.indices().await?;
+        let permissions = self.system.application_permissions.get();
+        retained.extend(
+            permissions
+                .execute_operations
+                .iter()
+                .flatten()
+                .chain(&permissions.mandatory_applications)
+                .copied(),
+        );
+        let count = self
+            .system
+            .registry
+            .retain_with_dependencies(retained)
+            .await?;
+        Ok(count)
+    }
+
+    /// Executes a message, leaving this view unchanged if it fails.
+    ///
+    /// The message runs on a copy of this view, which replaces it only on success. This view is
+    /// therefore also unchanged if the returned future is dropped before completion.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            chain_id = %context.chain_id,
+            height = %context.height,
+            application_id = ?message.application_id(),
+        ),
+        err(level = "debug")
+    )]
     pub async fn execute_message(
         &mut self,
         context: MessageContext,
@@ -379,7 +1009,42 @@ where
         txn_tracker: &mut TransactionTracker,
         resource_controller: &mut ResourceController<Option<Owner>>,
     ) -> Result<(), ExecutionError> {
-        assert_eq!(context.chain_id, self.context().extra().chain_id());
+        self.check_chain_id(context.chain_id)?;
+        #[cfg(with_metrics)]
+        let application_id = message.application_id();
+        let mut view = self.clone_unchecked()?;
+        let tracker_before = resource_controller.tracker;
+        let start = Instant::now();
+        let result = Box::pin(view.execute_message_without_rollback(
+            context,
+            local_time,
+            message,
+            grant,
+            txn_tracker,
+            resource_controller,
+        ))
+        .await;
+        #[cfg(with_metrics)]
+        track_execution(&EXECUTED_MESSAGES_COUNT, application_id, &result);
+        result?;
+        txn_tracker.add_resource_usage(
+            &tracker_before,
+            &resource_controller.tracker,
+            start.elapsed(),
+        );
+        *self = view;
+        self.trace_state_hash(txn_tracker).await
+    }
+
+    async fn execute_message_without_rollback(
+        &mut self,
+        context: MessageContext,
+        local_time: Timestamp,
+        message: Message,
+        grant: Option<&mut Amount>,
+        txn_tracker: &mut TransactionTracker,
+        resource_controller: &mut ResourceController<Option<Owner>>,
+    ) -> Result<(), ExecutionError> {
         match message {
             Message::System(message) => {
                 let outcome = self
@@ -415,7 +1080,7 @@ where
         message: Message,
         txn_tracker: &mut TransactionTracker,
     ) -> Result<(), ExecutionError> {
-        assert_eq!(context.chain_id, self.context().extra().chain_id());
+        self.check_chain_id(context.chain_id)?;
         match message {
             Message::System(message) => {
                 let mut outcome = RawExecutionOutcome {
@@ -461,7 +1126,7 @@ where
         account: Account,
         txn_tracker: &mut TransactionTracker,
     ) -> Result<(), ExecutionError> {
-        assert_eq!(context.chain_id, self.context().extra().chain_id());
+        self.check_chain_id(context.chain_id)?;
         let mut outcome = RawExecutionOutcome::default();
         let message = RawOutgoingMessage {
             destination: Destination::Recipient(account.chain_id),
@@ -479,13 +1144,36 @@ where
         Ok(())
     }
 
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            chain_id = %context.chain_id,
+            application_id = ?query.application_id(),
+        ),
+        err(level = "debug")
+    )]
     pub async fn query_application(
         &mut self,
         context: QueryContext,
         query: Query,
         endpoint: Option<&mut ServiceRuntimeEndpoint>,
     ) -> Result<QueryOutcome, ExecutionError> {
-        assert_eq!(context.chain_id, self.context().extra().chain_id());
+        #[cfg(with_metrics)]
+        let application_id = query.application_id();
+        let result = self.handle_query(context, query, endpoint).await;
+        #[cfg(with_metrics)]
+        track_execution(&HANDLED_QUERIES_COUNT, application_id, &result);
+        result
+    }
+
+    async fn handle_query(
+        &mut self,
+        context: QueryContext,
+        query: Query,
+        endpoint: Option<&mut ServiceRuntimeEndpoint>,
+    ) -> Result<QueryOutcome, ExecutionError> {
+        self.check_chain_id(context.chain_id)?;
         match query {
             Query::System(query) => {
                 let outcome = self.system.handle_query(context, query).await?;
@@ -495,7 +1183,9 @@ where
                 application_id,
                 bytes,
             } => {
-                let ExecutionRuntimeConfig {} = self.context().extra().execution_runtime_config();
+                let ExecutionRuntimeConfig {
+                    trace_state_hashes: _,
+                } = self.context().extra().execution_runtime_config();
                 let outcome = match endpoint {
                     Some(endpoint) => {
                         self.query_user_application_with_long_lived_service(
@@ -583,6 +1273,19 @@ where
         }
     }
 
+    /// Returns the number of bytes used by the stored state of a user application.
+    pub async fn application_state_size(
+        &self,
+        application_id: UserApplicationId,
+    ) -> Result<u64, ExecutionError> {
+        let Some(view) = self.users.try_load_entry(&application_id).await? else {
+            return Ok(0);
+        };
+        let size = view.total_size();
+        Ok(u64::from(size.key) + u64::from(size.value))
+    }
+
+    /// Lists the user applications registered on this chain, with their descriptions.
     pub async fn list_applications(
         &self,
     ) -> Result<Vec<(UserApplicationId, UserApplicationDescription)>, ExecutionError> {
@@ -598,3 +1301,69 @@ where
         Ok(applications)
     }
 }
+
+/// Splits the `keys` found under `prefix` for [`ExecutionStateView::export_state`]. Returns the
+/// keys to export now, relative to the base key, and the bytes extending `prefix` that must be
+/// exported next, in order, if there are too many keys to export them at once.
+fn split_export_prefix<K, E>(
+    prefix: &[u8],
+    keys: &K,
+) -> Result<(Vec<Vec<u8>>, BTreeSet<u8>), ViewError>
+where
+    K: KeyIterable<E>,
+    ViewError: From<E>,
+{
+    let mut chunk = Vec::new();
+    let mut next_bytes = BTreeSet::new();
+    let mut has_prefix_key = false;
+    for suffix in keys.iterator() {
+        let suffix = suffix?;
+        match suffix.first() {
+            Some(byte) => {
+                next_bytes.insert(*byte);
+            }
+            None => has_prefix_key = true,
+        }
+        if chunk.len() <= STATE_EXPORT_CHUNK_LEN {
+            chunk.push([prefix, suffix].concat());
+        }
+    }
+    if chunk.len() <= STATE_EXPORT_CHUNK_LEN {
+        return Ok((chunk, BTreeSet::new()));
+    }
+    // The key equal to the prefix comes first, then the keys under each of `next_bytes`.
+    chunk.clear();
+    if has_prefix_key {
+        chunk.push(prefix.to_vec());
+    }
+    Ok((chunk, next_bytes))
+}
+
+/// Writes `value` to `writer`, in BCS and prefixed with its length, as in the streams of
+/// [`ExecutionStateView::export_state`].
+pub fn write_frame(
+    writer: &mut impl io::Write,
+    value: &impl Serialize,
+) -> Result<(), ExecutionError> {
+    let bytes = bcs::to_bytes(value)?;
+    writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Reads a value written by [`write_frame`] from `reader`. Frames longer than
+/// [`MAX_FRAME_LEN`] bytes are rejected.
+pub fn read_frame<T: DeserializeOwned>(reader: &mut impl io::Read) -> Result<T, ExecutionError> {
+    let mut len = [0; 8];
+    reader.read_exact(&mut len)?;
+    let len = u64::from_le_bytes(len);
+    ensure!(len <= MAX_FRAME_LEN, ExecutionError::FrameTooLarge(len));
+    // The buffer grows with the data actually read, in case the stream is truncated.
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    ensure!(
+        bytes.len() as u64 == len,
+        io::Error::from(io::ErrorKind::UnexpectedEof)
+    );
+    Ok(bcs::from_bytes(&bytes)?)
+}
@@ -65,7 +65,7 @@ where
     ) -> Result<(UserContractCode, UserApplicationDescription), ExecutionError> {
         #[cfg(with_metrics)]
         let _latency = LOAD_CONTRACT_LATENCY.measure_latency();
-        let description = self.system.registry.describe_application(id).await?;
+        let description = self.describe_application(id).await?;
         let code = self
             .context()
             .extra()
@@ -80,7 +80,7 @@ where
     ) -> Result<(UserServiceCode, UserApplicationDescription), ExecutionError> {
         #[cfg(with_metrics)]
         let _latency = LOAD_SERVICE_LATENCY.measure_latency();
-        let description = self.system.registry.describe_application(id).await?;
+        let description = self.describe_application(id).await?;
         let code = self
             .context()
             .extra()
@@ -251,8 +251,14 @@ where
                 callback,
             } => {
                 let mut view = self.users.try_load_entry_mut(&id).await?;
+                let size_before = view.total_size().sum_i32()?;
                 view.write_batch(batch).await?;
-                callback.respond(());
+                let size_after = view.total_size().sum_i32()?;
+                callback.respond(size_after - size_before);
+            }
+
+            ApplicationStateSize { id, callback } => {
+                callback.respond(self.application_state_size(id).await?);
             }
 
             OpenChain {
@@ -270,6 +276,7 @@ where
                     committees: self.system.committees.get().clone(),
                     balance,
                     application_permissions,
+                    bytecode_publishers: self.system.bytecode_publishers.get().clone(),
                 };
                 let messages = self.system.open_chain(config, next_message_id).await?;
                 callback.respond(messages)
@@ -483,7 +490,13 @@ pub enum ExecutionRequest {
         id: UserApplicationId,
         batch: Batch,
         #[debug(skip)]
-        callback: Sender<()>,
+        callback: Sender<i32>,
+    },
+
+    ApplicationStateSize {
+        id: UserApplicationId,
+        #[debug(skip)]
+        callback: Sender<u64>,
     },
 
     OpenChain {
@@ -493,7 +506,7 @@ pub enum ExecutionRequest {
         next_message_id: MessageId,
         application_permissions: ApplicationPermissions,
         #[debug(skip)]
-        callback: Sender<[RawOutgoingMessage<SystemMessage, Amount>; 2]>,
+        callback: Sender<Vec<RawOutgoingMessage<SystemMessage, Amount>>>,
     },
 
     CloseChain {
@@ -34,7 +34,7 @@ use derive_more::Display;
 use js_sys::wasm_bindgen::JsValue;
 use linera_base::{
     abi::Abi,
-    crypto::{BcsHashable, CryptoHash},
+    crypto::{BcsHashable, CryptoHash, PublicKey},
     data_types::{
         Amount, ApplicationPermissions, ArithmeticError, Blob, BlockHeight, DecompressionError,
         Resources, SendMessageRequest, Timestamp, UserApplicationDescription,
@@ -64,7 +64,11 @@ pub use crate::wasm::{
 };
 pub use crate::{
     applications::ApplicationRegistryView,
-    execution::{ExecutionStateView, ServiceRuntimeEndpoint},
+    execution::{
+        first_state_hash_mismatch, read_frame, write_frame, ExecutionStateView,
+        ServiceRuntimeEndpoint, MAX_FRAME_LEN, STATE_EXPORT_CHUNK_LEN, STATE_EXPORT_VERSION,
+        STATE_IMPORT_BATCH_SIZE,
+    },
     execution_state_actor::ExecutionRequest,
     policy::ResourceControlPolicy,
     resources::{ResourceController, ResourceTracker},
@@ -76,13 +80,15 @@ pub use crate::{
         SystemExecutionError, SystemExecutionStateView, SystemMessage, SystemOperation,
         SystemQuery, SystemResponse,
     },
-    transaction_tracker::TransactionTracker,
+    transaction_tracker::{TransactionOutcome, TransactionTracker},
 };
 
 /// The maximum length of an event key in bytes.
 const MAX_EVENT_KEY_LEN: usize = 64;
 /// The maximum length of a stream name.
 const MAX_STREAM_NAME_LEN: usize = 64;
+/// The maximum number of pseudo-random bytes an application can request at once.
+const MAX_RANDOM_BYTES_LEN: u32 = 1024;
 
 /// An implementation of [`UserContractModule`].
 #[derive(Clone)]
@@ -207,6 +213,8 @@ pub enum ExecutionError {
 
     #[error("Attempted to perform a reentrant call to application {0}")]
     ReentrantCall(UserApplicationId),
+    #[error("Application {0} was already instantiated and has stored state")]
+    ApplicationAlreadyInstantiated(UserApplicationId),
     #[error(
         "Application {caller_id} attempted to perform a cross-application to {callee_id} call \
         from `finalize`"
@@ -215,6 +223,8 @@ pub enum ExecutionError {
         caller_id: Box<UserApplicationId>,
         callee_id: Box<UserApplicationId>,
     },
+    #[error("Application call stack is inconsistent after execution: {0:?}")]
+    UnexpectedCallStack(Vec<UserApplicationId>),
     #[error("Attempt to write to storage from a contract")]
     ServiceWriteAttempt,
     #[error("Failed to load bytecode from storage {0:?}")]
@@ -231,6 +241,46 @@ pub enum ExecutionError {
     MaximumFuelExceeded,
     #[error("Serialized size of the executed block exceeds limit")]
     ExecutedBlockTooLarge,
+    #[error("Execution request for chain {actual} was sent to chain {expected}")]
+    IncorrectChainId { expected: ChainId, actual: ChainId },
+    #[error("Application {0} sent too many messages in one transaction")]
+    TooManyMessages(UserApplicationId),
+    #[error("Application {0} sent a message that exceeds the size limit")]
+    MessageTooLarge(UserApplicationId),
+    #[error("Application {0} emitted too many events in one transaction")]
+    TooManyEvents(UserApplicationId),
+    #[error("Application {0} emitted an event that exceeds the size limit")]
+    EventTooLarge(UserApplicationId),
+    #[error("Block sends messages to {count} destinations, but at most {max} are allowed")]
+    TooManyRecipients { count: u64, max: u64 },
+    #[error(
+        "Call to application {application_id} at depth {depth} exceeds the maximum call depth"
+    )]
+    CallDepthExceeded {
+        depth: u64,
+        application_id: UserApplicationId,
+    },
+    #[error("Operation {index} failed: {error}")]
+    FailedOperation {
+        index: u32,
+        error: Box<ExecutionError>,
+    },
+    #[error("{error}\nCross-application call chain:\n{call_chain}")]
+    FailedCallChain {
+        call_chain: CallChain,
+        error: Box<ExecutionError>,
+    },
+    #[error(
+        "Failed to execute {kind} of application {application_id} \
+        with a {argument_size}-byte argument: {error}"
+    )]
+    FailedUserAction {
+        application_id: UserApplicationId,
+        kind: &'static str,
+        argument_size: usize,
+        #[source]
+        error: Box<ExecutionError>,
+    },
     #[error("Runtime failed to respond to application")]
     MissingRuntimeResponse,
     #[error("Bytecode ID {0:?} is invalid")]
@@ -261,6 +311,8 @@ pub enum ExecutionError {
     EventKeyTooLong,
     #[error("Stream names can be at most {MAX_STREAM_NAME_LEN} bytes.")]
     StreamNameTooLong,
+    #[error("At most {MAX_RANDOM_BYTES_LEN} random bytes can be requested at once.")]
+    TooManyRandomBytes,
     #[error("Blob exceeds size limit")]
     BlobTooLarge,
     #[error("Bytecode exceeds size limit")]
@@ -273,10 +325,91 @@ pub enum ExecutionError {
     ContractModuleSend(#[from] linera_base::task::SendError<UserContractCode>),
     #[error("Failed to send service code to worker thread: {0:?}")]
     ServiceModuleSend(#[from] linera_base::task::SendError<UserServiceCode>),
+    #[error("Cannot export an execution state with unsaved changes")]
+    UnsavedStateExport,
+    #[error("Unsupported execution state export version {0}")]
+    UnsupportedStateExportVersion(u32),
+    #[error("Cannot import an execution state into a non-empty context")]
+    StateImportIntoNonEmptyContext,
+    #[error("Frame of {0} bytes exceeds the maximum frame size")]
+    FrameTooLarge(u64),
+    #[error("Imported execution state has hash {actual} instead of {expected}")]
+    StateImportHashMismatch {
+        expected: CryptoHash,
+        actual: CryptoHash,
+    },
     #[error("Blobs not found: {0:?}")]
     BlobsNotFound(Vec<BlobId>),
 }
 
+impl ExecutionError {
+    /// Returns the name of this error's variant, e.g. to label metrics.
+    ///
+    /// Errors that only add context to another error return the name of the inner error.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            ExecutionError::ViewError(_) => "ViewError",
+            ExecutionError::ArithmeticError(_) => "ArithmeticError",
+            ExecutionError::SystemError(_) => "SystemError",
+            ExecutionError::UserError(_) => "UserError",
+            #[cfg(any(with_wasmer, with_wasmtime))]
+            ExecutionError::WasmError(_) => "WasmError",
+            ExecutionError::DecompressionError(_) => "DecompressionError",
+            ExecutionError::InvalidPromise => "InvalidPromise",
+            ExecutionError::ReentrantCall(_) => "ReentrantCall",
+            ExecutionError::ApplicationAlreadyInstantiated(_) => "ApplicationAlreadyInstantiated",
+            ExecutionError::CrossApplicationCallInFinalize { .. } => {
+                "CrossApplicationCallInFinalize"
+            }
+            ExecutionError::UnexpectedCallStack(_) => "UnexpectedCallStack",
+            ExecutionError::ServiceWriteAttempt => "ServiceWriteAttempt",
+            ExecutionError::ApplicationBytecodeNotFound(_) => "ApplicationBytecodeNotFound",
+            ExecutionError::UnsupportedDynamicApplicationLoad(_) => {
+                "UnsupportedDynamicApplicationLoad"
+            }
+            ExecutionError::ExcessiveRead => "ExcessiveRead",
+            ExecutionError::ExcessiveWrite => "ExcessiveWrite",
+            ExecutionError::MaximumFuelExceeded => "MaximumFuelExceeded",
+            ExecutionError::ExecutedBlockTooLarge => "ExecutedBlockTooLarge",
+            ExecutionError::IncorrectChainId { .. } => "IncorrectChainId",
+            ExecutionError::TooManyMessages(_) => "TooManyMessages",
+            ExecutionError::MessageTooLarge(_) => "MessageTooLarge",
+            ExecutionError::TooManyEvents(_) => "TooManyEvents",
+            ExecutionError::EventTooLarge(_) => "EventTooLarge",
+            ExecutionError::TooManyRecipients { .. } => "TooManyRecipients",
+            ExecutionError::CallDepthExceeded { .. } => "CallDepthExceeded",
+            ExecutionError::FailedOperation { error, .. }
+            | ExecutionError::FailedCallChain { error, .. }
+            | ExecutionError::FailedUserAction { error, .. } => error.variant_name(),
+            ExecutionError::MissingRuntimeResponse => "MissingRuntimeResponse",
+            ExecutionError::InvalidBytecodeId(_) => "InvalidBytecodeId",
+            ExecutionError::OwnerIsNone => "OwnerIsNone",
+            ExecutionError::UnauthorizedApplication(_) => "UnauthorizedApplication",
+            ExecutionError::ReqwestError(_) => "ReqwestError",
+            ExecutionError::IoError(_) => "IoError",
+            ExecutionError::UnexpectedOracleResponse => "UnexpectedOracleResponse",
+            ExecutionError::JsonError(_) => "JsonError",
+            ExecutionError::BcsError(_) => "BcsError",
+            ExecutionError::OracleResponseMismatch => "OracleResponseMismatch",
+            ExecutionError::AssertBefore { .. } => "AssertBefore",
+            ExecutionError::EventKeyTooLong => "EventKeyTooLong",
+            ExecutionError::StreamNameTooLong => "StreamNameTooLong",
+            ExecutionError::TooManyRandomBytes => "TooManyRandomBytes",
+            ExecutionError::BlobTooLarge => "BlobTooLarge",
+            ExecutionError::BytecodeTooLarge => "BytecodeTooLarge",
+            ExecutionError::UnstableOracle => "UnstableOracle",
+            ExecutionError::ContractModuleSend(_) => "ContractModuleSend",
+            ExecutionError::ServiceModuleSend(_) => "ServiceModuleSend",
+            ExecutionError::UnsavedStateExport => "UnsavedStateExport",
+            ExecutionError::UnsupportedStateExportVersion(_) => "UnsupportedStateExportVersion",
+            ExecutionError::StateImportIntoNonEmptyContext => "StateImportIntoNonEmptyContext",
+            ExecutionError::FrameTooLarge(_) => "FrameTooLarge",
+            ExecutionError::StateImportHashMismatch { .. } => "StateImportHashMismatch",
+            ExecutionError::BlobsNotFound(_) => "BlobsNotFound",
+        }
+    }
+}
+
 impl From<ViewError> for ExecutionError {
     fn from(error: ViewError) -> Self {
         match error {
@@ -297,6 +430,38 @@ impl From<SystemExecutionError> for ExecutionError {
     }
 }
 
+/// A cross-application call that was entered but did not complete.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApplicationCall {
+    /// The application that performed the call.
+    pub caller_id: UserApplicationId,
+    /// The application that was called.
+    pub callee_id: UserApplicationId,
+    /// The size of the serialized argument passed to the callee.
+    pub argument_len: usize,
+}
+
+/// The cross-application calls that were in progress when an error occurred, from the
+/// outermost to the innermost.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CallChain(pub Vec<ApplicationCall>);
+
+impl fmt::Display for CallChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (depth, call) in self.0.iter().enumerate() {
+            if depth > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "  {depth}: {} -> {} ({} argument bytes)",
+                call.caller_id, call.callee_id, call.argument_len
+            )?;
+        }
+        Ok(())
+    }
+}
+
 /// The public entry points provided by the contract part of an application.
 pub trait UserContract {
     /// Instantiate the application state on the chain that owns the application.
@@ -353,7 +518,11 @@ impl ApplicationCallOutcome {
 
 /// Configuration options for the execution runtime available to applications.
 #[derive(Clone, Copy, Default)]
-pub struct ExecutionRuntimeConfig {}
+pub struct ExecutionRuntimeConfig {
+    /// Whether to record the state hash after each operation and message, to help find
+    /// where validators diverge. Hashing the state is expensive, so this is off by default.
+    pub trace_state_hashes: bool,
+}
 
 /// Requirements for the `extra` field in our state views (and notably the
 /// [`ExecutionStateView`]).
@@ -497,6 +666,28 @@ pub trait BaseRuntime {
     /// Reads the current ownership configuration for this chain.
     fn chain_ownership(&mut self) -> Result<ChainOwnership, ExecutionError>;
 
+    /// Returns `length` pseudo-random bytes.
+    ///
+    /// In contracts, the bytes only depend on the chain, the block height, the operation or
+    /// message being executed and the application, so that every validator obtains the same
+    /// ones. They are therefore predictable by anyone who knows these, including the block
+    /// proposer, who may choose which operations to include. They must not be used as secrets,
+    /// nor where a proposer could profit from retrying until the outcome suits them.
+    ///
+    /// In services, the bytes are not reproducible.
+    fn random_bytes(&mut self, length: u32) -> Result<Vec<u8>, ExecutionError>;
+
+    /// Computes the SHA-256 hash of `bytes`.
+    fn sha256(&mut self, bytes: Vec<u8>) -> Result<[u8; 32], ExecutionError>;
+
+    /// Checks whether `signature` is a valid Ed25519 signature of `message` by `public_key`.
+    fn verify_ed25519(
+        &mut self,
+        public_key: PublicKey,
+        message: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<bool, ExecutionError>;
+
     /// Tests whether a key exists in the key-value store
     #[cfg(feature = "test")]
     fn contains_key(&mut self, key: Vec<u8>) -> Result<bool, ExecutionError> {
@@ -949,6 +1140,29 @@ impl ExecutionOutcome {
             ExecutionOutcome::User(_, outcome) => outcome.messages.len(),
         }
     }
+
+    pub fn event_count(&self) -> usize {
+        match self {
+            ExecutionOutcome::System(outcome) => outcome.events.len(),
+            ExecutionOutcome::User(_, outcome) => outcome.events.len(),
+        }
+    }
+
+    /// Returns the destinations of the outgoing messages in this outcome.
+    pub fn destinations(&self) -> Vec<&Destination> {
+        match self {
+            ExecutionOutcome::System(outcome) => outcome
+                .messages
+                .iter()
+                .map(|message| &message.destination)
+                .collect(),
+            ExecutionOutcome::User(_, outcome) => outcome
+                .messages
+                .iter()
+                .map(|message| &message.destination)
+                .collect(),
+        }
+    }
 }
 
 impl<Message, Grant> RawExecutionOutcome<Message, Grant> {
@@ -61,6 +61,29 @@ pub struct ResourceControlPolicy {
     pub maximum_bytes_read_per_block: u64,
     /// The maximum data to write per block
     pub maximum_bytes_written_per_block: u64,
+    /// The maximum size of the argument of an outgoing user message, in bytes.
+    pub maximum_message_size: u64,
+    /// The maximum number of outgoing messages per transaction.
+    pub maximum_messages_per_transaction: u64,
+    /// The maximum depth of nested cross-application calls.
+    pub maximum_call_depth: u64,
+    /// The maximum size of the value of an event, in bytes.
+    pub maximum_event_value_size: u64,
+    /// The maximum number of events emitted per transaction.
+    pub maximum_events_per_transaction: u64,
+    /// The maximum number of distinct destinations of the messages sent in a block.
+    pub maximum_recipients_per_block: u64,
+    /// The maximum number of operations in a block.
+    pub maximum_operations_per_block: u64,
+    /// The maximum number of incoming messages received in a block.
+    pub maximum_incoming_messages_per_block: u64,
+    /// The fuel charged for each call to the `sha256` host function, besides the fuel per byte.
+    pub sha256_fuel: u64,
+    /// The fuel charged for each call to the `verify_ed25519` host function, besides the fuel
+    /// per byte.
+    pub verify_ed25519_fuel: u64,
+    /// The fuel charged per byte hashed by the cryptographic host functions.
+    pub hashing_fuel_per_byte: u64,
 }
 
 impl fmt::Display for ResourceControlPolicy {
@@ -85,6 +108,17 @@ impl fmt::Display for ResourceControlPolicy {
             maximum_block_proposal_size,
             maximum_bytes_read_per_block,
             maximum_bytes_written_per_block,
+            maximum_message_size,
+            maximum_messages_per_transaction,
+            maximum_call_depth,
+            maximum_event_value_size,
+            maximum_events_per_transaction,
+            maximum_recipients_per_block,
+            maximum_operations_per_block,
+            maximum_incoming_messages_per_block,
+            sha256_fuel,
+            verify_ed25519_fuel,
+            hashing_fuel_per_byte,
         } = self;
         write!(
             f,
@@ -107,7 +141,18 @@ impl fmt::Display for ResourceControlPolicy {
             {maximum_bytecode_size} maximum size of service and contract bytecode\n\
             {maximum_block_proposal_size} maximum size of a block proposal\n\
             {maximum_bytes_read_per_block} maximum number bytes read per block\n\
-            {maximum_bytes_written_per_block} maximum number bytes written per block",
+            {maximum_bytes_written_per_block} maximum number bytes written per block\n\
+            {maximum_message_size} maximum size of the argument of an outgoing message\n\
+            {maximum_messages_per_transaction} maximum number of outgoing messages per transaction\n\
+            {maximum_call_depth} maximum depth of nested cross-application calls\n\
+            {maximum_event_value_size} maximum size of the value of an event\n\
+            {maximum_events_per_transaction} maximum number of events emitted per transaction\n\
+            {maximum_recipients_per_block} maximum number of message destinations per block\n\
+            {maximum_operations_per_block} maximum number of operations per block\n\
+            {maximum_incoming_messages_per_block} maximum number of incoming messages per block\n\
+            {sha256_fuel} fuel per call to sha256\n\
+            {verify_ed25519_fuel} fuel per call to verify_ed25519\n\
+            {hashing_fuel_per_byte} fuel per byte hashed by a host function",
         )
     }
 }
@@ -134,6 +179,17 @@ impl Default for ResourceControlPolicy {
             maximum_block_proposal_size: u64::MAX,
             maximum_bytes_read_per_block: u64::MAX,
             maximum_bytes_written_per_block: u64::MAX,
+            maximum_message_size: u64::MAX,
+            maximum_messages_per_transaction: u64::MAX,
+            maximum_call_depth: u64::MAX,
+            maximum_event_value_size: u64::MAX,
+            maximum_events_per_transaction: u64::MAX,
+            maximum_recipients_per_block: u64::MAX,
+            maximum_operations_per_block: u64::MAX,
+            maximum_incoming_messages_per_block: u64::MAX,
+            sha256_fuel: 1_000,
+            verify_ed25519_fuel: 50_000,
+            hashing_fuel_per_byte: 10,
         }
     }
 }
@@ -190,6 +246,12 @@ impl ResourceControlPolicy {
         self.fuel_unit.try_mul(u128::from(fuel))
     }
 
+    /// Returns the fuel charged by a cryptographic host function for hashing `size` bytes.
+    pub(crate) fn hashing_fuel(&self, base_fuel: u64, size: u64) -> u64 {
+        size.saturating_mul(self.hashing_fuel_per_byte)
+            .saturating_add(base_fuel)
+    }
+
     /// Returns how much fuel can be paid with the given balance.
     pub(crate) fn remaining_fuel(&self, balance: Amount) -> u64 {
         u64::try_from(balance.saturating_div(self.fuel_unit)).unwrap_or(u64::MAX)
@@ -279,6 +341,17 @@ impl ResourceControlPolicy {
             maximum_block_proposal_size: 13_000_000,
             maximum_bytes_read_per_block: 100_000_000,
             maximum_bytes_written_per_block: 10_000_000,
+            maximum_message_size: 1_000_000,
+            maximum_messages_per_transaction: 1_000,
+            maximum_call_depth: 10,
+            maximum_event_value_size: 1_000_000,
+            maximum_events_per_transaction: 1_000,
+            maximum_recipients_per_block: 1_000,
+            maximum_operations_per_block: 1_000,
+            maximum_incoming_messages_per_block: 10_000,
+            sha256_fuel: 1_000,
+            verify_ed25519_fuel: 50_000,
+            hashing_fuel_per_byte: 10,
         }
     }
 }
@@ -58,6 +58,8 @@ pub struct ResourceTracker {
     pub message_bytes: u64,
     /// The amount allocated to message grants.
     pub grants: Amount,
+    /// The number of calls made to other applications.
+    pub application_calls: u32,
 }
 
 /// How to access the balance of an account.
@@ -185,6 +187,17 @@ where
         }
     }
 
+    /// Tracks a call from one application to another.
+    pub(crate) fn track_application_call(&mut self) -> Result<(), ExecutionError> {
+        self.tracker.as_mut().application_calls = self
+            .tracker
+            .as_mut()
+            .application_calls
+            .checked_add(1)
+            .ok_or(ArithmeticError::Overflow)?;
+        Ok(())
+    }
+
     /// Tracks a number of fuel units used.
     pub(crate) fn track_fuel(&mut self, fuel: u64) -> Result<(), ExecutionError> {
         self.tracker.as_mut().fuel = self
@@ -251,19 +264,6 @@ where
         self.update_balance(self.policy.bytes_written_price(count)?)?;
         Ok(())
     }
-
-    /// Tracks a change in the number of bytes stored.
-    // TODO(#1536): This is not fully implemented.
-    #[allow(dead_code)]
-    pub(crate) fn track_stored_bytes(&mut self, delta: i32) -> Result<(), ExecutionError> {
-        self.tracker.as_mut().bytes_stored = self
-            .tracker
-            .as_mut()
-            .bytes_stored
-            .checked_add(delta)
-            .ok_or(ArithmeticError::Overflow)?;
-        Ok(())
-    }
 }
 
 impl<Account, Tracker> ResourceController<Account, Tracker>
@@ -309,6 +309,28 @@ where
         );
         Ok(())
     }
+
+    /// Tracks the number of distinct destinations of the messages sent in the block so far.
+    pub fn track_recipients(&mut self, count: usize) -> Result<(), ExecutionError> {
+        let count = u64::try_from(count).unwrap_or(u64::MAX);
+        let max = self.policy.maximum_recipients_per_block;
+        ensure!(
+            count <= max,
+            ExecutionError::TooManyRecipients { count, max }
+        );
+        Ok(())
+    }
+
+    /// Tracks a change in the number of bytes stored.
+    pub(crate) fn track_stored_bytes(&mut self, delta: i32) -> Result<(), ExecutionError> {
+        self.tracker.as_mut().bytes_stored = self
+            .tracker
+            .as_mut()
+            .bytes_stored
+            .checked_add(delta)
+            .ok_or(ArithmeticError::Overflow)?;
+        Ok(())
+    }
 }
 
 // The simplest `BalanceHolder` is an `Amount`.
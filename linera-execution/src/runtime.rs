@@ -3,6 +3,7 @@
 
 use std::{
     collections::{hash_map, BTreeMap, HashMap, HashSet},
+    hash::{BuildHasher, Hasher, RandomState},
     mem,
     ops::{Deref, DerefMut},
     sync::{Arc, Mutex},
@@ -10,7 +11,7 @@ use std::{
 
 use custom_debug_derive::Debug;
 use linera_base::{
-    crypto::CryptoHash,
+    crypto::{self, BcsHashable, CryptoHash, PublicKey, Signature},
     data_types::{
         Amount, ApplicationPermissions, ArithmeticError, BlockHeight, OracleResponse, Resources,
         SendMessageRequest, Timestamp,
@@ -24,6 +25,7 @@ use linera_base::{
 };
 use linera_views::batch::Batch;
 use oneshot::Receiver;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     execution::UserAction,
@@ -31,11 +33,11 @@ use crate::{
     resources::ResourceController,
     system::CreateApplicationResult,
     util::{ReceiverExt, UnboundedSenderExt},
-    BaseRuntime, BytecodeId, ContractRuntime, ExecutionError, FinalizeContext, MessageContext,
-    Operation, OperationContext, QueryContext, QueryOutcome, RawExecutionOutcome, ServiceRuntime,
-    TransactionTracker, UserApplicationDescription, UserApplicationId, UserContractCode,
-    UserContractInstance, UserServiceCode, UserServiceInstance, MAX_EVENT_KEY_LEN,
-    MAX_STREAM_NAME_LEN,
+    ApplicationCall, BaseRuntime, BytecodeId, CallChain, ContractRuntime, ExecutionError,
+    FinalizeContext, MessageContext, Operation, OperationContext, QueryContext, QueryOutcome,
+    RawExecutionOutcome, ServiceRuntime, TransactionTracker, UserApplicationDescription,
+    UserApplicationId, UserContractCode, UserContractInstance, UserServiceCode,
+    UserServiceInstance, MAX_EVENT_KEY_LEN, MAX_RANDOM_BYTES_LEN, MAX_STREAM_NAME_LEN,
 };
 
 #[cfg(test)]
@@ -93,6 +95,8 @@ pub struct SyncRuntimeInternal<UserInstance> {
     call_stack: Vec<ApplicationStatus>,
     /// The set of the IDs of the applications that are in the `call_stack`.
     active_applications: HashSet<UserApplicationId>,
+    /// The cross-application calls in progress, reported if an error escapes user code.
+    call_chain: Vec<ApplicationCall>,
     /// The tracking information for this transaction.
     transaction_tracker: TransactionTracker,
     /// The operations scheduled during this query.
@@ -106,8 +110,44 @@ pub struct SyncRuntimeInternal<UserInstance> {
     refund_grant_to: Option<Account>,
     /// Controller to track fuel and storage consumption.
     resource_controller: ResourceController,
+
+    /// The seed from which pseudo-random bytes are derived.
+    random_seed: CryptoHash,
+    /// The number of hashes derived from the `random_seed` so far.
+    random_counter: u64,
+}
+
+/// What the seed of the pseudo-random bytes available to applications is derived from.
+#[derive(Serialize, Deserialize)]
+pub(crate) enum RandomSeed {
+    /// A contract executing an operation or a message, with inputs that are the same on every
+    /// validator.
+    Action {
+        chain_id: ChainId,
+        height: BlockHeight,
+        operation_index: Option<u32>,
+        message_id: Option<MessageId>,
+        application_id: UserApplicationId,
+    },
+    /// A service handling a query, with some local entropy.
+    Query {
+        chain_id: ChainId,
+        local_time: Timestamp,
+        entropy: u64,
+    },
 }
 
+impl BcsHashable<'_> for RandomSeed {}
+
+/// The input of each hash from which pseudo-random bytes are taken.
+#[derive(Serialize, Deserialize)]
+struct RandomBlock {
+    seed: CryptoHash,
+    counter: u64,
+}
+
+impl BcsHashable<'_> for RandomBlock {}
+
 /// The runtime status of an application.
 #[derive(Debug)]
 struct ApplicationStatus {
@@ -296,6 +336,7 @@ impl<UserInstance> SyncRuntimeInternal<UserInstance> {
         refund_grant_to: Option<Account>,
         resource_controller: ResourceController,
         transaction_tracker: TransactionTracker,
+        random_seed: CryptoHash,
     ) -> Self {
         Self {
             chain_id,
@@ -310,14 +351,26 @@ impl<UserInstance> SyncRuntimeInternal<UserInstance> {
             loaded_applications: HashMap::new(),
             call_stack: Vec::new(),
             active_applications: HashSet::new(),
+            call_chain: Vec::new(),
             view_user_states: BTreeMap::new(),
             refund_grant_to,
             resource_controller,
             transaction_tracker,
             scheduled_operations: Vec::new(),
+            random_seed,
+            random_counter: 0,
         }
     }
 
+    /// Charges the fuel for a cryptographic host function that hashes `bytes`.
+    fn track_hashing_fuel(&mut self, base_fuel: u64, bytes: &[u8]) -> Result<(), ExecutionError> {
+        let fuel = self
+            .resource_controller
+            .policy
+            .hashing_fuel(base_fuel, bytes.len() as u64);
+        self.resource_controller.track_fuel(fuel)
+    }
+
     /// Returns the [`ApplicationStatus`] of the current application.
     ///
     /// The current application is the last to be pushed to the `call_stack`.
@@ -368,6 +421,34 @@ impl<UserInstance> SyncRuntimeInternal<UserInstance> {
         status
     }
 
+    /// Removes the [`current_application`][`Self::current_application`] from the `call_stack`,
+    /// checking that it is the application with the `expected_id`.
+    ///
+    /// If the call stack is inconsistent, it is discarded and an error listing the observed
+    /// stack is returned instead.
+    fn pop_expected_application(
+        &mut self,
+        expected_id: UserApplicationId,
+    ) -> Result<ApplicationStatus, ExecutionError> {
+        if self
+            .call_stack
+            .last()
+            .is_some_and(|status| status.id == expected_id)
+        {
+            Ok(self.pop_application())
+        } else {
+            Err(self.discard_call_stack())
+        }
+    }
+
+    /// Clears the `call_stack` and returns an [`ExecutionError::UnexpectedCallStack`] with the
+    /// IDs of the applications that were still in it.
+    fn discard_call_stack(&mut self) -> ExecutionError {
+        self.active_applications.clear();
+        let call_stack = self.call_stack.drain(..).map(|status| status.id).collect();
+        ExecutionError::UnexpectedCallStack(call_stack)
+    }
+
     /// Ensures that a call to `application_id` is not-reentrant.
     ///
     /// Returns an error if there already is an entry for `application_id` in the call stack.
@@ -417,15 +498,40 @@ impl SyncRuntimeInternal<UserContractInstance> {
         }
     }
 
+    /// Returns an error if the application `id` already has stored state, which instantiating it
+    /// again would silently overwrite.
+    fn check_not_instantiated(&mut self, id: UserApplicationId) -> Result<(), ExecutionError> {
+        let state_size = self
+            .execution_state_sender
+            .send_request(|callback| ExecutionRequest::ApplicationStateSize { id, callback })?
+            .recv_response()?;
+        ensure!(
+            state_size == 0,
+            ExecutionError::ApplicationAlreadyInstantiated(id)
+        );
+        Ok(())
+    }
+
     /// Configures the runtime for executing a call to a different contract.
     fn prepare_for_call(
         &mut self,
         this: ContractSyncRuntimeHandle,
         authenticated: bool,
         callee_id: UserApplicationId,
+        argument_len: usize,
     ) -> Result<(Arc<Mutex<UserContractInstance>>, OperationContext), ExecutionError> {
         self.check_for_reentrancy(callee_id)?;
 
+        // The application executing the operation or message is at depth zero.
+        let depth = u64::try_from(self.call_stack.len()).unwrap_or(u64::MAX);
+        ensure!(
+            depth <= self.resource_controller.policy.maximum_call_depth,
+            ExecutionError::CallDepthExceeded {
+                depth,
+                application_id: callee_id,
+            }
+        );
+
         ensure!(
             !self.is_finalizing,
             ExecutionError::CrossApplicationCallInFinalize {
@@ -462,6 +568,11 @@ impl SyncRuntimeInternal<UserContractInstance> {
             signer: authenticated_signer,
             outcome: RawExecutionOutcome::default(),
         });
+        self.call_chain.push(ApplicationCall {
+            caller_id,
+            callee_id,
+            argument_len,
+        });
         Ok((application.instance, callee_context))
     }
 
@@ -475,10 +586,26 @@ impl SyncRuntimeInternal<UserContractInstance> {
         } = self.pop_application();
 
         self.handle_outcome(outcome, signer, callee_id)?;
+        self.call_chain.pop();
 
         Ok(())
     }
 
+    /// Attaches the cross-application calls that were still in progress to an `error` escaping
+    /// user code.
+    fn attach_call_chain(&mut self, error: ExecutionError) -> ExecutionError {
+        let call_chain = mem::take(&mut self.call_chain);
+        match error {
+            // Missing blobs are reported unchanged so that callers can fetch them and retry.
+            ExecutionError::BlobsNotFound(_) => error,
+            error if call_chain.is_empty() => error,
+            error => ExecutionError::FailedCallChain {
+                call_chain: CallChain(call_chain),
+                error: Box::new(error),
+            },
+        }
+    }
+
     /// Handles a newly produced [`RawExecutionOutcome`], conditioning and adding it to the stack
     /// of outcomes.
     ///
@@ -490,6 +617,34 @@ impl SyncRuntimeInternal<UserContractInstance> {
         signer: Option<Owner>,
         application_id: UserApplicationId,
     ) -> Result<(), ExecutionError> {
+        let policy = &self.resource_controller.policy;
+        for message in &raw_outcome.messages {
+            ensure!(
+                u64::try_from(message.message.len())
+                    .is_ok_and(|size| size <= policy.maximum_message_size),
+                ExecutionError::MessageTooLarge(application_id)
+            );
+        }
+        let message_count = self.transaction_tracker.message_count() + raw_outcome.messages.len();
+        ensure!(
+            u64::try_from(message_count)
+                .is_ok_and(|count| count <= policy.maximum_messages_per_transaction),
+            ExecutionError::TooManyMessages(application_id)
+        );
+        for (_, _, value) in &raw_outcome.events {
+            ensure!(
+                u64::try_from(value.len())
+                    .is_ok_and(|size| size <= policy.maximum_event_value_size),
+                ExecutionError::EventTooLarge(application_id)
+            );
+        }
+        let event_count = self.transaction_tracker.event_count() + raw_outcome.events.len();
+        ensure!(
+            u64::try_from(event_count)
+                .is_ok_and(|count| count <= policy.maximum_events_per_transaction),
+            ExecutionError::TooManyEvents(application_id)
+        );
+
         let outcome = raw_outcome
             .with_refund_grant_to(self.refund_grant_to)
             .with_authenticated_signer(signer)
@@ -619,6 +774,23 @@ impl<UserInstance> BaseRuntime for SyncRuntimeHandle<UserInstance> {
         self.inner().chain_ownership()
     }
 
+    fn random_bytes(&mut self, length: u32) -> Result<Vec<u8>, ExecutionError> {
+        self.inner().random_bytes(length)
+    }
+
+    fn sha256(&mut self, bytes: Vec<u8>) -> Result<[u8; 32], ExecutionError> {
+        self.inner().sha256(bytes)
+    }
+
+    fn verify_ed25519(
+        &mut self,
+        public_key: PublicKey,
+        message: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<bool, ExecutionError> {
+        self.inner().verify_ed25519(public_key, message, signature)
+    }
+
     fn contains_key_new(&mut self, key: Vec<u8>) -> Result<Self::ContainsKey, ExecutionError> {
         self.inner().contains_key_new(key)
     }
@@ -792,6 +964,45 @@ impl<UserInstance> BaseRuntime for SyncRuntimeInternal<UserInstance> {
             .recv_response()
     }
 
+    fn random_bytes(&mut self, length: u32) -> Result<Vec<u8>, ExecutionError> {
+        ensure!(
+            length <= MAX_RANDOM_BYTES_LEN,
+            ExecutionError::TooManyRandomBytes
+        );
+        let length = length as usize;
+        let mut bytes = Vec::with_capacity(length);
+        while bytes.len() < length {
+            let block = CryptoHash::new(&RandomBlock {
+                seed: self.random_seed,
+                counter: self.random_counter,
+            });
+            self.random_counter += 1;
+            bytes.extend_from_slice(block.as_bytes().as_slice());
+        }
+        bytes.truncate(length);
+        Ok(bytes)
+    }
+
+    fn sha256(&mut self, bytes: Vec<u8>) -> Result<[u8; 32], ExecutionError> {
+        let base_fuel = self.resource_controller.policy.sha256_fuel;
+        self.track_hashing_fuel(base_fuel, &bytes)?;
+        Ok(crypto::sha256(&bytes))
+    }
+
+    fn verify_ed25519(
+        &mut self,
+        public_key: PublicKey,
+        message: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<bool, ExecutionError> {
+        let base_fuel = self.resource_controller.policy.verify_ed25519_fuel;
+        self.track_hashing_fuel(base_fuel, &message)?;
+        let Ok(signature) = Signature::from_slice(&signature) else {
+            return Ok(false);
+        };
+        Ok(signature.check_bytes(&message, public_key).is_ok())
+    }
+
     fn contains_key_new(&mut self, key: Vec<u8>) -> Result<Self::ContainsKey, ExecutionError> {
         let id = self.application_id()?;
         let state = self.view_user_states.entry(id).or_default();
@@ -1081,6 +1292,7 @@ impl ContractSyncRuntime {
         resource_controller: ResourceController,
         action: &UserAction,
         txn_tracker: TransactionTracker,
+        random_seed: CryptoHash,
     ) -> Self {
         SyncRuntime(Some(ContractSyncRuntimeHandle::from(
             SyncRuntimeInternal::new(
@@ -1098,6 +1310,7 @@ impl ContractSyncRuntime {
                 refund_grant_to,
                 resource_controller,
                 txn_tracker,
+                random_seed,
             ),
         )))
     }
@@ -1162,6 +1375,9 @@ impl ContractSyncRuntimeHandle {
             assert_eq!(runtime.chain_id, chain_id);
             assert_eq!(runtime.height, action.height());
         }
+        if matches!(action, UserAction::Instantiate(..)) {
+            self.inner().check_not_instantiated(application_id)?;
+        }
         self.execute(application_id, action.signer(), move |code| match action {
             UserAction::Instantiate(context, argument) => code.instantiate(context, argument),
             UserAction::Operation(context, operation) => {
@@ -1220,15 +1436,17 @@ impl ContractSyncRuntimeHandle {
                 .instance
                 .try_lock()
                 .expect("Application should not be already executing"),
-        )?;
+        )
+        .map_err(|error| self.inner().attach_call_chain(error))?;
 
         let mut runtime = self.inner();
-        let application_status = runtime.pop_application();
+        let application_status = runtime.pop_expected_application(application_id)?;
+        if !runtime.call_stack.is_empty() {
+            return Err(runtime.discard_call_stack());
+        }
         assert_eq!(application_status.caller_id, None);
-        assert_eq!(application_status.id, application_id);
         assert_eq!(application_status.parameters, contract.parameters);
         assert_eq!(application_status.signer, signer);
-        assert!(runtime.call_stack.is_empty());
 
         runtime.handle_outcome(application_status.outcome, signer, application_id)?;
 
@@ -1358,9 +1576,13 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
         callee_id: UserApplicationId,
         argument: Vec<u8>,
     ) -> Result<Vec<u8>, ExecutionError> {
-        let (contract, context) =
-            self.inner()
-                .prepare_for_call(self.clone(), authenticated, callee_id)?;
+        let (contract, context) = self.inner().prepare_for_call(
+            self.clone(),
+            authenticated,
+            callee_id,
+            argument.len(),
+        )?;
+        self.inner().resource_controller.track_application_call()?;
 
         let value = contract
             .try_lock()
@@ -1405,7 +1627,7 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
             index: this.transaction_tracker.next_message_index(),
         };
         let chain_id = ChainId::child(message_id);
-        let [open_chain_message, subscribe_message] = this
+        let messages = this
             .execution_state_sender
             .send_request(|callback| ExecutionRequest::OpenChain {
                 ownership,
@@ -1415,9 +1637,10 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
                 callback,
             })?
             .recv_response()?;
-        let outcome = RawExecutionOutcome::default()
-            .with_message(open_chain_message)
-            .with_message(subscribe_message);
+        let outcome = RawExecutionOutcome {
+            messages,
+            ..RawExecutionOutcome::default()
+        };
         this.transaction_tracker.add_system_outcome(outcome)?;
         Ok((message_id, chain_id))
     }
@@ -1467,6 +1690,7 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
 
         let CreateApplicationResult {
             app_id,
+            description,
             message,
             blobs_to_register,
         } = self
@@ -1489,8 +1713,14 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
         self.inner()
             .transaction_tracker
             .add_system_outcome(outcome)?;
+        self.inner()
+            .transaction_tracker
+            .add_created_application(app_id, description);
 
-        let (contract, context) = self.inner().prepare_for_call(self.clone(), true, app_id)?;
+        self.inner().check_not_instantiated(app_id)?;
+        let (contract, context) =
+            self.inner()
+                .prepare_for_call(self.clone(), true, app_id, argument.len())?;
 
         contract
             .try_lock()
@@ -1515,13 +1745,16 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
         )?;
         this.resource_controller
             .track_bytes_written(batch.size() as u64)?;
-        this.execution_state_sender
+        let stored_bytes_delta = this
+            .execution_state_sender
             .send_request(|callback| ExecutionRequest::WriteBatch {
                 id,
                 batch,
                 callback,
             })?
             .recv_response()?;
+        this.resource_controller
+            .track_stored_bytes(stored_bytes_delta)?;
         Ok(())
     }
 
@@ -1545,6 +1778,12 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
 impl ServiceSyncRuntime {
     /// Creates a new [`ServiceSyncRuntime`] ready to execute using a provided [`QueryContext`].
     pub fn new(execution_state_sender: ExecutionStateSender, context: QueryContext) -> Self {
+        // Queries don't affect consensus, so their pseudo-random bytes need not be reproducible.
+        let random_seed = CryptoHash::new(&RandomSeed::Query {
+            chain_id: context.chain_id,
+            local_time: context.local_time,
+            entropy: RandomState::new().build_hasher().finish(),
+        });
         let runtime = SyncRuntime(Some(
             SyncRuntimeInternal::new(
                 context.chain_id,
@@ -1557,6 +1796,7 @@ impl ServiceSyncRuntime {
                 None,
                 ResourceController::default(),
                 TransactionTracker::default(),
+                random_seed,
             )
             .into(),
         ));
@@ -1631,7 +1871,13 @@ impl ServiceSyncRuntime {
         query: Vec<u8>,
     ) -> Result<QueryOutcome<Vec<u8>>, ExecutionError> {
         let this = self.handle_mut();
-        let response = this.try_query_application(application_id, query)?;
+        let response = this
+            .try_query_application(application_id, query)
+            .inspect_err(|_| {
+                // Applications that failed may still be in the call stack, and the runtime is
+                // reused for the following queries.
+                this.inner().discard_call_stack();
+            })?;
         let operations = mem::take(&mut this.inner().scheduled_operations);
 
         Ok(QueryOutcome {
@@ -1657,6 +1903,7 @@ impl ServiceRuntime for ServiceSyncRuntimeHandle {
     ) -> Result<Vec<u8>, ExecutionError> {
         let (query_context, service) = {
             let mut this = self.inner();
+            this.check_for_reentrancy(queried_id)?;
 
             // Load the application.
             let application = this.load_service_instance(self.clone(), queried_id)?;
@@ -1679,7 +1926,7 @@ impl ServiceRuntime for ServiceSyncRuntimeHandle {
             .try_lock()
             .expect("Applications should not have reentrant calls")
             .handle_query(query_context, argument)?;
-        self.inner().pop_application();
+        self.inner().pop_expected_application(queried_id)?;
         Ok(response)
     }
 
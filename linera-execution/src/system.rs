@@ -9,7 +9,7 @@ mod tests;
 #[cfg(with_metrics)]
 use std::sync::LazyLock;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt::{self, Display, Formatter},
     iter,
 };
@@ -96,6 +96,8 @@ pub struct SystemExecutionStateView<C> {
     pub closed: HashedRegisterView<C, bool>,
     /// Permissions for applications on this chain.
     pub application_permissions: HashedRegisterView<C, ApplicationPermissions>,
+    /// The owners allowed to publish bytecode, or `None` if anyone may publish.
+    pub bytecode_publishers: HashedRegisterView<C, Option<BTreeSet<Owner>>>,
     /// Blobs that have been used or published on this chain.
     pub used_blobs: HashedSetView<C, BlobId>,
 }
@@ -109,6 +111,8 @@ pub struct OpenChainConfig {
     pub committees: BTreeMap<Epoch, Committee>,
     pub balance: Amount,
     pub application_permissions: ApplicationPermissions,
+    /// The owners allowed to publish bytecode. This is always inherited from the parent chain.
+    pub bytecode_publishers: Option<BTreeSet<Owner>>,
 }
 
 /// A system operation.
@@ -191,6 +195,12 @@ pub enum SystemOperation {
     },
     /// Operations that are only allowed on the admin chain.
     Admin(AdminOperation),
+    /// Removes the stored state of a user application, e.g. after it was retired. Only chain
+    /// owners can remove the state, and only if no other registered application requires it.
+    RemoveApplicationState { application_id: UserApplicationId },
+    /// Removes the descriptions of applications that are neither used on this chain nor
+    /// required by an application that is. Only chain owners can prune the registry.
+    PruneRegistry,
 }
 
 /// Operations that are only allowed on the admin chain.
@@ -204,6 +214,10 @@ pub enum AdminOperation {
     /// blocks from the retired epoch will not be accepted until they are followed (hence
     /// re-certified) by a block certified by a recent committee.
     RemoveCommittee { epoch: Epoch },
+    /// Restricts the publication of bytecode to the given owners, or lifts the restriction
+    /// if `None`. The resulting `SetBytecodePublishers` message is sent to the subscribers
+    /// of the admin chain.
+    SetBytecodePublishers { publishers: Option<BTreeSet<Owner>> },
 }
 
 /// A system message meant to be executed on a remote chain.
@@ -252,6 +266,8 @@ pub enum SystemMessage {
     /// Requests a `RegisterApplication` message from the target chain to register the specified
     /// application on the sender chain.
     RequestApplication(UserApplicationId),
+    /// Updates the owners allowed to publish bytecode.
+    SetBytecodePublishers { publishers: Option<BTreeSet<Owner>> },
 }
 
 /// A query to the system state.
@@ -350,6 +366,7 @@ impl UserData {
 #[derive(Clone, Debug)]
 pub struct CreateApplicationResult {
     pub app_id: UserApplicationId,
+    pub description: UserApplicationDescription,
     pub message: RawOutgoingMessage<SystemMessage, Amount>,
     pub blobs_to_register: Vec<BlobId>,
 }
@@ -385,6 +402,12 @@ pub enum SystemExecutionError {
     InvalidCommitteeCreation,
     #[error("Failed to remove committee")]
     InvalidCommitteeRemoval,
+    #[error("Cannot remove the committee of the current epoch")]
+    CurrentCommitteeRemoval,
+    #[error("Bytecode publishers can only be updated by the admin chain")]
+    BytecodePublishersUpdateFromNonAdminChain,
+    #[error("Signer {0:?} is not authorized to publish bytecode")]
+    UnauthorizedBytecodePublisher(Option<Owner>),
     #[error(
         "Chain {0} tried to subscribe to the admin channel ({1}) of a chain that is not the admin chain"
     )]
@@ -409,8 +432,21 @@ pub enum SystemExecutionError {
     TicksOutOfOrder,
     #[error("Application {0:?} is not registered by the chain")]
     UnknownApplicationId(Box<UserApplicationId>),
+    #[error("Only chain owners can remove the state of an application")]
+    UnauthorizedApplicationStateRemoval,
+    #[error("Only chain owners can prune the application registry")]
+    UnauthorizedRegistryPruning,
+    #[error("Application {application_id:?} is still required by application {dependent_id:?}")]
+    ApplicationStillRequired {
+        application_id: Box<UserApplicationId>,
+        dependent_id: Box<UserApplicationId>,
+    },
+    #[error("Cyclic application dependency: {path:?}")]
+    CyclicApplicationDependency { path: Vec<UserApplicationId> },
     #[error("Chain is not active yet.")]
     InactiveChain,
+    #[error("The chain ownership must include an owner or super owner, or have no fallback delay")]
+    EmptyOwnership,
 
     #[error("Blobs not found: {0:?}")]
     BlobsNotFound(Vec<BlobId>),
@@ -479,13 +515,15 @@ where
                 open_multi_leader_rounds,
                 timeout_config,
             } => {
-                self.ownership.set(ChainOwnership {
+                let ownership = ChainOwnership {
                     super_owners: super_owners.into_iter().collect(),
                     owners: owners.into_iter().collect(),
                     multi_leader_rounds,
                     open_multi_leader_rounds,
                     timeout_config,
-                });
+                };
+                ensure!(ownership.is_active(), SystemExecutionError::EmptyOwnership);
+                self.ownership.set(ownership);
             }
             ChangeApplicationPermissions(application_permissions) => {
                 self.application_permissions.set(application_permissions);
@@ -556,6 +594,10 @@ where
                         outcome.messages.push(message);
                     }
                     AdminOperation::RemoveCommittee { epoch } => {
+                        ensure!(
+                            *self.epoch.get() != Some(epoch),
+                            SystemExecutionError::CurrentCommitteeRemoval
+                        );
                         ensure!(
                             self.committees.get_mut().remove(&epoch).is_some(),
                             SystemExecutionError::InvalidCommitteeRemoval
@@ -569,6 +611,17 @@ where
                         };
                         outcome.messages.push(message);
                     }
+                    AdminOperation::SetBytecodePublishers { publishers } => {
+                        self.bytecode_publishers.set(publishers.clone());
+                        let message = RawOutgoingMessage {
+                            destination: Destination::Subscribers(SystemChannel::Admin.name()),
+                            authenticated: false,
+                            grant: Amount::ZERO,
+                            kind: MessageKind::Protected,
+                            message: SystemMessage::SetBytecodePublishers { publishers },
+                        };
+                        outcome.messages.push(message);
+                    }
                 }
             }
             Subscribe { chain_id, channel } => {
@@ -626,6 +679,13 @@ where
                 outcome.messages.push(message);
             }
             PublishBytecode { bytecode_id } => {
+                if let Some(publishers) = self.bytecode_publishers.get() {
+                    let signer = context.authenticated_signer;
+                    ensure!(
+                        signer.is_some_and(|owner| publishers.contains(&owner)),
+                        SystemExecutionError::UnauthorizedBytecodePublisher(signer)
+                    );
+                }
                 self.blob_published(&BlobId::new(
                     bytecode_id.contract_blob_hash,
                     BlobType::ContractBytecode,
@@ -644,6 +704,7 @@ where
                 let next_message_id = context.next_message_id(txn_tracker.next_message_index());
                 let CreateApplicationResult {
                     app_id,
+                    description,
                     message,
                     blobs_to_register,
                 } = self
@@ -656,6 +717,7 @@ where
                     .await?;
                 self.record_bytecode_blobs(blobs_to_register, txn_tracker)
                     .await?;
+                txn_tracker.add_created_application(app_id, description);
                 outcome.messages.push(message);
                 new_application = Some((app_id, instantiation_argument));
             }
@@ -679,6 +741,28 @@ where
                 self.read_blob_content(blob_id).await?;
                 self.blob_used(Some(txn_tracker), blob_id).await?;
             }
+            RemoveApplicationState { application_id } => {
+                ensure!(
+                    context
+                        .authenticated_signer
+                        .is_some_and(|signer| self.ownership.get().verify_owner(&signer)),
+                    SystemExecutionError::UnauthorizedApplicationStateRemoval
+                );
+                if let Some(dependent_id) = self.registry.find_dependent(application_id).await? {
+                    return Err(SystemExecutionError::ApplicationStillRequired {
+                        application_id: Box::new(application_id),
+                        dependent_id: Box::new(dependent_id),
+                    });
+                }
+            }
+            PruneRegistry => {
+                ensure!(
+                    context
+                        .authenticated_signer
+                        .is_some_and(|signer| self.ownership.get().verify_owner(&signer)),
+                    SystemExecutionError::UnauthorizedRegistryPruning
+                );
+            }
         }
 
         txn_tracker.add_system_outcome(outcome)?;
@@ -868,6 +952,13 @@ where
             RemoveCommittee { epoch } => {
                 self.committees.get_mut().remove(&epoch);
             }
+            SetBytecodePublishers { publishers } => {
+                ensure!(
+                    *self.admin_id.get() == Some(context.message_id.chain_id),
+                    SystemExecutionError::BytecodePublishersUpdateFromNonAdminChain
+                );
+                self.bytecode_publishers.set(publishers);
+            }
             RegisterApplications { applications } => {
                 for application in applications {
                     self.check_and_record_bytecode_blobs(&application.bytecode_id, txn_tracker)
@@ -915,6 +1006,7 @@ where
             committees,
             balance,
             application_permissions,
+            bytecode_publishers,
         } = config;
         let description = ChainDescription::Child(message_id);
         self.description.set(Some(description));
@@ -931,6 +1023,7 @@ where
         self.timestamp.set(timestamp);
         self.balance.set(balance);
         self.application_permissions.set(application_permissions);
+        self.bytecode_publishers.set(bytecode_publishers);
     }
 
     pub async fn handle_query(
@@ -948,13 +1041,17 @@ where
         })
     }
 
-    /// Returns the messages to open a new chain, and subtracts the new chain's balance
-    /// from this chain's.
+    /// Prepares the messages that open a new child chain with the given `config`, and
+    /// subtracts the new chain's balance from this chain's.
+    ///
+    /// The applications that the new chain is restricted to, or that are mandatory on it, are
+    /// registered on the new chain together with the `OpenChain` message, so that they can be
+    /// used in its first block.
     pub async fn open_chain(
         &mut self,
-        config: OpenChainConfig,
+        mut config: OpenChainConfig,
         next_message_id: MessageId,
-    ) -> Result<[RawOutgoingMessage<SystemMessage, Amount>; 2], SystemExecutionError> {
+    ) -> Result<Vec<RawOutgoingMessage<SystemMessage, Amount>>, SystemExecutionError> {
         let child_id = ChainId::child(next_message_id);
         ensure!(
             self.admin_id.get().as_ref() == Some(&config.admin_id),
@@ -972,19 +1069,43 @@ where
                 epoch: config.epoch,
             }
         );
+        let permissions = &config.application_permissions;
+        let applications_to_register = permissions
+            .execute_operations
+            .iter()
+            .flatten()
+            .chain(&permissions.mandatory_applications)
+            .copied()
+            .collect::<BTreeSet<_>>();
+        let applications = self
+            .registry
+            .describe_applications_with_dependencies(applications_to_register.into_iter().collect())
+            .await?;
         self.debit(None, config.balance).await?;
-        let open_chain_message = RawOutgoingMessage {
+        config
+            .bytecode_publishers
+            .clone_from(self.bytecode_publishers.get());
+        let mut messages = vec![RawOutgoingMessage {
             destination: Destination::Recipient(child_id),
             authenticated: false,
             grant: Amount::ZERO,
             kind: MessageKind::Protected,
             message: SystemMessage::OpenChain(config),
-        };
+        }];
+        if !applications.is_empty() {
+            messages.push(RawOutgoingMessage {
+                destination: Destination::Recipient(child_id),
+                authenticated: false,
+                grant: Amount::ZERO,
+                kind: MessageKind::Simple,
+                message: SystemMessage::RegisterApplications { applications },
+            });
+        }
         let subscription = ChannelSubscription {
             chain_id: admin_id,
             name: SystemChannel::Admin.name(),
         };
-        let subscribe_message = RawOutgoingMessage {
+        messages.push(RawOutgoingMessage {
             destination: Destination::Recipient(admin_id),
             authenticated: false,
             grant: Amount::ZERO,
@@ -993,8 +1114,8 @@ where
                 id: child_id,
                 subscription,
             },
-        };
-        Ok([open_chain_message, subscribe_message])
+        });
+        Ok(messages)
     }
 
     pub async fn close_chain(
@@ -1045,7 +1166,8 @@ where
                 blobs_to_register.push(service_bytecode_blob_id);
             }
         }
-        self.registry
+        let description = self
+            .registry
             .register_new_application(id, parameters, required_application_ids)
             .await?;
         // Send a message to ourself to increment the message ID.
@@ -1059,6 +1181,7 @@ where
 
         Ok(CreateApplicationResult {
             app_id: id,
+            description,
             message,
             blobs_to_register,
         })
@@ -50,6 +50,8 @@ pub struct SystemExecutionState {
     #[debug(skip_if = Not::not)]
     pub closed: bool,
     pub application_permissions: ApplicationPermissions,
+    #[debug(skip_if = Option::is_none)]
+    pub bytecode_publishers: Option<BTreeSet<Owner>>,
     #[debug(skip_if = Vec::is_empty)]
     pub extra_blobs: Vec<Blob>,
     #[debug(skip_if = BTreeMap::is_empty)]
@@ -97,6 +99,27 @@ impl SystemExecutionState {
         chain_id: ChainId,
         execution_runtime_config: ExecutionRuntimeConfig,
     ) -> ExecutionStateView<MemoryContext<TestExecutionRuntimeContext>> {
+        let extra = TestExecutionRuntimeContext::new(chain_id, execution_runtime_config);
+        let namespace = generate_test_namespace();
+        let root_key = &[];
+        let context = MemoryContext::new_for_testing(
+            TEST_MEMORY_MAX_STREAM_QUERIES,
+            &namespace,
+            root_key,
+            extra,
+        );
+        self.into_view_with_context(context).await
+    }
+
+    /// Writes this state into a new [`ExecutionStateView`] using the given `context`, which
+    /// can be backed by any storage.
+    ///
+    /// The blobs and mock applications of this state are added to the context's `extra`.
+    pub async fn into_view_with_context<C>(self, context: C) -> ExecutionStateView<C>
+    where
+        C: Context + Clone + Send + Sync + 'static,
+        C::Extra: ExecutionRuntimeContext,
+    {
         // Destructure, to make sure we don't miss any fields.
         let SystemExecutionState {
             description,
@@ -112,15 +135,16 @@ impl SystemExecutionState {
             used_blobs,
             closed,
             application_permissions,
+            bytecode_publishers,
             extra_blobs,
             mock_applications,
         } = self;
 
-        let extra = TestExecutionRuntimeContext::new(chain_id, execution_runtime_config);
+        let extra = context.extra();
         extra
             .add_blobs(extra_blobs)
             .await
-            .expect("Adding blobs to the `TestExecutionRuntimeContext` should not fail");
+            .expect("Adding blobs to the execution runtime context should not fail");
         for (id, mock_application) in mock_applications {
             extra
                 .user_contracts()
@@ -128,17 +152,9 @@ impl SystemExecutionState {
             extra.user_services().insert(id, mock_application.into());
         }
 
-        let namespace = generate_test_namespace();
-        let root_key = &[];
-        let context = MemoryContext::new_for_testing(
-            TEST_MEMORY_MAX_STREAM_QUERIES,
-            &namespace,
-            root_key,
-            extra,
-        );
         let mut view = ExecutionStateView::load(context)
             .await
-            .expect("Loading from memory should work");
+            .expect("Loading the execution state should work");
         view.system.description.set(description);
         view.system.epoch.set(epoch);
         view.system.admin_id.set(admin_id);
@@ -172,6 +188,7 @@ impl SystemExecutionState {
         view.system
             .application_permissions
             .set(application_permissions);
+        view.system.bytecode_publishers.set(bytecode_publishers);
         view
     }
 }
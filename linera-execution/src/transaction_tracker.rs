@@ -5,15 +5,47 @@ use std::vec;
 
 use custom_debug_derive::Debug;
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{Amount, ArithmeticError, OracleResponse},
     ensure,
-    identifiers::ApplicationId,
+    identifiers::{ApplicationId, Destination},
+    time::Duration,
 };
 
 use crate::{
-    ExecutionError, ExecutionOutcome, RawExecutionOutcome, SystemExecutionError, SystemMessage,
+    ExecutionError, ExecutionOutcome, RawExecutionOutcome, ResourceTracker, SystemExecutionError,
+    SystemMessage, UserApplicationDescription,
 };
 
+/// The results of a transaction, returned by [`TransactionTracker::destructure`].
+#[derive(Debug)]
+pub struct TransactionOutcome {
+    /// The outcomes of the system and the user applications, in execution order.
+    pub outcomes: Vec<ExecutionOutcome>,
+    /// The oracle responses that were recorded.
+    pub oracle_responses: Vec<OracleResponse>,
+    /// The index of the next outgoing message after this transaction.
+    pub next_message_index: u32,
+    /// The IDs and descriptions of the applications created by this transaction.
+    pub created_applications: Vec<(ApplicationId, UserApplicationDescription)>,
+    /// The number of application descriptions removed from the registry by this transaction.
+    pub pruned_application_count: usize,
+    /// The number of bytes of application state removed by this transaction.
+    pub freed_bytes: u64,
+    /// The hashes of the execution state after each operation or message of this
+    /// transaction, if state hashes are being traced.
+    pub state_hashes: Vec<CryptoHash>,
+    /// The number of calls from one application to another made by this transaction.
+    pub application_calls: u32,
+    /// The number of bytes read from storage by this transaction.
+    pub bytes_read: u64,
+    /// The number of bytes written to storage by this transaction.
+    pub bytes_written: u64,
+    /// The wall-clock time spent executing this transaction. This is not deterministic, so it
+    /// must not become part of the chain state.
+    pub duration: Duration,
+}
+
 /// Tracks oracle responses and execution outcomes of an ongoing transaction execution, as well
 /// as replayed oracle responses.
 #[derive(Debug, Default)]
@@ -25,6 +57,16 @@ pub struct TransactionTracker {
     #[debug(skip_if = Vec::is_empty)]
     outcomes: Vec<ExecutionOutcome>,
     next_message_index: u32,
+    #[debug(skip_if = Vec::is_empty)]
+    state_hashes: Vec<CryptoHash>,
+    #[debug(skip_if = Vec::is_empty)]
+    created_applications: Vec<(ApplicationId, UserApplicationDescription)>,
+    pruned_application_count: usize,
+    freed_bytes: u64,
+    application_calls: u32,
+    bytes_read: u64,
+    bytes_written: u64,
+    duration: Duration,
 }
 
 impl TransactionTracker {
@@ -34,6 +76,14 @@ impl TransactionTracker {
             next_message_index,
             oracle_responses: Vec::new(),
             outcomes: Vec::new(),
+            state_hashes: Vec::new(),
+            created_applications: Vec::new(),
+            pruned_application_count: 0,
+            freed_bytes: 0,
+            application_calls: 0,
+            bytes_read: 0,
+            bytes_written: 0,
+            duration: Duration::ZERO,
         }
     }
 
@@ -41,6 +91,74 @@ impl TransactionTracker {
         self.next_message_index
     }
 
+    /// Returns the number of outgoing messages in the outcomes of this transaction so far.
+    pub fn message_count(&self) -> usize {
+        self.outcomes
+            .iter()
+            .map(ExecutionOutcome::message_count)
+            .sum()
+    }
+
+    /// Returns the number of events in the outcomes of this transaction so far.
+    pub fn event_count(&self) -> usize {
+        self.outcomes
+            .iter()
+            .map(ExecutionOutcome::event_count)
+            .sum()
+    }
+
+    /// Returns the destinations of the outgoing messages in the outcomes of this transaction so
+    /// far.
+    pub fn destinations(&self) -> impl Iterator<Item = &Destination> {
+        self.outcomes
+            .iter()
+            .flat_map(ExecutionOutcome::destinations)
+    }
+
+    /// Returns the hashes of the execution state after each operation or message of this
+    /// transaction so far, if state hashes are being traced.
+    pub fn state_hashes(&self) -> &[CryptoHash] {
+        &self.state_hashes
+    }
+
+    pub(crate) fn add_state_hash(&mut self, state_hash: CryptoHash) {
+        self.state_hashes.push(state_hash);
+    }
+
+    pub(crate) fn add_created_application(
+        &mut self,
+        application_id: ApplicationId,
+        description: UserApplicationDescription,
+    ) {
+        self.created_applications
+            .push((application_id, description));
+    }
+
+    pub(crate) fn add_pruned_application_count(&mut self, count: usize) {
+        self.pruned_application_count += count;
+    }
+
+    pub(crate) fn add_freed_bytes(&mut self, count: u64) {
+        self.freed_bytes += count;
+    }
+
+    /// Records the resources used by one operation or message of this transaction, given the
+    /// resource tracker before and after it was executed, and how long it took.
+    pub(crate) fn add_resource_usage(
+        &mut self,
+        before: &ResourceTracker,
+        after: &ResourceTracker,
+        duration: Duration,
+    ) {
+        let application_calls = after.application_calls - before.application_calls;
+        let bytes_read = after.bytes_read - before.bytes_read;
+        let bytes_written = after.bytes_written - before.bytes_written;
+        self.application_calls = self.application_calls.saturating_add(application_calls);
+        self.bytes_read = self.bytes_read.saturating_add(bytes_read);
+        self.bytes_written = self.bytes_written.saturating_add(bytes_written);
+        self.duration = self.duration.saturating_add(duration);
+    }
+
     pub fn add_system_outcome(
         &mut self,
         outcome: RawExecutionOutcome<SystemMessage, Amount>,
@@ -112,14 +230,20 @@ impl TransactionTracker {
         Ok(Some(response))
     }
 
-    pub fn destructure(
-        self,
-    ) -> Result<(Vec<ExecutionOutcome>, Vec<OracleResponse>, u32), ExecutionError> {
+    pub fn destructure(self) -> Result<TransactionOutcome, ExecutionError> {
         let TransactionTracker {
             replaying_oracle_responses,
             oracle_responses,
             outcomes,
             next_message_index,
+            state_hashes,
+            created_applications,
+            pruned_application_count,
+            freed_bytes,
+            application_calls,
+            bytes_read,
+            bytes_written,
+            duration,
         } = self;
         if let Some(mut responses) = replaying_oracle_responses {
             ensure!(
@@ -127,7 +251,19 @@ impl TransactionTracker {
                 ExecutionError::UnexpectedOracleResponse
             );
         }
-        Ok((outcomes, oracle_responses, next_message_index))
+        Ok(TransactionOutcome {
+            outcomes,
+            oracle_responses,
+            next_message_index,
+            created_applications,
+            pruned_application_count,
+            freed_bytes,
+            state_hashes,
+            application_calls,
+            bytes_read,
+            bytes_written,
+            duration,
+        })
     }
 
     pub(crate) fn outcomes_mut(&mut self) -> &mut Vec<ExecutionOutcome> {
@@ -1,6 +1,7 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use assert_matches::assert_matches;
 use linera_base::{
     crypto::CryptoHash,
     data_types::BlockHeight,
@@ -10,6 +11,7 @@ use linera_base::{
 use super::{
     ApplicationRegistry, ApplicationRegistryView, UserApplicationDescription, UserApplicationId,
 };
+use crate::SystemExecutionError;
 
 fn message_id(index: u32) -> MessageId {
     MessageId {
@@ -79,11 +81,35 @@ async fn test_topological_sort_with_loop() {
         (0, vec![1]),
     ]))
     .unwrap();
-    let app_ids = view.find_dependencies(vec![app_id(1)]).await.unwrap();
-    assert_eq!(app_ids, Vec::from_iter([2, 3, 1].into_iter().map(app_id)));
-    let app_ids = view.find_dependencies(vec![app_id(0)]).await.unwrap();
+    let result = view.find_dependencies(vec![app_id(0)]).await;
+    assert_matches!(
+        result,
+        Err(SystemExecutionError::CyclicApplicationDependency { path })
+            if path == Vec::from_iter([1, 3, 1].into_iter().map(app_id))
+    );
+    let app_ids = view.find_dependencies(vec![app_id(2)]).await.unwrap();
+    assert_eq!(app_ids, vec![app_id(2)]);
+}
+
+#[tokio::test]
+async fn test_register_application_with_loop() {
+    let mut view = ApplicationRegistryView::new().await;
+    view.register_application(app_description(1, vec![]))
+        .await
+        .unwrap();
+    view.register_application(app_description(2, vec![1]))
+        .await
+        .unwrap();
+    let result = view.register_application(app_description(1, vec![2])).await;
+    assert_matches!(
+        result,
+        Err(SystemExecutionError::CyclicApplicationDependency { path })
+            if path == Vec::from_iter([1, 2, 1].into_iter().map(app_id))
+    );
     assert_eq!(
-        app_ids,
-        Vec::from_iter([2, 3, 1, 0].into_iter().map(app_id))
+        view.describe_application(app_id(1)).await.unwrap(),
+        app_description(1, vec![])
     );
+    let app_ids = view.find_dependencies(vec![app_id(2)]).await.unwrap();
+    assert_eq!(app_ids, Vec::from_iter([1, 2].into_iter().map(app_id)));
 }
@@ -0,0 +1,56 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use linera_base::data_types::Bytecode;
+use lru::LruCache;
+
+use super::ModuleCache;
+
+fn cache_with_max_size(max_size: u64) -> ModuleCache<u8> {
+    ModuleCache {
+        modules: LruCache::unbounded(),
+        total_size: 0,
+        max_size,
+        name: "test",
+    }
+}
+
+fn bytecode(byte: u8, size: usize) -> Bytecode {
+    Bytecode::new(vec![byte; size])
+}
+
+/// Tests that the least recently used modules are evicted once the cache is full.
+#[test]
+fn test_eviction_by_size() {
+    let mut cache = cache_with_max_size(10);
+    cache.insert(bytecode(0, 4), 0);
+    cache.insert(bytecode(1, 4), 1);
+    assert_eq!(cache.total_size, 8);
+
+    assert_eq!(cache.get(&bytecode(0, 4)), Some(0));
+    cache.insert(bytecode(2, 4), 2);
+    assert_eq!(cache.total_size, 8);
+    assert_eq!(cache.get(&bytecode(1, 4)), None);
+    assert_eq!(cache.get(&bytecode(0, 4)), Some(0));
+    assert_eq!(cache.get(&bytecode(2, 4)), Some(2));
+
+    cache.insert(bytecode(3, 11), 3);
+    assert_eq!(cache.get(&bytecode(3, 11)), None);
+    assert_eq!(cache.total_size, 8);
+}
+
+/// Tests that a module is only built once while it stays in the cache.
+#[test]
+fn test_get_or_insert_with_builds_once() {
+    let mut cache = cache_with_max_size(10);
+    let mut build_count = 0;
+    for _ in 0..2 {
+        let module = cache.get_or_insert_with(bytecode(0, 4), |_| {
+            build_count += 1;
+            Ok::<_, ()>(7)
+        });
+        assert_eq!(module, Ok(7));
+    }
+    assert_eq!(build_count, 1);
+    assert_eq!(cache.total_size, 4);
+}
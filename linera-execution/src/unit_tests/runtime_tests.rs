@@ -186,6 +186,7 @@ fn create_runtime<Application>() -> (
         None,
         resource_controller,
         TransactionTracker::new(0, Some(Vec::new())),
+        CryptoHash::test_hash("random seed"),
     );
 
     (runtime, execution_state_receiver)
@@ -1,8 +1,9 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use assert_matches::assert_matches;
 use linera_base::{
-    data_types::{Blob, BlockHeight, Bytecode},
+    data_types::{Blob, BlockHeight, Bytecode, TimeDelta},
     identifiers::ApplicationId,
 };
 use linera_views::context::MemoryContext;
@@ -60,7 +61,7 @@ async fn application_message_index() -> anyhow::Result<()> {
         .system
         .execute_operation(context, operation, &mut txn_tracker)
         .await?;
-    let [ExecutionOutcome::System(result)] = &txn_tracker.destructure()?.0[..] else {
+    let [ExecutionOutcome::System(result)] = &txn_tracker.destructure()?.outcomes[..] else {
         panic!("Unexpected outcome");
     };
     assert_eq!(
@@ -81,6 +82,81 @@ async fn application_message_index() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests that an application can only be created if its dependencies are registered.
+#[tokio::test]
+async fn create_application_requires_dependencies() -> anyhow::Result<()> {
+    let (mut view, context) = new_view_and_context().await;
+    let contract = Bytecode::new(b"contract".into());
+    let service = Bytecode::new(b"service".into());
+    let contract_blob = Blob::new_contract_bytecode(contract.compress());
+    let service_blob = Blob::new_service_bytecode(service.compress());
+    let bytecode_id = BytecodeId::new(contract_blob.id().hash, service_blob.id().hash);
+    view.context()
+        .extra()
+        .add_blobs([contract_blob, service_blob])
+        .await?;
+
+    let dependency = ApplicationId {
+        bytecode_id,
+        creation: MessageId {
+            chain_id: ChainId::root(1),
+            height: BlockHeight::from(0),
+            index: 0,
+        },
+    };
+    let operation = SystemOperation::CreateApplication {
+        bytecode_id,
+        parameters: vec![],
+        instantiation_argument: vec![],
+        required_application_ids: vec![dependency],
+    };
+
+    let mut txn_tracker = TransactionTracker::default();
+    let result = view
+        .system
+        .execute_operation(context, operation.clone(), &mut txn_tracker)
+        .await;
+    assert_matches!(
+        result,
+        Err(SystemExecutionError::UnknownApplicationId(id)) if *id == dependency
+    );
+    assert!(view
+        .system
+        .registry
+        .known_applications
+        .indices()
+        .await?
+        .is_empty());
+
+    view.system
+        .registry
+        .register_application(UserApplicationDescription {
+            bytecode_id,
+            creation: dependency.creation,
+            parameters: vec![],
+            required_application_ids: vec![],
+        })
+        .await?;
+    let mut txn_tracker = TransactionTracker::default();
+    let (application_id, _) = view
+        .system
+        .execute_operation(context, operation, &mut txn_tracker)
+        .await?
+        .expect("a new application should have been created");
+    let description = view
+        .system
+        .registry
+        .describe_application(application_id)
+        .await?;
+    assert_eq!(description.required_application_ids, vec![dependency]);
+    assert_eq!(
+        txn_tracker.destructure()?.created_applications,
+        vec![(application_id, description)]
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn open_chain_message_index() {
     let (mut view, context) = new_view_and_context().await;
@@ -96,6 +172,7 @@ async fn open_chain_message_index() {
         admin_id,
         balance: Amount::ZERO,
         application_permissions: Default::default(),
+        bytecode_publishers: None,
     };
     let mut txn_tracker = TransactionTracker::default();
     let operation = SystemOperation::OpenChain(config.clone());
@@ -105,7 +182,8 @@ async fn open_chain_message_index() {
         .await
         .unwrap();
     assert_eq!(new_application, None);
-    let [ExecutionOutcome::System(result)] = &txn_tracker.destructure().unwrap().0[..] else {
+    let [ExecutionOutcome::System(result)] = &txn_tracker.destructure().unwrap().outcomes[..]
+    else {
         panic!("Unexpected outcome");
     };
     assert_eq!(
@@ -134,3 +212,250 @@ async fn empty_accounts_are_removed() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Tests if the chain ownership can be changed to weighted owners or to a public chain, but not
+/// to an ownership that makes the chain inactive.
+#[tokio::test]
+async fn change_ownership_requires_owners() -> anyhow::Result<()> {
+    let (mut view, context) = new_view_and_context().await;
+    let owner = Owner(CryptoHash::test_hash("owner"));
+    let change_ownership = |owners, timeout_config| SystemOperation::ChangeOwnership {
+        super_owners: vec![],
+        owners,
+        multi_leader_rounds: 2,
+        open_multi_leader_rounds: false,
+        timeout_config,
+    };
+
+    let result = view
+        .system
+        .execute_operation(
+            context,
+            change_ownership(vec![], TimeoutConfig::default()),
+            &mut TransactionTracker::default(),
+        )
+        .await;
+    assert_matches!(result, Err(SystemExecutionError::EmptyOwnership));
+
+    // Without a fallback delay, a chain without owners is public.
+    let public_timeout_config = TimeoutConfig {
+        fallback_duration: TimeDelta::ZERO,
+        ..TimeoutConfig::default()
+    };
+    view.system
+        .execute_operation(
+            context,
+            change_ownership(vec![], public_timeout_config),
+            &mut TransactionTracker::default(),
+        )
+        .await?;
+    assert!(view.system.ownership.get().owners.is_empty());
+    assert!(view.system.ownership.get().is_active());
+
+    view.system
+        .execute_operation(
+            context,
+            change_ownership(vec![(owner, 100)], TimeoutConfig::default()),
+            &mut TransactionTracker::default(),
+        )
+        .await?;
+    let ownership = view.system.ownership.get();
+    assert_eq!(ownership.owners, BTreeMap::from([(owner, 100)]));
+    assert_eq!(ownership.multi_leader_rounds, 2);
+
+    Ok(())
+}
+
+/// Tests if the admin chain can remove the committee of an old epoch, but not the current one.
+#[tokio::test]
+async fn remove_committee() -> anyhow::Result<()> {
+    let admin_id = ChainId::root(0);
+    let mut view = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        epoch: Some(Epoch(1)),
+        admin_id: Some(admin_id),
+        committees: BTreeMap::from([
+            (Epoch::ZERO, Committee::default()),
+            (Epoch(1), Committee::default()),
+        ]),
+        ..SystemExecutionState::default()
+    }
+    .into_view()
+    .await;
+    let context = OperationContext {
+        chain_id: admin_id,
+        authenticated_signer: None,
+        authenticated_caller_id: None,
+        height: BlockHeight::from(1),
+        round: Some(0),
+        index: Some(0),
+    };
+    let remove_committee =
+        |epoch| SystemOperation::Admin(AdminOperation::RemoveCommittee { epoch });
+
+    let result = view
+        .system
+        .execute_operation(
+            context,
+            remove_committee(Epoch(1)),
+            &mut TransactionTracker::default(),
+        )
+        .await;
+    assert_matches!(result, Err(SystemExecutionError::CurrentCommitteeRemoval));
+
+    let mut txn_tracker = TransactionTracker::default();
+    view.system
+        .execute_operation(context, remove_committee(Epoch::ZERO), &mut txn_tracker)
+        .await?;
+    assert_eq!(
+        view.system.committees.get().keys().collect::<Vec<_>>(),
+        vec![&Epoch(1)]
+    );
+    let outcomes = txn_tracker.destructure()?.outcomes;
+    assert_matches!(
+        &outcomes[..],
+        [ExecutionOutcome::System(outcome)]
+            if outcome.messages.len() == 1
+                && outcome.messages[0].message
+                    == SystemMessage::RemoveCommittee { epoch: Epoch::ZERO }
+    );
+
+    let result = view
+        .system
+        .execute_operation(
+            context,
+            remove_committee(Epoch::ZERO),
+            &mut TransactionTracker::default(),
+        )
+        .await;
+    assert_matches!(result, Err(SystemExecutionError::InvalidCommitteeRemoval));
+
+    Ok(())
+}
+
+/// Tests that only the allowed owners can publish bytecode once the admin chain restricts it.
+#[tokio::test]
+async fn publish_bytecode_with_publishers() -> anyhow::Result<()> {
+    let (mut view, context) = new_view_and_context().await;
+    let allowed = Owner(CryptoHash::test_hash("allowed publisher"));
+    let rejected = Owner(CryptoHash::test_hash("rejected publisher"));
+
+    // Without an allowlist, anyone can publish.
+    publish_bytecode(&mut view, context, None).await?;
+    publish_bytecode(&mut view, context, Some(rejected)).await?;
+
+    view.system
+        .bytecode_publishers
+        .set(Some(BTreeSet::from([allowed])));
+    publish_bytecode(&mut view, context, Some(allowed)).await?;
+    assert_matches!(
+        publish_bytecode(&mut view, context, Some(rejected)).await,
+        Err(SystemExecutionError::UnauthorizedBytecodePublisher(Some(owner))) if owner == rejected
+    );
+    assert_matches!(
+        publish_bytecode(&mut view, context, None).await,
+        Err(SystemExecutionError::UnauthorizedBytecodePublisher(None))
+    );
+
+    Ok(())
+}
+
+/// Executes a `PublishBytecode` operation signed by `signer`.
+async fn publish_bytecode(
+    view: &mut ExecutionStateView<MemoryContext<TestExecutionRuntimeContext>>,
+    context: OperationContext,
+    signer: Option<Owner>,
+) -> Result<(), SystemExecutionError> {
+    let context = OperationContext {
+        authenticated_signer: signer,
+        ..context
+    };
+    let bytecode_id = BytecodeId::new(
+        CryptoHash::test_hash("contract"),
+        CryptoHash::test_hash("service"),
+    );
+    let operation = SystemOperation::PublishBytecode { bytecode_id };
+    view.system
+        .execute_operation(context, operation, &mut TransactionTracker::default())
+        .await?;
+    Ok(())
+}
+
+/// Tests that the bytecode publishers are set on the admin chain and accepted from it only.
+#[tokio::test]
+async fn set_bytecode_publishers() -> anyhow::Result<()> {
+    let admin_id = ChainId::root(0);
+    let mut admin_view =
+        SystemExecutionState::new(Epoch::ZERO, ChainDescription::Root(0), admin_id)
+            .into_view()
+            .await;
+    let context = OperationContext {
+        chain_id: admin_id,
+        authenticated_signer: None,
+        authenticated_caller_id: None,
+        height: BlockHeight::from(1),
+        round: Some(0),
+        index: Some(0),
+    };
+    let publishers = Some(BTreeSet::from([Owner(CryptoHash::test_hash("publisher"))]));
+    let operation = SystemOperation::Admin(AdminOperation::SetBytecodePublishers {
+        publishers: publishers.clone(),
+    });
+    let mut txn_tracker = TransactionTracker::default();
+    admin_view
+        .system
+        .execute_operation(context, operation, &mut txn_tracker)
+        .await?;
+    assert_eq!(*admin_view.system.bytecode_publishers.get(), publishers);
+    let outcomes = txn_tracker.destructure()?.outcomes;
+    let [ExecutionOutcome::System(outcome)] = &outcomes[..] else {
+        panic!("Unexpected outcome");
+    };
+    let [message] = &outcome.messages[..] else {
+        panic!("Expected exactly one message");
+    };
+    assert_eq!(
+        message.destination,
+        Destination::Subscribers(SystemChannel::Admin.name())
+    );
+
+    let (mut view, _) = new_view_and_context().await;
+    let message_context = |origin| MessageContext {
+        chain_id: ChainId::root(5),
+        is_bouncing: false,
+        authenticated_signer: None,
+        refund_grant_to: None,
+        height: BlockHeight::from(0),
+        round: Some(0),
+        certificate_hash: CryptoHash::test_hash("certificate"),
+        message_id: MessageId {
+            chain_id: origin,
+            height: BlockHeight::from(1),
+            index: 0,
+        },
+    };
+    let result = view
+        .system
+        .execute_message(
+            message_context(ChainId::root(1)),
+            message.message.clone(),
+            &mut TransactionTracker::default(),
+        )
+        .await;
+    assert_matches!(
+        result,
+        Err(SystemExecutionError::BytecodePublishersUpdateFromNonAdminChain)
+    );
+    assert_eq!(*view.system.bytecode_publishers.get(), None);
+
+    view.system
+        .execute_message(
+            message_context(admin_id),
+            message.message.clone(),
+            &mut TransactionTracker::default(),
+        )
+        .await?;
+    assert_eq!(*view.system.bytecode_publishers.get(), publishers);
+
+    Ok(())
+}
@@ -7,8 +7,47 @@
 //! estimate the total memory usage by the cache, since it's currently not possible to determine
 //! the size of a generic `Module`.
 
+#[cfg(test)]
+#[path = "../unit_tests/module_cache_tests.rs"]
+mod tests;
+
 use linera_base::data_types::Bytecode;
 use lru::LruCache;
+#[cfg(with_metrics)]
+use {
+    linera_base::prometheus_util::register_int_counter_vec, prometheus::IntCounterVec,
+    std::sync::LazyLock,
+};
+
+/// The number of modules found in a cache.
+#[cfg(with_metrics)]
+static MODULE_CACHE_HIT_COUNT: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec(
+        "module_cache_hit_count",
+        "The number of compiled Wasm modules found in a cache",
+        &["cache"],
+    )
+});
+
+/// The number of modules that had to be compiled because they were not in a cache.
+#[cfg(with_metrics)]
+static MODULE_CACHE_MISS_COUNT: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec(
+        "module_cache_miss_count",
+        "The number of Wasm modules compiled because they were not in a cache",
+        &["cache"],
+    )
+});
+
+/// The number of modules evicted from a cache to make room for new ones.
+#[cfg(with_metrics)]
+static MODULE_CACHE_EVICTION_COUNT: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec(
+        "module_cache_eviction_count",
+        "The number of compiled Wasm modules evicted from a cache",
+        &["cache"],
+    )
+});
 
 /// The default maximum size of the bytecodes stored in cache.
 const DEFAULT_MAX_CACHE_SIZE: u64 = 512 /* MiB */ * 1024 /* KiB */ * 1024 /* bytes */;
@@ -20,14 +59,19 @@ pub struct ModuleCache<Module> {
     modules: LruCache<Bytecode, Module>,
     total_size: u64,
     max_size: u64,
+    /// The name of the cache in the metrics, e.g. `wasmer_contract`.
+    #[cfg_attr(not(with_metrics), allow(dead_code))]
+    name: &'static str,
 }
 
-impl<Module> Default for ModuleCache<Module> {
-    fn default() -> Self {
+impl<Module> ModuleCache<Module> {
+    /// Creates an empty cache, called `name` in the metrics.
+    pub fn new(name: &'static str) -> Self {
         ModuleCache {
             modules: LruCache::unbounded(),
             total_size: 0,
             max_size: DEFAULT_MAX_CACHE_SIZE,
+            name,
         }
     }
 }
@@ -41,8 +85,14 @@ impl<Module: Clone> ModuleCache<Module> {
         module_builder: impl FnOnce(Bytecode) -> Result<Module, E>,
     ) -> Result<Module, E> {
         if let Some(module) = self.get(&bytecode) {
+            #[cfg(with_metrics)]
+            MODULE_CACHE_HIT_COUNT.with_label_values(&[self.name]).inc();
             Ok(module)
         } else {
+            #[cfg(with_metrics)]
+            MODULE_CACHE_MISS_COUNT
+                .with_label_values(&[self.name])
+                .inc();
             let module = module_builder(bytecode.clone())?;
             self.insert(bytecode, module.clone());
             Ok(module)
@@ -55,14 +105,20 @@ impl<Module: Clone> ModuleCache<Module> {
     }
 
     /// Inserts a `bytecode` and its compiled `module` in the cache.
+    ///
+    /// Bytecodes larger than the maximum size of the cache are not cached.
     pub fn insert(&mut self, bytecode: Bytecode, module: Module) {
         let bytecode_size = bytecode.as_ref().len() as u64;
 
+        if bytecode_size > self.max_size || self.modules.contains(&bytecode) {
+            return;
+        }
         if self.total_size + bytecode_size > self.max_size {
             self.reduce_size_to(self.max_size - bytecode_size);
         }
 
         self.modules.put(bytecode, module);
+        self.total_size += bytecode_size;
     }
 
     /// Evicts entries from the cache so that the total size of cached bytecodes is less than
@@ -76,6 +132,10 @@ impl<Module: Clone> ModuleCache<Module> {
             let bytecode_size = bytecode.as_ref().len() as u64;
 
             self.total_size -= bytecode_size;
+            #[cfg(with_metrics)]
+            MODULE_CACHE_EVICTION_COUNT
+                .with_label_values(&[self.name])
+                .inc();
         }
     }
 }
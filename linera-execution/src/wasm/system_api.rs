@@ -4,7 +4,7 @@
 use std::{any::Any, collections::HashMap, marker::PhantomData};
 
 use linera_base::{
-    crypto::CryptoHash,
+    crypto::{CryptoHash, PublicKey},
     data_types::{Amount, ApplicationPermissions, BlockHeight, SendMessageRequest, Timestamp},
     identifiers::{
         Account, AccountOwner, ApplicationId, ChainId, ChannelName, MessageId, Owner, StreamName,
@@ -452,6 +452,39 @@ where
             .validation_round()
             .map_err(|error| RuntimeError::Custom(error.into()))
     }
+
+    /// Returns `length` pseudo-random bytes, which are the same on every validator.
+    fn random_bytes(caller: &mut Caller, length: u32) -> Result<Vec<u8>, RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime_mut()
+            .random_bytes(length)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Computes the SHA-256 hash of `bytes`.
+    fn sha256(caller: &mut Caller, bytes: Vec<u8>) -> Result<Vec<u8>, RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime_mut()
+            .sha256(bytes)
+            .map(Vec::from)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Checks whether `signature` is a valid Ed25519 signature of `message` by `public_key`.
+    fn verify_ed25519(
+        caller: &mut Caller,
+        public_key: PublicKey,
+        message: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<bool, RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime_mut()
+            .verify_ed25519(public_key, message, signature)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
 }
 
 /// An implementation of the system API made available to services.
@@ -559,6 +592,15 @@ where
             .map_err(|error| RuntimeError::Custom(error.into()))
     }
 
+    /// Retrieves the owner configuration for the current chain.
+    fn get_chain_ownership(caller: &mut Caller) -> Result<ChainOwnership, RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .chain_ownership()
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
     /// Schedules an operation to be included in the block being built by this query.
     fn schedule_operation(caller: &mut Caller, operation: Vec<u8>) -> Result<(), RuntimeError> {
         caller
@@ -656,6 +698,39 @@ where
         }
         Ok(())
     }
+
+    /// Returns `length` random bytes, which are not reproducible.
+    fn random_bytes(caller: &mut Caller, length: u32) -> Result<Vec<u8>, RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime_mut()
+            .random_bytes(length)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Computes the SHA-256 hash of `bytes`.
+    fn sha256(caller: &mut Caller, bytes: Vec<u8>) -> Result<Vec<u8>, RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime_mut()
+            .sha256(bytes)
+            .map(Vec::from)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Checks whether `signature` is a valid Ed25519 signature of `message` by `public_key`.
+    fn verify_ed25519(
+        caller: &mut Caller,
+        public_key: PublicKey,
+        message: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<bool, RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime_mut()
+            .verify_ed25519(public_key, message, signature)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
 }
 
 /// An implementation of the system API used to access the view storage for both contracts and
@@ -39,10 +39,11 @@ static SERVICE_ENGINE: LazyLock<wasmer::Engine> = LazyLock::new(|| {
 
 /// A cache of compiled contract modules, with their respective [`wasmer::Engine`] instances.
 static CONTRACT_CACHE: LazyLock<Mutex<ModuleCache<CachedContractModule>>> =
-    LazyLock::new(Mutex::default);
+    LazyLock::new(|| Mutex::new(ModuleCache::new("wasmer_contract")));
 
 /// A cache of compiled service modules.
-static SERVICE_CACHE: LazyLock<Mutex<ModuleCache<wasmer::Module>>> = LazyLock::new(Mutex::default);
+static SERVICE_CACHE: LazyLock<Mutex<ModuleCache<wasmer::Module>>> =
+    LazyLock::new(|| Mutex::new(ModuleCache::new("wasmer_service")));
 
 /// Type representing a running [Wasmer](https://wasmer.io/) contract.
 pub(crate) struct WasmerContractInstance<Runtime> {
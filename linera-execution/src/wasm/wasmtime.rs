@@ -35,10 +35,12 @@ static CONTRACT_ENGINE: LazyLock<Engine> = LazyLock::new(|| {
 static SERVICE_ENGINE: LazyLock<Engine> = LazyLock::new(Engine::default);
 
 /// A cache of compiled contract modules.
-static CONTRACT_CACHE: LazyLock<Mutex<ModuleCache<Module>>> = LazyLock::new(Mutex::default);
+static CONTRACT_CACHE: LazyLock<Mutex<ModuleCache<Module>>> =
+    LazyLock::new(|| Mutex::new(ModuleCache::new("wasmtime_contract")));
 
 /// A cache of compiled service modules.
-static SERVICE_CACHE: LazyLock<Mutex<ModuleCache<Module>>> = LazyLock::new(Mutex::default);
+static SERVICE_CACHE: LazyLock<Mutex<ModuleCache<Module>>> =
+    LazyLock::new(|| Mutex::new(ModuleCache::new("wasmtime_service")));
 
 /// Type representing a running [Wasmtime](https://wasmtime.dev/) contract.
 ///
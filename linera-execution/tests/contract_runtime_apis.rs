@@ -5,33 +5,36 @@
 
 use std::{
     collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Mutex},
     vec,
 };
 
 use anyhow::bail;
 use assert_matches::assert_matches;
 use linera_base::{
-    crypto::CryptoHash,
+    crypto::{CryptoHash, KeyPair, PublicKey, Signature},
     data_types::{
         Amount, Blob, BlockHeight, CompressedBytecode, Timestamp, UserApplicationDescription,
     },
     identifiers::{
-        Account, AccountOwner, ApplicationId, BytecodeId, ChainDescription, ChainId, MessageId,
-        Owner,
+        Account, AccountOwner, ApplicationId, BytecodeId, ChainDescription, ChainId, ChannelName,
+        MessageId, Owner,
     },
     ownership::ChainOwnership,
 };
 use linera_execution::{
+    system::Recipient,
     test_utils::{
         create_dummy_message_context, create_dummy_operation_context, test_accounts_strategy,
         ExpectedCall, RegisterMockApplication, SystemExecutionState,
     },
     BaseRuntime, ContractRuntime, ExecutionError, ExecutionOutcome, Message, MessageContext,
     Operation, OperationContext, ResourceController, SystemExecutionError,
-    SystemExecutionStateView, TestExecutionRuntimeContext, TransactionTracker,
+    SystemExecutionStateView, SystemOperation, TestExecutionRuntimeContext, TransactionOutcome,
+    TransactionTracker,
 };
 use linera_views::context::MemoryContext;
-use test_case::test_matrix;
+use test_case::{test_case, test_matrix};
 use test_strategy::proptest;
 
 /// Tests the contract system API to transfer tokens between accounts.
@@ -90,7 +93,12 @@ async fn test_transfer_system_api(
     )
     .await?;
 
-    let (outcomes, oracle_responses, next_message_index) = tracker.destructure()?;
+    let TransactionOutcome {
+        outcomes,
+        oracle_responses,
+        next_message_index,
+        ..
+    } = tracker.destructure()?;
     assert_eq!(outcomes.len(), 3);
     assert!(oracle_responses.is_empty());
     assert_eq!(next_message_index, 1);
@@ -174,14 +182,81 @@ async fn test_unauthorized_transfer_system_api(
 
     assert_matches!(
         result,
-        Err(ExecutionError::SystemError(
-            SystemExecutionError::UnauthenticatedTransferOwner
-        ))
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(
+                *error,
+                ExecutionError::SystemError(SystemExecutionError::UnauthenticatedTransferOwner)
+            )
     );
 
     Ok(())
 }
 
+/// Tests that tokens an application takes from the chain balance are restored if the operation
+/// fails afterwards.
+#[test_log::test(tokio::test)]
+async fn test_transfer_is_reverted_when_operation_fails() -> anyhow::Result<()> {
+    let amount = Amount::ONE;
+    let sender = TransferTestEndpoint::Chain;
+
+    let mut view = sender.create_system_state(amount).into_view().await;
+
+    let (application_id, application) = view
+        .register_mock_application_with(
+            TransferTestEndpoint::sender_application_description(),
+            TransferTestEndpoint::sender_application_contract_blob(),
+            TransferTestEndpoint::sender_application_service_blob(),
+        )
+        .await?;
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, context, _operation| {
+            runtime.transfer(
+                None,
+                Account {
+                    owner: Some(AccountOwner::Application(
+                        TransferTestEndpoint::sender_application_id(),
+                    )),
+                    chain_id: context.chain_id,
+                },
+                amount,
+            )?;
+            Err(ExecutionError::UserError(
+                "failed after the transfer".to_owned(),
+            ))
+        },
+    ));
+
+    let context = OperationContext {
+        authenticated_signer: sender.signer(),
+        ..create_dummy_operation_context()
+    };
+    let mut controller = ResourceController::default();
+    let operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+    let result = view
+        .execute_operation(
+            context,
+            Timestamp::from(0),
+            operation,
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut controller,
+        )
+        .await;
+
+    assert_matches!(
+        result,
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(*error, ExecutionError::UserError(_))
+    );
+    assert_eq!(*view.system.balance.get(), amount);
+    assert!(view.system.balances.index_values().await?.is_empty());
+
+    Ok(())
+}
+
 /// Tests the contract system API to claim tokens from a remote account.
 #[test_matrix(
     [TransferTestEndpoint::User, TransferTestEndpoint::Application],
@@ -259,7 +334,12 @@ async fn test_claim_system_api(
         )
         .await?;
 
-    let (outcomes, oracle_responses, next_message_index) = tracker.destructure()?;
+    let TransactionOutcome {
+        outcomes,
+        oracle_responses,
+        next_message_index,
+        ..
+    } = tracker.destructure()?;
     assert_eq!(outcomes.len(), 3);
     assert!(oracle_responses.is_empty());
     assert_eq!(next_message_index, 1);
@@ -294,7 +374,12 @@ async fn test_claim_system_api(
         })
         .await?;
 
-    let (outcomes, oracle_responses, next_message_index) = tracker.destructure()?;
+    let TransactionOutcome {
+        outcomes,
+        oracle_responses,
+        next_message_index,
+        ..
+    } = tracker.destructure()?;
     assert_eq!(outcomes.len(), 1);
     assert!(oracle_responses.is_empty());
     assert_eq!(next_message_index, 1);
@@ -401,9 +486,11 @@ async fn test_unauthorized_claims(
 
     assert_matches!(
         result,
-        Err(ExecutionError::SystemError(
-            SystemExecutionError::UnauthenticatedClaimOwner
-        ))
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(
+                *error,
+                ExecutionError::SystemError(SystemExecutionError::UnauthenticatedClaimOwner)
+            )
     );
 
     Ok(())
@@ -448,6 +535,73 @@ async fn test_read_chain_balance_system_api(chain_balance: Amount) {
     .unwrap();
 }
 
+/// Tests that an application can refuse an operation based on the chain balance, which
+/// includes the changes made by earlier operations in the same block.
+#[tokio::test]
+async fn test_refuse_operation_below_chain_balance_threshold() -> anyhow::Result<()> {
+    let owner = Owner(CryptoHash::test_hash("owner"));
+    let threshold = Amount::from_tokens(3);
+    let mut view = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        balance: Amount::from_tokens(5),
+        ownership: ChainOwnership::single(owner),
+        ..SystemExecutionState::default()
+    }
+    .into_view()
+    .await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+
+    let check_balance = || {
+        ExpectedCall::execute_operation(move |runtime, _context, _operation| {
+            if runtime.read_chain_balance()? < threshold {
+                return Err(ExecutionError::UserError(
+                    "Chain balance is below the threshold".to_owned(),
+                ));
+            }
+            Ok(vec![])
+        })
+    };
+    application.expect_call(check_balance());
+    application.expect_call(ExpectedCall::default_finalize());
+    application.expect_call(check_balance());
+
+    let context = OperationContext {
+        authenticated_signer: Some(owner),
+        ..create_dummy_operation_context()
+    };
+    let user_operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+    let burn = Operation::System(SystemOperation::Transfer {
+        owner: None,
+        recipient: Recipient::Burn,
+        amount: Amount::from_tokens(3),
+    });
+    let result = view
+        .execute_operations(
+            context,
+            Timestamp::from(0),
+            vec![user_operation.clone(), burn, user_operation],
+            &mut ResourceController::default(),
+        )
+        .await;
+
+    assert_matches!(
+        result,
+        Err(ExecutionError::FailedOperation { index: 2, error })
+            if matches!(
+                *error,
+                ExecutionError::FailedUserAction { ref error, .. }
+                    if matches!(**error, ExecutionError::UserError(_))
+            )
+    );
+    assert_eq!(*view.system.balance.get(), Amount::from_tokens(2));
+
+    Ok(())
+}
+
 /// Tests the contract system API to read a single account balance.
 #[proptest(async = "tokio")]
 async fn test_read_owner_balance_system_api(
@@ -807,3 +961,238 @@ impl TransferTestEndpoint {
         Ok(())
     }
 }
+
+/// Tests that the pseudo-random bytes returned by the contract system API are the same when
+/// executing the same operation in separate views, and different for another operation.
+#[tokio::test]
+async fn test_random_bytes_system_api() -> anyhow::Result<()> {
+    let mut all_random_bytes = Vec::new();
+
+    for operation_index in [0, 0, 1] {
+        let mut view = SystemExecutionState {
+            description: Some(ChainDescription::Root(0)),
+            ..SystemExecutionState::default()
+        }
+        .into_view()
+        .await;
+
+        let (application_id, application) = view.register_mock_application().await?;
+        let random_bytes = Arc::new(Mutex::new(Vec::new()));
+
+        application.expect_call(ExpectedCall::execute_operation({
+            let random_bytes = random_bytes.clone();
+            move |runtime, _context, _operation| {
+                *random_bytes.lock().unwrap() = runtime.random_bytes(40)?;
+                Ok(vec![])
+            }
+        }));
+        application.expect_call(ExpectedCall::default_finalize());
+
+        let context = OperationContext {
+            index: Some(operation_index),
+            ..create_dummy_operation_context()
+        };
+        view.execute_operation(
+            context,
+            Timestamp::from(0),
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut ResourceController::default(),
+        )
+        .await?;
+
+        let random_bytes = random_bytes.lock().unwrap().clone();
+        assert_eq!(random_bytes.len(), 40);
+        all_random_bytes.push(random_bytes);
+    }
+
+    assert_eq!(all_random_bytes[0], all_random_bytes[1]);
+    assert_ne!(all_random_bytes[0], all_random_bytes[2]);
+
+    Ok(())
+}
+
+/// Tests that an application can verify an Ed25519 signature passed in its operation, and that
+/// the verification consumes fuel.
+#[test_case(true; "with a valid signature")]
+#[test_case(false; "with an invalid signature")]
+#[tokio::test]
+async fn test_verify_ed25519_system_api(is_valid: bool) -> anyhow::Result<()> {
+    let mut view = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        ..SystemExecutionState::default()
+    }
+    .into_view()
+    .await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+
+    application.expect_call(ExpectedCall::execute_operation(
+        |runtime, _context, operation| {
+            let (public_key, message, signature): (PublicKey, Vec<u8>, Vec<u8>) =
+                bcs::from_bytes(&operation)?;
+            if !runtime.verify_ed25519(public_key, message, signature)? {
+                return Err(ExecutionError::UserError("Invalid signature".to_owned()));
+            }
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let key_pair = KeyPair::generate();
+    let message = b"Hello, Linera!".to_vec();
+    let signed_message = if is_valid {
+        message.clone()
+    } else {
+        b"Goodbye, Linera!".to_vec()
+    };
+    let signature = Signature::new_for_bytes(&signed_message, &key_pair);
+    let operation = Operation::User {
+        application_id,
+        bytes: bcs::to_bytes(&(key_pair.public(), message, signature.to_bytes().to_vec()))?,
+    };
+
+    let mut controller = ResourceController::default();
+    let result = view
+        .execute_operation(
+            create_dummy_operation_context(),
+            Timestamp::from(0),
+            operation,
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut controller,
+        )
+        .await;
+
+    if is_valid {
+        assert_matches!(result, Ok(()));
+        assert!(controller.tracker.fuel > 0);
+    } else {
+        assert_matches!(
+            result,
+            Err(ExecutionError::FailedUserAction { error, .. })
+                if matches!(*error, ExecutionError::UserError(_))
+        );
+    }
+
+    Ok(())
+}
+
+/// Tests the contract system API to compute SHA-256 hashes.
+#[tokio::test]
+async fn test_sha256_system_api() -> anyhow::Result<()> {
+    let mut view = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        ..SystemExecutionState::default()
+    }
+    .into_view()
+    .await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+
+    application.expect_call(ExpectedCall::execute_operation(
+        |runtime, _context, _operation| {
+            assert_eq!(
+                linera_base::hex::encode(runtime.sha256(b"abc".to_vec())?),
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+            );
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    view.execute_operation(
+        create_dummy_operation_context(),
+        Timestamp::from(0),
+        Operation::User {
+            application_id,
+            bytes: vec![],
+        },
+        &mut TransactionTracker::new(0, Some(Vec::new())),
+        &mut ResourceController::default(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Tests that channel subscriptions requested by an application are attributed to it and
+/// emitted after the system outcomes produced earlier in the same action.
+#[tokio::test]
+async fn test_channel_subscriptions_system_api() -> anyhow::Result<()> {
+    let amount = Amount::ONE;
+    let sender = TransferTestEndpoint::Chain;
+    let mut view = sender.create_system_state(amount).into_view().await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+
+    let publisher = ChainId::root(1);
+    let former_publisher = ChainId::root(2);
+    let channel = ChannelName::from(b"news".to_vec());
+    let former_channel = ChannelName::from(b"gossip".to_vec());
+
+    application.expect_call(ExpectedCall::execute_operation({
+        let channel = channel.clone();
+        let former_channel = former_channel.clone();
+        move |runtime, _context, _operation| {
+            runtime.transfer(
+                None,
+                Account {
+                    owner: None,
+                    chain_id: publisher,
+                },
+                amount,
+            )?;
+            runtime.subscribe(publisher, channel)?;
+            runtime.unsubscribe(former_publisher, former_channel)?;
+            Ok(vec![])
+        }
+    }));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let context = OperationContext {
+        authenticated_signer: sender.signer(),
+        ..create_dummy_operation_context()
+    };
+    let mut tracker = TransactionTracker::new(0, Some(Vec::new()));
+    view.execute_operation(
+        context,
+        Timestamp::from(0),
+        Operation::User {
+            application_id,
+            bytes: vec![],
+        },
+        &mut tracker,
+        &mut ResourceController::default(),
+    )
+    .await?;
+
+    let outcomes = tracker.destructure()?.outcomes;
+    assert_matches!(outcomes[0], ExecutionOutcome::System(ref outcome) if outcome.messages.len() == 1);
+
+    let subscription_outcomes = outcomes
+        .iter()
+        .skip(1)
+        .filter_map(|outcome| match outcome {
+            ExecutionOutcome::User(id, outcome)
+                if !outcome.subscribe.is_empty() || !outcome.unsubscribe.is_empty() =>
+            {
+                Some((*id, outcome))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(subscription_outcomes.len(), 1);
+
+    let (id, outcome) = subscription_outcomes[0];
+    assert_eq!(id, application_id);
+    assert_eq!(outcome.subscribe, vec![(channel, publisher)]);
+    assert_eq!(
+        outcome.unsubscribe,
+        vec![(former_channel, former_publisher)]
+    );
+
+    Ok(())
+}
@@ -14,9 +14,11 @@ use linera_base::{
 };
 use linera_execution::{
     test_utils::{ExpectedCall, RegisterMockApplication, SystemExecutionState},
-    ContractRuntime, ExecutionError, ExecutionOutcome, Message, MessageContext,
-    RawExecutionOutcome, ResourceControlPolicy, ResourceController, TransactionTracker,
+    ContractRuntime, ExecutionError, ExecutionOutcome, Message, MessageContext, Operation,
+    OperationContext, RawExecutionOutcome, ResourceControlPolicy, ResourceController,
+    TransactionTracker,
 };
+use linera_views::views::CryptoHashView;
 use test_case::test_case;
 
 /// Tests if the chain balance is updated based on the fees spent for consuming resources.
@@ -151,6 +153,17 @@ async fn test_fee_consumption(
         maximum_block_proposal_size: 53,
         maximum_bytes_read_per_block: 59,
         maximum_bytes_written_per_block: 61,
+        maximum_message_size: 67,
+        maximum_messages_per_transaction: 71,
+        maximum_call_depth: 73,
+        maximum_event_value_size: 79,
+        maximum_events_per_transaction: 83,
+        maximum_recipients_per_block: 89,
+        maximum_operations_per_block: 97,
+        maximum_incoming_messages_per_block: 101,
+        sha256_fuel: 103,
+        verify_ed25519_fuel: 107,
+        hashing_fuel_per_byte: 109,
     };
 
     let consumed_fees = spends
@@ -214,7 +227,7 @@ async fn test_fee_consumption(
     )
     .await?;
 
-    let (outcomes, _, _) = txn_tracker.destructure()?;
+    let outcomes = txn_tracker.destructure()?.outcomes;
     assert_eq!(
         outcomes,
         vec![
@@ -290,6 +303,64 @@ async fn test_fee_consumption(
     Ok(())
 }
 
+/// Tests that an application that keeps consuming fuel is stopped once the block's fuel budget
+/// is exhausted, without changing the execution state.
+#[tokio::test]
+async fn test_fuel_exhaustion() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        ..SystemExecutionState::default()
+    };
+    let (application_id, application) = state.register_mock_application().await?;
+    let mut view = state.into_view().await;
+    let initial_hash = view.crypto_hash().await?;
+
+    let policy = ResourceControlPolicy {
+        maximum_fuel_per_block: 10_000,
+        ..ResourceControlPolicy::default()
+    };
+    let mut controller = ResourceController {
+        policy: Arc::new(policy),
+        ..ResourceController::default()
+    };
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| loop {
+            runtime.consume_fuel(1_000)?;
+        },
+    ));
+
+    let context = OperationContext {
+        chain_id: ChainId::root(0),
+        authenticated_signer: None,
+        authenticated_caller_id: None,
+        height: BlockHeight(0),
+        round: Some(0),
+        index: Some(0),
+    };
+    let result = view
+        .execute_operation(
+            context,
+            Timestamp::from(0),
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut controller,
+        )
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(*error, ExecutionError::MaximumFuelExceeded)
+    ));
+    assert_eq!(view.crypto_hash().await?, initial_hash);
+
+    Ok(())
+}
+
 /// A runtime operation that costs some amount of fees.
 pub enum FeeSpend {
     /// Consume some execution fuel.
@@ -6,13 +6,15 @@
 use std::{collections::BTreeMap, vec};
 
 use linera_base::{
+    crypto::CryptoHash,
     data_types::Amount,
-    identifiers::{AccountOwner, ChainDescription},
+    identifiers::{AccountOwner, ChainDescription, Owner},
+    ownership::ChainOwnership,
 };
 use linera_execution::{
     test_utils::{
-        create_dummy_query_context, test_accounts_strategy, ExpectedCall, RegisterMockApplication,
-        SystemExecutionState,
+        create_dummy_query_context, create_dummy_user_application_description,
+        test_accounts_strategy, ExpectedCall, RegisterMockApplication, SystemExecutionState,
     },
     BaseRuntime, Query,
 };
@@ -48,6 +50,84 @@ async fn test_read_chain_balance_system_api(chain_balance: Amount) {
     view.query_application(context, query, None).await.unwrap();
 }
 
+/// Tests the service system API to read the chain ownership.
+#[tokio::test]
+async fn test_chain_ownership_system_api() -> anyhow::Result<()> {
+    let ownership = ChainOwnership::single(Owner(CryptoHash::test_hash("owner")));
+    let mut view = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        ownership: ownership.clone(),
+        ..SystemExecutionState::default()
+    }
+    .into_view()
+    .await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+
+    application.expect_call(ExpectedCall::handle_query(
+        move |runtime, _context, _query| {
+            assert_eq!(runtime.chain_ownership()?, ownership);
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let context = create_dummy_query_context();
+    let query = Query::User {
+        application_id,
+        bytes: vec![],
+    };
+
+    view.query_application(context, query, None).await?;
+
+    Ok(())
+}
+
+/// Tests that a service can read the parameters its application was created with, and that they
+/// are part of the application's description in the registry.
+#[tokio::test]
+async fn test_application_parameters_system_api() -> anyhow::Result<()> {
+    let parameters = b"token symbol: LIN".to_vec();
+    let mut view = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        ..SystemExecutionState::default()
+    }
+    .into_view()
+    .await;
+
+    let (mut description, contract, service) = create_dummy_user_application_description(0);
+    description.parameters = parameters.clone();
+    let (application_id, application) = view
+        .register_mock_application_with(description, contract, service)
+        .await?;
+
+    application.expect_call(ExpectedCall::handle_query({
+        let parameters = parameters.clone();
+        move |runtime, _context, _query| {
+            assert_eq!(runtime.application_parameters()?, parameters);
+            Ok(vec![])
+        }
+    }));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let context = create_dummy_query_context();
+    let query = Query::User {
+        application_id,
+        bytes: vec![],
+    };
+
+    view.query_application(context, query, None).await?;
+
+    let description = view
+        .system
+        .registry
+        .describe_application(application_id)
+        .await?;
+    assert_eq!(description.parameters, parameters);
+
+    Ok(())
+}
+
 /// Tests the contract system API to read a single account balance.
 #[proptest(async = "tokio")]
 async fn test_read_owner_balance_system_api(
@@ -3,18 +3,20 @@
 
 #![allow(clippy::field_reassign_with_default)]
 
-use std::{collections::BTreeMap, vec};
+use std::{collections::BTreeMap, sync::Arc, vec};
 
 use anyhow::Context as _;
 use assert_matches::assert_matches;
-use futures::{stream, StreamExt, TryStreamExt};
+use futures::{future, stream, StreamExt, TryStreamExt};
 use linera_base::{
-    crypto::PublicKey,
+    crypto::{CryptoHash, PublicKey},
     data_types::{
         Amount, ApplicationPermissions, BlockHeight, Resources, SendMessageRequest, Timestamp,
+        UserApplicationDescription,
     },
     identifiers::{
-        Account, AccountOwner, ChainDescription, ChainId, Destination, MessageId, Owner,
+        Account, AccountOwner, BytecodeId, ChainDescription, ChainId, Destination, MessageId,
+        Owner, StreamName, UserApplicationId,
     },
     ownership::ChainOwnership,
 };
@@ -23,15 +25,20 @@ use linera_execution::{
     system::{SystemExecutionError, SystemMessage},
     test_utils::{
         create_dummy_message_context, create_dummy_operation_context,
-        create_dummy_user_application_registrations, ExpectedCall, RegisterMockApplication,
-        SystemExecutionState,
+        create_dummy_user_application_description, create_dummy_user_application_registrations,
+        ExpectedCall, MockApplication, RegisterMockApplication, SystemExecutionState,
     },
-    BaseRuntime, ContractRuntime, ExecutionError, ExecutionOutcome, ExecutionRuntimeContext,
-    Message, MessageKind, Operation, OperationContext, Query, QueryContext, QueryOutcome,
-    QueryResponse, RawExecutionOutcome, RawOutgoingMessage, ResourceControlPolicy,
-    ResourceController, SystemOperation, TransactionTracker,
+    ApplicationCall, BaseRuntime, CallChain, ContractRuntime, ExecutionError, ExecutionOutcome,
+    ExecutionRuntimeContext, ExecutionStateView, Message, MessageContext, MessageKind, Operation,
+    OperationContext, Query, QueryContext, QueryOutcome, QueryResponse, RawExecutionOutcome,
+    RawOutgoingMessage, ResourceControlPolicy, ResourceController, ServiceRuntime, SystemOperation,
+    TransactionTracker,
+};
+use linera_views::{
+    batch::Batch,
+    context::Context,
+    views::{CryptoHashView, View},
 };
-use linera_views::{batch::Batch, context::Context, views::View};
 use test_case::test_case;
 
 #[tokio::test]
@@ -64,7 +71,11 @@ async fn test_missing_bytecode_for_user_application() -> anyhow::Result<()> {
 
     assert_matches!(
         result,
-        Err(ExecutionError::ApplicationBytecodeNotFound(desc)) if &*desc == app_desc
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(
+                *error,
+                ExecutionError::ApplicationBytecodeNotFound(ref desc) if &**desc == app_desc
+            )
     );
     Ok(())
 }
@@ -157,7 +168,7 @@ async fn test_simple_user_operation() -> anyhow::Result<()> {
         chain_id: ChainId::root(0),
         owner: Some(AccountOwner::User(owner)),
     };
-    let (outcomes, _, _) = txn_tracker.destructure().unwrap();
+    let outcomes = txn_tracker.destructure().unwrap().outcomes;
     assert_eq!(
         outcomes,
         vec![
@@ -247,6 +258,157 @@ async fn test_simple_user_operation() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests that a service can read the block height and timestamp of the query context, both
+/// directly and through its runtime.
+#[tokio::test]
+async fn test_query_context() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+
+    application.expect_call(ExpectedCall::handle_query(|runtime, context, _query| {
+        assert_eq!(runtime.chain_id()?, context.chain_id);
+        assert_eq!(runtime.block_height()?, context.next_block_height);
+        let response = (
+            context.chain_id,
+            context.next_block_height,
+            context.local_time,
+        );
+        Ok(bcs::to_bytes(&response)?)
+    }));
+
+    let context = QueryContext {
+        chain_id: ChainId::root(0),
+        next_block_height: BlockHeight(7),
+        local_time: Timestamp::from(1_000),
+    };
+    let mut service_runtime_endpoint = context.spawn_service_runtime_actor();
+    let outcome = view
+        .query_application(
+            context,
+            Query::User {
+                application_id,
+                bytes: vec![],
+            },
+            Some(&mut service_runtime_endpoint),
+        )
+        .await?;
+
+    let QueryResponse::User(bytes) = outcome.response else {
+        panic!("Unexpected response: {:?}", outcome.response);
+    };
+    assert_eq!(
+        bcs::from_bytes::<(ChainId, BlockHeight, Timestamp)>(&bytes)?,
+        (ChainId::root(0), BlockHeight(7), Timestamp::from(1_000))
+    );
+    Ok(())
+}
+
+/// Tests that a service can aggregate the responses of other applications' services in a single
+/// query.
+#[tokio::test]
+async fn test_cross_application_query() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (aggregator_id, aggregator_application) = view.register_mock_application().await?;
+    let (first_id, first_application) = view.register_mock_application().await?;
+    let (second_id, second_application) = view.register_mock_application().await?;
+
+    aggregator_application.expect_call(ExpectedCall::handle_query(
+        move |runtime, _context, query| {
+            let mut response = runtime.try_query_application(first_id, query.clone())?;
+            response.extend(runtime.try_query_application(second_id, query)?);
+            Ok(response)
+        },
+    ));
+    first_application.expect_call(ExpectedCall::handle_query(|_runtime, _context, query| {
+        assert_eq!(query, vec![42]);
+        Ok(vec![1])
+    }));
+    second_application.expect_call(ExpectedCall::handle_query(|_runtime, _context, query| {
+        assert_eq!(query, vec![42]);
+        Ok(vec![2, 3])
+    }));
+
+    let context = QueryContext {
+        chain_id: ChainId::root(0),
+        next_block_height: BlockHeight(0),
+        local_time: Timestamp::from(0),
+    };
+    let mut service_runtime_endpoint = context.spawn_service_runtime_actor();
+    let outcome = view
+        .query_application(
+            context,
+            Query::User {
+                application_id: aggregator_id,
+                bytes: vec![42],
+            },
+            Some(&mut service_runtime_endpoint),
+        )
+        .await?;
+
+    assert_eq!(
+        outcome,
+        QueryOutcome {
+            response: QueryResponse::User(vec![1, 2, 3]),
+            operations: vec![],
+        }
+    );
+    Ok(())
+}
+
+/// Tests that cyclic cross-application queries are rejected, and that the service runtime can be
+/// used for later queries.
+#[tokio::test]
+async fn test_cyclic_cross_application_query() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (first_id, first_application) = view.register_mock_application().await?;
+    let (second_id, second_application) = view.register_mock_application().await?;
+
+    first_application.expect_call(ExpectedCall::handle_query(
+        move |runtime, _context, query| runtime.try_query_application(second_id, query),
+    ));
+    second_application.expect_call(ExpectedCall::handle_query(
+        move |runtime, _context, query| runtime.try_query_application(first_id, query),
+    ));
+
+    let context = QueryContext {
+        chain_id: ChainId::root(0),
+        next_block_height: BlockHeight(0),
+        local_time: Timestamp::from(0),
+    };
+    let mut service_runtime_endpoint = context.spawn_service_runtime_actor();
+    let query = Query::User {
+        application_id: first_id,
+        bytes: vec![],
+    };
+    let result = view
+        .query_application(context, query.clone(), Some(&mut service_runtime_endpoint))
+        .await;
+
+    assert_matches!(
+        result,
+        Err(ExecutionError::ReentrantCall(application_id)) if application_id == first_id
+    );
+
+    first_application.expect_call(ExpectedCall::handle_query(|_runtime, _context, _query| {
+        Ok(vec![1])
+    }));
+    let outcome = view
+        .query_application(context, query, Some(&mut service_runtime_endpoint))
+        .await?;
+
+    assert_eq!(outcome.response, QueryResponse::User(vec![1]));
+    Ok(())
+}
+
 /// A cross-application call to start or end a session.
 ///
 /// Here a session is a test scenario where the transaction is prevented from succeeding while
@@ -336,7 +498,7 @@ async fn test_simulated_session() -> anyhow::Result<()> {
         chain_id: ChainId::root(0),
         owner: None,
     };
-    let (outcomes, _, _) = txn_tracker.destructure().unwrap();
+    let outcomes = txn_tracker.destructure().unwrap().outcomes;
     assert_eq!(
         outcomes,
         vec![
@@ -430,7 +592,55 @@ async fn test_simulated_session_leak() -> anyhow::Result<()> {
         )
         .await;
 
-    assert_matches!(result, Err(ExecutionError::UserError(message)) if message == error_message);
+    assert_matches!(
+        result,
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(*error, ExecutionError::UserError(ref message) if message == error_message)
+    );
+    Ok(())
+}
+
+/// Tests that errors of user actions report the application, the kind of action and the size of
+/// its argument, and keep the original error as their source.
+#[tokio::test]
+async fn test_failed_user_action_context() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+    application.expect_call(ExpectedCall::execute_message(
+        |_runtime, _context, _message| Err(ExecutionError::UserError("failed".to_owned())),
+    ));
+
+    let result = view
+        .execute_message(
+            create_dummy_message_context(None),
+            Timestamp::from(0),
+            Message::User {
+                application_id,
+                bytes: vec![1, 2, 3],
+            },
+            None,
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut ResourceController::default(),
+        )
+        .await;
+
+    let error = result.expect_err("The message should fail");
+    assert_eq!(
+        std::error::Error::source(&error).map(ToString::to_string),
+        Some("User application reported an error: failed".to_owned())
+    );
+    assert_matches!(
+        error,
+        ExecutionError::FailedUserAction {
+            application_id: failed_id,
+            kind: "message",
+            argument_size: 3,
+            error,
+        } if failed_id == application_id && matches!(*error, ExecutionError::UserError(_))
+    );
     Ok(())
 }
 
@@ -468,7 +678,11 @@ async fn test_rejecting_block_from_finalize() -> anyhow::Result<()> {
         )
         .await;
 
-    assert_matches!(result, Err(ExecutionError::UserError(message)) if message == error_message);
+    assert_matches!(
+        result,
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(*error, ExecutionError::UserError(ref message) if message == error_message)
+    );
     Ok(())
 }
 
@@ -530,7 +744,11 @@ async fn test_rejecting_block_from_called_applications_finalize() -> anyhow::Res
         )
         .await;
 
-    assert_matches!(result, Err(ExecutionError::UserError(message)) if message == error_message);
+    assert_matches!(
+        result,
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(*error, ExecutionError::UserError(ref message) if message == error_message)
+    );
     Ok(())
 }
 
@@ -656,7 +874,7 @@ async fn test_sending_message_from_finalize() -> anyhow::Result<()> {
         owner: None,
     };
 
-    let (outcomes, _, _) = txn_tracker.destructure().unwrap();
+    let outcomes = txn_tracker.destructure().unwrap().outcomes;
     assert_eq!(
         outcomes,
         vec![
@@ -747,8 +965,12 @@ async fn test_cross_application_call_from_finalize() -> anyhow::Result<()> {
     let expected_callee_id = target_id;
     assert_matches!(
         result,
-        Err(ExecutionError::CrossApplicationCallInFinalize { caller_id, callee_id })
-            if *caller_id == expected_caller_id && *callee_id == expected_callee_id
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(
+                *error,
+                ExecutionError::CrossApplicationCallInFinalize { ref caller_id, ref callee_id }
+                    if **caller_id == expected_caller_id && **callee_id == expected_callee_id
+            )
     );
 
     Ok(())
@@ -802,8 +1024,12 @@ async fn test_cross_application_call_from_finalize_of_called_application() -> an
     let expected_callee_id = caller_id;
     assert_matches!(
         result,
-        Err(ExecutionError::CrossApplicationCallInFinalize { caller_id, callee_id })
-            if *caller_id == expected_caller_id && *callee_id == expected_callee_id
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(
+                *error,
+                ExecutionError::CrossApplicationCallInFinalize { ref caller_id, ref callee_id }
+                    if **caller_id == expected_caller_id && **callee_id == expected_callee_id
+            )
     );
 
     Ok(())
@@ -856,8 +1082,12 @@ async fn test_calling_application_again_from_finalize() -> anyhow::Result<()> {
     let expected_callee_id = target_id;
     assert_matches!(
         result,
-        Err(ExecutionError::CrossApplicationCallInFinalize { caller_id, callee_id })
-            if *caller_id == expected_caller_id && *callee_id == expected_callee_id
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(
+                *error,
+                ExecutionError::CrossApplicationCallInFinalize { ref caller_id, ref callee_id }
+                    if **caller_id == expected_caller_id && **callee_id == expected_callee_id
+            )
     );
 
     Ok(())
@@ -905,505 +1135,2347 @@ async fn test_cross_application_error() -> anyhow::Result<()> {
             &mut controller,
         )
         .await,
-        Err(ExecutionError::UserError(message)) if message == error_message
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(
+                *error,
+                ExecutionError::FailedCallChain { ref call_chain, ref error }
+                    if call_chain.0.len() == 1
+                        && matches!(
+                            **error,
+                            ExecutionError::UserError(ref message) if message == error_message
+                        )
+            )
     );
 
     Ok(())
 }
 
-/// Tests if an application is scheduled to be registered together with any messages it sends to
-/// other chains.
+/// Tests that a cycle of cross-application calls is rejected with an error instead of waiting
+/// for the state of the application that is already executing.
 #[tokio::test]
-async fn test_simple_message() -> anyhow::Result<()> {
+async fn test_cyclic_cross_application_call() -> anyhow::Result<()> {
     let mut state = SystemExecutionState::default();
     state.description = Some(ChainDescription::Root(0));
     let mut view = state.into_view().await;
 
-    let (application_id, application) = view.register_mock_application().await?;
-
-    let destination_chain = ChainId::from(ChainDescription::Root(1));
-    let dummy_message = SendMessageRequest {
-        destination: Destination::from(destination_chain),
-        authenticated: false,
-        is_tracked: false,
-        grant: Resources::default(),
-        message: b"msg".to_vec(),
-    };
-
-    let fee_policy = ResourceControlPolicy::default();
-    let expected_dummy_message =
-        RawOutgoingMessage::from(dummy_message.clone()).into_priced(&fee_policy)?;
+    let (first_id, first_application) = view.register_mock_application().await?;
+    let (second_id, second_application) = view.register_mock_application().await?;
 
-    application.expect_call(ExpectedCall::execute_operation(
-        move |runtime, _context, _operation| {
-            runtime.send_message(dummy_message)?;
-            Ok(vec![])
+    first_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, operation| {
+            runtime.try_call_application(/* authenticated */ false, second_id, operation)
+        },
+    ));
+    second_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, argument| {
+            runtime.try_call_application(/* authenticated */ false, first_id, argument)
         },
     ));
-    application.expect_call(ExpectedCall::default_finalize());
 
     let context = create_dummy_operation_context();
     let mut controller = ResourceController::default();
-    let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
-    view.execute_operation(
-        context,
-        Timestamp::from(0),
-        Operation::User {
-            application_id,
-            bytes: vec![],
-        },
-        &mut txn_tracker,
-        &mut controller,
-    )
-    .await?;
-    view.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
-        .await?;
-
-    let application_description = view
-        .system
-        .registry
-        .describe_application(application_id)
-        .await?;
-    let registration_message = RawOutgoingMessage {
-        destination: Destination::from(destination_chain),
-        authenticated: false,
-        grant: Amount::ZERO,
-        kind: MessageKind::Simple,
-        message: SystemMessage::RegisterApplications {
-            applications: vec![application_description],
-        },
-    };
-    let account = Account {
-        chain_id: ChainId::root(0),
-        owner: None,
-    };
-
-    let (outcomes, _, _) = txn_tracker.destructure().unwrap();
-    assert_eq!(
-        outcomes,
-        &[
-            ExecutionOutcome::System(
-                RawExecutionOutcome::default().with_message(registration_message)
-            ),
-            ExecutionOutcome::User(
-                application_id,
-                RawExecutionOutcome::default()
-                    .with_message(expected_dummy_message)
-                    .with_refund_grant_to(Some(account))
-            ),
-            ExecutionOutcome::User(
-                application_id,
-                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
-            ),
-        ]
+    assert_matches!(
+        view.execute_operation(
+            context,
+            Timestamp::from(0),
+            Operation::User {
+                application_id: first_id,
+                bytes: vec![],
+            },
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut controller,
+        )
+        .await,
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(
+                *error,
+                ExecutionError::FailedCallChain { ref call_chain, ref error }
+                    if call_chain.0.len() == 1
+                        && matches!(**error, ExecutionError::ReentrantCall(id) if id == first_id)
+            )
     );
 
     Ok(())
 }
 
-/// Tests if a message is scheduled to be sent while an application is handling a cross-application
-/// call.
+/// Tests if the path of nested cross-application calls is reported when the innermost call fails.
 #[tokio::test]
-async fn test_message_from_cross_application_call() -> anyhow::Result<()> {
+async fn test_failed_call_chain() -> anyhow::Result<()> {
     let mut state = SystemExecutionState::default();
     state.description = Some(ChainDescription::Root(0));
     let mut view = state.into_view().await;
 
-    let (caller_id, caller_application) = view.register_mock_application().await?;
-    let (target_id, target_application) = view.register_mock_application().await?;
+    let (first_id, first_application) = view.register_mock_application().await?;
+    let (second_id, second_application) = view.register_mock_application().await?;
+    let (third_id, third_application) = view.register_mock_application().await?;
 
-    caller_application.expect_call(ExpectedCall::execute_operation(
+    first_application.expect_call(ExpectedCall::execute_operation(
         move |runtime, _context, _operation| {
-            runtime.try_call_application(/* authenticated */ false, target_id, vec![])?;
+            runtime.try_call_application(/* authenticated */ false, second_id, vec![1, 2])?;
             Ok(vec![])
         },
     ));
-
-    let destination_chain = ChainId::from(ChainDescription::Root(1));
-    let dummy_message = SendMessageRequest {
-        destination: Destination::from(destination_chain),
-        authenticated: false,
-        is_tracked: false,
-        grant: Resources::default(),
-        message: b"msg".to_vec(),
-    };
-
-    let fee_policy = ResourceControlPolicy::default();
-    let expected_dummy_message =
-        RawOutgoingMessage::from(dummy_message.clone()).into_priced(&fee_policy)?;
-
-    target_application.expect_call(ExpectedCall::execute_operation(
-        |runtime, _context, _argument| {
-            runtime.send_message(dummy_message)?;
+    second_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _argument| {
+            runtime.try_call_application(/* authenticated */ true, third_id, vec![3])?;
             Ok(vec![])
         },
     ));
+    third_application.expect_call(ExpectedCall::execute_operation(
+        |_runtime, _context, _argument| Err(ExecutionError::UserError("failed".to_owned())),
+    ));
 
-    target_application.expect_call(ExpectedCall::default_finalize());
+    let result = view
+        .execute_operation(
+            create_dummy_operation_context(),
+            Timestamp::from(0),
+            Operation::User {
+                application_id: first_id,
+                bytes: vec![],
+            },
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut ResourceController::default(),
+        )
+        .await;
+
+    let expected_call_chain = CallChain(vec![
+        ApplicationCall {
+            caller_id: first_id,
+            callee_id: second_id,
+            argument_len: 2,
+        },
+        ApplicationCall {
+            caller_id: second_id,
+            callee_id: third_id,
+            argument_len: 1,
+        },
+    ]);
+    let error = result.expect_err("The innermost call should fail");
+    let message = error.to_string();
+    let ExecutionError::FailedUserAction { error, .. } = error else {
+        panic!("Expected the failed user action to be reported, got {error:?}");
+    };
+    assert_matches!(
+        *error,
+        ExecutionError::FailedCallChain { call_chain, error }
+            if call_chain == expected_call_chain
+                && matches!(*error, ExecutionError::UserError(_))
+    );
+    assert!(message.contains(&format!("0: {first_id} -> {second_id} (2 argument bytes)")));
+    assert!(message.contains(&format!("1: {second_id} -> {third_id} (1 argument bytes)")));
+
+    Ok(())
+}
+
+/// Tests if an application that ignores a failed cross-application call is rejected.
+///
+/// The failed call leaves the callee in the call stack, which should be reported as an error
+/// instead of causing a panic.
+#[tokio::test]
+async fn test_ignored_cross_application_error() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (caller_id, caller_application) = view.register_mock_application().await?;
+    let (target_id, target_application) = view.register_mock_application().await?;
+
+    caller_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            let result =
+                runtime.try_call_application(/* authenticated */ false, target_id, vec![]);
+            assert!(result.is_err());
+            Ok(vec![])
+        },
+    ));
+    target_application.expect_call(ExpectedCall::execute_operation(
+        |_runtime, _context, _argument| Err(ExecutionError::UserError("failed".to_owned())),
+    ));
+
+    let context = create_dummy_operation_context();
+    let mut controller = ResourceController::default();
+    assert_matches!(
+        view.execute_operation(
+            context,
+            Timestamp::from(0),
+            Operation::User {
+                application_id: caller_id,
+                bytes: vec![],
+            },
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut controller,
+        )
+        .await,
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(
+                *error,
+                ExecutionError::UnexpectedCallStack(ref call_stack)
+                    if *call_stack == vec![caller_id, target_id]
+            )
+    );
+
+    Ok(())
+}
+
+/// Tests if the state changes of a failed operation are rolled back, including the changes made
+/// by applications that were called successfully before the failure.
+#[tokio::test]
+async fn test_rollback_of_failed_operation() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (caller_id, caller_application) = view.register_mock_application().await?;
+    let (target_id, target_application) = view.register_mock_application().await?;
+    let initial_hash = view.crypto_hash().await?;
+
+    caller_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![0], vec![1]);
+            runtime.write_batch(batch)?;
+            runtime.try_call_application(/* authenticated */ false, target_id, vec![])?;
+            Err(ExecutionError::UserError("failed after call".to_owned()))
+        },
+    ));
+    target_application.expect_call(ExpectedCall::execute_operation(
+        |runtime, _context, _argument| {
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![2], vec![3]);
+            runtime.write_batch(batch)?;
+            Ok(vec![])
+        },
+    ));
+
+    let context = create_dummy_operation_context();
+    let mut controller = ResourceController::default();
+    let result = view
+        .execute_operation(
+            context,
+            Timestamp::from(0),
+            Operation::User {
+                application_id: caller_id,
+                bytes: vec![],
+            },
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut controller,
+        )
+        .await;
+
+    assert_matches!(
+        result,
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(*error, ExecutionError::UserError(_))
+    );
+    assert_eq!(view.crypto_hash().await?, initial_hash);
+
+    Ok(())
+}
+
+/// Tests that the view is unchanged if the execution of an operation is dropped after the
+/// application has modified its state.
+#[tokio::test]
+async fn test_dropped_operation_leaves_view_unchanged() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+    let initial_hash = view.crypto_hash().await?;
+
+    let (written_sender, written_receiver) = futures::channel::oneshot::channel();
+    let (release_sender, release_receiver) = futures::channel::oneshot::channel::<()>();
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![0], vec![1]);
+            runtime.write_batch(batch)?;
+            written_sender.send(()).expect("the test should be waiting");
+            // Block until the test has dropped the execution.
+            let _ = futures::executor::block_on(release_receiver);
+            Err(ExecutionError::UserError(
+                "execution was dropped".to_owned(),
+            ))
+        },
+    ));
+
+    let context = create_dummy_operation_context();
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
+    let execution = Box::pin(view.execute_operation(
+        context,
+        Timestamp::from(0),
+        Operation::User {
+            application_id,
+            bytes: vec![],
+        },
+        &mut txn_tracker,
+        &mut controller,
+    ));
+
+    match futures::future::select(execution, written_receiver).await {
+        future::Either::Left((result, _)) => panic!("Execution finished early: {result:?}"),
+        future::Either::Right((written, execution)) => {
+            written?;
+            drop(execution);
+        }
+    }
+    release_sender
+        .send(())
+        .expect("the application should be waiting");
+
+    assert_eq!(view.crypto_hash().await?, initial_hash);
+
+    Ok(())
+}
+
+/// A tracing layer that records the names and field names of the spans that are created.
+#[derive(Clone, Default)]
+struct SpanRecorder(Arc<std::sync::Mutex<Vec<(&'static str, Vec<&'static str>)>>>);
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpanRecorder {
+    fn on_new_span(
+        &self,
+        attributes: &tracing::span::Attributes<'_>,
+        _id: &tracing::span::Id,
+        _context: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let fields = attributes
+            .fields()
+            .iter()
+            .map(|field| field.name())
+            .collect();
+        self.0
+            .lock()
+            .unwrap()
+            .push((attributes.metadata().name(), fields));
+    }
+}
+
+/// Tests that executing a user operation creates spans identifying the chain and the application.
+#[tokio::test]
+async fn test_execution_spans() -> anyhow::Result<()> {
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    let recorder = SpanRecorder::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+    let (application_id, application) = view.register_mock_application().await?;
+    application.expect_call(ExpectedCall::execute_operation(
+        |_runtime, _context, _operation| Ok(vec![]),
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    view.execute_operation(
+        create_dummy_operation_context(),
+        Timestamp::from(0),
+        Operation::User {
+            application_id,
+            bytes: vec![1, 2, 3],
+        },
+        &mut TransactionTracker::new(0, Some(Vec::new())),
+        &mut ResourceController::default(),
+    )
+    .await?;
+
+    let spans = recorder.0.lock().unwrap().clone();
+    assert!(spans.contains(&(
+        "execute_operation",
+        vec!["chain_id", "height", "application_id"]
+    )));
+    assert!(spans.contains(&(
+        "run_user_action",
+        vec!["chain_id", "application_id", "kind", "argument_size"]
+    )));
+
+    Ok(())
+}
+
+/// Tests that executed operations and their errors are counted per application.
+#[cfg(feature = "metrics")]
+#[tokio::test]
+async fn test_executed_operations_metric() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+    // Use an application that no other test executes, so that its counters are exact.
+    let (description, contract, service) = create_dummy_user_application_description(1_000);
+    let (application_id, application) = view
+        .register_mock_application_with(description, contract, service)
+        .await?;
+    let label = application_id.to_string();
+
+    let operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+    for _ in 0..3 {
+        application.expect_call(ExpectedCall::execute_operation(
+            |_runtime, _context, _operation| Ok(vec![]),
+        ));
+        application.expect_call(ExpectedCall::default_finalize());
+        view.execute_operation(
+            create_dummy_operation_context(),
+            Timestamp::from(0),
+            operation.clone(),
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut ResourceController::default(),
+        )
+        .await?;
+    }
+    application.expect_call(ExpectedCall::execute_operation(
+        |_runtime, _context, _operation| Err(ExecutionError::UserError("failed".to_owned())),
+    ));
+    let result = view
+        .execute_operation(
+            create_dummy_operation_context(),
+            Timestamp::from(0),
+            operation,
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut ResourceController::default(),
+        )
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(*error, ExecutionError::UserError(_))
+    );
+
+    assert_eq!(
+        counter_value("linera_executed_operations_count", &[&label]),
+        4
+    );
+    assert_eq!(
+        counter_value("linera_execution_errors_count", &[&label, "UserError"]),
+        1
+    );
+
+    Ok(())
+}
+
+/// Returns the value of the counter with the given name and label values.
+#[cfg(feature = "metrics")]
+fn counter_value(name: &str, label_values: &[&str]) -> u64 {
+    prometheus::gather()
+        .iter()
+        .filter(|family| family.get_name() == name)
+        .flat_map(|family| family.get_metric())
+        .filter(|metric| {
+            metric
+                .get_label()
+                .iter()
+                .map(|label| label.get_value())
+                .eq(label_values.iter().copied())
+        })
+        .map(|metric| metric.get_counter().get_value() as u64)
+        .sum()
+}
+
+/// Tests that the number of cross-application calls is reported by the resource tracker.
+#[test_case(0; "without calls")]
+#[test_case(1; "with a single call")]
+#[test_case(3; "with nested calls")]
+#[tokio::test]
+async fn test_application_call_count(depth: usize) -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let mut applications = Vec::with_capacity(depth + 1);
+    for _ in 0..=depth {
+        applications.push(view.register_mock_application().await?);
+    }
+
+    for (index, (_, application)) in applications.iter().enumerate() {
+        let next_id = applications.get(index + 1).map(|(id, _)| *id);
+        application.expect_call(ExpectedCall::execute_operation(
+            move |runtime, _context, _operation| {
+                if let Some(next_id) = next_id {
+                    runtime.try_call_application(
+                        /* authenticated */ false,
+                        next_id,
+                        vec![],
+                    )?;
+                }
+                Ok(vec![])
+            },
+        ));
+    }
+    for (_, application) in applications.iter().rev() {
+        application.expect_call(ExpectedCall::default_finalize());
+    }
+
+    let context = create_dummy_operation_context();
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
+    view.execute_operation(
+        context,
+        Timestamp::from(0),
+        Operation::User {
+            application_id: applications[0].0,
+            bytes: vec![],
+        },
+        &mut txn_tracker,
+        &mut controller,
+    )
+    .await?;
+
+    assert_eq!(controller.tracker.application_calls, depth as u32);
+    assert_eq!(txn_tracker.destructure()?.application_calls, depth as u32);
+
+    Ok(())
+}
+
+/// Tests that each transaction outcome reports the bytes read and written by that transaction.
+#[tokio::test]
+async fn test_transaction_bytes_read_and_written() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+    let state_key = vec![0];
+    let value = vec![1; 10];
+    let mut batch = Batch::new();
+    batch.put_key_value_bytes(state_key.clone(), value.clone());
+    let batch_size = batch.size() as u64;
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            runtime.write_batch(batch)?;
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+    application.expect_call(ExpectedCall::execute_operation({
+        let value = value.clone();
+        move |runtime, _context, _operation| {
+            assert_eq!(runtime.read_value_bytes(state_key)?, Some(value));
+            Ok(vec![])
+        }
+    }));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let mut controller = ResourceController::default();
+    let mut outcomes = Vec::new();
+    for index in 0..2 {
+        let context = OperationContext {
+            index: Some(index),
+            ..create_dummy_operation_context()
+        };
+        let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
+        view.execute_operation(
+            context,
+            Timestamp::from(0),
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+            &mut txn_tracker,
+            &mut controller,
+        )
+        .await?;
+        outcomes.push(txn_tracker.destructure()?);
+    }
+
+    assert_eq!(outcomes[0].bytes_written, batch_size);
+    assert_eq!(outcomes[0].bytes_read, 0);
+    assert_eq!(outcomes[1].bytes_written, 0);
+    assert_eq!(outcomes[1].bytes_read, value.len() as u64);
+    assert_eq!(controller.tracker.bytes_written, batch_size);
+    assert_eq!(controller.tracker.bytes_read, value.len() as u64);
+
+    Ok(())
+}
+
+/// Tests that nested cross-application calls are limited by the maximum call depth.
+#[test_case(2, 2; "within the limit")]
+#[test_case(3, 2; "exceeding the limit")]
+#[tokio::test]
+async fn test_maximum_call_depth(depth: usize, maximum_call_depth: u64) -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let mut applications = Vec::with_capacity(depth + 1);
+    for _ in 0..=depth {
+        applications.push(view.register_mock_application().await?);
+    }
+
+    for (index, (_, application)) in applications.iter().enumerate() {
+        let next_id = applications.get(index + 1).map(|(id, _)| *id);
+        application.expect_call(ExpectedCall::execute_operation(
+            move |runtime, _context, _operation| {
+                if let Some(next_id) = next_id {
+                    runtime.try_call_application(
+                        /* authenticated */ false,
+                        next_id,
+                        vec![],
+                    )?;
+                }
+                Ok(vec![])
+            },
+        ));
+    }
+    for (_, application) in applications.iter().rev() {
+        application.expect_call(ExpectedCall::default_finalize());
+    }
+
+    let policy = ResourceControlPolicy {
+        maximum_call_depth,
+        ..ResourceControlPolicy::default()
+    };
+    let mut controller = ResourceController {
+        policy: Arc::new(policy),
+        ..ResourceController::default()
+    };
+    let result = view
+        .execute_operation(
+            create_dummy_operation_context(),
+            Timestamp::from(0),
+            Operation::User {
+                application_id: applications[0].0,
+                bytes: vec![],
+            },
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut controller,
+        )
+        .await;
+
+    if depth as u64 <= maximum_call_depth {
+        assert_matches!(result, Ok(()));
+    } else {
+        let deepest_id = applications[depth].0;
+        assert_matches!(
+            result,
+            Err(ExecutionError::FailedUserAction { error, .. })
+                if matches!(
+                    *error,
+                    ExecutionError::FailedCallChain { ref call_chain, ref error }
+                        if call_chain.0.len() == depth - 1
+                            && matches!(
+                                **error,
+                                ExecutionError::CallDepthExceeded {
+                                    depth: error_depth,
+                                    application_id,
+                                } if error_depth == depth as u64 && application_id == deepest_id
+                            )
+                )
+        );
+    }
+
+    Ok(())
+}
+
+/// Tests if an application is scheduled to be registered together with any messages it sends to
+/// other chains.
+#[tokio::test]
+async fn test_simple_message() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+
+    let destination_chain = ChainId::from(ChainDescription::Root(1));
+    let dummy_message = SendMessageRequest {
+        destination: Destination::from(destination_chain),
+        authenticated: false,
+        is_tracked: false,
+        grant: Resources::default(),
+        message: b"msg".to_vec(),
+    };
+
+    let fee_policy = ResourceControlPolicy::default();
+    let expected_dummy_message =
+        RawOutgoingMessage::from(dummy_message.clone()).into_priced(&fee_policy)?;
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            runtime.send_message(dummy_message)?;
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let context = create_dummy_operation_context();
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
+    view.execute_operation(
+        context,
+        Timestamp::from(0),
+        Operation::User {
+            application_id,
+            bytes: vec![],
+        },
+        &mut txn_tracker,
+        &mut controller,
+    )
+    .await?;
+    view.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
+        .await?;
+
+    let application_description = view
+        .system
+        .registry
+        .describe_application(application_id)
+        .await?;
+    let registration_message = RawOutgoingMessage {
+        destination: Destination::from(destination_chain),
+        authenticated: false,
+        grant: Amount::ZERO,
+        kind: MessageKind::Simple,
+        message: SystemMessage::RegisterApplications {
+            applications: vec![application_description],
+        },
+    };
+    let account = Account {
+        chain_id: ChainId::root(0),
+        owner: None,
+    };
+
+    let outcomes = txn_tracker.destructure().unwrap().outcomes;
+    assert_eq!(
+        outcomes,
+        &[
+            ExecutionOutcome::System(
+                RawExecutionOutcome::default().with_message(registration_message)
+            ),
+            ExecutionOutcome::User(
+                application_id,
+                RawExecutionOutcome::default()
+                    .with_message(expected_dummy_message)
+                    .with_refund_grant_to(Some(account))
+            ),
+            ExecutionOutcome::User(
+                application_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+/// Tests if a message is scheduled to be sent while an application is handling a cross-application
+/// call.
+#[tokio::test]
+async fn test_message_from_cross_application_call() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (caller_id, caller_application) = view.register_mock_application().await?;
+    let (target_id, target_application) = view.register_mock_application().await?;
+
+    caller_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            runtime.try_call_application(/* authenticated */ false, target_id, vec![])?;
+            Ok(vec![])
+        },
+    ));
+
+    let destination_chain = ChainId::from(ChainDescription::Root(1));
+    let dummy_message = SendMessageRequest {
+        destination: Destination::from(destination_chain),
+        authenticated: false,
+        is_tracked: false,
+        grant: Resources::default(),
+        message: b"msg".to_vec(),
+    };
+
+    let fee_policy = ResourceControlPolicy::default();
+    let expected_dummy_message =
+        RawOutgoingMessage::from(dummy_message.clone()).into_priced(&fee_policy)?;
+
+    target_application.expect_call(ExpectedCall::execute_operation(
+        |runtime, _context, _argument| {
+            runtime.send_message(dummy_message)?;
+            Ok(vec![])
+        },
+    ));
+
+    target_application.expect_call(ExpectedCall::default_finalize());
+    caller_application.expect_call(ExpectedCall::default_finalize());
+
+    let context = create_dummy_operation_context();
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
+    view.execute_operation(
+        context,
+        Timestamp::from(0),
+        Operation::User {
+            application_id: caller_id,
+            bytes: vec![],
+        },
+        &mut txn_tracker,
+        &mut controller,
+    )
+    .await?;
+    view.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
+        .await?;
+
+    let target_description = view.system.registry.describe_application(target_id).await?;
+    let registration_message = RawOutgoingMessage {
+        destination: Destination::from(destination_chain),
+        authenticated: false,
+        grant: Amount::ZERO,
+        kind: MessageKind::Simple,
+        message: SystemMessage::RegisterApplications {
+            applications: vec![target_description],
+        },
+    };
+    let account = Account {
+        chain_id: ChainId::root(0),
+        owner: None,
+    };
+
+    let outcomes = txn_tracker.destructure().unwrap().outcomes;
+    assert_eq!(
+        outcomes,
+        &[
+            ExecutionOutcome::System(
+                RawExecutionOutcome::default().with_message(registration_message)
+            ),
+            ExecutionOutcome::User(
+                target_id,
+                RawExecutionOutcome::default()
+                    .with_message(expected_dummy_message)
+                    .with_refund_grant_to(Some(account))
+            ),
+            ExecutionOutcome::User(
+                caller_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
+            ),
+            ExecutionOutcome::User(
+                target_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
+            ),
+            ExecutionOutcome::User(
+                caller_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+/// Tests if a message is scheduled to be sent by a deeper cross-application call.
+#[tokio::test]
+async fn test_message_from_deeper_call() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (caller_id, caller_application) = view.register_mock_application().await?;
+    let (middle_id, middle_application) = view.register_mock_application().await?;
+    let (target_id, target_application) = view.register_mock_application().await?;
+
+    caller_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            runtime.try_call_application(/* authenticated */ false, middle_id, vec![])?;
+            Ok(vec![])
+        },
+    ));
+
+    middle_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _argument| {
+            runtime.try_call_application(/* authenticated */ false, target_id, vec![])?;
+            Ok(vec![])
+        },
+    ));
+
+    let destination_chain = ChainId::from(ChainDescription::Root(1));
+    let dummy_message = SendMessageRequest {
+        destination: Destination::from(destination_chain),
+        authenticated: false,
+        is_tracked: false,
+        grant: Resources::default(),
+        message: b"msg".to_vec(),
+    };
+
+    let fee_policy = ResourceControlPolicy::default();
+    let expected_dummy_message =
+        RawOutgoingMessage::from(dummy_message.clone()).into_priced(&fee_policy)?;
+
+    target_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _argument| {
+            runtime.send_message(dummy_message)?;
+            Ok(vec![])
+        },
+    ));
+
+    target_application.expect_call(ExpectedCall::default_finalize());
+    middle_application.expect_call(ExpectedCall::default_finalize());
+    caller_application.expect_call(ExpectedCall::default_finalize());
+
+    let context = create_dummy_operation_context();
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
+    view.execute_operation(
+        context,
+        Timestamp::from(0),
+        Operation::User {
+            application_id: caller_id,
+            bytes: vec![],
+        },
+        &mut txn_tracker,
+        &mut controller,
+    )
+    .await?;
+
+    let target_description = view.system.registry.describe_application(target_id).await?;
+    let registration_message = RawOutgoingMessage {
+        destination: Destination::from(destination_chain),
+        authenticated: false,
+        grant: Amount::ZERO,
+        kind: MessageKind::Simple,
+        message: SystemMessage::RegisterApplications {
+            applications: vec![target_description],
+        },
+    };
+    view.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
+        .await?;
+    let account = Account {
+        chain_id: ChainId::root(0),
+        owner: None,
+    };
+    let outcomes = txn_tracker.destructure().unwrap().outcomes;
+    assert_eq!(
+        outcomes,
+        &[
+            ExecutionOutcome::System(
+                RawExecutionOutcome::default().with_message(registration_message)
+            ),
+            ExecutionOutcome::User(
+                target_id,
+                RawExecutionOutcome::default()
+                    .with_message(expected_dummy_message)
+                    .with_refund_grant_to(Some(account))
+            ),
+            ExecutionOutcome::User(
+                middle_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
+            ),
+            ExecutionOutcome::User(
+                caller_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
+            ),
+            ExecutionOutcome::User(
+                target_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
+            ),
+            ExecutionOutcome::User(
+                middle_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
+            ),
+            ExecutionOutcome::User(
+                caller_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+/// Tests if multiple messages are scheduled to be sent by different applications to different
+/// chains.
+///
+/// Ensures that in a more complex scenario, chains receive application registration messages only
+/// for the applications that will receive messages on them.
+#[tokio::test]
+async fn test_multiple_messages_from_different_applications() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    // The entrypoint application, which sends a message and calls other applications
+    let (caller_id, caller_application) = view.register_mock_application().await?;
+    // An application that does not send any messages
+    let (silent_target_id, silent_target_application) = view.register_mock_application().await?;
+    // An application that sends a message when handling a cross-application call
+    let (sending_target_id, sending_target_application) = view.register_mock_application().await?;
+
+    // The first destination chain receives messages from the caller and the sending applications
+    let first_destination_chain = ChainId::from(ChainDescription::Root(1));
+    // The second destination chain only receives a message from the sending application
+    let second_destination_chain = ChainId::from(ChainDescription::Root(2));
+
+    // The message sent to the first destination chain by the caller and the sending applications
+    let first_message = SendMessageRequest {
+        destination: Destination::from(first_destination_chain),
+        authenticated: false,
+        is_tracked: false,
+        grant: Resources::default(),
+        message: b"first".to_vec(),
+    };
+
+    let fee_policy = ResourceControlPolicy::default();
+    let expected_first_message =
+        RawOutgoingMessage::from(first_message.clone()).into_priced(&fee_policy)?;
+
+    // The entrypoint sends a message to the first chain and calls the silent and the sending
+    // applications
+    caller_application.expect_call(ExpectedCall::execute_operation({
+        let first_message = first_message.clone();
+        move |runtime, _context, _operation| {
+            runtime.try_call_application(
+                /* authenticated */ false,
+                silent_target_id,
+                vec![],
+            )?;
+            runtime.send_message(first_message)?;
+            runtime.try_call_application(
+                /* authenticated */ false,
+                sending_target_id,
+                vec![],
+            )?;
+            Ok(vec![])
+        }
+    }));
+
+    // The silent application does nothing
+    silent_target_application.expect_call(ExpectedCall::execute_operation(
+        |_runtime, _context, _argument| Ok(vec![]),
+    ));
+
+    // The message sent to the second destination chain by the sending application
+    let second_message = SendMessageRequest {
+        destination: Destination::from(second_destination_chain),
+        authenticated: false,
+        is_tracked: false,
+        grant: Resources::default(),
+        message: b"second".to_vec(),
+    };
+
+    let expected_second_message =
+        RawOutgoingMessage::from(second_message.clone()).into_priced(&fee_policy)?;
+
+    // The sending application sends two messages, one to each of the destination chains
+    sending_target_application.expect_call(ExpectedCall::execute_operation(
+        |runtime, _context, _argument| {
+            runtime.send_message(first_message)?;
+            runtime.send_message(second_message)?;
+            Ok(vec![])
+        },
+    ));
+
+    sending_target_application.expect_call(ExpectedCall::default_finalize());
+    silent_target_application.expect_call(ExpectedCall::default_finalize());
+    caller_application.expect_call(ExpectedCall::default_finalize());
+
+    // Execute the operation, starting the test scenario
+    let context = create_dummy_operation_context();
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
+    view.execute_operation(
+        context,
+        Timestamp::from(0),
+        Operation::User {
+            application_id: caller_id,
+            bytes: vec![],
+        },
+        &mut txn_tracker,
+        &mut controller,
+    )
+    .await?;
+    view.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
+        .await?;
+
+    // Describe the two applications that sent messages, and will therefore handle them in the
+    // other chains
+    let caller_description = view.system.registry.describe_application(caller_id).await?;
+    let sending_target_description = view
+        .system
+        .registry
+        .describe_application(sending_target_id)
+        .await?;
+
+    // The registration message for the first destination chain
+    let first_registration_message = RawOutgoingMessage {
+        destination: Destination::from(first_destination_chain),
+        authenticated: false,
+        grant: Amount::ZERO,
+        kind: MessageKind::Simple,
+        message: SystemMessage::RegisterApplications {
+            applications: vec![sending_target_description.clone(), caller_description],
+        },
+    };
+
+    // The registration message for the second destination chain
+    let second_registration_message = RawOutgoingMessage {
+        destination: Destination::from(second_destination_chain),
+        authenticated: false,
+        grant: Amount::ZERO,
+        kind: MessageKind::Simple,
+        message: SystemMessage::RegisterApplications {
+            applications: vec![sending_target_description],
+        },
+    };
+
+    let account = Account {
+        chain_id: ChainId::root(0),
+        owner: None,
+    };
+
+    // Return to checking the user application outcomes
+    let outcomes = txn_tracker.destructure().unwrap().outcomes;
+    assert_eq!(
+        outcomes,
+        &[
+            ExecutionOutcome::System(
+                RawExecutionOutcome::default()
+                    .with_message(second_registration_message)
+                    .with_message(first_registration_message)
+            ),
+            ExecutionOutcome::User(
+                silent_target_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account)),
+            ),
+            ExecutionOutcome::User(
+                sending_target_id,
+                RawExecutionOutcome::default()
+                    .with_message(expected_first_message.clone())
+                    .with_message(expected_second_message)
+                    .with_refund_grant_to(Some(account))
+            ),
+            ExecutionOutcome::User(
+                caller_id,
+                RawExecutionOutcome::default()
+                    .with_message(expected_first_message)
+                    .with_refund_grant_to(Some(account))
+            ),
+            ExecutionOutcome::User(
+                sending_target_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
+            ),
+            ExecutionOutcome::User(
+                silent_target_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account)),
+            ),
+            ExecutionOutcome::User(
+                caller_id,
+                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+/// Tests if a sequence of operations is executed in order, stopping at the first failure.
+#[test_case(None; "without failures")]
+#[test_case(Some(1); "with a failing operation")]
+#[tokio::test]
+async fn test_execute_operations(failing_index: Option<u32>) -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+
+    let operation_count = 3;
+    let executed_count = failing_index.map_or(operation_count, |index| index + 1);
+    for index in 0..executed_count {
+        application.expect_call(ExpectedCall::execute_operation(
+            move |_runtime, context, _operation| {
+                assert_eq!(context.index, Some(index));
+                if Some(index) == failing_index {
+                    Err(ExecutionError::UserError("failed".to_owned()))
+                } else {
+                    Ok(vec![])
+                }
+            },
+        ));
+        if Some(index) != failing_index {
+            application.expect_call(ExpectedCall::default_finalize());
+        }
+    }
+
+    let operations = (0..operation_count)
+        .map(|_| Operation::User {
+            application_id,
+            bytes: vec![],
+        })
+        .collect();
+    let result = view
+        .execute_operations(
+            create_dummy_operation_context(),
+            Timestamp::from(0),
+            operations,
+            &mut ResourceController::default(),
+        )
+        .await;
+
+    match failing_index {
+        None => {
+            let outcomes = result?;
+            assert_eq!(outcomes.len(), operation_count as usize);
+        }
+        Some(failing_index) => assert_matches!(
+            result,
+            Err(ExecutionError::FailedOperation { index, error })
+                if index == failing_index
+                    && matches!(
+                        *error,
+                        ExecutionError::FailedUserAction { ref error, .. }
+                            if matches!(**error, ExecutionError::UserError(_))
+                    )
+        ),
+    }
+
+    Ok(())
+}
+
+/// Tests that the application descriptions shared by the operations of `execute_operations` are
+/// read again after a system operation, which may remove them from the registry.
+#[tokio::test]
+async fn test_execute_operations_after_registry_pruning() -> anyhow::Result<()> {
+    let owner = Owner::from(PublicKey::test_key(0));
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    state.ownership = ChainOwnership::single(owner);
+    let mut view = state.into_view().await;
+
+    // The application has no state, so its description is pruned.
+    let (application_id, application) = view.register_mock_application().await?;
+    application.expect_call(ExpectedCall::execute_operation(
+        |_runtime, _context, _operation| Ok(vec![]),
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let user_operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+    let mut context = create_dummy_operation_context();
+    context.authenticated_signer = Some(owner);
+    let result = view
+        .execute_operations(
+            context,
+            Timestamp::from(0),
+            vec![
+                user_operation.clone(),
+                Operation::System(SystemOperation::PruneRegistry),
+                user_operation,
+            ],
+            &mut ResourceController::default(),
+        )
+        .await;
+
+    assert_matches!(
+        result,
+        Err(ExecutionError::FailedOperation { index: 2, error })
+            if matches!(
+                *error,
+                ExecutionError::FailedUserAction { ref error, .. }
+                    if matches!(
+                        **error,
+                        ExecutionError::SystemError(SystemExecutionError::UnknownApplicationId(_))
+                    )
+            )
+    );
+    Ok(())
+}
+
+/// Tests that all the applications created by a transaction are reported in its outcome.
+#[tokio::test]
+async fn test_created_applications_in_transaction_outcome() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (creator_id, creator_application) = view.register_mock_application().await?;
+    let context = create_dummy_operation_context();
+    // Each creation sends a message, so the applications are created at consecutive indices.
+    let created_ids = (0..2)
+        .map(|index| UserApplicationId {
+            bytecode_id: creator_id.bytecode_id,
+            creation: MessageId {
+                chain_id: context.chain_id,
+                height: context.height,
+                index,
+            },
+        })
+        .collect::<Vec<_>>();
+    for created_id in &created_ids {
+        let application = MockApplication::default();
+        application.expect_call(ExpectedCall::instantiate(|_, _, _| Ok(())));
+        application.expect_call(ExpectedCall::default_finalize());
+        view.context()
+            .extra()
+            .user_contracts()
+            .insert(*created_id, application.into());
+    }
+
+    let bytecode_id = creator_id.bytecode_id;
+    creator_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            for _ in 0..2 {
+                runtime.create_application(bytecode_id, vec![], vec![], vec![])?;
+            }
+            Ok(vec![])
+        },
+    ));
+    creator_application.expect_call(ExpectedCall::default_finalize());
+
+    let mut txn_tracker = TransactionTracker::new(0, None);
+    view.execute_operation(
+        context,
+        Timestamp::from(0),
+        Operation::User {
+            application_id: creator_id,
+            bytes: vec![],
+        },
+        &mut txn_tracker,
+        &mut ResourceController::default(),
+    )
+    .await?;
+
+    let created_applications = txn_tracker.destructure()?.created_applications;
+    let reported_ids = created_applications
+        .iter()
+        .map(|(application_id, _)| *application_id)
+        .collect::<Vec<_>>();
+    assert_eq!(reported_ids, created_ids);
+    for (application_id, description) in created_applications {
+        assert_eq!(UserApplicationId::from(&description), application_id);
+    }
+
+    Ok(())
+}
+
+/// Tests if simulating an operation predicts its outcomes and the resulting state hash, without
+/// modifying the state.
+#[tokio::test]
+async fn test_simulate_operation() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (caller_id, caller_application) = view.register_mock_application().await?;
+    let (target_id, target_application) = view.register_mock_application().await?;
+    let initial_hash = view.crypto_hash().await?;
+
+    let dummy_message = SendMessageRequest {
+        destination: Destination::from(ChainId::root(1)),
+        authenticated: false,
+        is_tracked: false,
+        grant: Resources::default(),
+        message: b"msg".to_vec(),
+    };
+
+    // Expect the same calls twice: once for the simulation and once for the execution.
+    for _ in 0..2 {
+        let dummy_message = dummy_message.clone();
+        caller_application.expect_call(ExpectedCall::execute_operation(
+            move |runtime, _context, _operation| {
+                let mut batch = Batch::new();
+                batch.put_key_value_bytes(vec![0], vec![1]);
+                runtime.write_batch(batch)?;
+                runtime.try_call_application(/* authenticated */ false, target_id, vec![])?;
+                runtime.send_message(dummy_message)?;
+                Ok(vec![])
+            },
+        ));
+        target_application.expect_call(ExpectedCall::execute_operation(
+            |runtime, _context, _argument| {
+                let mut batch = Batch::new();
+                batch.put_key_value_bytes(vec![2], vec![3]);
+                runtime.write_batch(batch)?;
+                Ok(vec![])
+            },
+        ));
+        target_application.expect_call(ExpectedCall::default_finalize());
+        caller_application.expect_call(ExpectedCall::default_finalize());
+    }
+
+    let context = create_dummy_operation_context();
+    let operation = Operation::User {
+        application_id: caller_id,
+        bytes: vec![],
+    };
+    let (simulated_outcome, predicted_hash) = view
+        .simulate_operation(
+            context,
+            Timestamp::from(0),
+            operation.clone(),
+            &mut ResourceController::default(),
+        )
+        .await?;
+    assert_eq!(view.crypto_hash().await?, initial_hash);
+    assert_ne!(predicted_hash, initial_hash);
+
+    let mut txn_tracker = TransactionTracker::new(0, None);
+    view.execute_operation(
+        context,
+        Timestamp::from(0),
+        operation,
+        &mut txn_tracker,
+        &mut ResourceController::default(),
+    )
+    .await?;
+    view.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
+        .await?;
+    let outcomes = txn_tracker.destructure()?.outcomes;
+
+    assert_eq!(outcomes, simulated_outcome.outcomes);
+    assert_eq!(view.crypto_hash().await?, predicted_hash);
+
+    Ok(())
+}
+
+/// Tests if executing an operation in a fork of the execution state leaves the original view
+/// untouched, and that the changes of the fork can be flattened into a batch.
+#[tokio::test]
+async fn test_fork_execution_state() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+    let initial_hash = view.crypto_hash().await?;
+
+    application.expect_call(ExpectedCall::execute_operation(
+        |runtime, _context, _operation| {
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![0], vec![1]);
+            runtime.write_batch(batch)?;
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let mut fork = view.fork()?;
+    fork.execute_operation(
+        create_dummy_operation_context(),
+        Timestamp::from(0),
+        Operation::User {
+            application_id,
+            bytes: vec![],
+        },
+        &mut TransactionTracker::new(0, Some(Vec::new())),
+        &mut ResourceController::default(),
+    )
+    .await?;
+
+    let fork_hash = fork.crypto_hash().await?;
+    assert_ne!(fork_hash, initial_hash);
+    assert_eq!(view.crypto_hash().await?, initial_hash);
+
+    let mut batch = Batch::new();
+    fork.flush(&mut batch)?;
+    view.context().write_batch(batch).await?;
+    let reloaded_view = ExecutionStateView::load(view.context().clone()).await?;
+    assert_eq!(reloaded_view.crypto_hash().await?, fork_hash);
+
+    Ok(())
+}
+
+/// Tests if the limits on the number and size of outgoing messages are enforced.
+#[test_case(3, b"msg", 2, MessageLimit::Count; "with too many messages")]
+#[test_case(1, b"message", 2, MessageLimit::Size; "with a message that is too large")]
+#[test_case(2, b"msg", 2, MessageLimit::None; "within the limits")]
+#[tokio::test]
+async fn test_message_limits(
+    message_count: usize,
+    message: &'static [u8],
+    maximum_messages_per_transaction: u64,
+    exceeded_limit: MessageLimit,
+) -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+
+    let dummy_message = SendMessageRequest {
+        destination: Destination::from(ChainId::root(1)),
+        authenticated: false,
+        is_tracked: false,
+        grant: Resources::default(),
+        message: message.to_vec(),
+    };
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            for _ in 0..message_count {
+                runtime.send_message(dummy_message.clone())?;
+            }
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let policy = ResourceControlPolicy {
+        maximum_message_size: 3,
+        maximum_messages_per_transaction,
+        ..ResourceControlPolicy::default()
+    };
+    let mut controller = ResourceController {
+        policy: Arc::new(policy),
+        ..ResourceController::default()
+    };
+    let result = view
+        .execute_operation(
+            create_dummy_operation_context(),
+            Timestamp::from(0),
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut controller,
+        )
+        .await;
+
+    match exceeded_limit {
+        MessageLimit::None => assert_matches!(result, Ok(())),
+        MessageLimit::Count => assert_matches!(
+            result,
+            Err(ExecutionError::FailedUserAction { error, .. })
+                if matches!(*error, ExecutionError::TooManyMessages(id) if id == application_id)
+        ),
+        MessageLimit::Size => assert_matches!(
+            result,
+            Err(ExecutionError::FailedUserAction { error, .. })
+                if matches!(*error, ExecutionError::MessageTooLarge(id) if id == application_id)
+        ),
+    }
+
+    Ok(())
+}
+
+/// The message limit that is expected to be exceeded in [`test_message_limits`].
+#[derive(Clone, Copy, Debug)]
+enum MessageLimit {
+    None,
+    Count,
+    Size,
+}
+
+/// Tests that the number and size of the events emitted in a transaction are limited, and that
+/// events are recorded in the order in which they were emitted.
+#[test_case(3, b"evt", 2, EventLimit::Count; "with too many events")]
+#[test_case(1, b"event", 2, EventLimit::Size; "with an event that is too large")]
+#[test_case(2, b"evt", 2, EventLimit::None; "within the limits")]
+#[tokio::test]
+async fn test_event_limits(
+    event_count: u8,
+    value: &'static [u8],
+    maximum_events_per_transaction: u64,
+    exceeded_limit: EventLimit,
+) -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+
+    let stream_name = StreamName(b"stream".to_vec());
+    let expected_events = (0..event_count)
+        .map(|index| (stream_name.clone(), vec![index], value.to_vec()))
+        .collect::<Vec<_>>();
+
+    application.expect_call(ExpectedCall::execute_operation({
+        let events = expected_events.clone();
+        move |runtime, _context, _operation| {
+            for (name, key, value) in events {
+                runtime.emit(name, key, value)?;
+            }
+            Ok(vec![])
+        }
+    }));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let policy = ResourceControlPolicy {
+        maximum_event_value_size: 3,
+        maximum_events_per_transaction,
+        ..ResourceControlPolicy::default()
+    };
+    let mut controller = ResourceController {
+        policy: Arc::new(policy),
+        ..ResourceController::default()
+    };
+    let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
+    let result = view
+        .execute_operation(
+            create_dummy_operation_context(),
+            Timestamp::from(0),
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+            &mut txn_tracker,
+            &mut controller,
+        )
+        .await;
+
+    match exceeded_limit {
+        EventLimit::None => {
+            assert_matches!(result, Ok(()));
+            let outcomes = txn_tracker.destructure()?.outcomes;
+            let events = outcomes
+                .into_iter()
+                .flat_map(|outcome| match outcome {
+                    ExecutionOutcome::User(_, outcome) => outcome.events,
+                    ExecutionOutcome::System(outcome) => outcome.events,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(events, expected_events);
+        }
+        EventLimit::Count => assert_matches!(
+            result,
+            Err(ExecutionError::FailedUserAction { error, .. })
+                if matches!(*error, ExecutionError::TooManyEvents(id) if id == application_id)
+        ),
+        EventLimit::Size => assert_matches!(
+            result,
+            Err(ExecutionError::FailedUserAction { error, .. })
+                if matches!(*error, ExecutionError::EventTooLarge(id) if id == application_id)
+        ),
+    }
+
+    Ok(())
+}
+
+/// The event limit that is expected to be exceeded in [`test_event_limits`].
+#[derive(Clone, Copy, Debug)]
+enum EventLimit {
+    None,
+    Count,
+    Size,
+}
+
+/// Tests if applications are registered at most once per destination, and never on the current
+/// chain.
+#[tokio::test]
+async fn test_deduplicated_application_registrations() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+
+    let local_chain = ChainId::from(ChainDescription::Root(0));
+    let destination_chain = ChainId::from(ChainDescription::Root(1));
+    let message_to = |chain_id: ChainId| SendMessageRequest {
+        destination: Destination::from(chain_id),
+        authenticated: false,
+        is_tracked: false,
+        grant: Resources::default(),
+        message: b"msg".to_vec(),
+    };
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            for _ in 0..3 {
+                runtime.send_message(message_to(destination_chain))?;
+            }
+            runtime.send_message(message_to(local_chain))?;
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let context = create_dummy_operation_context();
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
+    view.execute_operation(
+        context,
+        Timestamp::from(0),
+        Operation::User {
+            application_id,
+            bytes: vec![],
+        },
+        &mut txn_tracker,
+        &mut controller,
+    )
+    .await?;
+    view.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
+        .await?;
+
+    let outcomes = txn_tracker.destructure()?.outcomes;
+    let registration_destinations = outcomes
+        .iter()
+        .filter_map(|outcome| match outcome {
+            ExecutionOutcome::System(outcome) => Some(&outcome.messages),
+            ExecutionOutcome::User(_, _) => None,
+        })
+        .flatten()
+        .filter(|message| matches!(message.message, SystemMessage::RegisterApplications { .. }))
+        .map(|message| message.destination.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        registration_destinations,
+        vec![Destination::from(destination_chain)]
+    );
+
+    Ok(())
+}
+
+/// Tests that the application registration messages are ordered deterministically: in a single
+/// system outcome before the user outcomes, sorted by destination, regardless of the order in
+/// which the applications sent their messages.
+#[tokio::test]
+async fn test_application_registrations_order() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (caller_id, caller_application) = view.register_mock_application().await?;
+    let (target_id, target_application) = view.register_mock_application().await?;
+
+    let first_chain = ChainId::root(1);
+    let second_chain = ChainId::root(2);
+    let message_to = |chain_id: ChainId| SendMessageRequest {
+        destination: Destination::from(chain_id),
+        authenticated: false,
+        is_tracked: false,
+        grant: Resources::default(),
+        message: b"msg".to_vec(),
+    };
+
+    caller_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            runtime.send_message(message_to(second_chain))?;
+            runtime.try_call_application(/* authenticated */ false, target_id, vec![])?;
+            runtime.send_message(message_to(first_chain))?;
+            Ok(vec![])
+        },
+    ));
+    target_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            runtime.send_message(message_to(first_chain))?;
+            Ok(vec![])
+        },
+    ));
+    target_application.expect_call(ExpectedCall::default_finalize());
+    caller_application.expect_call(ExpectedCall::default_finalize());
+
+    let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
+    view.execute_operation(
+        create_dummy_operation_context(),
+        Timestamp::from(0),
+        Operation::User {
+            application_id: caller_id,
+            bytes: vec![],
+        },
+        &mut txn_tracker,
+        &mut ResourceController::default(),
+    )
+    .await?;
+    view.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
+        .await?;
+
+    let outcomes = txn_tracker.destructure()?.outcomes;
+    let Some((ExecutionOutcome::System(registrations), user_outcomes)) = outcomes.split_first()
+    else {
+        panic!("Expected the registrations to come first, got {outcomes:?}");
+    };
+    assert!(user_outcomes
+        .iter()
+        .all(|outcome| matches!(outcome, ExecutionOutcome::User(_, _))));
+
+    let registry = &view.system.registry;
+    let mut both_ids = vec![caller_id, target_id];
+    both_ids.sort();
+    let mut expected_registrations = vec![
+        (
+            Destination::from(first_chain),
+            registry
+                .describe_applications_with_dependencies(both_ids)
+                .await?,
+        ),
+        (
+            Destination::from(second_chain),
+            vec![registry.describe_application(caller_id).await?],
+        ),
+    ];
+    expected_registrations.sort_by(|(first, _), (second, _)| first.cmp(second));
+
+    let registrations = registrations
+        .messages
+        .iter()
+        .map(|message| match &message.message {
+            SystemMessage::RegisterApplications { applications } => {
+                (message.destination.clone(), applications.clone())
+            }
+            other => panic!("Unexpected system message: {other:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(registrations, expected_registrations);
+
+    Ok(())
+}
+
+/// Tests the exact serialized registration messages, which are part of the chain state, for
+/// applications with fixed IDs.
+#[tokio::test]
+async fn test_application_registrations_serialization() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let hash = |n: u64| CryptoHash::from([0, 0, 0, n]);
+    let creation = |index: u32| MessageId {
+        chain_id: ChainId(hash(3)),
+        height: BlockHeight(0),
+        index,
+    };
+    let caller_description = UserApplicationDescription {
+        bytecode_id: BytecodeId::new(hash(1), hash(2)),
+        creation: creation(0),
+        parameters: vec![],
+        required_application_ids: vec![],
+    };
+    let target_description = UserApplicationDescription {
+        bytecode_id: BytecodeId::new(hash(1), hash(2)),
+        creation: creation(1),
+        parameters: vec![],
+        required_application_ids: vec![UserApplicationId::from(&caller_description)],
+    };
+    let (_, contract_blob, service_blob) = create_dummy_user_application_description(0);
+    let (caller_id, caller_application) = view
+        .register_mock_application_with(
+            caller_description,
+            contract_blob.clone(),
+            service_blob.clone(),
+        )
+        .await?;
+    let (target_id, target_application) = view
+        .register_mock_application_with(target_description, contract_blob, service_blob)
+        .await?;
+
+    let first_chain = ChainId(hash(4));
+    let second_chain = ChainId(hash(5));
+    let message_to = |chain_id: ChainId| SendMessageRequest {
+        destination: Destination::from(chain_id),
+        authenticated: false,
+        is_tracked: false,
+        grant: Resources::default(),
+        message: b"msg".to_vec(),
+    };
+
+    caller_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            runtime.send_message(message_to(second_chain))?;
+            runtime.try_call_application(/* authenticated */ false, target_id, vec![])?;
+            Ok(vec![])
+        },
+    ));
+    target_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _context, _operation| {
+            runtime.send_message(message_to(first_chain))?;
+            Ok(vec![])
+        },
+    ));
+    target_application.expect_call(ExpectedCall::default_finalize());
     caller_application.expect_call(ExpectedCall::default_finalize());
 
-    let context = create_dummy_operation_context();
-    let mut controller = ResourceController::default();
     let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
     view.execute_operation(
-        context,
+        create_dummy_operation_context(),
         Timestamp::from(0),
         Operation::User {
             application_id: caller_id,
             bytes: vec![],
         },
         &mut txn_tracker,
-        &mut controller,
+        &mut ResourceController::default(),
     )
     .await?;
     view.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
         .await?;
 
-    let target_description = view.system.registry.describe_application(target_id).await?;
-    let registration_message = RawOutgoingMessage {
-        destination: Destination::from(destination_chain),
-        authenticated: false,
-        grant: Amount::ZERO,
-        kind: MessageKind::Simple,
-        message: SystemMessage::RegisterApplications {
-            applications: vec![target_description],
-        },
-    };
-    let account = Account {
-        chain_id: ChainId::root(0),
-        owner: None,
+    let outcomes = txn_tracker.destructure()?.outcomes;
+    let Some(ExecutionOutcome::System(registrations)) = outcomes.first() else {
+        panic!("Expected the registrations to come first, got {outcomes:?}");
     };
+    let registrations = registrations
+        .messages
+        .iter()
+        .map(|message| (&message.destination, &message.message))
+        .collect::<Vec<_>>();
+
+    // The hex encodings of the application IDs: the bytecode ID, then the creation message ID.
+    let encoded_id =
+        |index: &str| format!("{}{}{}0000000000000000{index}", hash(1), hash(2), hash(3));
+    let encoded_caller_id = encoded_id("00000000");
+    let encoded_target_id = encoded_id("01000000");
+    // The descriptions, with empty parameters and then the list of dependencies.
+    let caller = format!("{encoded_caller_id}0000");
+    let target = format!("{encoded_target_id}0001{encoded_caller_id}");
+    let expected = [
+        // Two messages.
+        "02",
+        // `RegisterApplications` to the first chain, with the caller before its dependent.
+        "00",
+        &hash(4).to_string(),
+        "08",
+        "02",
+        &caller,
+        &target,
+        // `RegisterApplications` to the second chain, with only the caller.
+        "00",
+        &hash(5).to_string(),
+        "08",
+        "01",
+        &caller,
+    ]
+    .concat();
+    assert_eq!(hex::encode(bcs::to_bytes(&registrations)?), expected);
+
+    Ok(())
+}
+
+/// Tests that all applications registered on a chain are listed.
+#[tokio::test]
+async fn test_list_applications() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    assert!(view.list_applications().await?.is_empty());
+
+    let (first_id, _first_application) = view.register_mock_application().await?;
+    let (second_id, _second_application) = view.register_mock_application().await?;
+
+    let applications = view
+        .list_applications()
+        .await?
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
 
-    let (outcomes, _, _) = txn_tracker.destructure().unwrap();
     assert_eq!(
-        outcomes,
-        &[
-            ExecutionOutcome::System(
-                RawExecutionOutcome::default().with_message(registration_message)
-            ),
-            ExecutionOutcome::User(
-                target_id,
-                RawExecutionOutcome::default()
-                    .with_message(expected_dummy_message)
-                    .with_refund_grant_to(Some(account))
-            ),
-            ExecutionOutcome::User(
-                caller_id,
-                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
-            ),
-            ExecutionOutcome::User(
-                target_id,
-                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
-            ),
-            ExecutionOutcome::User(
-                caller_id,
-                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
-            ),
-        ]
+        applications,
+        BTreeMap::from([
+            (first_id, create_dummy_user_application_description(0).0),
+            (second_id, create_dummy_user_application_description(1).0),
+        ])
     );
-
     Ok(())
 }
 
-/// Tests if a message is scheduled to be sent by a deeper cross-application call.
+/// The possible outcomes of an attempt to remove an application's state.
+enum StateRemoval {
+    Authorized,
+    Unauthorized,
+    RequiredByDependent,
+}
+
+/// Tests that the state of an application can only be removed by a chain owner, and only if no
+/// other application requires it.
+#[test_case(StateRemoval::Authorized; "authorized")]
+#[test_case(StateRemoval::Unauthorized; "unauthorized")]
+#[test_case(StateRemoval::RequiredByDependent; "required by a dependent application")]
 #[tokio::test]
-async fn test_message_from_deeper_call() -> anyhow::Result<()> {
+async fn test_remove_application_state(removal: StateRemoval) -> anyhow::Result<()> {
+    let owner = Owner::from(PublicKey::test_key(0));
     let mut state = SystemExecutionState::default();
     state.description = Some(ChainDescription::Root(0));
+    state.ownership = ChainOwnership::single(owner);
     let mut view = state.into_view().await;
 
-    let (caller_id, caller_application) = view.register_mock_application().await?;
-    let (middle_id, middle_application) = view.register_mock_application().await?;
-    let (target_id, target_application) = view.register_mock_application().await?;
+    let (application_id, application) = view.register_mock_application().await?;
 
-    caller_application.expect_call(ExpectedCall::execute_operation(
-        move |runtime, _context, _operation| {
-            runtime.try_call_application(/* authenticated */ false, middle_id, vec![])?;
+    application.expect_call(ExpectedCall::execute_operation(
+        |runtime, _context, _operation| {
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![1], vec![2; 10]);
+            runtime.write_batch(batch)?;
             Ok(vec![])
         },
     ));
+    application.expect_call(ExpectedCall::default_finalize());
 
-    middle_application.expect_call(ExpectedCall::execute_operation(
-        move |runtime, _context, _argument| {
-            runtime.try_call_application(/* authenticated */ false, target_id, vec![])?;
-            Ok(vec![])
+    view.execute_operation(
+        create_dummy_operation_context(),
+        Timestamp::from(0),
+        Operation::User {
+            application_id,
+            bytes: vec![],
         },
-    ));
+        &mut TransactionTracker::new(0, Some(Vec::new())),
+        &mut ResourceController::default(),
+    )
+    .await?;
+    assert!(view.users.contains_key(&application_id).await?);
+
+    let mut dependent_id = None;
+    if let StateRemoval::RequiredByDependent = removal {
+        let (mut description, contract, service) = create_dummy_user_application_description(1);
+        description.required_application_ids = vec![application_id];
+        let (id, _application) = view
+            .register_mock_application_with(description, contract, service)
+            .await?;
+        dependent_id = Some(id);
+    }
 
-    let destination_chain = ChainId::from(ChainDescription::Root(1));
-    let dummy_message = SendMessageRequest {
-        destination: Destination::from(destination_chain),
-        authenticated: false,
-        is_tracked: false,
-        grant: Resources::default(),
-        message: b"msg".to_vec(),
-    };
+    let mut context = create_dummy_operation_context();
+    if !matches!(removal, StateRemoval::Unauthorized) {
+        context.authenticated_signer = Some(owner);
+    }
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
+    let result = view
+        .execute_operation(
+            context,
+            Timestamp::from(0),
+            Operation::System(SystemOperation::RemoveApplicationState { application_id }),
+            &mut txn_tracker,
+            &mut controller,
+        )
+        .await;
 
-    let fee_policy = ResourceControlPolicy::default();
-    let expected_dummy_message =
-        RawOutgoingMessage::from(dummy_message.clone()).into_priced(&fee_policy)?;
+    match removal {
+        StateRemoval::Authorized => {
+            assert_matches!(result, Ok(()));
+            assert!(!view.users.contains_key(&application_id).await?);
+            assert!(controller.tracker.bytes_stored < 0);
+            assert_eq!(
+                txn_tracker.destructure()?.freed_bytes,
+                u64::from(controller.tracker.bytes_stored.unsigned_abs())
+            );
+        }
+        StateRemoval::Unauthorized => {
+            assert_matches!(
+                result,
+                Err(ExecutionError::SystemError(
+                    SystemExecutionError::UnauthorizedApplicationStateRemoval
+                ))
+            );
+            assert!(view.users.contains_key(&application_id).await?);
+        }
+        StateRemoval::RequiredByDependent => {
+            assert_matches!(
+                result,
+                Err(ExecutionError::SystemError(
+                    SystemExecutionError::ApplicationStillRequired {
+                        application_id: removed_id,
+                        dependent_id: error_dependent_id,
+                    }
+                )) if *removed_id == application_id && Some(*error_dependent_id) == dependent_id
+            );
+            assert!(view.users.contains_key(&application_id).await?);
+        }
+    }
 
-    target_application.expect_call(ExpectedCall::execute_operation(
-        move |runtime, _context, _argument| {
-            runtime.send_message(dummy_message)?;
+    Ok(())
+}
+
+/// Tests that pruning the registry only removes the descriptions of applications that are not
+/// used on the chain and not required by a used application.
+#[tokio::test]
+async fn test_prune_registry() -> anyhow::Result<()> {
+    let owner = Owner::from(PublicKey::test_key(0));
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    state.ownership = ChainOwnership::single(owner);
+    let mut view = state.into_view().await;
+
+    let (description, contract, service) = create_dummy_user_application_description(0);
+    let (dependency_id, _dependency) = view
+        .register_mock_application_with(description, contract, service)
+        .await?;
+    let (mut description, contract, service) = create_dummy_user_application_description(1);
+    description.required_application_ids = vec![dependency_id];
+    let (application_id, application) = view
+        .register_mock_application_with(description, contract, service)
+        .await?;
+    let (description, contract, service) = create_dummy_user_application_description(2);
+    let (unused_id, _unused) = view
+        .register_mock_application_with(description, contract, service)
+        .await?;
+
+    application.expect_call(ExpectedCall::execute_operation(
+        |runtime, _context, _operation| {
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![1], vec![2; 10]);
+            runtime.write_batch(batch)?;
             Ok(vec![])
         },
     ));
+    application.expect_call(ExpectedCall::default_finalize());
+    view.execute_operation(
+        create_dummy_operation_context(),
+        Timestamp::from(0),
+        Operation::User {
+            application_id,
+            bytes: vec![],
+        },
+        &mut TransactionTracker::new(0, Some(Vec::new())),
+        &mut ResourceController::default(),
+    )
+    .await?;
 
-    target_application.expect_call(ExpectedCall::default_finalize());
-    middle_application.expect_call(ExpectedCall::default_finalize());
-    caller_application.expect_call(ExpectedCall::default_finalize());
+    let result = view
+        .execute_operation(
+            create_dummy_operation_context(),
+            Timestamp::from(0),
+            Operation::System(SystemOperation::PruneRegistry),
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut ResourceController::default(),
+        )
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::SystemError(
+            SystemExecutionError::UnauthorizedRegistryPruning
+        ))
+    );
 
-    let context = create_dummy_operation_context();
-    let mut controller = ResourceController::default();
+    let mut context = create_dummy_operation_context();
+    context.authenticated_signer = Some(owner);
     let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
     view.execute_operation(
         context,
         Timestamp::from(0),
-        Operation::User {
-            application_id: caller_id,
-            bytes: vec![],
-        },
+        Operation::System(SystemOperation::PruneRegistry),
         &mut txn_tracker,
-        &mut controller,
+        &mut ResourceController::default(),
     )
     .await?;
+    assert_eq!(txn_tracker.destructure()?.pruned_application_count, 1);
 
-    let target_description = view.system.registry.describe_application(target_id).await?;
-    let registration_message = RawOutgoingMessage {
-        destination: Destination::from(destination_chain),
-        authenticated: false,
-        grant: Amount::ZERO,
-        kind: MessageKind::Simple,
-        message: SystemMessage::RegisterApplications {
-            applications: vec![target_description],
-        },
+    let known_applications = &view.system.registry.known_applications;
+    assert!(known_applications.contains_key(&application_id).await?);
+    assert!(known_applications.contains_key(&dependency_id).await?);
+    assert!(!known_applications.contains_key(&unused_id).await?);
+
+    Ok(())
+}
+
+/// Tests that an application with stored state can't be instantiated again, unless its state
+/// was removed first.
+#[test_case(false; "with existing state")]
+#[test_case(true; "after state removal")]
+#[tokio::test]
+async fn test_repeated_instantiation(remove_state: bool) -> anyhow::Result<()> {
+    let owner = Owner::from(PublicKey::test_key(0));
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    state.ownership = ChainOwnership::single(owner);
+    let mut view = state.into_view().await;
+
+    let (mut description, contract_blob, service_blob) =
+        create_dummy_user_application_description(0);
+    description.creation.chain_id = ChainId::root(0);
+    let application_id = UserApplicationId::from(&description);
+    let application = MockApplication::default();
+    let write_state = || {
+        ExpectedCall::instantiate(|runtime, _context, _argument| {
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![1], vec![2; 10]);
+            runtime.write_batch(batch)?;
+            Ok(())
+        })
     };
-    view.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
+
+    application.expect_call(write_state());
+    application.expect_call(ExpectedCall::default_finalize());
+    view.simulate_instantiation(
+        application.clone().into(),
+        Timestamp::from(0),
+        description.clone(),
+        vec![],
+        contract_blob.clone(),
+        service_blob.clone(),
+    )
+    .await?;
+    assert!(view.users.contains_key(&application_id).await?);
+
+    if remove_state {
+        let mut context = create_dummy_operation_context();
+        context.authenticated_signer = Some(owner);
+        view.execute_operation(
+            context,
+            Timestamp::from(0),
+            Operation::System(SystemOperation::RemoveApplicationState { application_id }),
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut ResourceController::default(),
+        )
         .await?;
-    let account = Account {
-        chain_id: ChainId::root(0),
-        owner: None,
-    };
-    let (outcomes, _, _) = txn_tracker.destructure().unwrap();
-    assert_eq!(
-        outcomes,
-        &[
-            ExecutionOutcome::System(
-                RawExecutionOutcome::default().with_message(registration_message)
-            ),
-            ExecutionOutcome::User(
-                target_id,
-                RawExecutionOutcome::default()
-                    .with_message(expected_dummy_message)
-                    .with_refund_grant_to(Some(account))
-            ),
-            ExecutionOutcome::User(
-                middle_id,
-                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
-            ),
-            ExecutionOutcome::User(
-                caller_id,
-                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
-            ),
-            ExecutionOutcome::User(
-                target_id,
-                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
-            ),
-            ExecutionOutcome::User(
-                middle_id,
-                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
-            ),
-            ExecutionOutcome::User(
-                caller_id,
-                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
-            ),
-        ]
-    );
+        application.expect_call(write_state());
+        application.expect_call(ExpectedCall::default_finalize());
+    }
+
+    let result = view
+        .simulate_instantiation(
+            application.clone().into(),
+            Timestamp::from(0),
+            description,
+            vec![],
+            contract_blob,
+            service_blob,
+        )
+        .await;
+
+    if remove_state {
+        assert_matches!(result, Ok(()));
+        assert!(view.application_state_size(application_id).await? > 0);
+    } else {
+        assert_matches!(
+            result,
+            Err(ExecutionError::FailedUserAction { error, .. })
+                if matches!(
+                    *error,
+                    ExecutionError::ApplicationAlreadyInstantiated(id) if id == application_id
+                )
+        );
+    }
+    application.assert_no_more_expected_calls();
 
     Ok(())
 }
 
-/// Tests if multiple messages are scheduled to be sent by different applications to different
-/// chains.
-///
-/// Ensures that in a more complex scenario, chains receive application registration messages only
-/// for the applications that will receive messages on them.
+/// Tests that an application created by another one is not instantiated if it already has
+/// stored state.
 #[tokio::test]
-async fn test_multiple_messages_from_different_applications() -> anyhow::Result<()> {
+async fn test_create_application_with_existing_state() -> anyhow::Result<()> {
     let mut state = SystemExecutionState::default();
     state.description = Some(ChainDescription::Root(0));
     let mut view = state.into_view().await;
 
-    // The entrypoint application, which sends a message and calls other applications
-    let (caller_id, caller_application) = view.register_mock_application().await?;
-    // An application that does not send any messages
-    let (silent_target_id, silent_target_application) = view.register_mock_application().await?;
-    // An application that sends a message when handling a cross-application call
-    let (sending_target_id, sending_target_application) = view.register_mock_application().await?;
-
-    // The first destination chain receives messages from the caller and the sending applications
-    let first_destination_chain = ChainId::from(ChainDescription::Root(1));
-    // The second destination chain only receives a message from the sending application
-    let second_destination_chain = ChainId::from(ChainDescription::Root(2));
-
-    // The message sent to the first destination chain by the caller and the sending applications
-    let first_message = SendMessageRequest {
-        destination: Destination::from(first_destination_chain),
-        authenticated: false,
-        is_tracked: false,
-        grant: Resources::default(),
-        message: b"first".to_vec(),
+    let (creator_id, creator_application) = view.register_mock_application().await?;
+    let context = create_dummy_operation_context();
+    let created_id = UserApplicationId {
+        bytecode_id: creator_id.bytecode_id,
+        creation: MessageId {
+            chain_id: context.chain_id,
+            height: context.height,
+            index: 0,
+        },
     };
+    // The created application must not be called.
+    let created_application = MockApplication::default();
+    view.context()
+        .extra()
+        .user_contracts()
+        .insert(created_id, created_application.clone().into());
+    view.users
+        .try_load_entry_mut(&created_id)
+        .await?
+        .insert(vec![1], vec![2])
+        .await?;
 
-    let fee_policy = ResourceControlPolicy::default();
-    let expected_first_message =
-        RawOutgoingMessage::from(first_message.clone()).into_priced(&fee_policy)?;
-
-    // The entrypoint sends a message to the first chain and calls the silent and the sending
-    // applications
-    caller_application.expect_call(ExpectedCall::execute_operation({
-        let first_message = first_message.clone();
+    let bytecode_id = creator_id.bytecode_id;
+    creator_application.expect_call(ExpectedCall::execute_operation(
         move |runtime, _context, _operation| {
-            runtime.try_call_application(
-                /* authenticated */ false,
-                silent_target_id,
-                vec![],
-            )?;
-            runtime.send_message(first_message)?;
-            runtime.try_call_application(
-                /* authenticated */ false,
-                sending_target_id,
-                vec![],
-            )?;
+            runtime.create_application(bytecode_id, vec![], vec![], vec![])?;
             Ok(vec![])
-        }
-    }));
-
-    // The silent application does nothing
-    silent_target_application.expect_call(ExpectedCall::execute_operation(
-        |_runtime, _context, _argument| Ok(vec![]),
+        },
     ));
 
-    // The message sent to the second destination chain by the sending application
-    let second_message = SendMessageRequest {
-        destination: Destination::from(second_destination_chain),
-        authenticated: false,
-        is_tracked: false,
-        grant: Resources::default(),
-        message: b"second".to_vec(),
-    };
+    let result = view
+        .execute_operation(
+            context,
+            Timestamp::from(0),
+            Operation::User {
+                application_id: creator_id,
+                bytes: vec![],
+            },
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut ResourceController::default(),
+        )
+        .await;
 
-    let expected_second_message =
-        RawOutgoingMessage::from(second_message.clone()).into_priced(&fee_policy)?;
+    assert_matches!(
+        result,
+        Err(ExecutionError::FailedUserAction { error, .. })
+            if matches!(
+                *error,
+                ExecutionError::ApplicationAlreadyInstantiated(id) if id == created_id
+            )
+    );
+    creator_application.assert_no_more_expected_calls();
+    created_application.assert_no_more_expected_calls();
 
-    // The sending application sends two messages, one to each of the destination chains
-    sending_target_application.expect_call(ExpectedCall::execute_operation(
-        |runtime, _context, _argument| {
-            runtime.send_message(first_message)?;
-            runtime.send_message(second_message)?;
+    Ok(())
+}
+
+/// Tests that the size of an application's stored state is tracked when it writes and deletes
+/// entries, and that it persists when the view is reloaded.
+#[tokio::test]
+async fn test_application_state_size() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    let mut view = state.into_view().await;
+
+    let (application_id, application) = view.register_mock_application().await?;
+
+    application.expect_call(ExpectedCall::execute_operation(
+        |runtime, _context, _operation| {
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![1], vec![2; 10]);
+            batch.put_key_value_bytes(vec![3], vec![4; 20]);
+            runtime.write_batch(batch)?;
             Ok(vec![])
         },
     ));
+    application.expect_call(ExpectedCall::default_finalize());
+    application.expect_call(ExpectedCall::execute_operation(
+        |runtime, _context, _operation| {
+            let mut batch = Batch::new();
+            batch.delete_key(vec![1]);
+            runtime.write_batch(batch)?;
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
 
-    sending_target_application.expect_call(ExpectedCall::default_finalize());
-    silent_target_application.expect_call(ExpectedCall::default_finalize());
-    caller_application.expect_call(ExpectedCall::default_finalize());
-
-    // Execute the operation, starting the test scenario
-    let context = create_dummy_operation_context();
+    let operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
     let mut controller = ResourceController::default();
-    let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
     view.execute_operation(
-        context,
+        create_dummy_operation_context(),
         Timestamp::from(0),
-        Operation::User {
-            application_id: caller_id,
-            bytes: vec![],
-        },
-        &mut txn_tracker,
+        operation.clone(),
+        &mut TransactionTracker::new(0, Some(Vec::new())),
         &mut controller,
     )
     .await?;
-    view.update_execution_outcomes_with_app_registrations(&mut txn_tracker)
-        .await?;
-
-    // Describe the two applications that sent messages, and will therefore handle them in the
-    // other chains
-    let caller_description = view.system.registry.describe_application(caller_id).await?;
-    let sending_target_description = view
-        .system
-        .registry
-        .describe_application(sending_target_id)
-        .await?;
 
-    // The registration message for the first destination chain
-    let first_registration_message = RawOutgoingMessage {
-        destination: Destination::from(first_destination_chain),
-        authenticated: false,
-        grant: Amount::ZERO,
-        kind: MessageKind::Simple,
-        message: SystemMessage::RegisterApplications {
-            applications: vec![sending_target_description.clone(), caller_description],
-        },
-    };
+    let size_after_write = view.application_state_size(application_id).await?;
+    assert!(size_after_write >= 32);
+    assert_eq!(
+        i64::from(controller.tracker.bytes_stored),
+        size_after_write as i64
+    );
 
-    // The registration message for the second destination chain
-    let second_registration_message = RawOutgoingMessage {
-        destination: Destination::from(second_destination_chain),
-        authenticated: false,
-        grant: Amount::ZERO,
-        kind: MessageKind::Simple,
-        message: SystemMessage::RegisterApplications {
-            applications: vec![sending_target_description],
-        },
-    };
+    let mut controller = ResourceController::default();
+    view.execute_operation(
+        create_dummy_operation_context(),
+        Timestamp::from(0),
+        operation,
+        &mut TransactionTracker::new(0, Some(Vec::new())),
+        &mut controller,
+    )
+    .await?;
 
-    let account = Account {
-        chain_id: ChainId::root(0),
-        owner: None,
-    };
+    let size_after_deletion = view.application_state_size(application_id).await?;
+    assert!(size_after_deletion < size_after_write);
+    assert_eq!(
+        i64::from(controller.tracker.bytes_stored),
+        size_after_deletion as i64 - size_after_write as i64
+    );
 
-    // Return to checking the user application outcomes
-    let (outcomes, _, _) = txn_tracker.destructure().unwrap();
+    let context = view.context().clone();
+    let mut batch = Batch::new();
+    view.flush(&mut batch)?;
+    context.write_batch(batch).await?;
+    let view = ExecutionStateView::load(context).await?;
     assert_eq!(
-        outcomes,
-        &[
-            ExecutionOutcome::System(
-                RawExecutionOutcome::default()
-                    .with_message(second_registration_message)
-                    .with_message(first_registration_message)
-            ),
-            ExecutionOutcome::User(
-                silent_target_id,
-                RawExecutionOutcome::default().with_refund_grant_to(Some(account)),
-            ),
-            ExecutionOutcome::User(
-                sending_target_id,
-                RawExecutionOutcome::default()
-                    .with_message(expected_first_message.clone())
-                    .with_message(expected_second_message)
-                    .with_refund_grant_to(Some(account))
-            ),
-            ExecutionOutcome::User(
-                caller_id,
-                RawExecutionOutcome::default()
-                    .with_message(expected_first_message)
-                    .with_refund_grant_to(Some(account))
-            ),
-            ExecutionOutcome::User(
-                sending_target_id,
-                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
-            ),
-            ExecutionOutcome::User(
-                silent_target_id,
-                RawExecutionOutcome::default().with_refund_grant_to(Some(account)),
-            ),
-            ExecutionOutcome::User(
-                caller_id,
-                RawExecutionOutcome::default().with_refund_grant_to(Some(account))
-            ),
-        ]
+        view.application_state_size(application_id).await?,
+        size_after_deletion
     );
 
     Ok(())
@@ -1473,7 +3545,7 @@ async fn test_open_chain() -> anyhow::Result<()> {
     .await?;
 
     assert_eq!(*view.system.balance.get(), Amount::from_tokens(3));
-    let (outcomes, _, _) = txn_tracker.destructure()?;
+    let outcomes = txn_tracker.destructure()?.outcomes;
     let message = outcomes
         .iter()
         .flat_map(|outcome| match outcome {
@@ -1495,10 +3567,36 @@ async fn test_open_chain() -> anyhow::Result<()> {
     assert_eq!(config.ownership, child_ownership);
     assert_eq!(config.committees, committees);
 
+    // The application the child chain is restricted to is registered there right away.
+    let (description, contract_blob, service_blob) = create_dummy_user_application_description(0);
+    let register_message = outcomes
+        .iter()
+        .flat_map(|outcome| match outcome {
+            ExecutionOutcome::System(outcome) => &outcome.messages,
+            ExecutionOutcome::User(_, _) => panic!("Unexpected message"),
+        })
+        .nth((index - first_message_index) as usize + 1)
+        .context("Message index out of bounds")?;
+    assert_eq!(
+        register_message.destination,
+        Destination::Recipient(ChainId::child(message_id))
+    );
+    assert_eq!(
+        register_message.message,
+        SystemMessage::RegisterApplications {
+            applications: vec![description]
+        }
+    );
+
     // Initialize the child chain using the config from the message.
-    let mut child_view = SystemExecutionState::default()
-        .into_view_with(ChainId::child(message_id), Default::default())
-        .await;
+    let child_id = ChainId::child(message_id);
+    let mut child_view = SystemExecutionState {
+        extra_blobs: vec![contract_blob, service_blob],
+        mock_applications: BTreeMap::from([(application_id, application.clone())]),
+        ..SystemExecutionState::default()
+    }
+    .into_view_with(child_id, Default::default())
+    .await;
     child_view
         .system
         .initialize_chain(message_id, Timestamp::from(0), config.clone());
@@ -1510,6 +3608,42 @@ async fn test_open_chain() -> anyhow::Result<()> {
         ApplicationPermissions::new_single(application_id)
     );
 
+    // After the registration, the child chain can execute operations of the application.
+    child_view
+        .execute_message(
+            MessageContext {
+                chain_id: child_id,
+                ..create_dummy_message_context(None)
+            },
+            Timestamp::from(0),
+            Message::System(register_message.message.clone()),
+            None,
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut controller,
+        )
+        .await?;
+
+    application.expect_call(ExpectedCall::execute_operation(
+        |_runtime, _context, _operation| Ok(vec![]),
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    child_view
+        .execute_operation(
+            OperationContext {
+                chain_id: child_id,
+                ..create_dummy_operation_context()
+            },
+            Timestamp::from(0),
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut controller,
+        )
+        .await?;
+
     Ok(())
 }
 
@@ -1683,5 +3817,9 @@ async fn test_message_receipt_spending_chain_balance(
         )
         .await;
 
-    Ok(execution_result)
+    // Report the error of the transfer itself.
+    Ok(execution_result.map_err(|error| match error {
+        ExecutionError::FailedUserAction { error, .. } => *error,
+        error => error,
+    }))
 }
@@ -3,18 +3,34 @@
 
 #![allow(clippy::field_reassign_with_default)]
 
+use assert_matches::assert_matches;
 use linera_base::{
     crypto::{CryptoHash, KeyPair},
     data_types::{Amount, BlockHeight, Timestamp},
-    identifiers::{Account, AccountOwner, ChainDescription, ChainId, MessageId, Owner},
+    identifiers::{
+        Account, AccountOwner, ChainDescription, ChainId, MessageId, Owner, UserApplicationId,
+    },
     ownership::ChainOwnership,
 };
 use linera_execution::{
-    system::Recipient, test_utils::SystemExecutionState, ExecutionOutcome, Message, MessageContext,
-    Operation, OperationContext, Query, QueryContext, QueryOutcome, QueryResponse,
+    first_state_hash_mismatch,
+    system::Recipient,
+    test_utils::{create_dummy_user_application_description, SystemExecutionState},
+    ExecutionError, ExecutionOutcome, ExecutionRuntimeConfig, ExecutionStateView, Message,
+    MessageContext, Operation, OperationContext, Query, QueryContext, QueryOutcome, QueryResponse,
     RawExecutionOutcome, ResourceController, SystemMessage, SystemOperation, SystemQuery,
-    SystemResponse, TransactionTracker,
+    SystemResponse, TestExecutionRuntimeContext, TransactionTracker, STATE_EXPORT_CHUNK_LEN,
 };
+use linera_views::{
+    batch::Batch,
+    context::{Context as _, MemoryContext},
+    memory::TEST_MEMORY_MAX_STREAM_QUERIES,
+    random::generate_test_namespace,
+    views::{CryptoHashView, View as _, ViewError},
+};
+#[cfg(feature = "rocksdb")]
+use linera_views::{context::ViewContext, rocks_db::RocksDbStore, store::TestKeyValueStore as _};
+use test_case::test_case;
 
 #[tokio::test]
 async fn test_simple_system_operation() -> anyhow::Result<()> {
@@ -59,7 +75,7 @@ async fn test_simple_system_operation() -> anyhow::Result<()> {
         chain_id: ChainId::root(0),
         owner: Some(AccountOwner::User(owner)),
     };
-    let (outcomes, _, _) = txn_tracker.destructure().unwrap();
+    let outcomes = txn_tracker.destructure().unwrap().outcomes;
     assert_eq!(
         outcomes,
         vec![ExecutionOutcome::System(
@@ -108,7 +124,7 @@ async fn test_simple_system_message() -> anyhow::Result<()> {
     .await
     .unwrap();
     assert_eq!(view.system.balance.get(), &Amount::from_tokens(4));
-    let (outcomes, _, _) = txn_tracker.destructure().unwrap();
+    let outcomes = txn_tracker.destructure().unwrap().outcomes;
     assert_eq!(
         outcomes,
         vec![ExecutionOutcome::System(RawExecutionOutcome::default())]
@@ -116,6 +132,86 @@ async fn test_simple_system_message() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests that the state hash is recorded after each message of a transaction only if tracing
+/// is enabled.
+#[test_case(false; "without tracing")]
+#[test_case(true; "with tracing")]
+#[tokio::test]
+async fn test_state_hash_tracing(trace_state_hashes: bool) -> anyhow::Result<()> {
+    let state = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        ..SystemExecutionState::default()
+    };
+    let mut view = state
+        .into_view_with(
+            ChainId::root(0),
+            ExecutionRuntimeConfig { trace_state_hashes },
+        )
+        .await;
+    let context = MessageContext {
+        chain_id: ChainId::root(0),
+        is_bouncing: false,
+        height: BlockHeight(0),
+        round: Some(0),
+        certificate_hash: CryptoHash::test_hash("certificate"),
+        message_id: MessageId {
+            chain_id: ChainId::root(1),
+            height: BlockHeight(0),
+            index: 0,
+        },
+        authenticated_signer: None,
+        refund_grant_to: None,
+    };
+    let mut controller = ResourceController::default();
+    // Both messages are part of the same incoming bundle, i.e. the same transaction.
+    let mut txn_tracker = TransactionTracker::new(0, Some(Vec::new()));
+    let mut expected_hashes = Vec::new();
+    for index in 0..2 {
+        let message = SystemMessage::Credit {
+            amount: Amount::ONE,
+            target: None,
+            source: None,
+        };
+        view.execute_message(
+            MessageContext {
+                message_id: MessageId {
+                    index,
+                    ..context.message_id
+                },
+                ..context
+            },
+            Timestamp::from(0),
+            Message::System(message),
+            None,
+            &mut txn_tracker,
+            &mut controller,
+        )
+        .await?;
+        if trace_state_hashes {
+            expected_hashes.push(view.crypto_hash_mut().await?);
+        }
+    }
+    assert_eq!(txn_tracker.state_hashes(), expected_hashes);
+    if trace_state_hashes {
+        assert_ne!(expected_hashes[0], expected_hashes[1]);
+    }
+    assert_eq!(txn_tracker.destructure()?.state_hashes, expected_hashes);
+    Ok(())
+}
+
+#[test]
+fn test_first_state_hash_mismatch() {
+    let hashes = ["a", "b", "c"].map(CryptoHash::test_hash);
+    let other = CryptoHash::test_hash("other");
+    assert_eq!(first_state_hash_mismatch(&hashes, &hashes), None);
+    assert_eq!(
+        first_state_hash_mismatch(&hashes, &[hashes[0], other, hashes[2]]),
+        Some(1)
+    );
+    assert_eq!(first_state_hash_mismatch(&hashes, &hashes[..2]), Some(2));
+    assert_eq!(first_state_hash_mismatch(&[], &hashes), Some(0));
+}
+
 #[tokio::test]
 async fn test_simple_system_query() -> anyhow::Result<()> {
     let mut state = SystemExecutionState::default();
@@ -144,3 +240,255 @@ async fn test_simple_system_query() -> anyhow::Result<()> {
     assert!(operations.is_empty());
     Ok(())
 }
+
+/// Tests that requests for a different chain are rejected without modifying the state.
+#[tokio::test]
+async fn test_incorrect_chain_id() -> anyhow::Result<()> {
+    let mut state = SystemExecutionState::default();
+    state.description = Some(ChainDescription::Root(0));
+    state.balance = Amount::from_tokens(4);
+    let mut view = state.into_view().await;
+    let initial_hash = view.crypto_hash().await?;
+    let wrong_chain_id = ChainId::root(1);
+
+    let context = OperationContext {
+        chain_id: wrong_chain_id,
+        height: BlockHeight(0),
+        round: Some(0),
+        index: Some(0),
+        authenticated_signer: None,
+        authenticated_caller_id: None,
+    };
+    let operation = SystemOperation::Transfer {
+        owner: None,
+        amount: Amount::from_tokens(4),
+        recipient: Recipient::Burn,
+    };
+    let result = view
+        .execute_operation(
+            context,
+            Timestamp::from(0),
+            Operation::System(operation),
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut ResourceController::default(),
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(ExecutionError::IncorrectChainId { expected, actual })
+            if expected == ChainId::root(0) && actual == wrong_chain_id
+    ));
+
+    let context = MessageContext {
+        chain_id: wrong_chain_id,
+        is_bouncing: false,
+        height: BlockHeight(0),
+        round: Some(0),
+        certificate_hash: CryptoHash::test_hash("certificate"),
+        message_id: MessageId {
+            chain_id: ChainId::root(2),
+            height: BlockHeight(0),
+            index: 0,
+        },
+        authenticated_signer: None,
+        refund_grant_to: None,
+    };
+    let message = SystemMessage::Credit {
+        amount: Amount::from_tokens(4),
+        target: None,
+        source: None,
+    };
+    let result = view
+        .execute_message(
+            context,
+            Timestamp::from(0),
+            Message::System(message),
+            None,
+            &mut TransactionTracker::new(0, Some(Vec::new())),
+            &mut ResourceController::default(),
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(ExecutionError::IncorrectChainId { expected, actual })
+            if expected == ChainId::root(0) && actual == wrong_chain_id
+    ));
+
+    let context = QueryContext {
+        chain_id: wrong_chain_id,
+        next_block_height: BlockHeight(0),
+        local_time: Timestamp::from(0),
+    };
+    let result = view
+        .query_application(context, Query::System(SystemQuery), None)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ExecutionError::IncorrectChainId { expected, actual })
+            if expected == ChainId::root(0) && actual == wrong_chain_id
+    ));
+
+    assert_eq!(view.crypto_hash().await?, initial_hash);
+    Ok(())
+}
+
+/// Tests that a state written to RocksDB has the same hash as the same state in memory.
+#[cfg(feature = "rocksdb")]
+#[tokio::test]
+async fn test_rocks_db_view_matches_memory_view() -> anyhow::Result<()> {
+    let owner = Owner(CryptoHash::test_hash("owner"));
+    let state = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        ownership: ChainOwnership::single(owner),
+        balance: Amount::from_tokens(4),
+        balances: [(AccountOwner::User(owner), Amount::ONE)]
+            .into_iter()
+            .collect(),
+        ..SystemExecutionState::default()
+    };
+    let mut memory_view = state.clone().into_view().await;
+
+    let store = RocksDbStore::new_test_store().await?;
+    let extra =
+        TestExecutionRuntimeContext::new(ChainId::root(0), ExecutionRuntimeConfig::default());
+    let context = ViewContext::create_root_context(store, extra).await?;
+    let mut rocks_db_view = state.into_view_with_context(context).await;
+
+    assert_eq!(
+        rocks_db_view.crypto_hash_mut().await?,
+        memory_view.crypto_hash_mut().await?
+    );
+    Ok(())
+}
+
+/// Tests that an exported execution state, including a user application state that spans
+/// several chunks, can be imported into fresh storage with the same hash.
+#[tokio::test]
+async fn test_export_import_state() -> anyhow::Result<()> {
+    let owner = Owner(CryptoHash::test_hash("owner"));
+    let state = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        ownership: ChainOwnership::single(owner),
+        balance: Amount::from_tokens(4),
+        ..SystemExecutionState::default()
+    };
+    let mut view = state.into_view().await;
+    let (description, _, _) = create_dummy_user_application_description(0);
+    let application_id = UserApplicationId::from(&description);
+    let mut application_state = view.users.try_load_entry_mut(&application_id).await?;
+    for index in 0..2 * STATE_EXPORT_CHUNK_LEN as u32 {
+        application_state
+            .insert(index.to_be_bytes().to_vec(), vec![1; 100])
+            .await?;
+    }
+    drop(application_state);
+
+    let result = view.export_state(Vec::<u8>::new()).await;
+    assert_matches!(result, Err(ExecutionError::UnsavedStateExport));
+
+    let mut batch = Batch::new();
+    view.flush(&mut batch)?;
+    view.context().write_batch(batch).await?;
+    let state_hash = view.crypto_hash().await?;
+
+    let mut exported = Vec::new();
+    view.export_state(&mut exported).await?;
+    let mut exported_again = Vec::new();
+    view.export_state(&mut exported_again).await?;
+    assert_eq!(exported, exported_again);
+
+    let extra =
+        TestExecutionRuntimeContext::new(ChainId::root(0), ExecutionRuntimeConfig::default());
+    let new_memory_context = || {
+        MemoryContext::new_for_testing(
+            TEST_MEMORY_MAX_STREAM_QUERIES,
+            &generate_test_namespace(),
+            &[],
+            extra.clone(),
+        )
+    };
+    let imported = ExecutionStateView::import_state(new_memory_context(), &exported[..]).await?;
+    assert_eq!(imported.crypto_hash().await?, state_hash);
+
+    #[cfg(feature = "rocksdb")]
+    {
+        let store = RocksDbStore::new_test_store().await?;
+        let context = ViewContext::create_root_context(store, extra.clone()).await?;
+        let imported = ExecutionStateView::import_state(context, &exported[..]).await?;
+        assert_eq!(imported.crypto_hash().await?, state_hash);
+    }
+
+    // Importing into a context that holds some data fails without touching it.
+    let result = ExecutionStateView::import_state(view.context().clone(), &exported[..]).await;
+    assert_matches!(result, Err(ExecutionError::StateImportIntoNonEmptyContext));
+    assert_eq!(view.crypto_hash().await?, state_hash);
+
+    // The stream starts with the frame length and the version, followed by the state hash.
+    // A failed import leaves nothing behind.
+    exported[12] ^= 1;
+    let context = new_memory_context();
+    let result = ExecutionStateView::import_state(context.clone(), &exported[..]).await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::StateImportHashMismatch { actual, .. }) if actual == state_hash
+    );
+    assert_eq!(context.total_size(), 0);
+
+    Ok(())
+}
+
+/// Saves an execution state, like a root view containing it would.
+async fn save(
+    view: &mut ExecutionStateView<MemoryContext<TestExecutionRuntimeContext>>,
+) -> Result<(), ViewError> {
+    let mut batch = Batch::new();
+    view.flush(&mut batch)?;
+    view.context().write_batch(batch).await?;
+    Ok(())
+}
+
+/// Saves the given state into a new memory store, then stages some user application
+/// state without writing it yet.
+async fn view_with_unsaved_application_state(
+    state: SystemExecutionState,
+) -> anyhow::Result<ExecutionStateView<MemoryContext<TestExecutionRuntimeContext>>> {
+    let mut view = state.into_view().await;
+    save(&mut view).await?;
+
+    let (description, _, _) = create_dummy_user_application_description(0);
+    let application_id = UserApplicationId::from(&description);
+    let mut application_state = view.users.try_load_entry_mut(&application_id).await?;
+    application_state.insert(vec![0], vec![1; 100]).await?;
+    drop(application_state);
+    Ok(view)
+}
+
+/// Tests that the storage consumed by an application can be measured, and that saving
+/// an execution state beyond the capacity of the memory store fails with a
+/// [`ViewError::StoreCapacityExceeded`] without writing anything.
+#[tokio::test]
+async fn test_memory_store_capacity() -> anyhow::Result<()> {
+    let state = SystemExecutionState {
+        description: Some(ChainDescription::Root(0)),
+        ..SystemExecutionState::default()
+    };
+
+    let mut view = view_with_unsaved_application_state(state.clone()).await?;
+    let initial_size = view.context().total_size();
+    save(&mut view).await?;
+    let consumed_size = view.context().total_size() - initial_size;
+    assert!(consumed_size > 100);
+
+    let mut view = view_with_unsaved_application_state(state).await?;
+    let initial_size = view.context().total_size();
+    view.context()
+        .set_max_total_size(Some(initial_size + consumed_size - 1));
+    let result = save(&mut view).await;
+    assert_matches!(
+        result,
+        Err(ViewError::StoreCapacityExceeded(capacity))
+            if capacity == initial_size + consumed_size - 1
+    );
+    assert_eq!(view.context().total_size(), initial_size);
+    Ok(())
+}
@@ -101,7 +101,7 @@ async fn test_fuel_for_counter_wasm_application(
             &mut controller,
         )
         .await?;
-        let (outcomes, _, _) = txn_tracker.destructure().unwrap();
+        let outcomes = txn_tracker.destructure().unwrap().outcomes;
         assert_eq!(
             outcomes,
             vec![
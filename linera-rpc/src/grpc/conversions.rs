@@ -551,6 +551,14 @@ impl TryFrom<api::ChainInfoQuery> for ChainInfoQuery {
             .request_sent_certificate_hashes_in_range
             .map(|range| bincode::deserialize(&range))
             .transpose()?;
+        let request_receipts_in_range = chain_info_query
+            .request_receipts_in_range
+            .map(|range| bincode::deserialize(&range))
+            .transpose()?;
+        let request_received_log_page = chain_info_query
+            .request_received_log_page
+            .map(|page| bincode::deserialize(&page))
+            .transpose()?;
 
         Ok(Self {
             request_committees: chain_info_query.request_committees,
@@ -561,12 +569,15 @@ impl TryFrom<api::ChainInfoQuery> for ChainInfoQuery {
             request_pending_message_bundles: chain_info_query.request_pending_message_bundles,
             chain_id: try_proto_convert(chain_info_query.chain_id)?,
             request_sent_certificate_hashes_in_range,
+            request_receipts_in_range,
             request_received_log_excluding_first_n: chain_info_query
                 .request_received_log_excluding_first_n,
+            request_received_log_page,
             test_next_block_height: chain_info_query.test_next_block_height.map(Into::into),
             request_manager_values: chain_info_query.request_manager_values,
             request_leader_timeout: chain_info_query.request_leader_timeout,
             request_fallback: chain_info_query.request_fallback,
+            request_parked_bundle_counts: chain_info_query.request_parked_bundle_counts,
         })
     }
 }
@@ -579,6 +590,14 @@ impl TryFrom<ChainInfoQuery> for api::ChainInfoQuery {
             .request_sent_certificate_hashes_in_range
             .map(|range| bincode::serialize(&range))
             .transpose()?;
+        let request_receipts_in_range = chain_info_query
+            .request_receipts_in_range
+            .map(|range| bincode::serialize(&range))
+            .transpose()?;
+        let request_received_log_page = chain_info_query
+            .request_received_log_page
+            .map(|page| bincode::serialize(&page))
+            .transpose()?;
         let request_owner_balance = chain_info_query
             .request_owner_balance
             .map(|owner| owner.try_into())
@@ -591,11 +610,14 @@ impl TryFrom<ChainInfoQuery> for api::ChainInfoQuery {
             request_pending_message_bundles: chain_info_query.request_pending_message_bundles,
             test_next_block_height: chain_info_query.test_next_block_height.map(Into::into),
             request_sent_certificate_hashes_in_range,
+            request_receipts_in_range,
             request_received_log_excluding_first_n: chain_info_query
                 .request_received_log_excluding_first_n,
+            request_received_log_page,
             request_manager_values: chain_info_query.request_manager_values,
             request_leader_timeout: chain_info_query.request_leader_timeout,
             request_fallback: chain_info_query.request_fallback,
+            request_parked_bundle_counts: chain_info_query.request_parked_bundle_counts,
         })
     }
 }
@@ -1061,8 +1083,11 @@ pub mod tests {
             requested_owner_balance: None,
             requested_pending_message_bundles: vec![],
             requested_sent_certificate_hashes: vec![],
+            requested_receipts: vec![],
             count_received_log: 0,
             requested_received_log: vec![],
+            requested_received_log_page: vec![],
+            requested_parked_bundle_counts: vec![],
         });
 
         let chain_info_response_none = ChainInfoResponse {
@@ -1097,10 +1122,19 @@ pub mod tests {
                     limit: Some(5),
                 },
             ),
+            request_receipts_in_range: Some(linera_core::data_types::BlockHeightRange {
+                start: BlockHeight::from(1),
+                limit: None,
+            }),
             request_received_log_excluding_first_n: None,
+            request_received_log_page: Some(linera_core::data_types::LogPage {
+                before: Some(200),
+                limit: 50,
+            }),
             request_manager_values: false,
             request_leader_timeout: false,
             request_fallback: true,
+            request_parked_bundle_counts: true,
         };
         round_trip_check::<_, api::ChainInfoQuery>(chain_info_query_some);
     }
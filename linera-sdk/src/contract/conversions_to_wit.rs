@@ -4,7 +4,7 @@
 //! Conversions from types declared in [`linera-sdk`] to types generated by [`wit-bindgen`].
 
 use linera_base::{
-    crypto::CryptoHash,
+    crypto::{CryptoHash, PublicKey},
     data_types::{
         Amount, ApplicationPermissions, BlockHeight, Resources, SendMessageRequest, TimeDelta,
         Timestamp,
@@ -31,6 +31,19 @@ impl From<CryptoHash> for wit_system_api::CryptoHash {
     }
 }
 
+impl From<PublicKey> for wit_system_api::PublicKey {
+    fn from(public_key: PublicKey) -> Self {
+        let parts = <[u64; 4]>::from(public_key);
+
+        wit_system_api::PublicKey {
+            part1: parts[0],
+            part2: parts[1],
+            part3: parts[2],
+            part4: parts[3],
+        }
+    }
+}
+
 impl From<ChainId> for wit_system_api::CryptoHash {
     fn from(chain_id: ChainId) -> Self {
         chain_id.0.into()
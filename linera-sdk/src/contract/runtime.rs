@@ -5,6 +5,7 @@
 
 use linera_base::{
     abi::{ContractAbi, ServiceAbi},
+    crypto::{PublicKey, Signature},
     data_types::{
         Amount, ApplicationPermissions, BlockHeight, Resources, SendMessageRequest, Timestamp,
     },
@@ -338,6 +339,37 @@ where
     pub fn validation_round(&mut self) -> Option<u32> {
         wit::validation_round()
     }
+
+    /// Returns `length` pseudo-random bytes, at most 1024 at once.
+    ///
+    /// Every validator obtains the same bytes: they are derived from the chain ID, the block
+    /// height, the operation or message being executed and the application ID. Anyone knowing
+    /// these can predict them, including the block proposer, who may also choose to leave out an
+    /// operation if they don't like its outcome. Don't use them as secrets or where that
+    /// matters, e.g. for lotteries with significant stakes.
+    pub fn random_bytes(&mut self, length: u32) -> Vec<u8> {
+        wit::random_bytes(length)
+    }
+
+    /// Computes the SHA-256 hash of `bytes`.
+    ///
+    /// The host computes it, which is cheaper than compiling a hash function into the
+    /// application.
+    pub fn sha256(&mut self, bytes: &[u8]) -> [u8; 32] {
+        wit::sha256(bytes)
+            .try_into()
+            .expect("SHA-256 hashes have 32 bytes")
+    }
+
+    /// Checks whether `signature` is a valid Ed25519 signature of `message` by `public_key`.
+    pub fn verify_ed25519(
+        &mut self,
+        public_key: PublicKey,
+        message: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        wit::verify_ed25519(public_key.into(), message, &signature.to_bytes())
+    }
 }
 
 /// A helper type that uses the builder pattern to configure how a message is sent, and then
@@ -10,6 +10,7 @@ use std::{
 
 use linera_base::{
     abi::{ContractAbi, ServiceAbi},
+    crypto::{self, PublicKey, Signature},
     data_types::{
         Amount, ApplicationPermissions, BlockHeight, Resources, SendMessageRequest, Timestamp,
     },
@@ -66,6 +67,7 @@ where
     expected_open_chain_calls:
         VecDeque<(ChainOwnership, ApplicationPermissions, Amount, MessageId)>,
     expected_create_application_calls: VecDeque<ExpectedCreateApplicationCall>,
+    random_bytes: VecDeque<u8>,
     key_value_store: KeyValueStore,
 }
 
@@ -114,6 +116,7 @@ where
             expected_assert_data_blob_exists_requests: VecDeque::new(),
             expected_open_chain_calls: VecDeque::new(),
             expected_create_application_calls: VecDeque::new(),
+            random_bytes: VecDeque::new(),
             key_value_store: KeyValueStore::mock().to_mut(),
         }
     }
@@ -898,6 +901,44 @@ where
     pub fn validation_round(&mut self) -> Option<u32> {
         self.round
     }
+
+    /// Configures the pseudo-random bytes to return during the test.
+    pub fn with_random_bytes(mut self, random_bytes: Vec<u8>) -> Self {
+        self.random_bytes = random_bytes.into();
+        self
+    }
+
+    /// Configures the pseudo-random bytes to return during the test.
+    pub fn set_random_bytes(&mut self, random_bytes: Vec<u8>) -> &mut Self {
+        self.random_bytes = random_bytes.into();
+        self
+    }
+
+    /// Returns the next `length` mocked pseudo-random bytes.
+    pub fn random_bytes(&mut self, length: u32) -> Vec<u8> {
+        let length = length as usize;
+        assert!(
+            self.random_bytes.len() >= length,
+            "Not enough random bytes have been mocked, \
+            please call `MockContractRuntime::set_random_bytes` first"
+        );
+        self.random_bytes.drain(..length).collect()
+    }
+
+    /// Computes the SHA-256 hash of `bytes`.
+    pub fn sha256(&mut self, bytes: &[u8]) -> [u8; 32] {
+        crypto::sha256(bytes)
+    }
+
+    /// Checks whether `signature` is a valid Ed25519 signature of `message` by `public_key`.
+    pub fn verify_ed25519(
+        &mut self,
+        public_key: PublicKey,
+        message: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        signature.check_bytes(message, public_key).is_ok()
+    }
 }
 
 /// A type alias for the handler for cross-application calls.
@@ -5,8 +5,9 @@
 
 use linera_base::{
     crypto::CryptoHash,
-    data_types::{Amount, BlockHeight, Timestamp},
+    data_types::{Amount, BlockHeight, TimeDelta, Timestamp},
     identifiers::{AccountOwner, ApplicationId, BytecodeId, ChainId, MessageId, Owner},
+    ownership::{ChainOwnership, TimeoutConfig},
 };
 
 use super::wit::service_system_api as wit_system_api;
@@ -98,3 +99,48 @@ impl From<wit_system_api::Timestamp> for Timestamp {
         Timestamp::from(timestamp.inner0)
     }
 }
+
+impl From<wit_system_api::TimeoutConfig> for TimeoutConfig {
+    fn from(guest: wit_system_api::TimeoutConfig) -> TimeoutConfig {
+        let wit_system_api::TimeoutConfig {
+            fast_round_duration,
+            base_timeout,
+            timeout_increment,
+            fallback_duration,
+        } = guest;
+        TimeoutConfig {
+            fast_round_duration: fast_round_duration.map(TimeDelta::from),
+            base_timeout: base_timeout.into(),
+            timeout_increment: timeout_increment.into(),
+            fallback_duration: fallback_duration.into(),
+        }
+    }
+}
+
+impl From<wit_system_api::TimeDelta> for TimeDelta {
+    fn from(guest: wit_system_api::TimeDelta) -> Self {
+        TimeDelta::from_micros(guest.inner0)
+    }
+}
+
+impl From<wit_system_api::ChainOwnership> for ChainOwnership {
+    fn from(guest: wit_system_api::ChainOwnership) -> ChainOwnership {
+        let wit_system_api::ChainOwnership {
+            super_owners,
+            owners,
+            multi_leader_rounds,
+            open_multi_leader_rounds,
+            timeout_config,
+        } = guest;
+        ChainOwnership {
+            super_owners: super_owners.into_iter().map(Into::into).collect(),
+            owners: owners
+                .into_iter()
+                .map(|(owner, weight)| (owner.into(), weight))
+                .collect(),
+            multi_leader_rounds,
+            open_multi_leader_rounds,
+            timeout_config: timeout_config.into(),
+        }
+    }
+}
@@ -7,8 +7,10 @@ use std::sync::Mutex;
 
 use linera_base::{
     abi::ServiceAbi,
+    crypto::{PublicKey, Signature},
     data_types::{Amount, BlockHeight, Timestamp},
     identifiers::{AccountOwner, ApplicationId, ChainId},
+    ownership::ChainOwnership,
 };
 use serde::Serialize;
 
@@ -28,6 +30,7 @@ where
     chain_balance: Mutex<Option<Amount>>,
     owner_balances: Mutex<Option<Vec<(AccountOwner, Amount)>>>,
     balance_owners: Mutex<Option<Vec<AccountOwner>>>,
+    chain_ownership: Mutex<Option<ChainOwnership>>,
 }
 
 impl<Application> ServiceRuntime<Application>
@@ -45,6 +48,7 @@ where
             chain_balance: Mutex::new(None),
             owner_balances: Mutex::new(None),
             balance_owners: Mutex::new(None),
+            chain_ownership: Mutex::new(None),
         }
     }
 
@@ -120,6 +124,11 @@ where
         })
     }
 
+    /// Retrieves the owner configuration for the current chain.
+    pub fn chain_ownership(&self) -> ChainOwnership {
+        Self::fetch_value_through_cache(&self.chain_ownership, || wit::get_chain_ownership().into())
+    }
+
     /// Schedules an operation to be included in the block being built.
     ///
     /// The operation is specified as an opaque blob of bytes.
@@ -182,4 +191,31 @@ where
     pub fn assert_data_blob_exists(&self, hash: DataBlobHash) {
         wit::assert_data_blob_exists(hash.0.into())
     }
+
+    /// Returns `length` random bytes, at most 1024 at once.
+    ///
+    /// Unlike in contracts, the bytes are different every time.
+    pub fn random_bytes(&self, length: u32) -> Vec<u8> {
+        wit::random_bytes(length)
+    }
+
+    /// Computes the SHA-256 hash of `bytes`.
+    ///
+    /// The host computes it, which is cheaper than compiling a hash function into the
+    /// application.
+    pub fn sha256(&self, bytes: &[u8]) -> [u8; 32] {
+        wit::sha256(bytes)
+            .try_into()
+            .expect("SHA-256 hashes have 32 bytes")
+    }
+
+    /// Checks whether `signature` is a valid Ed25519 signature of `message` by `public_key`.
+    pub fn verify_ed25519(
+        &self,
+        public_key: PublicKey,
+        message: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        wit::verify_ed25519(public_key.into(), message, &signature.to_bytes())
+    }
 }
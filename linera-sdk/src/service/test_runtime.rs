@@ -7,9 +7,11 @@ use std::{collections::HashMap, mem, sync::Mutex};
 
 use linera_base::{
     abi::ServiceAbi,
+    crypto::{self, PublicKey, Signature},
     data_types::{Amount, BlockHeight, Timestamp},
     hex,
     identifiers::{AccountOwner, ApplicationId, ChainId},
+    ownership::ChainOwnership,
 };
 use serde::{de::DeserializeOwned, Serialize};
 
@@ -27,10 +29,12 @@ where
     timestamp: Mutex<Option<Timestamp>>,
     chain_balance: Mutex<Option<Amount>>,
     owner_balances: Mutex<Option<HashMap<AccountOwner, Amount>>>,
+    chain_ownership: Mutex<Option<ChainOwnership>>,
     query_application_handler: Mutex<Option<QueryApplicationHandler>>,
     url_blobs: Mutex<Option<HashMap<String, Vec<u8>>>>,
     blobs: Mutex<Option<HashMap<DataBlobHash, Vec<u8>>>>,
     scheduled_operations: Mutex<Vec<Vec<u8>>>,
+    random_bytes: Mutex<Vec<u8>>,
     key_value_store: KeyValueStore,
 }
 
@@ -57,10 +61,12 @@ where
             timestamp: Mutex::new(None),
             chain_balance: Mutex::new(None),
             owner_balances: Mutex::new(None),
+            chain_ownership: Mutex::new(None),
             query_application_handler: Mutex::new(None),
             url_blobs: Mutex::new(None),
             blobs: Mutex::new(None),
             scheduled_operations: Mutex::new(vec![]),
+            random_bytes: Mutex::new(vec![]),
             key_value_store: KeyValueStore::mock(),
         }
     }
@@ -287,6 +293,27 @@ where
             .collect()
     }
 
+    /// Configures the chain ownership configuration to return during the test.
+    pub fn with_chain_ownership(self, chain_ownership: ChainOwnership) -> Self {
+        *self.chain_ownership.lock().unwrap() = Some(chain_ownership);
+        self
+    }
+
+    /// Configures the chain ownership configuration to return during the test.
+    pub fn set_chain_ownership(&self, chain_ownership: ChainOwnership) -> &Self {
+        *self.chain_ownership.lock().unwrap() = Some(chain_ownership);
+        self
+    }
+
+    /// Retrieves the owner configuration for the current chain.
+    pub fn chain_ownership(&self) -> ChainOwnership {
+        Self::fetch_mocked_value(
+            &self.chain_ownership,
+            "Chain ownership has not been mocked, \
+            please call `MockServiceRuntime::set_chain_ownership` first",
+        )
+    }
+
     /// Schedules an operation to be included in the block being built.
     ///
     /// The operation is specified as an opaque blob of bytes.
@@ -477,6 +504,45 @@ where
             });
     }
 
+    /// Configures the random bytes to return during the test.
+    pub fn with_random_bytes(self, random_bytes: Vec<u8>) -> Self {
+        *self.random_bytes.lock().unwrap() = random_bytes;
+        self
+    }
+
+    /// Configures the random bytes to return during the test.
+    pub fn set_random_bytes(&self, random_bytes: Vec<u8>) -> &Self {
+        *self.random_bytes.lock().unwrap() = random_bytes;
+        self
+    }
+
+    /// Returns the next `length` mocked random bytes.
+    pub fn random_bytes(&self, length: u32) -> Vec<u8> {
+        let mut random_bytes = self.random_bytes.lock().unwrap();
+        let length = length as usize;
+        assert!(
+            random_bytes.len() >= length,
+            "Not enough random bytes have been mocked, \
+            please call `MockServiceRuntime::set_random_bytes` first"
+        );
+        random_bytes.drain(..length).collect()
+    }
+
+    /// Computes the SHA-256 hash of `bytes`.
+    pub fn sha256(&self, bytes: &[u8]) -> [u8; 32] {
+        crypto::sha256(bytes)
+    }
+
+    /// Checks whether `signature` is a valid Ed25519 signature of `message` by `public_key`.
+    pub fn verify_ed25519(
+        &self,
+        public_key: PublicKey,
+        message: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        signature.check_bytes(message, public_key).is_ok()
+    }
+
     /// Loads a mocked value from the `slot` cache or panics with a provided `message`.
     fn fetch_mocked_value<T>(slot: &Mutex<Option<T>>, message: &str) -> T
     where
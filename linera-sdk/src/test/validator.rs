@@ -201,6 +201,7 @@ impl TestValidator {
             epoch: Epoch::ZERO,
             balance: Amount::ZERO,
             application_permissions: ApplicationPermissions::default(),
+            bytecode_publishers: None,
         };
 
         let certificate = admin_chain
@@ -254,6 +254,17 @@ impl ClientWrapper {
             maximum_block_proposal_size,
             maximum_bytes_read_per_block,
             maximum_bytes_written_per_block,
+            maximum_message_size,
+            maximum_messages_per_transaction,
+            maximum_call_depth,
+            maximum_event_value_size,
+            maximum_events_per_transaction,
+            maximum_recipients_per_block,
+            maximum_operations_per_block,
+            maximum_incoming_messages_per_block,
+            sha256_fuel,
+            verify_ed25519_fuel,
+            hashing_fuel_per_byte,
         } = policy;
         let mut command = self.command().await?;
         command
@@ -303,6 +314,38 @@ impl ClientWrapper {
             .args([
                 "--maximum-bytes-written-per-block",
                 &maximum_bytes_written_per_block.to_string(),
+            ])
+            .args(["--maximum-message-size", &maximum_message_size.to_string()])
+            .args([
+                "--maximum-messages-per-transaction",
+                &maximum_messages_per_transaction.to_string(),
+            ])
+            .args(["--maximum-call-depth", &maximum_call_depth.to_string()])
+            .args([
+                "--maximum-event-value-size",
+                &maximum_event_value_size.to_string(),
+            ])
+            .args([
+                "--maximum-events-per-transaction",
+                &maximum_events_per_transaction.to_string(),
+            ])
+            .args([
+                "--maximum-recipients-per-block",
+                &maximum_recipients_per_block.to_string(),
+            ])
+            .args([
+                "--maximum-operations-per-block",
+                &maximum_operations_per_block.to_string(),
+            ])
+            .args([
+                "--maximum-incoming-messages-per-block",
+                &maximum_incoming_messages_per_block.to_string(),
+            ])
+            .args(["--sha256-fuel", &sha256_fuel.to_string()])
+            .args(["--verify-ed25519-fuel", &verify_ed25519_fuel.to_string()])
+            .args([
+                "--hashing-fuel-per-byte",
+                &hashing_fuel_per_byte.to_string(),
             ]);
         if let Some(seed) = self.testing_prng_seed {
             command.arg("--testing-prng-seed").arg(seed.to_string());
@@ -6,7 +6,14 @@
 #![deny(clippy::large_futures)]
 
 use std::{
-    borrow::Cow, collections::HashMap, env, path::PathBuf, process, sync::Arc, time::Instant,
+    borrow::Cow,
+    collections::HashMap,
+    env,
+    io::{self, Write as _},
+    path::PathBuf,
+    process,
+    sync::Arc,
+    time::Instant,
 };
 
 use anyhow::{anyhow, bail, ensure, Context};
@@ -16,7 +23,7 @@ use colored::Colorize;
 use futures::{lock::Mutex, FutureExt as _, StreamExt};
 use linera_base::{
     crypto::{CryptoHash, CryptoRng},
-    data_types::{ApplicationPermissions, Timestamp},
+    data_types::{ApplicationPermissions, TimeDelta, Timestamp},
     identifiers::{AccountOwner, ChainDescription, ChainId, MessageId, Owner},
     ownership::ChainOwnership,
 };
@@ -33,7 +40,7 @@ use linera_client::{
     wallet::{UserChain, Wallet},
 };
 use linera_core::{
-    data_types::{ChainInfoQuery, ClientOutcome},
+    data_types::{BlockReplayReport, ChainInfoQuery, ClientOutcome},
     node::ValidatorNodeProvider,
     remote_node::RemoteNode,
     worker::Reason,
@@ -41,7 +48,7 @@ use linera_core::{
 };
 use linera_execution::{
     committee::{Committee, ValidatorName, ValidatorState},
-    Message, ResourceControlPolicy, SystemMessage,
+    Message, ResourceControlPolicy, SystemMessage, WithWasmDefault as _,
 };
 use linera_service::{
     cli_wrappers,
@@ -50,7 +57,7 @@ use linera_service::{
     project::{self, Project},
     util, wallet,
 };
-use linera_storage::Storage;
+use linera_storage::{PruningPolicy, Storage};
 use linera_views::store::CommonStoreConfig;
 use serde_json::Value;
 use tokio::task::JoinSet;
@@ -637,6 +644,17 @@ impl Runnable for Job {
                                     maximum_block_proposal_size,
                                     maximum_bytes_read_per_block,
                                     maximum_bytes_written_per_block,
+                                    maximum_message_size,
+                                    maximum_messages_per_transaction,
+                                    maximum_call_depth,
+                                    maximum_event_value_size,
+                                    maximum_events_per_transaction,
+                                    maximum_recipients_per_block,
+                                    maximum_operations_per_block,
+                                    maximum_incoming_messages_per_block,
+                                    sha256_fuel,
+                                    verify_ed25519_fuel,
+                                    hashing_fuel_per_byte,
                                 } => {
                                     if let Some(block) = block {
                                         policy.block = block;
@@ -707,6 +725,55 @@ impl Runnable for Job {
                                         policy.maximum_bytes_written_per_block =
                                             maximum_bytes_written_per_block;
                                     }
+                                    if let Some(maximum_message_size) = maximum_message_size {
+                                        policy.maximum_message_size = maximum_message_size;
+                                    }
+                                    if let Some(maximum_messages_per_transaction) =
+                                        maximum_messages_per_transaction
+                                    {
+                                        policy.maximum_messages_per_transaction =
+                                            maximum_messages_per_transaction;
+                                    }
+                                    if let Some(maximum_call_depth) = maximum_call_depth {
+                                        policy.maximum_call_depth = maximum_call_depth;
+                                    }
+                                    if let Some(maximum_event_value_size) = maximum_event_value_size
+                                    {
+                                        policy.maximum_event_value_size = maximum_event_value_size;
+                                    }
+                                    if let Some(maximum_events_per_transaction) =
+                                        maximum_events_per_transaction
+                                    {
+                                        policy.maximum_events_per_transaction =
+                                            maximum_events_per_transaction;
+                                    }
+                                    if let Some(maximum_recipients_per_block) =
+                                        maximum_recipients_per_block
+                                    {
+                                        policy.maximum_recipients_per_block =
+                                            maximum_recipients_per_block;
+                                    }
+                                    if let Some(maximum_operations_per_block) =
+                                        maximum_operations_per_block
+                                    {
+                                        policy.maximum_operations_per_block =
+                                            maximum_operations_per_block;
+                                    }
+                                    if let Some(maximum_incoming_messages_per_block) =
+                                        maximum_incoming_messages_per_block
+                                    {
+                                        policy.maximum_incoming_messages_per_block =
+                                            maximum_incoming_messages_per_block;
+                                    }
+                                    if let Some(sha256_fuel) = sha256_fuel {
+                                        policy.sha256_fuel = sha256_fuel;
+                                    }
+                                    if let Some(verify_ed25519_fuel) = verify_ed25519_fuel {
+                                        policy.verify_ed25519_fuel = verify_ed25519_fuel;
+                                    }
+                                    if let Some(hashing_fuel_per_byte) = hashing_fuel_per_byte {
+                                        policy.hashing_fuel_per_byte = hashing_fuel_per_byte;
+                                    }
                                     info!("{policy}");
                                     if committee.policy() == &policy {
                                         return Ok(ClientOutcome::Committed(None));
@@ -1247,6 +1314,7 @@ impl Runnable for Job {
             | Keygen
             | Net(_)
             | Storage { .. }
+            | CompareReplayReports { .. }
             | Wallet(_)
             | ExtractScriptFromMarkdown { .. }
             | HelpMarkdown => {
@@ -1469,6 +1537,7 @@ fn log_file_name_for(command: &ClientCommand) -> Cow<'static, str> {
         ClientCommand::Project { .. } => "project".into(),
         ClientCommand::Watch { .. } => "watch".into(),
         ClientCommand::Storage { .. } => "storage".into(),
+        ClientCommand::CompareReplayReports { .. } => "tool".into(),
         ClientCommand::Service { port, .. } => format!("service-{port}").into(),
         ClientCommand::Faucet { .. } => "faucet".into(),
         ClientCommand::HelpMarkdown | ClientCommand::ExtractScriptFromMarkdown { .. } => {
@@ -1528,6 +1597,17 @@ async fn run(options: &ClientOptions) -> Result<i32, anyhow::Error> {
             maximum_block_proposal_size,
             maximum_bytes_read_per_block,
             maximum_bytes_written_per_block,
+            maximum_message_size,
+            maximum_messages_per_transaction,
+            maximum_call_depth,
+            maximum_event_value_size,
+            maximum_events_per_transaction,
+            maximum_recipients_per_block,
+            maximum_operations_per_block,
+            maximum_incoming_messages_per_block,
+            sha256_fuel,
+            verify_ed25519_fuel,
+            hashing_fuel_per_byte,
             testing_prng_seed,
             network_name,
         } => {
@@ -1543,6 +1623,22 @@ async fn run(options: &ClientOptions) -> Result<i32, anyhow::Error> {
             let maximum_published_blobs = maximum_published_blobs.unwrap_or(u64::MAX);
             let maximum_bytecode_size = maximum_bytecode_size.unwrap_or(u64::MAX);
             let maximum_block_proposal_size = maximum_block_proposal_size.unwrap_or(u64::MAX);
+            let maximum_message_size = maximum_message_size.unwrap_or(u64::MAX);
+            let maximum_messages_per_transaction =
+                maximum_messages_per_transaction.unwrap_or(u64::MAX);
+            let maximum_call_depth = maximum_call_depth.unwrap_or(u64::MAX);
+            let maximum_event_value_size = maximum_event_value_size.unwrap_or(u64::MAX);
+            let maximum_events_per_transaction = maximum_events_per_transaction.unwrap_or(u64::MAX);
+            let maximum_recipients_per_block = maximum_recipients_per_block.unwrap_or(u64::MAX);
+            let maximum_operations_per_block = maximum_operations_per_block.unwrap_or(u64::MAX);
+            let maximum_incoming_messages_per_block =
+                maximum_incoming_messages_per_block.unwrap_or(u64::MAX);
+            let default_policy = ResourceControlPolicy::default();
+            let sha256_fuel = sha256_fuel.unwrap_or(default_policy.sha256_fuel);
+            let verify_ed25519_fuel =
+                verify_ed25519_fuel.unwrap_or(default_policy.verify_ed25519_fuel);
+            let hashing_fuel_per_byte =
+                hashing_fuel_per_byte.unwrap_or(default_policy.hashing_fuel_per_byte);
             let policy = ResourceControlPolicy {
                 block: *block_price,
                 fuel_unit: *fuel_unit_price,
@@ -1563,6 +1659,17 @@ async fn run(options: &ClientOptions) -> Result<i32, anyhow::Error> {
                 maximum_block_proposal_size,
                 maximum_bytes_read_per_block,
                 maximum_bytes_written_per_block,
+                maximum_message_size,
+                maximum_messages_per_transaction,
+                maximum_call_depth,
+                maximum_event_value_size,
+                maximum_events_per_transaction,
+                maximum_recipients_per_block,
+                maximum_operations_per_block,
+                maximum_incoming_messages_per_block,
+                sha256_fuel,
+                verify_ed25519_fuel,
+                hashing_fuel_per_byte,
             };
             let timestamp = start_timestamp
                 .map(|st| {
@@ -1814,10 +1921,107 @@ async fn run(options: &ClientOptions) -> Result<i32, anyhow::Error> {
                     );
                     println!("The list of root keys is {:?}", root_keys);
                 }
+                DatabaseToolCommand::VerifyChainIntegrity {
+                    chain_id, repair, ..
+                } => {
+                    let orphan_keys =
+                        Box::pin(full_storage_config.verify_chain_integrity(*chain_id, *repair))
+                            .await?;
+                    info!(
+                        "Chain integrity verified in {} ms",
+                        start_time.elapsed().as_millis()
+                    );
+                    for key in &orphan_keys {
+                        println!("{}", hex::encode(key));
+                    }
+                    if orphan_keys.is_empty() {
+                        println!("The state of the chain has no orphan keys");
+                    } else if *repair {
+                        println!("Deleted {} orphan keys", orphan_keys.len());
+                    } else {
+                        println!("Found {} orphan keys", orphan_keys.len());
+                        return Ok(1);
+                    }
+                }
+                DatabaseToolCommand::ReplayBlock {
+                    certificate_hash, ..
+                } => {
+                    let report = full_storage_config
+                        .replay_block(*certificate_hash, options.wasm_runtime.with_wasm_default())
+                        .await?;
+                    info!("Block replayed in {} ms", start_time.elapsed().as_millis());
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                    if report.state_hash != report.expected_state_hash {
+                        return Ok(1);
+                    }
+                }
+                DatabaseToolCommand::PruneCertificates {
+                    chain_id,
+                    keep_latest_blocks,
+                    max_age_secs,
+                    ..
+                } => {
+                    let policy = match (keep_latest_blocks, max_age_secs) {
+                        (Some(count), _) => PruningPolicy::KeepLatestBlocks(*count),
+                        (None, Some(secs)) => PruningPolicy::MaxAge(TimeDelta::from_secs(*secs)),
+                        (None, None) => unreachable!("clap requires one of the two options"),
+                    };
+                    let pruned =
+                        Box::pin(full_storage_config.prune_certificates(*chain_id, policy)).await?;
+                    info!(
+                        "Certificates pruned in {} ms",
+                        start_time.elapsed().as_millis()
+                    );
+                    for hash in &pruned {
+                        println!("{}", hash);
+                    }
+                    println!("Deleted {} certificates", pruned.len());
+                }
+                DatabaseToolCommand::ExportChain { chain_id, path, .. } => {
+                    let mut writer = io::BufWriter::new(fs_err::File::create(path)?);
+                    Box::pin(full_storage_config.export_chain(*chain_id, &mut writer)).await?;
+                    writer.flush()?;
+                    info!("Chain exported in {} ms", start_time.elapsed().as_millis());
+                }
+                DatabaseToolCommand::ImportChain { path, force, .. } => {
+                    let reader = io::BufReader::new(fs_err::File::open(path)?);
+                    let chain_id =
+                        Box::pin(full_storage_config.import_chain(reader, *force)).await?;
+                    info!("Chain imported in {} ms", start_time.elapsed().as_millis());
+                    println!("Imported chain {}", chain_id);
+                }
             }
             Ok(0)
         }
 
+        ClientCommand::CompareReplayReports { first, second } => {
+            let first: BlockReplayReport = serde_json::from_slice(&fs_err::read(first)?)?;
+            let second: BlockReplayReport = serde_json::from_slice(&fs_err::read(second)?)?;
+            anyhow::ensure!(
+                first.certificate_hash == second.certificate_hash,
+                "The reports are for different blocks: {} and {}",
+                first.certificate_hash,
+                second.certificate_hash
+            );
+            let Some((index, step)) = first.first_divergence(&second) else {
+                println!("The execution states agree after every transaction");
+                return Ok(0);
+            };
+            let bundle_count = first.incoming_bundle_count as usize;
+            if index < bundle_count {
+                println!(
+                    "The execution states first differ after message {step} of incoming bundle \
+                    {index}"
+                );
+            } else {
+                println!(
+                    "The execution states first differ after operation {}",
+                    index - bundle_count
+                );
+            }
+            Ok(1)
+        }
+
         ClientCommand::Wallet(wallet_command) => match wallet_command {
             WalletCommand::Show {
                 chain_id,
@@ -1,7 +1,7 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{borrow::Cow, iter, net::SocketAddr, num::NonZeroU16, sync::Arc};
+use std::{borrow::Cow, collections::BTreeMap, iter, net::SocketAddr, num::NonZeroU16, sync::Arc};
 
 use async_graphql::{
     futures_util::Stream,
@@ -15,21 +15,25 @@ use axum::{extract::Path, http::StatusCode, response, response::IntoResponse, Ex
 use futures::{lock::Mutex, Future};
 use linera_base::{
     crypto::{CryptoError, CryptoHash},
-    data_types::{Amount, ApplicationPermissions, Bytecode, TimeDelta, UserApplicationDescription},
+    data_types::{
+        Amount, ApplicationPermissions, BlockHeight, Bytecode, TimeDelta,
+        UserApplicationDescription,
+    },
     ensure,
     hashed::Hashed,
-    identifiers::{ApplicationId, BytecodeId, ChainId, Owner, UserApplicationId},
+    identifiers::{ApplicationId, BytecodeId, ChainId, Destination, Owner, UserApplicationId},
     ownership::{ChainOwnership, TimeoutConfig},
     BcsHexParseError,
 };
 use linera_chain::{
+    data_types::{ChainAndHeight, OutgoingMessage},
     types::{ConfirmedBlock, GenericCertificate},
     ChainStateView,
 };
 use linera_client::chain_listener::{ChainListener, ChainListenerConfig, ClientContext};
 use linera_core::{
     client::{ChainClient, ChainClientError},
-    data_types::ClientOutcome,
+    data_types::{ClientOutcome, MAX_LOG_PAGE_LEN},
     worker::Notification,
 };
 use linera_execution::{
@@ -735,6 +739,75 @@ where
         }
     }
 
+    /// Returns a page of the blocks received by the chain, newest first. The page ends just
+    /// before the entry at index `before`, or with the newest entry. It has at most `limit`
+    /// entries, 10 by default and 1000 at most.
+    async fn received_log(
+        &self,
+        chain_id: ChainId,
+        before: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<ReceivedLogPage, Error> {
+        let client = self.context.lock().await.make_chain_client(chain_id)?;
+        let limit = u64::from(limit.unwrap_or(10)).min(MAX_LOG_PAGE_LEN);
+        let (end, log_entries) = {
+            let view = client.chain_state_view().await?;
+            let count = view.received_log.count() as u64;
+            let end = before.map_or(count, |before| before.min(count));
+            let log_entries = view.read_received_log_backward(Some(end), limit).await?;
+            (end, log_entries)
+        };
+        let storage = client.storage_client();
+        let messages = received_messages(&storage, &log_entries, chain_id).await?;
+        let entries = (0..end)
+            .rev()
+            .zip(log_entries)
+            .zip(messages)
+            .map(|((index, chain_and_height), messages)| ReceivedLogEntry {
+                index,
+                chain_id: chain_and_height.chain_id,
+                height: chain_and_height.height,
+                messages,
+            })
+            .collect::<Vec<_>>();
+        let start = end - entries.len() as u64;
+        let next = (start > 0).then_some(start);
+        Ok(ReceivedLogPage { entries, next })
+    }
+
+    /// Returns a page of the blocks of the chain with the messages they sent, newest first.
+    /// The page ends just before the block at height `before`, or with the latest block. It
+    /// has at most `limit` entries, 10 by default and 1000 at most.
+    async fn sent_log(
+        &self,
+        chain_id: ChainId,
+        before: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<SentLogPage, Error> {
+        let client = self.context.lock().await.make_chain_client(chain_id)?;
+        let limit = u64::from(limit.unwrap_or(10)).min(MAX_LOG_PAGE_LEN);
+        let (end, hashes) = {
+            let view = client.chain_state_view().await?;
+            let count = view.confirmed_log.count() as u64;
+            let end = before.map_or(count, |before| before.min(count));
+            let hashes = view.read_confirmed_log_backward(Some(end), limit).await?;
+            (end, hashes)
+        };
+        let mut entries = Vec::with_capacity(hashes.len());
+        for (height, hash) in (0..end).rev().zip(hashes) {
+            let block = client.read_hashed_confirmed_block(hash).await?;
+            let messages = block.inner().block().messages().concat();
+            entries.push(SentLogEntry {
+                height: BlockHeight(height),
+                hash,
+                messages,
+            });
+        }
+        let start = end - entries.len() as u64;
+        let next = (start > 0).then_some(start);
+        Ok(SentLogPage { entries, next })
+    }
+
     /// Returns the version information on this node service.
     async fn version(&self) -> linera_version::VersionInfo {
         linera_version::VersionInfo::default()
@@ -818,6 +891,96 @@ pub struct ApplicationOverview {
     link: String,
 }
 
+/// A block received by a chain.
+#[derive(SimpleObject)]
+pub struct ReceivedLogEntry {
+    /// The index of the entry in the chain's received log.
+    index: u64,
+    /// The chain that sent the block.
+    chain_id: ChainId,
+    /// The height of the block.
+    height: BlockHeight,
+    /// The messages the block sent directly to the receiving chain, if the block is
+    /// available in the local storage.
+    messages: Option<Vec<OutgoingMessage>>,
+}
+
+/// A page of a chain's received log, newest first.
+#[derive(SimpleObject)]
+pub struct ReceivedLogPage {
+    entries: Vec<ReceivedLogEntry>,
+    /// The value of `before` to query the next page, if there are older entries.
+    next: Option<u64>,
+}
+
+/// A block of a chain, with the messages it sent.
+#[derive(SimpleObject)]
+pub struct SentLogEntry {
+    /// The height of the block.
+    height: BlockHeight,
+    /// The hash of the block.
+    hash: CryptoHash,
+    /// The messages sent by the block.
+    messages: Vec<OutgoingMessage>,
+}
+
+/// A page of a chain's blocks, newest first.
+#[derive(SimpleObject)]
+pub struct SentLogPage {
+    entries: Vec<SentLogEntry>,
+    /// The value of `before` to query the next page, if there are older blocks.
+    next: Option<u64>,
+}
+
+/// Returns the messages that each of the `senders` blocks sent directly to `recipient`, or
+/// `None` for the blocks that are not in the local storage. Each sender chain is loaded once.
+async fn received_messages<S: Storage>(
+    storage: &S,
+    senders: &[ChainAndHeight],
+    recipient: ChainId,
+) -> Result<Vec<Option<Vec<OutgoingMessage>>>, Error> {
+    let mut positions_by_sender = BTreeMap::<ChainId, Vec<(usize, usize)>>::new();
+    for (position, sender) in senders.iter().enumerate() {
+        let index = usize::try_from(sender.height.0)?;
+        positions_by_sender
+            .entry(sender.chain_id)
+            .or_default()
+            .push((position, index));
+    }
+    let mut hashes = vec![None; senders.len()];
+    for (sender_id, positions) in positions_by_sender {
+        let (positions, indices): (Vec<_>, Vec<_>) = positions.into_iter().unzip();
+        let sender_hashes = storage
+            .load_chain(sender_id)
+            .await?
+            .confirmed_log
+            .multi_get(indices)
+            .await?;
+        for (position, hash) in positions.into_iter().zip(sender_hashes) {
+            hashes[position] = hash;
+        }
+    }
+    let mut messages = Vec::with_capacity(hashes.len());
+    for hash in hashes {
+        let Some(hash) = hash else {
+            messages.push(None);
+            continue;
+        };
+        let block = storage.read_hashed_confirmed_block(hash).await?;
+        let block_messages = block
+            .inner()
+            .block()
+            .messages()
+            .iter()
+            .flatten()
+            .filter(|message| message.destination == Destination::Recipient(recipient))
+            .cloned()
+            .collect();
+        messages.push(Some(block_messages));
+    }
+    Ok(messages)
+}
+
 impl ApplicationOverview {
     fn new(
         id: UserApplicationId,
@@ -346,10 +346,13 @@ where
             | ViewError::TryLockError(_)
             | ViewError::InconsistentEntries
             | ViewError::PostLoadValuesError
+            | ViewError::UnsupportedViewVersion { .. }
             | ViewError::IoError(_) => Status::internal(err.to_string()),
-            ViewError::KeyTooLong | ViewError::ArithmeticError(_) => {
-                Status::out_of_range(err.to_string())
-            }
+            ViewError::StoreCapacityExceeded(_) => Status::resource_exhausted(err.to_string()),
+            ViewError::KeyTooLong
+            | ViewError::ArithmeticError(_)
+            | ViewError::QueueFull(_)
+            | ViewError::RangeOutOfBounds { .. } => Status::out_of_range(err.to_string()),
             ViewError::NotFound(_)
             | ViewError::BlobsNotFound(_)
             | ViewError::CannotAcquireCollectionEntry
@@ -46,6 +46,7 @@ struct ServerContext {
     shard: Option<usize>,
     grace_period: Duration,
     max_loaded_chains: NonZeroUsize,
+    max_pending_bundles_per_origin: Option<usize>,
 }
 
 impl ServerContext {
@@ -69,7 +70,8 @@ impl ServerContext {
         )
         .with_allow_inactive_chains(false)
         .with_allow_messages_from_deprecated_epochs(false)
-        .with_grace_period(self.grace_period);
+        .with_grace_period(self.grace_period)
+        .with_max_pending_bundles_per_origin(self.max_pending_bundles_per_origin);
         (state, shard_id, shard.clone())
     }
 
@@ -349,6 +351,11 @@ enum ServerCommand {
         #[arg(long, default_value = "400")]
         max_loaded_chains: NonZeroUsize,
 
+        /// The maximal number of received bundles waiting in the inbox of each origin.
+        /// Further bundles are parked until the inbox is drained.
+        #[arg(long)]
+        max_pending_bundles_per_origin: Option<usize>,
+
         /// The maximal number of simultaneous queries to the database
         #[arg(long)]
         max_concurrent_queries: Option<usize>,
@@ -499,6 +506,7 @@ async fn run(options: ServerOptions) {
             grace_period,
             wasm_runtime,
             max_loaded_chains,
+            max_pending_bundles_per_origin,
             max_concurrent_queries,
             max_stream_queries,
             cache_size,
@@ -524,6 +532,7 @@ async fn run(options: ServerOptions) {
                 shard,
                 grace_period,
                 max_loaded_chains,
+                max_pending_bundles_per_origin,
             };
             let wasm_runtime = wasm_runtime.with_wasm_default();
             let common_config = CommonStoreConfig {
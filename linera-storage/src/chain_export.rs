@@ -0,0 +1,202 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Export and import of the state of a single chain.
+
+use std::{io, mem};
+
+use linera_base::{ensure, identifiers::ChainId};
+use linera_chain::types::ConfirmedBlockCertificate;
+use linera_execution::{
+    read_frame, write_frame, ExecutionError, ExecutionRuntimeContext, ExecutionStateView,
+};
+use linera_views::{
+    batch::Batch,
+    context::{Context, ViewContext},
+    memory::{MemoryStore, MemoryStoreConfig},
+    store::{AdminKeyValueStore as _, KeyIterable as _},
+    views::{View, ViewError},
+};
+
+use crate::{ChainRuntimeContext, Storage};
+
+/// The version of the format written by [`Storage::export_chain`].
+pub const CHAIN_EXPORT_VERSION: u32 = 1;
+
+/// The number of keys, or certificates, that are read at once while exporting a chain.
+const CHAIN_EXPORT_CHUNK_LEN: usize = 1000;
+
+/// The approximate size in bytes of the batches written while importing a chain.
+const CHAIN_IMPORT_BATCH_SIZE: usize = 1 << 20;
+
+/// The maximum number of stream queries of the memory store staging an imported chain.
+const CHAIN_IMPORT_STREAM_QUERIES: usize = 10;
+
+/// An error while exporting or importing a chain.
+#[derive(Debug, thiserror::Error)]
+pub enum ChainExportError {
+    #[error(transparent)]
+    ViewError(#[from] ViewError),
+    #[error(transparent)]
+    ExecutionError(#[from] Box<ExecutionError>),
+    #[error("Unsupported chain export version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Chain {0} already exists in storage")]
+    ChainAlreadyExists(ChainId),
+}
+
+impl From<ExecutionError> for ChainExportError {
+    fn from(error: ExecutionError) -> Self {
+        ChainExportError::ExecutionError(Box::new(error))
+    }
+}
+
+pub(crate) async fn export_chain<S>(
+    storage: &S,
+    chain_id: ChainId,
+    mut writer: impl io::Write + Send,
+) -> Result<(), ChainExportError>
+where
+    S: Storage,
+    ChainRuntimeContext<S>: ExecutionRuntimeContext,
+{
+    let chain = storage.load_chain(chain_id).await?;
+    write_frame(&mut writer, &(CHAIN_EXPORT_VERSION, chain_id))?;
+    chain.execution_state.export_state(&mut writer).await?;
+
+    // The rest of the chain state, e.g. the inboxes and outboxes.
+    let context = chain.context();
+    let base_key = context.base_key();
+    let execution_prefix = chain.execution_state.context().base_key()[base_key.len()..].to_vec();
+    let keys = context
+        .find_keys_by_prefix(&base_key)
+        .await
+        .map_err(ViewError::from)?
+        .iterator()
+        .map(|key| key.map(<[u8]>::to_vec))
+        .filter(|key| {
+            key.as_ref()
+                .map_or(true, |key| !key.starts_with(&execution_prefix))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ViewError::from)?;
+    for chunk in keys.chunks(CHAIN_EXPORT_CHUNK_LEN) {
+        let full_keys = chunk.iter().map(|key| context.base_index(key)).collect();
+        let values = context
+            .read_multi_values_bytes(full_keys)
+            .await
+            .map_err(ViewError::from)?;
+        for (key, value) in chunk.iter().zip(values) {
+            if let Some(value) = value {
+                write_frame(&mut writer, &Some((key, value)))?;
+            }
+        }
+    }
+    write_frame(&mut writer, &None::<(Vec<u8>, Vec<u8>)>)?;
+
+    // Certificates that were pruned are skipped.
+    let hashes = chain.confirmed_log.read(..).await?;
+    for chunk in hashes.chunks(CHAIN_EXPORT_CHUNK_LEN) {
+        for certificate in storage
+            .maybe_read_certificates(chunk)
+            .await?
+            .iter()
+            .flatten()
+        {
+            write_frame(&mut writer, &Some(certificate))?;
+        }
+    }
+    write_frame(&mut writer, &None::<ConfirmedBlockCertificate>)?;
+    Ok(())
+}
+
+pub(crate) async fn import_chain<S>(
+    storage: &S,
+    mut reader: impl io::Read + Send,
+    force: bool,
+) -> Result<ChainId, ChainExportError>
+where
+    S: Storage + 'static,
+    ChainRuntimeContext<S>: ExecutionRuntimeContext,
+{
+    let (version, chain_id): (u32, ChainId) = read_frame(&mut reader)?;
+    ensure!(
+        version == CHAIN_EXPORT_VERSION,
+        ChainExportError::UnsupportedVersion(version)
+    );
+    let chain = storage.load_chain(chain_id).await?;
+    let context = chain.context().clone();
+    let execution_context = chain.execution_state.context().clone();
+    drop(chain);
+
+    let base_key = context.base_key();
+    let is_present = context
+        .find_keys_by_prefix(&base_key)
+        .await
+        .map_err(ViewError::from)?
+        .iterator()
+        .next()
+        .is_some();
+    ensure!(
+        force || !is_present,
+        ChainExportError::ChainAlreadyExists(chain_id)
+    );
+
+    // The execution state is staged in memory, so that its hash is checked, and the whole
+    // stream is read, before the existing chain state is replaced.
+    let namespace = format!("chain_import_{chain_id}");
+    let staging_store =
+        MemoryStore::new(CHAIN_IMPORT_STREAM_QUERIES, &namespace, &[]).map_err(ViewError::from)?;
+    let result = async {
+        let staging_context =
+            ViewContext::new_unsafe(staging_store, Vec::new(), context.extra().clone());
+        ExecutionStateView::import_state(staging_context.clone(), &mut reader).await?;
+        let mut entries = Vec::new();
+        while let Some(entry) = read_frame::<Option<(Vec<u8>, Vec<u8>)>>(&mut reader)? {
+            entries.push(entry);
+        }
+        // Certificates do not depend on the chain state, so they can be written first.
+        while let Some(certificate) = read_frame::<Option<ConfirmedBlockCertificate>>(&mut reader)?
+        {
+            storage
+                .write_blobs_and_certificate(&[], &certificate)
+                .await?;
+        }
+
+        if is_present {
+            let mut batch = Batch::new();
+            batch.delete_key_prefix(base_key);
+            context.write_batch(batch).await.map_err(ViewError::from)?;
+        }
+        let execution_entries = staging_context
+            .find_key_values_by_prefix(&[])
+            .await
+            .map_err(ViewError::from)?
+            .into_iter()
+            .map(|(key, value)| (execution_context.base_index(&key), value));
+        let entries = entries
+            .into_iter()
+            .map(|(key, value)| (context.base_index(&key), value));
+        let mut batch = Batch::new();
+        for (key, value) in execution_entries.chain(entries) {
+            batch.put_key_value_bytes(key, value);
+            if batch.size() >= CHAIN_IMPORT_BATCH_SIZE {
+                context
+                    .write_batch(mem::take(&mut batch))
+                    .await
+                    .map_err(ViewError::from)?;
+            }
+        }
+        context.write_batch(batch).await.map_err(ViewError::from)?;
+        Ok::<_, ChainExportError>(())
+    }
+    .await;
+    MemoryStore::delete(
+        &MemoryStoreConfig::new(CHAIN_IMPORT_STREAM_QUERIES),
+        &namespace,
+    )
+    .await
+    .map_err(ViewError::from)?;
+    result?;
+    Ok(chain_id)
+}
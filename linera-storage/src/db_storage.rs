@@ -422,6 +422,13 @@ where
         &self.clock
     }
 
+    fn with_execution_runtime_config(&self, config: ExecutionRuntimeConfig) -> Self {
+        Self {
+            execution_runtime_config: config,
+            ..self.clone()
+        }
+    }
+
     async fn load_chain(
         &self,
         chain_id: ChainId,
@@ -741,6 +748,40 @@ where
         Ok(certificates)
     }
 
+    async fn maybe_read_certificates(
+        &self,
+        hashes: &[CryptoHash],
+    ) -> Result<Vec<Option<ConfirmedBlockCertificate>>, ViewError> {
+        if hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+        let keys = Self::get_keys_for_certificates(hashes)?;
+        let values = self.store.read_multi_values_bytes(keys).await?;
+        #[cfg(with_metrics)]
+        READ_CERTIFICATES_COUNTER.with_label_values(&[]).inc();
+        let mut certificates = Vec::new();
+        for (pair, hash) in values.chunks_exact(2).zip(hashes) {
+            let certificate = if pair.iter().all(Option::is_some) {
+                Some(Self::deserialize_certificate(pair, *hash)?)
+            } else {
+                None
+            };
+            certificates.push(certificate);
+        }
+        Ok(certificates)
+    }
+
+    async fn delete_certificates(&self, hashes: &[CryptoHash]) -> Result<(), ViewError> {
+        if hashes.is_empty() {
+            return Ok(());
+        }
+        let mut batch = Batch::new();
+        for key in Self::get_keys_for_certificates(hashes)? {
+            batch.delete_key(key);
+        }
+        self.write_batch(batch).await
+    }
+
     fn wasm_runtime(&self) -> Option<WasmRuntime> {
         self.wasm_runtime
     }
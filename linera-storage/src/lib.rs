@@ -5,9 +5,11 @@
 
 #![deny(clippy::large_futures)]
 
+mod chain_export;
 mod db_storage;
+mod pruning;
 
-use std::sync::Arc;
+use std::{io, sync::Arc};
 
 use async_trait::async_trait;
 use dashmap::{mapref::entry::Entry, DashMap};
@@ -43,11 +45,15 @@ use {
 
 #[cfg(with_testing)]
 pub use crate::db_storage::TestClock;
-pub use crate::db_storage::{list_all_blob_ids, ChainStatesFirstAssignment, DbStorage, WallClock};
 #[cfg(with_metrics)]
 pub use crate::db_storage::{
     READ_CERTIFICATE_COUNTER, READ_HASHED_CONFIRMED_BLOCK_COUNTER, WRITE_CERTIFICATE_COUNTER,
 };
+pub use crate::{
+    chain_export::{ChainExportError, CHAIN_EXPORT_VERSION},
+    db_storage::{list_all_blob_ids, ChainStatesFirstAssignment, DbStorage, WallClock},
+    pruning::PruningPolicy,
+};
 
 /// Communicate with a persistent storage using the "views" abstraction.
 #[cfg_attr(not(web), async_trait)]
@@ -62,6 +68,10 @@ pub trait Storage: Sized {
     /// Returns the current wall clock time.
     fn clock(&self) -> &Self::Clock;
 
+    /// Returns a storage that loads chains with the given execution runtime configuration,
+    /// e.g. to trace state hashes while replaying a block.
+    fn with_execution_runtime_config(&self, config: ExecutionRuntimeConfig) -> Self;
+
     /// Loads the view of a chain state.
     ///
     /// # Notes
@@ -163,6 +173,61 @@ pub trait Storage: Sized {
         hashes: I,
     ) -> Result<Vec<ConfirmedBlockCertificate>, ViewError>;
 
+    /// Reads a number of certificates, returning `None` for those that are not in storage.
+    async fn maybe_read_certificates(
+        &self,
+        hashes: &[CryptoHash],
+    ) -> Result<Vec<Option<ConfirmedBlockCertificate>>, ViewError>;
+
+    /// Deletes the certificates with the given hashes, and their blocks.
+    async fn delete_certificates(&self, hashes: &[CryptoHash]) -> Result<(), ViewError>;
+
+    /// Deletes the certificates of the given chain that are older than allowed by the
+    /// `policy`, and returns their hashes. The following certificates are always retained:
+    /// * the latest certificate of the chain,
+    /// * the certificates of blocks that create or remove a committee,
+    /// * the certificates of blocks with messages that are still in flight or waiting in
+    ///   the inbox of a recipient.
+    ///
+    /// Pruned certificates can no longer be downloaded from this storage.
+    async fn prune_certificates(
+        &self,
+        chain_id: ChainId,
+        policy: PruningPolicy,
+    ) -> Result<Vec<CryptoHash>, ViewError> {
+        pruning::prune_certificates(self, chain_id, policy).await
+    }
+
+    /// Writes a chain to `writer`, as a stream of length-prefixed frames starting with
+    /// [`CHAIN_EXPORT_VERSION`]: its execution state, the rest of its chain state including
+    /// its inboxes and outboxes, and the certificates of its blocks that were not pruned.
+    ///
+    /// # Notes
+    ///
+    /// This should not be called while the chain is in use by a worker.
+    async fn export_chain<W>(&self, chain_id: ChainId, writer: W) -> Result<(), ChainExportError>
+    where
+        W: io::Write + Send,
+        ChainRuntimeContext<Self>: ExecutionRuntimeContext,
+    {
+        chain_export::export_chain(self, chain_id, writer).await
+    }
+
+    /// Reads a chain written by [`export_chain`][`Self::export_chain`] from `reader` and
+    /// returns its ID. The imported execution state must have the exported hash.
+    ///
+    /// If the chain already exists in this storage, its state is replaced if `force` is
+    /// set, and an error is returned otherwise. The whole stream is read and checked before
+    /// the existing state is replaced, so an invalid stream leaves the chain unchanged.
+    async fn import_chain<R>(&self, reader: R, force: bool) -> Result<ChainId, ChainExportError>
+    where
+        Self: 'static,
+        R: io::Read + Send,
+        ChainRuntimeContext<Self>: ExecutionRuntimeContext,
+    {
+        chain_export::import_chain(self, reader, force).await
+    }
+
     /// Loads the view of a chain state and checks that it is active.
     ///
     /// # Notes
@@ -185,6 +250,33 @@ pub trait Storage: Sized {
         Ok(chain)
     }
 
+    /// Returns the stored keys of the state of a chain that belong to no entry of its
+    /// collections, relative to the root key of the chain. If `repair` is set, these keys
+    /// are deleted.
+    ///
+    /// # Notes
+    ///
+    /// This should not be called while the chain is in use by a worker: entries that are
+    /// concurrently written or removed may be reported or deleted.
+    async fn verify_chain_integrity(
+        &self,
+        id: ChainId,
+        repair: bool,
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        let chain = self.load_chain(id).await?;
+        let mut orphan_keys = chain.execution_state.users.verify_integrity(repair).await?;
+        orphan_keys.extend(
+            chain
+                .pending_proposed_blobs
+                .verify_integrity(repair)
+                .await?,
+        );
+        orphan_keys.extend(chain.inboxes.verify_integrity(repair).await?);
+        orphan_keys.extend(chain.outboxes.verify_integrity(repair).await?);
+        orphan_keys.extend(chain.channels.verify_integrity(repair).await?);
+        Ok(orphan_keys)
+    }
+
     /// Initializes a chain in a simple way (used for testing and to create a genesis state).
     ///
     /// # Notes
@@ -0,0 +1,187 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deletion of old certificates from storage.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::{BlockHeight, TimeDelta},
+    identifiers::{ChainId, Destination},
+};
+use linera_chain::{
+    data_types::{ChannelFullName, Origin},
+    types::{Block, ConfirmedBlockCertificate},
+    ChainStateView,
+};
+use linera_execution::{
+    system::AdminOperation, Message, Operation, SystemMessage, SystemOperation,
+};
+use linera_views::views::ViewError;
+
+use crate::{Clock as _, Storage};
+
+/// The number of certificates that are read at once while pruning.
+const PRUNING_BATCH_SIZE: usize = 1000;
+
+/// Which certificates of a chain are retained, see [`Storage::prune_certificates`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PruningPolicy {
+    /// Retains the certificates of the latest blocks of the chain, up to this number.
+    KeepLatestBlocks(u64),
+    /// Retains the certificates of the blocks with a timestamp at most this old.
+    MaxAge(TimeDelta),
+}
+
+pub(crate) async fn prune_certificates<S>(
+    storage: &S,
+    chain_id: ChainId,
+    policy: PruningPolicy,
+) -> Result<Vec<CryptoHash>, ViewError>
+where
+    S: Storage,
+{
+    let chain = storage.load_chain(chain_id).await?;
+    // The latest certificate is always retained.
+    let count = chain.confirmed_log.count();
+    let mut end = count.saturating_sub(1);
+    let mut cutoff = None;
+    match policy {
+        PruningPolicy::KeepLatestBlocks(retained) => {
+            let retained = usize::try_from(retained).unwrap_or(usize::MAX);
+            end = end.min(count.saturating_sub(retained));
+        }
+        PruningPolicy::MaxAge(age) => {
+            cutoff = Some(storage.clock().current_time().saturating_sub(age));
+        }
+    }
+    let heights_in_flight = chain
+        .outbox_counters
+        .get()
+        .keys()
+        .copied()
+        .collect::<BTreeSet<BlockHeight>>();
+    let mut pruned = Vec::new();
+    let mut start = 0;
+    while start < end {
+        let batch_end = end.min(start + PRUNING_BATCH_SIZE);
+        let hashes = chain.confirmed_log.read(start..batch_end).await?;
+        let mut candidates = Vec::new();
+        let mut reached_cutoff = false;
+        for certificate in storage.maybe_read_certificates(&hashes).await? {
+            // Certificates that are missing were pruned already.
+            let Some(certificate) = certificate else {
+                continue;
+            };
+            let block = certificate.block();
+            if cutoff.is_some_and(|cutoff| block.header.timestamp >= cutoff) {
+                reached_cutoff = true;
+                break;
+            }
+            if heights_in_flight.contains(&block.header.height) || changes_committees(block) {
+                continue;
+            }
+            candidates.push(certificate);
+        }
+        let waiting = certificates_waiting_in_inboxes(storage, &chain, &candidates).await?;
+        let hashes = candidates
+            .iter()
+            .map(ConfirmedBlockCertificate::hash)
+            .filter(|hash| !waiting.contains(hash))
+            .collect::<Vec<_>>();
+        storage.delete_certificates(&hashes).await?;
+        pruned.extend(hashes);
+        if reached_cutoff {
+            break;
+        }
+        start = batch_end;
+    }
+    Ok(pruned)
+}
+
+/// Returns whether the block creates or removes a committee, i.e. changes the epoch of
+/// the admin chain or of a chain that follows it.
+fn changes_committees(block: &Block) -> bool {
+    let operations = block.body.operations.iter().any(|operation| {
+        matches!(
+            operation,
+            Operation::System(SystemOperation::Admin(
+                AdminOperation::CreateCommittee { .. } | AdminOperation::RemoveCommittee { .. }
+            ))
+        )
+    });
+    let messages = block
+        .body
+        .incoming_bundles
+        .iter()
+        .flat_map(|incoming_bundle| &incoming_bundle.bundle.messages)
+        .any(|posted_message| {
+            matches!(
+                posted_message.message,
+                Message::System(
+                    SystemMessage::CreateCommittee { .. } | SystemMessage::RemoveCommittee { .. }
+                )
+            )
+        });
+    operations || messages
+}
+
+/// Returns the hashes of the given certificates that have message bundles still waiting
+/// in the inbox of a recipient.
+async fn certificates_waiting_in_inboxes<S>(
+    storage: &S,
+    chain: &ChainStateView<S::Context>,
+    certificates: &[ConfirmedBlockCertificate],
+) -> Result<HashSet<CryptoHash>, ViewError>
+where
+    S: Storage,
+{
+    let sender = chain.chain_id();
+    let mut origins_by_recipient = BTreeMap::<ChainId, BTreeSet<Origin>>::new();
+    for certificate in certificates {
+        for outgoing_message in certificate.block().body.messages.iter().flatten() {
+            match &outgoing_message.destination {
+                Destination::Recipient(recipient) => {
+                    origins_by_recipient
+                        .entry(*recipient)
+                        .or_default()
+                        .insert(Origin::chain(sender));
+                }
+                Destination::Subscribers(name) => {
+                    let full_name = ChannelFullName {
+                        application_id: outgoing_message.message.application_id(),
+                        name: name.clone(),
+                    };
+                    let Some(channel) = chain.channels.try_load_entry(&full_name).await? else {
+                        continue;
+                    };
+                    for subscriber in channel.subscribers.indices().await? {
+                        origins_by_recipient
+                            .entry(subscriber)
+                            .or_default()
+                            .insert(Origin::channel(sender, full_name.clone()));
+                    }
+                }
+            }
+        }
+    }
+    let mut waiting = HashSet::new();
+    for (recipient, origins) in origins_by_recipient {
+        let recipient_chain = storage.load_chain(recipient).await?;
+        for origin in origins {
+            let Some(inbox) = recipient_chain.inboxes.try_load_entry(&origin).await? else {
+                continue;
+            };
+            let bundles = inbox.added_bundles.elements().await?;
+            let parked_bundles = inbox.parked_bundles.elements().await?;
+            waiting.extend(
+                bundles
+                    .iter()
+                    .chain(&parked_bundles)
+                    .map(|bundle| bundle.certificate_hash),
+            );
+        }
+    }
+    Ok(waiting)
+}
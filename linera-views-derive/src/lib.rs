@@ -79,6 +79,82 @@ fn empty_where_clause() -> WhereClause {
     }
 }
 
+/// How the derive macros handle a field of a view.
+enum FieldKind {
+    /// A sub-view, stored at the given position.
+    View { index: usize, ty: Type },
+    /// A field marked with `#[view(skip)]`, which is not stored.
+    Skipped,
+}
+
+fn is_skipped(attributes: &[Attribute]) -> bool {
+    attributes
+        .iter()
+        .filter(|attribute| attribute.path().is_ident("view"))
+        .any(|attribute| match attribute.parse_args::<syn::Path>() {
+            Ok(path) if path.is_ident("skip") => true,
+            _ => panic!(
+                r#"Invalid `view` attribute syntax on a field. \
+                Expected syntax: `#[view(skip)]`"#,
+            ),
+        })
+}
+
+/// Rejects the `Option` fields, whose presence could not be tracked in storage.
+fn check_not_option(ty: &Type) {
+    if let Type::Path(TypePath { qself: None, path }) = ty {
+        if path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option")
+        {
+            panic!("Optional sub-views should use `OptionalView` instead of `Option`");
+        }
+    }
+}
+
+/// Returns the fields of a view with their kinds. Skipped fields do not take a position,
+/// so that they can be added or removed without changing the storage layout.
+fn view_fields(input: &ItemStruct) -> Vec<(syn::Field, FieldKind)> {
+    let mut index = 0;
+    let mut fields = Vec::new();
+    for field in &input.fields {
+        assert!(
+            field.ident.is_some(),
+            "The fields of a view should be named"
+        );
+        let kind = if is_skipped(&field.attrs) {
+            FieldKind::Skipped
+        } else {
+            check_not_option(&field.ty);
+            let kind = FieldKind::View {
+                index,
+                ty: field.ty.clone(),
+            };
+            index += 1;
+            kind
+        };
+        fields.push((field.clone(), kind));
+    }
+    fields
+}
+
+/// Returns the name of the first field that is a view, whose context provides the
+/// context of the whole view.
+fn first_view_field(fields: &[(syn::Field, FieldKind)]) -> syn::Ident {
+    fields
+        .iter()
+        .find_map(|(field, kind)| match kind {
+            FieldKind::View { .. } => field.ident.clone(),
+            FieldKind::Skipped => None,
+        })
+        .expect("A view should have a field that is a view and is not skipped")
+}
+
+fn index_literal(index: usize) -> syn::LitInt {
+    syn::LitInt::new(&index.to_string(), Span::call_site())
+}
+
 fn get_extended_entry(e: Type) -> TokenStream2 {
     let syn::Type::Path(typepath) = e else {
         panic!("The type should be a path");
@@ -90,6 +166,7 @@ fn get_extended_entry(e: Type) -> TokenStream2 {
 }
 
 fn generate_view_code(input: ItemStruct, root: bool) -> TokenStream2 {
+    let fields = view_fields(&input);
     let struct_name = input.ident;
     let (impl_generics, type_generics, maybe_where_clause) = input.generics.split_for_impl();
     let template_vect = get_seq_parameter(input.generics.clone());
@@ -112,39 +189,45 @@ fn generate_view_code(input: ItemStruct, root: bool) -> TokenStream2 {
     let mut num_init_keys_quotes = Vec::new();
     let mut pre_load_keys_quotes = Vec::new();
     let mut post_load_keys_quotes = Vec::new();
-    for (idx, e) in input.fields.into_iter().enumerate() {
-        let name = e.clone().ident.unwrap();
-        let test_flush_ident = format_ident!("deleted{}", idx);
-        let idx_lit = syn::LitInt::new(&idx.to_string(), Span::call_site());
-        let g = get_extended_entry(e.ty.clone());
+    let first_name_quote = first_view_field(&fields);
+    for (field, kind) in fields {
+        let name = field.ident.unwrap();
         name_quotes.push(quote! { #name });
-        rollback_quotes.push(quote! { self.#name.rollback(); });
-        flush_quotes.push(quote! { let #test_flush_ident = self.#name.flush(batch)?; });
-        test_flush_quotes.push(quote! { #test_flush_ident });
-        clear_quotes.push(quote! { self.#name.clear(); });
-        has_pending_changes_quotes.push(quote! {
-            if self.#name.has_pending_changes().await {
-                return true;
+        match kind {
+            FieldKind::View { index, ty } => {
+                let test_flush_ident = format_ident!("deleted{}", index);
+                let idx_lit = index_literal(index);
+                let g = get_extended_entry(ty);
+                rollback_quotes.push(quote! { self.#name.rollback(); });
+                flush_quotes.push(quote! { let #test_flush_ident = self.#name.flush(batch)?; });
+                test_flush_quotes.push(quote! { #test_flush_ident });
+                clear_quotes.push(quote! { self.#name.clear(); });
+                has_pending_changes_quotes.push(quote! {
+                    if self.#name.has_pending_changes().await {
+                        return true;
+                    }
+                });
+                num_init_keys_quotes.push(quote! { #g :: NUM_INIT_KEYS });
+                pre_load_keys_quotes.push(quote! {
+                    let index = #idx_lit;
+                    let base_key = context.derive_tag_key(linera_views::views::MIN_VIEW_TAG, &index)?;
+                    keys.extend(#g :: pre_load(&context.clone_with_base_key(base_key))?);
+                });
+                post_load_keys_quotes.push(quote! {
+                    let index = #idx_lit;
+                    let pos_next = pos + #g :: NUM_INIT_KEYS;
+                    let base_key = context.derive_tag_key(linera_views::views::MIN_VIEW_TAG, &index)?;
+                    let #name = #g :: post_load(context.clone_with_base_key(base_key), &values[pos..pos_next])?;
+                    pos = pos_next;
+                });
             }
-        });
-        num_init_keys_quotes.push(quote! { #g :: NUM_INIT_KEYS });
-        pre_load_keys_quotes.push(quote! {
-            let index = #idx_lit;
-            let base_key = context.derive_tag_key(linera_views::views::MIN_VIEW_TAG, &index)?;
-            keys.extend(#g :: pre_load(&context.clone_with_base_key(base_key))?);
-        });
-        post_load_keys_quotes.push(quote! {
-            let index = #idx_lit;
-            let pos_next = pos + #g :: NUM_INIT_KEYS;
-            let base_key = context.derive_tag_key(linera_views::views::MIN_VIEW_TAG, &index)?;
-            let #name = #g :: post_load(context.clone_with_base_key(base_key), &values[pos..pos_next])?;
-            pos = pos_next;
-        });
+            FieldKind::Skipped => {
+                post_load_keys_quotes.push(quote! {
+                    let #name = Default::default();
+                });
+            }
+        }
     }
-    let first_name_quote = name_quotes
-        .first()
-        .expect("list of names should be non-empty");
-
     let load_metrics = if root && cfg!(feature = "metrics") {
         quote! {
             #[cfg(not(target_arch = "wasm32"))]
@@ -223,6 +306,7 @@ fn generate_view_code(input: ItemStruct, root: bool) -> TokenStream2 {
 }
 
 fn generate_save_delete_view_code(input: ItemStruct) -> TokenStream2 {
+    let fields = view_fields(&input);
     let struct_name = input.ident;
     let (impl_generics, type_generics, maybe_where_clause) = input.generics.split_for_impl();
     let template_vect = get_seq_parameter(input.generics.clone());
@@ -237,24 +321,38 @@ fn generate_save_delete_view_code(input: ItemStruct) -> TokenStream2 {
         .extend(context_constraints.predicates);
 
     let mut flushes = Vec::new();
-    let mut deletes = Vec::new();
-    for e in input.fields {
-        let name = e.clone().ident.unwrap();
-        flushes.push(quote! { self.#name.flush(&mut batch)?; });
-        deletes.push(quote! { self.#name.delete(batch); });
+    for (field, kind) in fields {
+        let name = field.ident.unwrap();
+        if let FieldKind::View { .. } = kind {
+            flushes.push(quote! { self.#name.flush(&mut batch)?; });
+        }
     }
 
-    let increment_counter = if cfg!(feature = "metrics") {
-        quote! {
-            #[cfg(not(target_arch = "wasm32"))]
-            linera_views::metrics::increment_counter(
-                &linera_views::metrics::SAVE_VIEW_COUNTER,
-                stringify!(#struct_name),
-                &self.context().base_key(),
-            );
-        }
+    let (increment_counter, finish_flush, finish_write) = if cfg!(feature = "metrics") {
+        (
+            quote! {
+                #[cfg(not(target_arch = "wasm32"))]
+                linera_views::metrics::increment_counter(
+                    &linera_views::metrics::SAVE_VIEW_COUNTER,
+                    stringify!(#struct_name),
+                    &self.context().base_key(),
+                );
+                #[cfg(not(target_arch = "wasm32"))]
+                use linera_views::metrics::SaveViewMeasurement;
+                #[cfg(not(target_arch = "wasm32"))]
+                let mut measurement = SaveViewMeasurement::start(stringify!(#struct_name));
+            },
+            quote! {
+                #[cfg(not(target_arch = "wasm32"))]
+                measurement.finish_flush(&statistics);
+            },
+            quote! {
+                #[cfg(not(target_arch = "wasm32"))]
+                measurement.finish_write();
+            },
+        )
     } else {
-        quote! {}
+        (quote! {}, quote! {}, quote! {})
     };
 
     quote! {
@@ -263,20 +361,32 @@ fn generate_save_delete_view_code(input: ItemStruct) -> TokenStream2 {
         #where_clause
         {
             async fn save(&mut self) -> Result<(), linera_views::views::ViewError> {
+                use linera_views::views::RootView as _;
+                self.save_with_statistics().await?;
+                Ok(())
+            }
+
+            async fn save_with_statistics(
+                &mut self,
+            ) -> Result<linera_views::batch::BatchStatistics, linera_views::views::ViewError> {
                 use linera_views::{context::Context, batch::Batch, views::View};
                 #increment_counter
                 let mut batch = Batch::new();
                 #(#flushes)*
+                let statistics = batch.statistics();
+                #finish_flush
                 if !batch.is_empty() {
                     self.context().write_batch(batch).await?;
                 }
-                Ok(())
+                #finish_write
+                Ok(statistics)
             }
         }
     }
 }
 
 fn generate_hash_view_code(input: ItemStruct) -> TokenStream2 {
+    let fields = view_fields(&input);
     let struct_name = input.ident;
     let (impl_generics, type_generics, maybe_where_clause) = input.generics.split_for_impl();
     let template_vect = get_seq_parameter(input.generics.clone());
@@ -292,10 +402,16 @@ fn generate_hash_view_code(input: ItemStruct) -> TokenStream2 {
 
     let mut field_hashes_mut = Vec::new();
     let mut field_hashes = Vec::new();
-    for e in input.fields {
-        let name = e.clone().ident.unwrap();
-        field_hashes_mut.push(quote! { hasher.write_all(self.#name.hash_mut().await?.as_ref())?; });
-        field_hashes.push(quote! { hasher.write_all(self.#name.hash().await?.as_ref())?; });
+    for (field, kind) in fields {
+        let name = field.ident.unwrap();
+        match kind {
+            FieldKind::View { .. } => {
+                field_hashes_mut
+                    .push(quote! { hasher.write_all(self.#name.hash_mut().await?.as_ref())?; });
+                field_hashes.push(quote! { hasher.write_all(self.#name.hash().await?.as_ref())?; });
+            }
+            FieldKind::Skipped => {}
+        }
     }
 
     quote! {
@@ -381,15 +497,19 @@ fn generate_crypto_hash_code(input: ItemStruct) -> TokenStream2 {
 }
 
 fn generate_clonable_view_code(input: ItemStruct) -> TokenStream2 {
+    let fields = view_fields(&input);
     let struct_name = input.ident;
     let generics = input.generics;
     let template_vect = get_seq_parameter(generics.clone());
 
     let (context, context_constraints) = context_and_constraints(&input.attrs, &template_vect);
 
-    let clone_unchecked_quotes = input.fields.iter().map(|field| {
-        let name = &field.ident;
-        quote! { #name: self.#name.clone_unchecked()?, }
+    let clone_unchecked_quotes = fields.into_iter().map(|(field, kind)| {
+        let name = field.ident;
+        match kind {
+            FieldKind::View { .. } => quote! { #name: self.#name.clone_unchecked()?, },
+            FieldKind::Skipped => quote! { #name: self.#name.clone(), },
+        }
     });
 
     quote! {
@@ -541,6 +661,52 @@ pub mod tests {
         }
     }
 
+    #[test]
+    #[should_panic(expected = "is a view and is not skipped")]
+    fn test_view_without_plain_field() {
+        let input = parse_quote! {
+            struct TestView<C> {
+                #[view(skip)]
+                cache: Vec<usize>,
+            }
+        };
+        generate_view_code(input, false);
+    }
+
+    #[test]
+    #[should_panic(expected = "should use `OptionalView`")]
+    fn test_view_with_option() {
+        let input = parse_quote! {
+            struct TestView<C> {
+                register: RegisterView<C, usize>,
+                map: Option<MapView<C, usize, usize>>,
+            }
+        };
+        generate_view_code(input, false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid `view` attribute syntax on a field")]
+    fn test_view_with_invalid_field_attribute() {
+        let input = parse_quote! {
+            struct TestView<C> {
+                register: RegisterView<C, usize>,
+                #[view(context = "CustomContext")]
+                map: MapView<C, usize, usize>,
+            }
+        };
+        generate_hash_view_code(input);
+    }
+
+    #[test]
+    #[should_panic(expected = "should be named")]
+    fn test_view_with_unnamed_fields() {
+        let input = parse_quote! {
+            struct TestView<C>(RegisterView<C, usize>);
+        };
+        generate_clonable_view_code(input);
+    }
+
     #[derive(Clone)]
     pub struct SpecificContextInfo {
         name: String,
@@ -0,0 +1,124 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    fmt::Debug,
+    time::{Duration, Instant},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+#[cfg(with_dynamodb)]
+use linera_views::dynamo_db::DynamoDbStore;
+#[cfg(with_rocksdb)]
+use linera_views::rocks_db::RocksDbStore;
+#[cfg(with_scylladb)]
+use linera_views::scylla_db::ScyllaDbStore;
+use linera_views::{
+    context::ViewContext,
+    map_view::MapView,
+    memory::MemoryStore,
+    store::{KeyValueStore, TestKeyValueStore as _},
+    views::{CryptoHashRootView, RootView, View},
+};
+use tokio::runtime::Runtime;
+
+/// The number of entries in the map
+const N_ENTRIES: u64 = 1000;
+
+#[derive(CryptoHashRootView)]
+pub struct MapStateView<C> {
+    pub map: MapView<C, u64, u64>,
+}
+
+/// Creates a saved map with `N_ENTRIES` entries and returns its context.
+async fn prepare_map<S: KeyValueStore + Clone + Sync + 'static>(store: S) -> ViewContext<(), S>
+where
+    S::Error: Debug + Send + Sync + 'static,
+{
+    let context = ViewContext::<(), S>::create_root_context(store, ())
+        .await
+        .unwrap();
+    let mut view = MapStateView::load(context.clone()).await.unwrap();
+    for index in 0..N_ENTRIES {
+        view.map.insert(&index, index).unwrap();
+    }
+    view.save().await.unwrap();
+    context
+}
+
+/// Reads half of the existing entries and as many missing ones, one key at a time
+/// if `batched` is false and with a single `multi_get` otherwise.
+pub async fn performance_map_view_reads<S: KeyValueStore + Clone + Sync + 'static>(
+    store: S,
+    iterations: u64,
+    batched: bool,
+) -> Duration
+where
+    S::Error: Debug + Send + Sync + 'static,
+{
+    let context = prepare_map(store).await;
+    let indices = (N_ENTRIES / 2..N_ENTRIES + N_ENTRIES / 2).collect::<Vec<_>>();
+    let mut total_time = Duration::ZERO;
+    for _ in 0..iterations {
+        let view = MapStateView::load(context.clone()).await.unwrap();
+        let measurement = Instant::now();
+        if batched {
+            black_box(view.map.multi_get(&indices).await.unwrap());
+        } else {
+            for index in &indices {
+                black_box(view.map.get(index).await.unwrap());
+            }
+        }
+        total_time += measurement.elapsed();
+    }
+
+    total_time
+}
+
+fn bench_map_view_reads(criterion: &mut Criterion) {
+    for batched in [false, true] {
+        let suffix = if batched { "multi_get" } else { "get" };
+
+        criterion.bench_function(&format!("memory_map_view_{suffix}"), |bencher| {
+            bencher
+                .to_async(Runtime::new().expect("Failed to create Tokio runtime"))
+                .iter_custom(|iterations| async move {
+                    let store = MemoryStore::new_test_store().await.unwrap();
+                    performance_map_view_reads(store, iterations, batched).await
+                })
+        });
+
+        #[cfg(with_rocksdb)]
+        criterion.bench_function(&format!("rocksdb_map_view_{suffix}"), |bencher| {
+            bencher
+                .to_async(Runtime::new().expect("Failed to create Tokio runtime"))
+                .iter_custom(|iterations| async move {
+                    let store = RocksDbStore::new_test_store().await.unwrap();
+                    performance_map_view_reads(store, iterations, batched).await
+                })
+        });
+
+        #[cfg(with_dynamodb)]
+        criterion.bench_function(&format!("dynamodb_map_view_{suffix}"), |bencher| {
+            bencher
+                .to_async(Runtime::new().expect("Failed to create Tokio runtime"))
+                .iter_custom(|iterations| async move {
+                    let store = DynamoDbStore::new_test_store().await.unwrap();
+                    performance_map_view_reads(store, iterations, batched).await
+                })
+        });
+
+        #[cfg(with_scylladb)]
+        criterion.bench_function(&format!("scylladb_map_view_{suffix}"), |bencher| {
+            bencher
+                .to_async(Runtime::new().expect("Failed to create Tokio runtime"))
+                .iter_custom(|iterations| async move {
+                    let store = ScyllaDbStore::new_test_store().await.unwrap();
+                    performance_map_view_reads(store, iterations, batched).await
+                })
+        });
+    }
+}
+
+criterion_group!(benches, bench_map_view_reads);
+criterion_main!(benches);
@@ -385,6 +385,14 @@ where
     E2: KeyValueStoreError,
 {
     const BACKEND: &'static str = "dual_store";
+
+    fn exceeded_capacity(&self) -> Option<usize> {
+        match self {
+            DualStoreError::First(error) => error.exceeded_capacity(),
+            DualStoreError::Second(error) => error.exceeded_capacity(),
+            DualStoreError::BcsError(_) => None,
+        }
+    }
 }
 
 /// A set of keys returned by [`DualStore::find_keys_by_prefix`].
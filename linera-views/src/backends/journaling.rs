@@ -413,3 +413,319 @@ impl<K> JournalingKeyValueStore<K> {
         Self { store }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use async_trait::async_trait;
+    use thiserror::Error;
+
+    use super::{
+        get_journaling_key, DirectWritableKeyValueStore, JournalConsistencyError,
+        JournalingKeyValueStore, KeyTag, JOURNAL_TAG,
+    };
+    use crate::{
+        batch::{Batch, SimpleUnorderedBatch},
+        memory::{
+            MemoryStore, MemoryStoreConfig, MemoryStoreError, TEST_MEMORY_MAX_STREAM_QUERIES,
+        },
+        random::generate_test_namespace,
+        store::{
+            AdminKeyValueStore, KeyValueStoreError, ReadableKeyValueStore, WithError,
+            WritableKeyValueStore,
+        },
+    };
+
+    /// The error type of [`FaultyTestStore`].
+    #[derive(Error, Debug)]
+    enum FaultyTestStoreError {
+        /// Memory store error
+        #[error(transparent)]
+        MemoryStoreError(#[from] MemoryStoreError),
+
+        /// Serialization error with BCS.
+        #[error(transparent)]
+        BcsError(#[from] bcs::Error),
+
+        /// The journal is not consistent
+        #[error(transparent)]
+        JournalConsistencyError(#[from] JournalConsistencyError),
+
+        /// A failure injected by the test
+        #[error("injected write failure")]
+        InjectedFailure,
+    }
+
+    impl KeyValueStoreError for FaultyTestStoreError {
+        const BACKEND: &'static str = "faulty_test";
+    }
+
+    /// A memory store with small batch limits whose writes start failing once a given
+    /// number of batches have been written. This simulates a crash in the middle of a
+    /// sequence of writes.
+    #[derive(Clone)]
+    struct FaultyTestStore {
+        store: MemoryStore,
+        remaining_writes: Arc<AtomicUsize>,
+    }
+
+    impl FaultyTestStore {
+        fn new() -> Self {
+            let namespace = generate_test_namespace();
+            let store =
+                MemoryStore::new_for_testing(TEST_MEMORY_MAX_STREAM_QUERIES, &namespace, &[])
+                    .unwrap();
+            let remaining_writes = Arc::new(AtomicUsize::new(usize::MAX));
+            Self {
+                store,
+                remaining_writes,
+            }
+        }
+
+        /// Makes every write fail after `count` more successful writes.
+        fn fail_after(&self, count: usize) {
+            self.remaining_writes.store(count, Ordering::SeqCst);
+        }
+
+        /// Lets writes succeed again, as after a restart.
+        fn restart(&self) {
+            self.remaining_writes.store(usize::MAX, Ordering::SeqCst);
+        }
+    }
+
+    impl WithError for FaultyTestStore {
+        type Error = FaultyTestStoreError;
+    }
+
+    impl ReadableKeyValueStore for FaultyTestStore {
+        const MAX_KEY_SIZE: usize = usize::MAX;
+        type Keys = Vec<Vec<u8>>;
+        type KeyValues = Vec<(Vec<u8>, Vec<u8>)>;
+
+        fn max_stream_queries(&self) -> usize {
+            TEST_MEMORY_MAX_STREAM_QUERIES
+        }
+
+        async fn read_value_bytes(
+            &self,
+            key: &[u8],
+        ) -> Result<Option<Vec<u8>>, FaultyTestStoreError> {
+            Ok(self.store.read_value_bytes(key).await?)
+        }
+
+        async fn contains_key(&self, key: &[u8]) -> Result<bool, FaultyTestStoreError> {
+            Ok(self.store.contains_key(key).await?)
+        }
+
+        async fn contains_keys(
+            &self,
+            keys: Vec<Vec<u8>>,
+        ) -> Result<Vec<bool>, FaultyTestStoreError> {
+            Ok(self.store.contains_keys(keys).await?)
+        }
+
+        async fn read_multi_values_bytes(
+            &self,
+            keys: Vec<Vec<u8>>,
+        ) -> Result<Vec<Option<Vec<u8>>>, FaultyTestStoreError> {
+            Ok(self.store.read_multi_values_bytes(keys).await?)
+        }
+
+        async fn find_keys_by_prefix(
+            &self,
+            key_prefix: &[u8],
+        ) -> Result<Self::Keys, FaultyTestStoreError> {
+            Ok(self.store.find_keys_by_prefix(key_prefix).await?)
+        }
+
+        async fn find_key_values_by_prefix(
+            &self,
+            key_prefix: &[u8],
+        ) -> Result<Self::KeyValues, FaultyTestStoreError> {
+            Ok(self.store.find_key_values_by_prefix(key_prefix).await?)
+        }
+    }
+
+    #[async_trait]
+    impl DirectWritableKeyValueStore for FaultyTestStore {
+        // Artificially low limits so that modest batches take the journaling path.
+        const MAX_BATCH_SIZE: usize = 5;
+        const MAX_BATCH_TOTAL_SIZE: usize = 1000;
+        const MAX_VALUE_SIZE: usize = 200;
+
+        type Batch = SimpleUnorderedBatch;
+
+        async fn write_batch(&self, batch: Self::Batch) -> Result<(), FaultyTestStoreError> {
+            assert!(batch.deletions.len() + batch.insertions.len() <= Self::MAX_BATCH_SIZE);
+            self.remaining_writes
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                    count.checked_sub(1)
+                })
+                .map_err(|_| FaultyTestStoreError::InjectedFailure)?;
+            let mut inner_batch = Batch::new();
+            for key in batch.deletions {
+                inner_batch.delete_key(key);
+            }
+            for (key, value) in batch.insertions {
+                inner_batch.put_key_value_bytes(key, value);
+            }
+            Ok(self.store.write_batch(inner_batch).await?)
+        }
+    }
+
+    impl AdminKeyValueStore for FaultyTestStore {
+        type Config = MemoryStoreConfig;
+
+        fn get_name() -> String {
+            "faulty test".to_string()
+        }
+
+        async fn connect(
+            config: &Self::Config,
+            namespace: &str,
+            root_key: &[u8],
+        ) -> Result<Self, FaultyTestStoreError> {
+            let store = MemoryStore::connect(config, namespace, root_key).await?;
+            let remaining_writes = Arc::new(AtomicUsize::new(usize::MAX));
+            Ok(Self {
+                store,
+                remaining_writes,
+            })
+        }
+
+        fn clone_with_root_key(&self, root_key: &[u8]) -> Result<Self, FaultyTestStoreError> {
+            let store = self.store.clone_with_root_key(root_key)?;
+            let remaining_writes = self.remaining_writes.clone();
+            Ok(Self {
+                store,
+                remaining_writes,
+            })
+        }
+
+        async fn list_all(config: &Self::Config) -> Result<Vec<String>, FaultyTestStoreError> {
+            Ok(MemoryStore::list_all(config).await?)
+        }
+
+        async fn list_root_keys(
+            config: &Self::Config,
+            namespace: &str,
+        ) -> Result<Vec<Vec<u8>>, FaultyTestStoreError> {
+            Ok(MemoryStore::list_root_keys(config, namespace).await?)
+        }
+
+        async fn exists(
+            config: &Self::Config,
+            namespace: &str,
+        ) -> Result<bool, FaultyTestStoreError> {
+            Ok(MemoryStore::exists(config, namespace).await?)
+        }
+
+        async fn create(
+            config: &Self::Config,
+            namespace: &str,
+        ) -> Result<(), FaultyTestStoreError> {
+            Ok(MemoryStore::create(config, namespace).await?)
+        }
+
+        async fn delete(
+            config: &Self::Config,
+            namespace: &str,
+        ) -> Result<(), FaultyTestStoreError> {
+            Ok(MemoryStore::delete(config, namespace).await?)
+        }
+    }
+
+    type KeyValues = Vec<(Vec<u8>, Vec<u8>)>;
+
+    async fn read_state(store: &JournalingKeyValueStore<FaultyTestStore>) -> KeyValues {
+        let mut key_values = store.find_key_values_by_prefix(&[1]).await.unwrap();
+        key_values.extend(store.find_key_values_by_prefix(&[2]).await.unwrap());
+        key_values
+    }
+
+    async fn has_journal_header(store: &JournalingKeyValueStore<FaultyTestStore>) -> bool {
+        let key = get_journaling_key(KeyTag::Journal as u8, 0).unwrap();
+        store.contains_key(&key).await.unwrap()
+    }
+
+    /// Writes an initial state that fits in a single batch and returns it.
+    async fn write_initial_state(store: &JournalingKeyValueStore<FaultyTestStore>) -> KeyValues {
+        let mut batch = Batch::new();
+        for i in 0..4 {
+            batch.put_key_value_bytes(vec![1, i], vec![0]);
+        }
+        store.write_batch(batch).await.unwrap();
+        read_state(store).await
+    }
+
+    /// A batch that is too large for `FaultyTestStore` and thus goes through the journal.
+    fn oversized_batch() -> Batch {
+        let mut batch = Batch::new();
+        for i in 0..2 {
+            batch.delete_key(vec![1, i]);
+        }
+        for i in 0..20 {
+            batch.put_key_value_bytes(vec![2, i], vec![i; 10]);
+        }
+        batch
+    }
+
+    #[tokio::test]
+    async fn test_journaling_oversized_batch() {
+        let store = JournalingKeyValueStore::new(FaultyTestStore::new());
+        write_initial_state(&store).await;
+        store.write_batch(oversized_batch()).await.unwrap();
+        let mut expected = Vec::new();
+        for i in 2..4 {
+            expected.push((vec![1, i], vec![0]));
+        }
+        for i in 0..20 {
+            expected.push((vec![2, i], vec![i; 10]));
+        }
+        assert_eq!(read_state(&store).await, expected);
+        let journal_keys = store.find_keys_by_prefix(&[JOURNAL_TAG]).await.unwrap();
+        assert!(journal_keys.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_journaling_recovery_after_failed_write() {
+        let reference = JournalingKeyValueStore::new(FaultyTestStore::new());
+        write_initial_state(&reference).await;
+        reference.write_batch(oversized_batch()).await.unwrap();
+        let final_state = read_state(&reference).await;
+
+        let mut num_partial_states = 0;
+        let mut num_recovered_writes = 0;
+        for failure_point in 0.. {
+            let store = JournalingKeyValueStore::new(FaultyTestStore::new());
+            let initial_state = write_initial_state(&store).await;
+            store.store.fail_after(failure_point);
+            if store.write_batch(oversized_batch()).await.is_ok() {
+                assert_eq!(read_state(&store).await, final_state);
+                break;
+            }
+            // The crash may leave the store in an intermediate state.
+            let state = read_state(&store).await;
+            if state != initial_state && state != final_state {
+                num_partial_states += 1;
+            }
+            // Restarting and clearing the journal either discards the batch entirely or
+            // applies it entirely.
+            store.store.restart();
+            store.clear_journal().await.unwrap();
+            let state = read_state(&store).await;
+            if state == final_state {
+                num_recovered_writes += 1;
+            } else {
+                assert_eq!(state, initial_state);
+            }
+            assert!(!has_journal_header(&store).await);
+        }
+        assert!(num_partial_states > 0);
+        assert!(num_recovered_writes > 0);
+    }
+}
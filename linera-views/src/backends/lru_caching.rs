@@ -35,6 +35,15 @@ static NUM_CACHE_FAULT: LazyLock<IntCounterVec> =
 static NUM_CACHE_SUCCESS: LazyLock<IntCounterVec> =
     LazyLock::new(|| register_int_counter_vec("num_cache_success", "Number of cache success", &[]));
 
+/// The number of cache hits and misses of an [`LruCachingStore`] since its creation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStatistics {
+    /// The number of values that were found in the cache.
+    pub hits: u64,
+    /// The number of values that had to be read from the inner store.
+    pub misses: u64,
+}
+
 /// The `LruPrefixCache` stores the data for simple `read_values` queries
 /// It is inspired by the crate `lru-cache`.
 ///
@@ -48,6 +57,7 @@ struct LruPrefixCache {
     map: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
     queue: LinkedHashMap<Vec<u8>, (), RandomState>,
     max_cache_size: usize,
+    statistics: CacheStatistics,
 }
 
 impl<'a> LruPrefixCache {
@@ -57,6 +67,7 @@ impl<'a> LruPrefixCache {
             map: BTreeMap::new(),
             queue: LinkedHashMap::new(),
             max_cache_size,
+            statistics: CacheStatistics::default(),
         }
     }
 
@@ -129,12 +140,15 @@ where
         };
         // First inquiring in the read_value_bytes LRU
         {
-            let lru_read_values_container = lru_read_values.lock().unwrap();
+            let mut lru_read_values_container = lru_read_values.lock().unwrap();
             if let Some(value) = lru_read_values_container.query(key) {
+                let value = value.clone();
                 #[cfg(with_metrics)]
                 NUM_CACHE_SUCCESS.with_label_values(&[]).inc();
-                return Ok(value.clone());
+                lru_read_values_container.statistics.hits += 1;
+                return Ok(value);
             }
+            lru_read_values_container.statistics.misses += 1;
         }
         #[cfg(with_metrics)]
         NUM_CACHE_FAULT.with_label_values(&[]).inc();
@@ -194,15 +208,17 @@ where
         let mut cache_miss_indices = Vec::new();
         let mut miss_keys = Vec::new();
         {
-            let lru_read_values_container = lru_read_values.lock().unwrap();
+            let mut lru_read_values_container = lru_read_values.lock().unwrap();
             for (i, key) in keys.into_iter().enumerate() {
                 if let Some(value) = lru_read_values_container.query(&key) {
+                    result.push(value.clone());
                     #[cfg(with_metrics)]
                     NUM_CACHE_SUCCESS.with_label_values(&[]).inc();
-                    result.push(value.clone());
+                    lru_read_values_container.statistics.hits += 1;
                 } else {
                     #[cfg(with_metrics)]
                     NUM_CACHE_FAULT.with_label_values(&[]).inc();
+                    lru_read_values_container.statistics.misses += 1;
                     result.push(None);
                     cache_miss_indices.push(i);
                     miss_keys.push(key);
@@ -379,6 +395,18 @@ impl<K> LruCachingStore<K> {
             }
         }
     }
+
+    /// Gets the number of cache hits and misses of the value reads. This is always zero
+    /// if caching is disabled.
+    pub fn cache_statistics(&self) -> CacheStatistics {
+        match &self.lru_read_values {
+            None => CacheStatistics::default(),
+            Some(lru_read_values) => {
+                let lru_read_values = lru_read_values.lock().unwrap();
+                lru_read_values.statistics
+            }
+        }
+    }
 }
 
 /// A memory store with caching.
@@ -5,7 +5,11 @@
 
 use std::{
     collections::BTreeMap,
-    sync::{Arc, LazyLock, Mutex, RwLock},
+    ops::Deref,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, LazyLock, Mutex, RwLock,
+    },
 };
 
 use thiserror::Error;
@@ -46,7 +50,111 @@ pub const TEST_MEMORY_MAX_STREAM_QUERIES: usize = 10;
 
 /// The data is serialized in memory just like for RocksDB / DynamoDB
 /// The analog of the database is the BTreeMap
-type MemoryStoreMap = BTreeMap<Vec<u8>, Vec<u8>>;
+#[derive(Default)]
+struct MemoryStoreMap {
+    /// The keys and values.
+    entries: BTreeMap<Vec<u8>, Vec<u8>>,
+    /// The total size in bytes of the keys and values.
+    total_size: usize,
+}
+
+/// The previous values of the keys modified by a batch, in order.
+type UndoLog = Vec<(Vec<u8>, Option<Vec<u8>>)>;
+
+impl Deref for MemoryStoreMap {
+    type Target = BTreeMap<Vec<u8>, Vec<u8>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.entries
+    }
+}
+
+impl MemoryStoreMap {
+    /// Inserts a key and a value, and returns the previous value.
+    fn insert(&mut self, key: Vec<u8>, value: Vec<u8>) -> Option<Vec<u8>> {
+        self.total_size += key.len() + value.len();
+        let old_value = self.entries.insert(key, value)?;
+        self.total_size -= old_value.len();
+        Some(old_value)
+    }
+
+    /// Removes a key, and returns its value.
+    fn remove(&mut self, key: Vec<u8>) -> Option<(Vec<u8>, Vec<u8>)> {
+        let (key, value) = self.entries.remove_entry(&key)?;
+        self.total_size -= key.len() + value.len();
+        Some((key, value))
+    }
+
+    /// Applies a write operation. The previous values of the modified keys are recorded
+    /// in `undo_log`, if any.
+    fn apply(&mut self, operation: WriteOperation, undo_log: Option<&mut UndoLog>) {
+        let removed_entries = match operation {
+            WriteOperation::Put { key, value } => {
+                match undo_log {
+                    Some(undo_log) => {
+                        let old_value = self.insert(key.clone(), value);
+                        undo_log.push((key, old_value));
+                    }
+                    None => {
+                        self.insert(key, value);
+                    }
+                }
+                return;
+            }
+            WriteOperation::Delete { key } => self.remove(key).into_iter().collect(),
+            WriteOperation::DeletePrefix { key_prefix } => {
+                let key_list = self
+                    .range(get_interval(key_prefix))
+                    .map(|x| x.0.to_vec())
+                    .collect::<Vec<_>>();
+                key_list
+                    .into_iter()
+                    .filter_map(|key| self.remove(key))
+                    .collect::<Vec<_>>()
+            }
+        };
+        if let Some(undo_log) = undo_log {
+            undo_log.extend(
+                removed_entries
+                    .into_iter()
+                    .map(|(key, value)| (key, Some(value))),
+            );
+        }
+    }
+
+    /// Applies the operations of a batch. If the total size would exceed `max_total_size`,
+    /// the batch is undone and an error is returned.
+    fn apply_batch(
+        &mut self,
+        operations: Vec<WriteOperation>,
+        max_total_size: Option<usize>,
+    ) -> Result<(), MemoryStoreError> {
+        let Some(max_total_size) = max_total_size else {
+            for operation in operations {
+                self.apply(operation, None);
+            }
+            return Ok(());
+        };
+        let mut undo_log = UndoLog::new();
+        for operation in operations {
+            self.apply(operation, Some(&mut undo_log));
+        }
+        if self.total_size > max_total_size {
+            for (key, old_value) in undo_log.into_iter().rev() {
+                match old_value {
+                    Some(value) => {
+                        self.insert(key, value);
+                    }
+                    None => {
+                        self.remove(key);
+                    }
+                }
+            }
+            return Err(MemoryStoreError::StoreCapacityExceeded(max_total_size));
+        }
+        Ok(())
+    }
+}
 
 /// The container for the `MemoryStoreMap`s by namespace and then root key
 #[derive(Default)]
@@ -67,14 +175,16 @@ impl MemoryStores {
             return Err(MemoryStoreError::NamespaceNotFound);
         };
         let store = stores.entry(root_key.to_vec()).or_insert_with(|| {
-            let map = MemoryStoreMap::new();
+            let map = MemoryStoreMap::default();
             Arc::new(RwLock::new(map))
         });
         let map = store.clone();
+        let max_total_size = Arc::new(AtomicUsize::new(usize::MAX));
         let namespace = namespace.to_string();
         let root_key = root_key.to_vec();
         Ok(MemoryStore {
             map,
+            max_total_size,
             max_stream_queries,
             namespace,
             root_key,
@@ -115,6 +225,8 @@ static MEMORY_STORES: LazyLock<Mutex<MemoryStores>> =
 pub struct MemoryStore {
     /// The map used for storing the data.
     map: Arc<RwLock<MemoryStoreMap>>,
+    /// The maximal total size of the keys and values, `usize::MAX` if unbounded.
+    max_total_size: Arc<AtomicUsize>,
     /// The maximum number of queries used for the stream.
     max_stream_queries: usize,
     /// The namespace of the store
@@ -234,26 +346,7 @@ impl WritableKeyValueStore for MemoryStore {
             .map
             .write()
             .expect("MemoryStore lock should not be poisoned");
-        for ent in batch.operations {
-            match ent {
-                WriteOperation::Put { key, value } => {
-                    map.insert(key, value);
-                }
-                WriteOperation::Delete { key } => {
-                    map.remove(&key);
-                }
-                WriteOperation::DeletePrefix { key_prefix } => {
-                    let key_list = map
-                        .range(get_interval(key_prefix))
-                        .map(|x| x.0.to_vec())
-                        .collect::<Vec<_>>();
-                    for key in key_list {
-                        map.remove(&key);
-                    }
-                }
-            }
-        }
-        Ok(())
+        map.apply_batch(batch.operations, self.max_total_size())
     }
 
     async fn clear_journal(&self) -> Result<(), MemoryStoreError> {
@@ -306,6 +399,29 @@ impl MemoryStore {
         let kill_on_drop = true;
         MemoryStore::sync_maybe_create_and_connect(&config, namespace, root_key, kill_on_drop)
     }
+
+    /// Gets the total size in bytes of the keys and values currently stored.
+    pub fn total_size(&self) -> usize {
+        let map = self
+            .map
+            .read()
+            .expect("MemoryStore lock should not be poisoned");
+        map.total_size
+    }
+
+    /// Gets the maximal total size in bytes of the keys and values, if any.
+    pub fn max_total_size(&self) -> Option<usize> {
+        let max_total_size = self.max_total_size.load(Ordering::Acquire);
+        (max_total_size != usize::MAX).then_some(max_total_size)
+    }
+
+    /// Sets the maximal total size in bytes of the keys and values. Batches that would
+    /// exceed it fail with [`MemoryStoreError::StoreCapacityExceeded`]. This is shared by
+    /// the clones of this store.
+    pub fn set_max_total_size(&self, max_total_size: Option<usize>) {
+        let max_total_size = max_total_size.unwrap_or(usize::MAX);
+        self.max_total_size.store(max_total_size, Ordering::Release);
+    }
 }
 
 impl AdminKeyValueStore for MemoryStore {
@@ -417,8 +533,19 @@ pub enum MemoryStoreError {
     /// The namespace does not exist
     #[error("The namespace does not exist")]
     NamespaceNotFound,
+
+    /// The batch would make the store exceed its capacity
+    #[error("The batch would make the MemoryStore exceed its capacity of {0} bytes")]
+    StoreCapacityExceeded(usize),
 }
 
 impl KeyValueStoreError for MemoryStoreError {
     const BACKEND: &'static str = "memory";
+
+    fn exceeded_capacity(&self) -> Option<usize> {
+        match self {
+            MemoryStoreError::StoreCapacityExceeded(capacity) => Some(*capacity),
+            _ => None,
+        }
+    }
 }
@@ -47,6 +47,10 @@ const MAX_VALUE_SIZE: usize = 3221225072;
 // 8388608 and so for offset reason we decrease by 400
 const MAX_KEY_SIZE: usize = 8388208;
 
+// The number of bits per key of the bloom filters. 10 bits give a false positive rate
+// of about 1%.
+const BLOOM_FILTER_BITS_PER_KEY: f64 = 10.0;
+
 /// The RocksDB client that we use.
 type DB = rocksdb::DBWithThreadMode<rocksdb::MultiThreaded>;
 
@@ -288,6 +292,11 @@ impl RocksDbStoreInternal {
         }
         let mut options = rocksdb::Options::default();
         options.create_if_missing(true);
+        // Most reads are point lookups of keys that may be absent: a full bloom filter
+        // avoids reading data blocks for them.
+        let mut block_options = rocksdb::BlockBasedOptions::default();
+        block_options.set_bloom_filter(BLOOM_FILTER_BITS_PER_KEY, false);
+        options.set_block_based_table_factory(&block_options);
         let db = DB::open(&options, path_buf)?;
         let executor = RocksDbStoreExecutor {
             db: Arc::new(db),
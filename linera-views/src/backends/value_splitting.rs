@@ -49,6 +49,13 @@ impl<E: KeyValueStoreError> From<bcs::Error> for ValueSplittingError<E> {
 
 impl<E: KeyValueStoreError + 'static> KeyValueStoreError for ValueSplittingError<E> {
     const BACKEND: &'static str = "value splitting";
+
+    fn exceeded_capacity(&self) -> Option<usize> {
+        match self {
+            ValueSplittingError::InnerStoreError(error) => error.exceeded_capacity(),
+            _ => None,
+        }
+    }
 }
 
 /// A key-value store with no size limit for values.
@@ -19,7 +19,7 @@ use std::{
     collections::{BTreeMap, BTreeSet, HashSet},
     fmt::Debug,
     iter::Peekable,
-    ops::Bound,
+    ops::{AddAssign, Bound},
     vec::IntoIter,
 };
 
@@ -66,6 +66,26 @@ pub struct Batch {
     pub operations: Vec<WriteOperation>,
 }
 
+/// The number of operations and bytes in a [`Batch`]. Statistics can be added up to
+/// aggregate several batches.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BatchStatistics {
+    /// The number of insertions.
+    pub puts: usize,
+    /// The number of deletions of keys and of key prefixes.
+    pub deletes: usize,
+    /// The total size of the keys, key prefixes and values.
+    pub bytes: usize,
+}
+
+impl AddAssign for BatchStatistics {
+    fn add_assign(&mut self, other: Self) {
+        self.puts += other.puts;
+        self.deletes += other.deletes;
+        self.bytes += other.bytes;
+    }
+}
+
 /// A batch of deletions and insertions that operate on disjoint keys, thus can be
 /// executed in any order.
 #[derive(Default, Serialize, Deserialize)]
@@ -210,6 +230,44 @@ impl Batch {
         self.operations.len()
     }
 
+    /// Counts the insertions, the deletions and the bytes of the batch.
+    /// ```rust
+    /// # use linera_views::batch::{Batch, BatchStatistics};
+    /// let mut batch = Batch::new();
+    /// batch.put_key_value_bytes(vec![0, 1], vec![2, 3, 4]);
+    /// batch.delete_key(vec![5]);
+    /// batch.delete_key_prefix(vec![6]);
+    /// let statistics = batch.statistics();
+    /// assert_eq!(
+    ///     statistics,
+    ///     BatchStatistics {
+    ///         puts: 1,
+    ///         deletes: 2,
+    ///         bytes: 7
+    ///     }
+    /// );
+    /// ```
+    pub fn statistics(&self) -> BatchStatistics {
+        let mut statistics = BatchStatistics::default();
+        for operation in &self.operations {
+            match operation {
+                WriteOperation::Delete { key } => {
+                    statistics.deletes += 1;
+                    statistics.bytes += key.len();
+                }
+                WriteOperation::DeletePrefix { key_prefix } => {
+                    statistics.deletes += 1;
+                    statistics.bytes += key_prefix.len();
+                }
+                WriteOperation::Put { key, value } => {
+                    statistics.puts += 1;
+                    statistics.bytes += key.len() + value.len();
+                }
+            }
+        }
+        statistics
+    }
+
     /// Builds a batch from a builder function.
     pub async fn build<F>(builder: F) -> Result<Self, ViewError>
     where
@@ -11,6 +11,7 @@ use std::{
     },
 };
 
+use linera_base::data_types::{BlockHeight, Timestamp};
 use serde::de::DeserializeOwned;
 
 use crate::views::ViewError;
@@ -305,6 +306,41 @@ impl CustomSerialize for u128 {
     }
 }
 
+impl CustomSerialize for u64 {
+    fn to_custom_bytes(&self) -> Result<Vec<u8>, ViewError> {
+        let mut bytes = bcs::to_bytes(&self)?;
+        bytes.reverse();
+        Ok(bytes)
+    }
+
+    fn from_custom_bytes(bytes: &[u8]) -> Result<Self, ViewError> {
+        let mut bytes = bytes.to_vec();
+        bytes.reverse();
+        let value = bcs::from_bytes(&bytes)?;
+        Ok(value)
+    }
+}
+
+impl CustomSerialize for BlockHeight {
+    fn to_custom_bytes(&self) -> Result<Vec<u8>, ViewError> {
+        self.0.to_custom_bytes()
+    }
+
+    fn from_custom_bytes(bytes: &[u8]) -> Result<Self, ViewError> {
+        Ok(BlockHeight(u64::from_custom_bytes(bytes)?))
+    }
+}
+
+impl CustomSerialize for Timestamp {
+    fn to_custom_bytes(&self) -> Result<Vec<u8>, ViewError> {
+        self.micros().to_custom_bytes()
+    }
+
+    fn from_custom_bytes(bytes: &[u8]) -> Result<Self, ViewError> {
+        Ok(Timestamp::from(u64::from_custom_bytes(bytes)?))
+    }
+}
+
 /// This computes the offset of the BCS serialization of a vector.
 /// The formula that should be satisfied is
 /// serialized_size(vec![v_1, ...., v_n]) = get_uleb128_size(n)
@@ -321,37 +357,44 @@ pub(crate) const fn get_uleb128_size(len: usize) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::BTreeSet;
+    use std::{collections::BTreeSet, fmt::Debug};
 
+    use linera_base::data_types::{BlockHeight, Timestamp};
     use linera_views::common::CustomSerialize;
     use rand::Rng;
 
-    #[test]
-    fn test_ordering_serialization() {
-        let mut rng = crate::random::make_deterministic_rng();
-        let n = 1000;
-        let mut set = BTreeSet::new();
-        for _ in 0..n {
-            let val = rng.gen::<u128>();
-            set.insert(val);
-        }
-        let mut vec = Vec::new();
-        for val in set {
-            vec.push(val);
-        }
+    fn check_ordering_serialization<T>(values: impl IntoIterator<Item = T>)
+    where
+        T: CustomSerialize + Debug + Ord,
+    {
+        let vec = values.into_iter().collect::<BTreeSet<_>>();
+        let vec = vec.into_iter().collect::<Vec<_>>();
         for i in 1..vec.len() {
-            let val1 = vec[i - 1];
-            let val2 = vec[i];
+            let val1 = &vec[i - 1];
+            let val2 = &vec[i];
             assert!(val1 < val2);
             let vec1 = val1.to_custom_bytes().unwrap();
             let vec2 = val2.to_custom_bytes().unwrap();
             assert!(vec1 < vec2);
-            let val_ret1 = u128::from_custom_bytes(&vec1).unwrap();
-            let val_ret2 = u128::from_custom_bytes(&vec2).unwrap();
-            assert_eq!(val1, val_ret1);
-            assert_eq!(val2, val_ret2);
+            let val_ret1 = T::from_custom_bytes(&vec1).unwrap();
+            let val_ret2 = T::from_custom_bytes(&vec2).unwrap();
+            assert_eq!(val1, &val_ret1);
+            assert_eq!(val2, &val_ret2);
         }
     }
+
+    #[test]
+    fn test_ordering_serialization() {
+        let mut rng = crate::random::make_deterministic_rng();
+        let n = 1000;
+        check_ordering_serialization((0..n).map(|_| rng.gen::<u128>()));
+        check_ordering_serialization((0..n).map(|_| rng.gen::<u64>()));
+        check_ordering_serialization((0..n).map(|_| BlockHeight(rng.gen())));
+        check_ordering_serialization((0..n).map(|_| Timestamp::from(rng.gen::<u64>())));
+        // Small values and values differing only in their high bytes are the ones
+        // that the default serialization orders wrongly.
+        check_ordering_serialization((0..300u64).chain((0..256u64).map(|i| i << 56)));
+    }
 }
 
 #[test]
@@ -303,6 +303,17 @@ impl<E> MemoryContext<E> {
             extra,
         }
     }
+
+    /// Gets the total size in bytes of the keys and values in the underlying memory store.
+    pub fn total_size(&self) -> usize {
+        self.store.total_size()
+    }
+
+    /// Sets the maximal total size in bytes of the underlying memory store. See
+    /// [`MemoryStore::set_max_total_size`].
+    pub fn set_max_total_size(&self, max_total_size: Option<usize>) {
+        self.store.set_max_total_size(max_total_size)
+    }
 }
 
 impl DeletePrefixExpander for MemoryContext<()> {
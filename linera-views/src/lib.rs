@@ -54,6 +54,11 @@ The following views implement the `View` trait:
 * `ViewContainer<C>` implements a `KeyValueStore` and is used internally.
 
 The `LogView` can be seen as an analog of `VecDeque` while `MapView` is an analog of `BTreeMap`.
+
+Views can be grouped in a struct deriving `View`, `RootView` or another derive macro
+of the crate. A sub-view that is only present on some instances is wrapped in an
+`OptionalView`, which stores and hashes whether it is present. A field marked with
+`#[view(skip)]` is not stored and is set to its default value when the view is loaded.
 */
 
 #![deny(missing_docs)]
@@ -105,7 +110,7 @@ pub use backends::scylla_db;
 pub use backends::{journaling, lru_caching, memory, value_splitting};
 pub use views::{
     bucket_queue_view, collection_view, hashable_wrapper, key_value_store_view, log_view, map_view,
-    queue_view, reentrant_collection_view, register_view, set_view,
+    optional_view, queue_view, reentrant_collection_view, register_view, set_view, versioned_view,
 };
 /// Re-exports used by the derive macros of this library.
 #[doc(hidden)]
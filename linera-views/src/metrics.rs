@@ -1,12 +1,14 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::LazyLock;
+use std::{sync::LazyLock, time::Instant};
 
 // Re-export for macros.
 #[doc(hidden)]
 pub use linera_base::prometheus_util::{self, bucket_latencies};
-use prometheus::IntCounterVec;
+use prometheus::{HistogramVec, IntCounterVec};
+
+use crate::batch::BatchStatistics;
 
 /// Increments the metrics counter with the given name, with the struct and base key as labels.
 pub fn increment_counter(counter: &LazyLock<IntCounterVec>, struct_name: &str, base_key: &[u8]) {
@@ -44,3 +46,84 @@ pub static SAVE_VIEW_COUNTER: LazyLock<IntCounterVec> = LazyLock::new(|| {
         &["type", "base_key"],
     )
 });
+
+/// The metric counting the insertions and deletions written when saving root views.
+pub static SAVE_VIEW_OPERATIONS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    prometheus_util::register_int_counter_vec(
+        "save_view_operations",
+        "The number of insertions and deletions written when saving root views",
+        &["type", "operation"],
+    )
+});
+
+/// The metric counting the bytes written when saving root views.
+pub static SAVE_VIEW_BYTES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    prometheus_util::register_int_counter_vec(
+        "save_view_bytes",
+        "The number of bytes written when saving root views",
+        &["type"],
+    )
+});
+
+/// The metric tracking the latency of the flush of root views into a batch.
+pub static SAVE_VIEW_FLUSH_LATENCY: LazyLock<HistogramVec> = LazyLock::new(|| {
+    prometheus_util::register_histogram_vec(
+        "save_view_flush_latency",
+        "Latency of the flush of root views into a batch",
+        &["type"],
+        bucket_latencies(5.0),
+    )
+});
+
+/// The metric tracking the latency of the write of the batch of a root view to storage.
+pub static SAVE_VIEW_WRITE_LATENCY: LazyLock<HistogramVec> = LazyLock::new(|| {
+    prometheus_util::register_histogram_vec(
+        "save_view_write_latency",
+        "Latency of the write of the batch of a root view to storage",
+        &["type"],
+        bucket_latencies(50.0),
+    )
+});
+
+/// Measures the flush and the write of a root view being saved.
+#[doc(hidden)]
+pub struct SaveViewMeasurement {
+    struct_name: &'static str,
+    start: Instant,
+}
+
+impl SaveViewMeasurement {
+    /// Starts measuring the flush of the root view with the given type name.
+    pub fn start(struct_name: &'static str) -> Self {
+        Self {
+            struct_name,
+            start: Instant::now(),
+        }
+    }
+
+    /// Records the flush latency and the content of the resulting batch, then starts
+    /// measuring the write.
+    pub fn finish_flush(&mut self, statistics: &BatchStatistics) {
+        let labels = [self.struct_name];
+        SAVE_VIEW_FLUSH_LATENCY
+            .with_label_values(&labels)
+            .observe(self.start.elapsed().as_secs_f64() * 1000.0);
+        SAVE_VIEW_OPERATIONS
+            .with_label_values(&[self.struct_name, "put"])
+            .inc_by(statistics.puts as u64);
+        SAVE_VIEW_OPERATIONS
+            .with_label_values(&[self.struct_name, "delete"])
+            .inc_by(statistics.deletes as u64);
+        SAVE_VIEW_BYTES
+            .with_label_values(&labels)
+            .inc_by(statistics.bytes as u64);
+        self.start = Instant::now();
+    }
+
+    /// Records the write latency.
+    pub fn finish_write(self) {
+        SAVE_VIEW_WRITE_LATENCY
+            .with_label_values(&[self.struct_name])
+            .observe(self.start.elapsed().as_secs_f64() * 1000.0);
+    }
+}
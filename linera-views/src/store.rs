@@ -55,10 +55,18 @@ impl Default for CommonStoreConfig {
 pub trait KeyValueStoreError: std::error::Error + Debug + From<bcs::Error> {
     /// The name of the backend.
     const BACKEND: &'static str;
+
+    /// Returns the capacity in bytes of the store, if the error is that it was exceeded.
+    fn exceeded_capacity(&self) -> Option<usize> {
+        None
+    }
 }
 
 impl<E: KeyValueStoreError> From<E> for ViewError {
     fn from(error: E) -> Self {
+        if let Some(capacity) = error.exceeded_capacity() {
+            return Self::StoreCapacityExceeded(capacity);
+        }
         Self::StoreError {
             backend: E::BACKEND.to_string(),
             error: error.to_string(),
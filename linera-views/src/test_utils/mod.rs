@@ -3,6 +3,9 @@
 
 pub mod test_views;
 
+/// A randomized test suite for the views, to be run on any backend.
+pub mod view_suite;
+
 /// Functions for computing the performance of stores.
 #[cfg(not(target_arch = "wasm32"))]
 pub mod performance;
@@ -14,6 +17,7 @@ use std::{
 
 use rand::{seq::SliceRandom, Rng};
 
+pub use self::view_suite::run_view_test_suite;
 use crate::{
     batch::{
         Batch, WriteOperation,
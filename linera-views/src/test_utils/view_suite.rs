@@ -0,0 +1,268 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A randomized test suite for the views, which can be run on the [`Context`] of any
+//! backend.
+//!
+//! Random operations are applied both to a view containing every kind of container and
+//! to a plain model of its state. The staged and the persisted states of the view are
+//! regularly compared to the model, and the hash of the view is compared to the hash of a
+//! view built directly from the model.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+};
+
+use linera_base::crypto::CryptoHash;
+use rand::Rng;
+
+use crate::{
+    self as linera_views,
+    collection_view::CollectionView,
+    context::{create_test_memory_context, Context},
+    log_view::LogView,
+    map_view::MapView,
+    queue_view::QueueView,
+    random::make_deterministic_rng,
+    reentrant_collection_view::ReentrantCollectionView,
+    register_view::RegisterView,
+    set_view::SetView,
+    views::{CryptoHashRootView, CryptoHashView, RootView, View, ViewError},
+};
+
+/// The number of independent scenarios, each one run on a fresh context.
+const NUM_SCENARIOS: usize = 4;
+
+/// The number of rounds of each scenario. A round stages a few operations and then
+/// saves, rolls back or keeps the staged changes.
+const NUM_ROUNDS: usize = 50;
+
+/// The maximal number of operations staged in a round.
+const MAX_OPERATIONS_PER_ROUND: usize = 6;
+
+/// The number of distinct indices, kept small so that operations often hit existing
+/// entries.
+const NUM_INDICES: u8 = 8;
+
+/// A view containing every kind of container.
+#[derive(CryptoHashRootView)]
+struct SuiteView<C> {
+    register: RegisterView<C, u32>,
+    map: MapView<C, u8, u32>,
+    set: SetView<C, u8>,
+    log: LogView<C, u32>,
+    queue: QueueView<C, u32>,
+    collection: CollectionView<C, u8, RegisterView<C, u32>>,
+    reentrant_collection: ReentrantCollectionView<C, u8, RegisterView<C, u32>>,
+}
+
+/// The model of the state of a [`SuiteView`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct SuiteState {
+    register: u32,
+    map: BTreeMap<u8, u32>,
+    set: BTreeSet<u8>,
+    log: Vec<u32>,
+    queue: Vec<u32>,
+    collection: BTreeMap<u8, u32>,
+    reentrant_collection: BTreeMap<u8, u32>,
+}
+
+impl<C> SuiteView<C>
+where
+    C: Context + Send + Sync + Clone + 'static,
+{
+    /// Reads the staged state of the view.
+    async fn read(&self) -> Result<SuiteState, ViewError> {
+        let mut collection = BTreeMap::new();
+        for index in self.collection.indices().await? {
+            let entry = self.collection.try_load_entry(&index).await?;
+            let entry = entry.expect("listed entries should be present");
+            collection.insert(index, *entry.get());
+        }
+        let mut reentrant_collection = BTreeMap::new();
+        for index in self.reentrant_collection.indices().await? {
+            let entry = self.reentrant_collection.try_load_entry(&index).await?;
+            let entry = entry.expect("listed entries should be present");
+            reentrant_collection.insert(index, *entry.get());
+        }
+        Ok(SuiteState {
+            register: *self.register.get(),
+            map: self.map.index_values().await?.into_iter().collect(),
+            set: self.set.indices().await?.into_iter().collect(),
+            log: self.log.read(..).await?,
+            queue: self.queue.elements().await?,
+            collection,
+            reentrant_collection,
+        })
+    }
+
+    /// Stages a random operation on the view and applies it to the model.
+    async fn apply_random_operation<R: Rng>(
+        &mut self,
+        rng: &mut R,
+        state: &mut SuiteState,
+    ) -> Result<(), ViewError> {
+        let index = rng.gen_range(0..NUM_INDICES);
+        let value = rng.gen::<u32>();
+        match rng.gen_range(0..14) {
+            0 => {
+                self.register.set(value);
+                state.register = value;
+            }
+            1 => {
+                self.map.insert(&index, value)?;
+                state.map.insert(index, value);
+            }
+            2 => {
+                self.map.remove(&index)?;
+                state.map.remove(&index);
+            }
+            3 => {
+                self.set.insert(&index)?;
+                state.set.insert(index);
+            }
+            4 => {
+                self.set.remove(&index)?;
+                state.set.remove(&index);
+            }
+            5 => {
+                self.log.push(value);
+                state.log.push(value);
+            }
+            6 => {
+                self.queue.push_back(value);
+                state.queue.push(value);
+            }
+            7 => {
+                self.queue.delete_front();
+                if !state.queue.is_empty() {
+                    state.queue.remove(0);
+                }
+            }
+            8 => {
+                self.collection.load_entry_mut(&index).await?.set(value);
+                state.collection.insert(index, value);
+            }
+            9 => {
+                self.collection.remove_entry(&index)?;
+                state.collection.remove(&index);
+            }
+            10 => {
+                let mut entry = self.reentrant_collection.try_load_entry_mut(&index).await?;
+                entry.set(value);
+                state.reentrant_collection.insert(index, value);
+            }
+            11 => {
+                self.reentrant_collection.remove_entry(&index)?;
+                state.reentrant_collection.remove(&index);
+            }
+            12 => match index % 7 {
+                0 => {
+                    self.register.clear();
+                    state.register = 0;
+                }
+                1 => {
+                    self.map.clear();
+                    state.map.clear();
+                }
+                2 => {
+                    self.set.clear();
+                    state.set.clear();
+                }
+                3 => {
+                    self.log.clear();
+                    state.log.clear();
+                }
+                4 => {
+                    self.queue.clear();
+                    state.queue.clear();
+                }
+                5 => {
+                    self.collection.clear();
+                    state.collection.clear();
+                }
+                _ => {
+                    self.reentrant_collection.clear();
+                    state.reentrant_collection.clear();
+                }
+            },
+            _ => {
+                self.clear();
+                *state = SuiteState::default();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Computes the hash of a view holding the given state, built without any history.
+async fn model_hash(state: &SuiteState) -> Result<CryptoHash, ViewError> {
+    let mut view = SuiteView::load(create_test_memory_context()).await?;
+    view.register.set(state.register);
+    for (index, value) in &state.map {
+        view.map.insert(index, *value)?;
+    }
+    for index in &state.set {
+        view.set.insert(index)?;
+    }
+    for value in &state.log {
+        view.log.push(*value);
+    }
+    for value in &state.queue {
+        view.queue.push_back(*value);
+    }
+    for (index, value) in &state.collection {
+        view.collection.load_entry_mut(index).await?.set(*value);
+    }
+    for (index, value) in &state.reentrant_collection {
+        let mut entry = view.reentrant_collection.try_load_entry_mut(index).await?;
+        entry.set(*value);
+    }
+    view.crypto_hash().await
+}
+
+/// Runs randomized operation sequences on every kind of view, checking the staged and
+/// persisted states against a model, as well as the hashes. Each scenario runs on a new
+/// context obtained from `context_factory`, which must not share storage with the
+/// contexts it previously returned.
+pub async fn run_view_test_suite<C, F, Fut>(mut context_factory: F) -> Result<(), ViewError>
+where
+    C: Context + Send + Sync + Clone + 'static,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = C>,
+{
+    let mut rng = make_deterministic_rng();
+    for _ in 0..NUM_SCENARIOS {
+        let context = context_factory().await;
+        let mut view = SuiteView::load(context.clone()).await?;
+        let mut persisted = view.read().await?;
+        assert_eq!(persisted, SuiteState::default());
+        let mut staged = persisted.clone();
+        for _ in 0..NUM_ROUNDS {
+            let num_operations = rng.gen_range(1..=MAX_OPERATIONS_PER_ROUND);
+            for _ in 0..num_operations {
+                view.apply_random_operation(&mut rng, &mut staged).await?;
+            }
+            match rng.gen_range(0..3) {
+                0 => {
+                    view.save().await?;
+                    assert!(!view.has_pending_changes().await);
+                    persisted = staged.clone();
+                    let stored_view = SuiteView::load(context.clone()).await?;
+                    assert_eq!(stored_view.read().await?, persisted);
+                    assert_eq!(stored_view.crypto_hash().await?, view.crypto_hash().await?);
+                }
+                1 => {
+                    view.rollback();
+                    staged = persisted.clone();
+                }
+                _ => {}
+            }
+            assert_eq!(view.read().await?, staged);
+            assert_eq!(view.crypto_hash().await?, model_hash(&staged).await?);
+        }
+    }
+    Ok(())
+}
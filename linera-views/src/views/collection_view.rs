@@ -5,7 +5,7 @@
 use std::sync::LazyLock;
 use std::{
     borrow::Borrow,
-    collections::{btree_map, BTreeMap},
+    collections::{btree_map, BTreeMap, BTreeSet},
     io::Write,
     marker::PhantomData,
     mem,
@@ -66,6 +66,19 @@ impl<'a, W> std::ops::Deref for ReadGuardedView<'a, W> {
     }
 }
 
+/// A read-only subview that was taken out of a [`CollectionView`]. It is no longer part
+/// of the collection, so its staged changes can be read but are never flushed.
+#[derive(Debug)]
+pub struct DetachedView<W>(W);
+
+impl<W> std::ops::Deref for DetachedView<W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        &self.0
+    }
+}
+
 /// We need to find new base keys in order to implement the collection_view.
 /// We do this by appending a value to the base_key.
 ///
@@ -81,6 +94,42 @@ enum KeyTag {
     Subview,
 }
 
+/// Returns the keys stored by the sub-views of the collection with the given context
+/// whose index is not listed in the collection. If `repair` is set, these keys are
+/// deleted. Only the keys of the collection itself are checked, not those of nested
+/// collections.
+pub(crate) async fn find_orphan_keys<C>(
+    context: &C,
+    repair: bool,
+) -> Result<Vec<Vec<u8>>, ViewError>
+where
+    C: Context,
+    ViewError: From<C::Error>,
+{
+    let key_prefix = context.base_tag(KeyTag::Index as u8);
+    let mut indices = BTreeSet::new();
+    for index in context.find_keys_by_prefix(&key_prefix).await?.iterator() {
+        indices.insert(index?.to_vec());
+    }
+    let mut orphan_keys = Vec::new();
+    let key_prefix = context.base_tag(KeyTag::Subview as u8);
+    for key in context.find_keys_by_prefix(&key_prefix).await?.iterator() {
+        let key = key?;
+        // The keys of a sub-view start with its index.
+        if !(1..=key.len()).any(|len| indices.contains(&key[..len])) {
+            orphan_keys.push(context.base_tag_index(KeyTag::Subview as u8, key));
+        }
+    }
+    if repair && !orphan_keys.is_empty() {
+        let mut batch = Batch::new();
+        for key in &orphan_keys {
+            batch.delete_key(key.clone());
+        }
+        context.write_batch(batch).await?;
+    }
+    Ok(orphan_keys)
+}
+
 #[async_trait]
 impl<C, W> View<C> for ByteCollectionView<C, W>
 where
@@ -317,6 +366,92 @@ where
         }
     }
 
+    /// Loads multiple entries for reading at once. Absent entries are returned as
+    /// `None`. The entries that need to be read from storage are fetched in a single
+    /// batch. The entries in short_keys have to be all distinct.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::collection_view::ByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: ByteCollectionView<_, RegisterView<_, String>> =
+    ///     ByteCollectionView::load(context).await.unwrap();
+    /// {
+    ///     let _subview = view.load_entry_or_insert(&[0, 1]).await.unwrap();
+    /// }
+    /// let short_keys = vec![vec![0, 1], vec![2, 3]];
+    /// let subviews = view.try_load_entries(short_keys).await.unwrap();
+    /// assert!(subviews[1].is_none());
+    /// let value0 = subviews[0].as_ref().unwrap().get();
+    /// assert_eq!(*value0, String::default());
+    /// # })
+    /// ```
+    pub async fn try_load_entries(
+        &self,
+        short_keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<Option<ReadGuardedView<W>>>, ViewError> {
+        let mut updates = self
+            .updates
+            .try_write()
+            .ok_or(ViewError::CannotAcquireCollectionEntry)?;
+        let mut found = vec![false; short_keys.len()];
+        let mut keys_to_check = Vec::new();
+        let mut keys_to_check_positions = Vec::new();
+        for (position, short_key) in short_keys.iter().enumerate() {
+            match updates.get(short_key) {
+                Some(Update::Set(_)) => found[position] = true,
+                Some(Update::Removed) => {}
+                None => {
+                    if !self.delete_storage_first {
+                        keys_to_check.push(self.get_index_key(short_key));
+                        keys_to_check_positions.push(position);
+                    }
+                }
+            }
+        }
+        let found_keys = self.context.contains_keys(keys_to_check).await?;
+        let entries_to_load = keys_to_check_positions
+            .into_iter()
+            .zip(found_keys)
+            .filter_map(|(position, found)| found.then_some(position))
+            .map(|position| {
+                let subview_key = self.get_subview_key(&short_keys[position]);
+                (position, self.context.clone_with_base_key(subview_key))
+            })
+            .collect::<Vec<_>>();
+        if !entries_to_load.is_empty() {
+            let mut keys_to_load = Vec::with_capacity(entries_to_load.len() * W::NUM_INIT_KEYS);
+            for (_, context) in &entries_to_load {
+                keys_to_load.extend(W::pre_load(context)?);
+            }
+            let values = self.context.read_multi_values_bytes(keys_to_load).await?;
+            for (loaded_values, (position, context)) in
+                values.chunks_exact(W::NUM_INIT_KEYS).zip(entries_to_load)
+            {
+                let view = W::post_load(context, loaded_values)?;
+                updates.insert(short_keys[position].clone(), Update::Set(view));
+                found[position] = true;
+            }
+        }
+        drop(updates);
+        short_keys
+            .into_iter()
+            .zip(found)
+            .map(|(short_key, found)| {
+                if !found {
+                    return Ok(None);
+                }
+                let guard = self
+                    .updates
+                    .try_read()
+                    .ok_or(ViewError::CannotAcquireCollectionEntry)?;
+                Ok(Some(ReadGuardedView { guard, short_key }))
+            })
+            .collect()
+    }
+
     /// Resets an entry to the default value.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -406,6 +541,49 @@ where
         }
     }
 
+    /// Marks the entry as removed and returns its subview, including the staged changes,
+    /// for final reads. If absent then `None` is returned and nothing is done.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::collection_view::ByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: ByteCollectionView<_, RegisterView<_, String>> =
+    ///     ByteCollectionView::load(context).await.unwrap();
+    /// let subview = view.load_entry_mut(&[0, 1]).await.unwrap();
+    /// subview.set(String::from("Hello"));
+    /// let subview = view.take_entry(&[0, 1]).await.unwrap().unwrap();
+    /// assert_eq!(*subview.get(), String::from("Hello"));
+    /// assert!(view.keys().await.unwrap().is_empty());
+    /// # })
+    /// ```
+    pub async fn take_entry(
+        &mut self,
+        short_key: &[u8],
+    ) -> Result<Option<DetachedView<W>>, ViewError> {
+        let view = match self.updates.get_mut().remove(short_key) {
+            Some(Update::Set(view)) => view,
+            Some(Update::Removed) => {
+                self.updates
+                    .get_mut()
+                    .insert(short_key.to_vec(), Update::Removed);
+                return Ok(None);
+            }
+            None => {
+                let key_index = self.get_index_key(short_key);
+                if self.delete_storage_first || !self.context.contains_key(&key_index).await? {
+                    return Ok(None);
+                }
+                let key = self.get_subview_key(short_key);
+                W::load(self.context.clone_with_base_key(key)).await?
+            }
+        };
+        self.remove_entry(short_key.to_vec());
+        Ok(Some(DetachedView(view)))
+    }
+
     /// Gets the extra data.
     pub fn extra(&self) -> &C::Extra {
         self.context.extra()
@@ -610,6 +788,26 @@ where
         .await?;
         Ok(count)
     }
+
+    /// Returns the stored keys of sub-views whose entry is not listed in storage, for
+    /// instance after an interrupted write. If `repair` is set, these keys are deleted
+    /// directly from storage. Staged changes are ignored.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::collection_view::ByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: ByteCollectionView<_, RegisterView<_, String>> =
+    ///     ByteCollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&[0, 1]).await.unwrap();
+    /// assert!(view.verify_integrity(false).await.unwrap().is_empty());
+    /// # })
+    /// ```
+    pub async fn verify_integrity(&self, repair: bool) -> Result<Vec<Vec<u8>>, ViewError> {
+        find_orphan_keys(&self.context, repair).await
+    }
 }
 
 #[async_trait]
@@ -845,6 +1043,42 @@ where
         self.collection.try_load_entry(&short_key).await
     }
 
+    /// Loads multiple entries for reading at once. Absent entries are returned as
+    /// `None`. The entries in indices have to be all distinct.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::collection_view::CollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: CollectionView<_, u64, RegisterView<_, String>> =
+    ///     CollectionView::load(context).await.unwrap();
+    /// {
+    ///     let _subview = view.load_entry_or_insert(&23).await.unwrap();
+    /// }
+    /// let indices = vec![23, 42];
+    /// let subviews = view.try_load_entries(&indices).await.unwrap();
+    /// assert!(subviews[1].is_none());
+    /// let value0 = subviews[0].as_ref().unwrap().get();
+    /// assert_eq!(*value0, String::default());
+    /// # })
+    /// ```
+    pub async fn try_load_entries<'a, Q>(
+        &'a self,
+        indices: impl IntoIterator<Item = &'a Q>,
+    ) -> Result<Vec<Option<ReadGuardedView<W>>>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + 'a,
+    {
+        let short_keys = indices
+            .into_iter()
+            .map(|index| C::derive_short_key(index))
+            .collect::<Result<_, _>>()?;
+        self.collection.try_load_entries(short_keys).await
+    }
+
     /// Resets an entry to the default value.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -901,6 +1135,33 @@ where
         Ok(())
     }
 
+    /// Removes an entry from the CollectionView and returns its subview for final
+    /// reads. If absent then `None` is returned and nothing is done.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::collection_view::CollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: CollectionView<_, u64, RegisterView<_, String>> =
+    ///     CollectionView::load(context).await.unwrap();
+    /// let subview = view.load_entry_mut(&23).await.unwrap();
+    /// subview.set(String::from("Hello"));
+    /// let subview = view.take_entry(&23).await.unwrap().unwrap();
+    /// assert_eq!(*subview.get(), String::from("Hello"));
+    /// assert!(view.take_entry(&23).await.unwrap().is_none());
+    /// # })
+    /// ```
+    pub async fn take_entry<Q>(&mut self, index: &Q) -> Result<Option<DetachedView<W>>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = C::derive_short_key(index)?;
+        self.collection.take_entry(&short_key).await
+    }
+
     /// Gets the extra data.
     pub fn extra(&self) -> &C::Extra {
         self.collection.extra()
@@ -959,6 +1220,25 @@ where
     pub async fn count(&self) -> Result<usize, ViewError> {
         self.collection.count().await
     }
+
+    /// Returns the stored keys of sub-views whose entry is not listed in storage. If
+    /// `repair` is set, these keys are deleted directly from storage.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::collection_view::CollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: CollectionView<_, u64, RegisterView<_, String>> =
+    ///     CollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&23).await.unwrap();
+    /// assert!(view.verify_integrity(false).await.unwrap().is_empty());
+    /// # })
+    /// ```
+    pub async fn verify_integrity(&self, repair: bool) -> Result<Vec<Vec<u8>>, ViewError> {
+        self.collection.verify_integrity(repair).await
+    }
 }
 
 impl<C, I, W> CollectionView<C, I, W>
@@ -1225,6 +1505,42 @@ where
         self.collection.try_load_entry(&short_key).await
     }
 
+    /// Loads multiple entries for reading at once. Absent entries are returned as
+    /// `None`. The entries in indices have to be all distinct.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::collection_view::CustomCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: CustomCollectionView<_, u128, RegisterView<_, String>> =
+    ///     CustomCollectionView::load(context).await.unwrap();
+    /// {
+    ///     let _subview = view.load_entry_or_insert(&23).await.unwrap();
+    /// }
+    /// let indices = vec![23, 42];
+    /// let subviews = view.try_load_entries(indices).await.unwrap();
+    /// assert!(subviews[1].is_none());
+    /// let value0 = subviews[0].as_ref().unwrap().get();
+    /// assert_eq!(*value0, String::default());
+    /// # })
+    /// ```
+    pub async fn try_load_entries<Q>(
+        &self,
+        indices: impl IntoIterator<Item = Q>,
+    ) -> Result<Vec<Option<ReadGuardedView<W>>>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: CustomSerialize,
+    {
+        let short_keys = indices
+            .into_iter()
+            .map(|index| index.to_custom_bytes())
+            .collect::<Result<_, _>>()?;
+        self.collection.try_load_entries(short_keys).await
+    }
+
     /// Marks the entry so that it is removed in the next flush.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -1281,6 +1597,33 @@ where
         Ok(())
     }
 
+    /// Removes an entry from the CustomCollectionView and returns its subview for final
+    /// reads. If absent then `None` is returned and nothing is done.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::collection_view::CustomCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: CustomCollectionView<_, u128, RegisterView<_, String>> =
+    ///     CustomCollectionView::load(context).await.unwrap();
+    /// let subview = view.load_entry_mut(&23).await.unwrap();
+    /// subview.set(String::from("Hello"));
+    /// let subview = view.take_entry(&23).await.unwrap().unwrap();
+    /// assert_eq!(*subview.get(), String::from("Hello"));
+    /// assert!(view.take_entry(&23).await.unwrap().is_none());
+    /// # })
+    /// ```
+    pub async fn take_entry<Q>(&mut self, index: &Q) -> Result<Option<DetachedView<W>>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: CustomSerialize,
+    {
+        let short_key = index.to_custom_bytes()?;
+        self.collection.take_entry(&short_key).await
+    }
+
     /// Gets the extra data.
     pub fn extra(&self) -> &C::Extra {
         self.collection.extra()
@@ -1338,6 +1681,25 @@ where
     pub async fn count(&self) -> Result<usize, ViewError> {
         self.collection.count().await
     }
+
+    /// Returns the stored keys of sub-views whose entry is not listed in storage. If
+    /// `repair` is set, these keys are deleted directly from storage.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::collection_view::CustomCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: CustomCollectionView<_, u128, RegisterView<_, String>> =
+    ///     CustomCollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&23).await.unwrap();
+    /// assert!(view.verify_integrity(false).await.unwrap().is_empty());
+    /// # })
+    /// ```
+    pub async fn verify_integrity(&self, repair: bool) -> Result<Vec<Vec<u8>>, ViewError> {
+        self.collection.verify_integrity(repair).await
+    }
 }
 
 impl<C, I, W> CustomCollectionView<C, I, W>
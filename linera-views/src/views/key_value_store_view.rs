@@ -972,6 +972,70 @@ where
         self.write_batch(batch).await
     }
 
+    /// Sets the value at `index` to `value`, or removes it if `value` is `None`,
+    /// provided that the current value is `expected`. Returns whether the change was
+    /// made. If not, the view is left untouched.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::key_value_store_view::KeyValueStoreView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view = KeyValueStoreView::load(context).await.unwrap();
+    /// assert!(view
+    ///     .compare_and_set(vec![0, 1], None, Some(vec![34]))
+    ///     .await
+    ///     .unwrap());
+    /// assert!(!view
+    ///     .compare_and_set(vec![0, 1], None, Some(vec![42]))
+    ///     .await
+    ///     .unwrap());
+    /// assert_eq!(view.get(&[0, 1]).await.unwrap(), Some(vec![34]));
+    /// # })
+    /// ```
+    pub async fn compare_and_set(
+        &mut self,
+        index: Vec<u8>,
+        expected: Option<&[u8]>,
+        value: Option<Vec<u8>>,
+    ) -> Result<bool, ViewError> {
+        if self.get(&index).await?.as_deref() != expected {
+            return Ok(false);
+        }
+        match value {
+            Some(value) => self.insert(index, value).await?,
+            None => self.remove(index).await?,
+        }
+        Ok(true)
+    }
+
+    /// Replaces the value at `index` with the result of `f` applied to the current
+    /// value. The value is removed if `f` returns `None`.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::key_value_store_view::KeyValueStoreView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view = KeyValueStoreView::load(context).await.unwrap();
+    /// view.insert(vec![0, 1], vec![34]).await.unwrap();
+    /// view.update(vec![0, 1], |value| Some([value?, vec![42]].concat()))
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(view.get(&[0, 1]).await.unwrap(), Some(vec![34, 42]));
+    /// # })
+    /// ```
+    pub async fn update<F>(&mut self, index: Vec<u8>, f: F) -> Result<(), ViewError>
+    where
+        F: FnOnce(Option<Vec<u8>>) -> Option<Vec<u8>> + Send,
+    {
+        let value = self.get(&index).await?;
+        match f(value) {
+            Some(value) => self.insert(index, value).await,
+            None => self.remove(index).await,
+        }
+    }
+
     /// Iterates over all the keys matching the given prefix. The prefix is not included in the returned keys.
     /// ```rust
     /// # tokio_test::block_on(async {
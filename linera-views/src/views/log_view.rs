@@ -298,22 +298,87 @@ where
     where
         R: RangeBounds<usize>,
     {
-        let effective_stored_count = if self.delete_storage_first {
-            0
-        } else {
-            self.stored_count
-        };
-        let end = match range.end_bound() {
-            Bound::Included(end) => *end + 1,
-            Bound::Excluded(end) => *end,
-            Bound::Unbounded => self.count(),
+        let (start, end) = self.bounds(range);
+        self.read_bounded(start, end.min(self.count())).await
+    }
+
+    /// Reads the logged values in the given range (including staged ones). Unlike
+    /// [`LogView::read`], the range is not truncated: an error is returned if it
+    /// extends beyond the end of the log.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::log_view::LogView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut log = LogView::load(context).await.unwrap();
+    /// log.push(34);
+    /// log.push(42);
+    /// log.push(56);
+    /// assert_eq!(log.try_read(1..3).await.unwrap(), vec![42, 56]);
+    /// assert!(log.try_read(2..4).await.is_err());
+    /// # })
+    /// ```
+    pub async fn try_read<R>(&self, range: R) -> Result<Vec<T>, ViewError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self.bounds(range);
+        let count = self.count();
+        if start > end || end > count {
+            return Err(ViewError::RangeOutOfBounds { start, end, count });
         }
-        .min(self.count());
+        self.read_bounded(start, end).await
+    }
+
+    /// Reads the `count` last logged values (including staged ones), or all of them if
+    /// there are fewer.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::log_view::LogView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut log = LogView::load(context).await.unwrap();
+    /// log.push(34);
+    /// log.push(42);
+    /// log.push(56);
+    /// assert_eq!(log.read_last(2).await.unwrap(), vec![42, 56]);
+    /// assert_eq!(log.read_last(5).await.unwrap(), vec![34, 42, 56]);
+    /// # })
+    /// ```
+    pub async fn read_last(&self, count: usize) -> Result<Vec<T>, ViewError> {
+        let end = self.count();
+        self.read_bounded(end.saturating_sub(count), end).await
+    }
+
+    /// Returns the start and end of the given range. The end is the count if the
+    /// range is unbounded.
+    fn bounds<R>(&self, range: R) -> (usize, usize)
+    where
+        R: RangeBounds<usize>,
+    {
         let start = match range.start_bound() {
             Bound::Included(start) => *start,
             Bound::Excluded(start) => *start + 1,
             Bound::Unbounded => 0,
         };
+        let end = match range.end_bound() {
+            Bound::Included(end) => *end + 1,
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => self.count(),
+        };
+        (start, end)
+    }
+
+    /// Reads the values from `start` to `end`, with `end` at most the count. The
+    /// stored values are read with a single batched request.
+    async fn read_bounded(&self, start: usize, end: usize) -> Result<Vec<T>, ViewError> {
+        let effective_stored_count = if self.delete_storage_first {
+            0
+        } else {
+            self.stored_count
+        };
         if start >= end {
             return Ok(Vec::new());
         }
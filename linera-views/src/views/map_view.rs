@@ -41,6 +41,7 @@ use std::{
     collections::{btree_map::Entry, BTreeMap},
     marker::PhantomData,
     mem,
+    ops::Range,
 };
 
 use async_trait::async_trait;
@@ -609,6 +610,39 @@ where
         Ok(count)
     }
 
+    /// Returns the number of keys of the map, stopping the enumeration once `limit`
+    /// keys have been found.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello"));
+    /// map.insert(vec![1, 2], String::from("Bonjour"));
+    /// map.insert(vec![2, 2], String::from("Hallo"));
+    /// assert_eq!(map.count_up_to(2).await.unwrap(), 2);
+    /// assert_eq!(map.count_up_to(5).await.unwrap(), 3);
+    /// # })
+    /// ```
+    pub async fn count_up_to(&self, limit: usize) -> Result<usize, ViewError> {
+        let mut count = 0;
+        if limit == 0 {
+            return Ok(count);
+        }
+        let prefix = Vec::new();
+        self.for_each_key_while(
+            |_key| {
+                count += 1;
+                Ok(count < limit)
+            },
+            prefix,
+        )
+        .await?;
+        Ok(count)
+    }
+
     /// Applies a function f on each key/value pair matching a prefix. The key is the
     /// shortened one by the prefix. The value is an enum that can be either a value
     /// or its serialization. This is needed in order to avoid a scenario where we
@@ -789,6 +823,71 @@ where
         )
         .await
     }
+
+    /// Applies a function f on each key/value pair whose key lies in `range`. Unlike
+    /// the prefix-based functions, the full keys are sent to the function f. Keys
+    /// and values are visited in the lexicographic order. Pending insertions and
+    /// removals are taken into account.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello"));
+    /// map.insert(vec![1, 2], String::from("Bonjour"));
+    /// map.insert(vec![1, 3], String::from("Hallo"));
+    /// map.insert(vec![2], String::from("Ciao"));
+    /// let mut keys = Vec::new();
+    /// map.for_each_key_value_in_range(vec![1]..vec![2], |key, _value| {
+    ///     keys.push(key.to_vec());
+    ///     Ok(())
+    /// })
+    /// .await
+    /// .unwrap();
+    /// assert_eq!(keys, vec![vec![1, 2], vec![1, 3]]);
+    /// # })
+    /// ```
+    pub async fn for_each_key_value_in_range<'a, F>(
+        &'a self,
+        range: Range<Vec<u8>>,
+        mut f: F,
+    ) -> Result<(), ViewError>
+    where
+        F: FnMut(&[u8], Cow<'a, V>) -> Result<(), ViewError> + Send,
+    {
+        if range.start >= range.end {
+            return Ok(());
+        }
+        // All the keys in the range share the common prefix of its bounds.
+        let prefix_len = range
+            .start
+            .iter()
+            .zip(&range.end)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let prefix = range.start[..prefix_len].to_vec();
+        let start = &range.start[prefix_len..];
+        let end = &range.end[prefix_len..];
+        let mut key = prefix.clone();
+        self.for_each_key_value_while(
+            |short_key, value| {
+                if short_key < start {
+                    return Ok(true);
+                }
+                if short_key >= end {
+                    return Ok(false);
+                }
+                key.truncate(prefix_len);
+                key.extend_from_slice(short_key);
+                f(&key, value)?;
+                Ok(true)
+            },
+            prefix,
+        )
+        .await
+    }
 }
 
 impl<C, V> ByteMapView<C, V>
@@ -1123,6 +1222,35 @@ where
         self.map.get(&short_key).await
     }
 
+    /// Reads the values at the given positions, if any. The values that are not
+    /// staged in the view are read from storage in a single batch.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::map_view::MapView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut map: MapView<_, u32, _> = MapView::load(context).await.unwrap();
+    /// map.insert(&(37 as u32), String::from("Hello"));
+    /// let values = map.multi_get(&[37 as u32, 34]).await.unwrap();
+    /// assert_eq!(values, vec![Some(String::from("Hello")), None]);
+    /// # })
+    /// ```
+    pub async fn multi_get<'a, Q>(
+        &self,
+        indices: impl IntoIterator<Item = &'a Q>,
+    ) -> Result<Vec<Option<V>>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + 'a,
+    {
+        let short_keys = indices
+            .into_iter()
+            .map(|index| C::derive_short_key(index))
+            .collect::<Result<_, _>>()?;
+        self.map.multi_get(short_keys).await
+    }
+
     /// Obtains a mutable reference to a value at a given position if available
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -1618,6 +1746,35 @@ where
         self.map.get(&short_key).await
     }
 
+    /// Reads the values at the given positions, if any. The values that are not
+    /// staged in the view are read from storage in a single batch.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::map_view::CustomMapView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut map: CustomMapView<_, u128, String> = CustomMapView::load(context).await.unwrap();
+    /// map.insert(&(34 as u128), String::from("Hello"));
+    /// let values = map.multi_get([34 as u128, 37]).await.unwrap();
+    /// assert_eq!(values, vec![Some(String::from("Hello")), None]);
+    /// # })
+    /// ```
+    pub async fn multi_get<Q>(
+        &self,
+        indices: impl IntoIterator<Item = Q>,
+    ) -> Result<Vec<Option<V>>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: CustomSerialize,
+    {
+        let short_keys = indices
+            .into_iter()
+            .map(|index| index.to_custom_bytes())
+            .collect::<Result<_, _>>()?;
+        self.map.multi_get(short_keys).await
+    }
+
     /// Obtains a mutable reference to a value at a given position if available
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -1867,6 +2024,102 @@ where
         Ok(key_values)
     }
 
+    /// Obtains the `(index,value)` pairs whose index lies in `range`, in the order
+    /// determined by the custom serialization. Only the stored keys sharing the
+    /// common prefix of the serialized bounds are scanned.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::map_view::CustomMapView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut map: CustomMapView<_, u64, String> = CustomMapView::load(context).await.unwrap();
+    /// map.insert(&1, String::from("Hello"));
+    /// map.insert(&256, String::from("Bonjour"));
+    /// map.insert(&1000, String::from("Hallo"));
+    /// let index_values = map.range(2..1000).await.unwrap();
+    /// assert_eq!(index_values, vec![(256, String::from("Bonjour"))]);
+    /// # })
+    /// ```
+    pub async fn range<Q>(&self, range: Range<Q>) -> Result<Vec<(I, V)>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: CustomSerialize,
+    {
+        let range = range.start.to_custom_bytes()?..range.end.to_custom_bytes()?;
+        let mut key_values = Vec::new();
+        self.map
+            .for_each_key_value_in_range(range, |key, value| {
+                let index = I::from_custom_bytes(key)?;
+                key_values.push((index, value.into_owned()));
+                Ok(())
+            })
+            .await?;
+        Ok(key_values)
+    }
+
+    /// Obtains the `(index,value)` pair with the smallest index for the order
+    /// determined by the custom serialization.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::map_view::CustomMapView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut map: CustomMapView<_, u64, String> = CustomMapView::load(context).await.unwrap();
+    /// assert_eq!(map.first().await.unwrap(), None);
+    /// map.insert(&256, String::from("Bonjour"));
+    /// map.insert(&1, String::from("Hello"));
+    /// assert_eq!(map.first().await.unwrap(), Some((1, String::from("Hello"))));
+    /// # })
+    /// ```
+    pub async fn first(&self) -> Result<Option<(I, V)>, ViewError> {
+        let mut first = None;
+        self.for_each_index_value_while(|index, value| {
+            first = Some((index, value.into_owned()));
+            Ok(false)
+        })
+        .await?;
+        Ok(first)
+    }
+
+    /// Obtains the `(index,value)` pair with the largest index for the order
+    /// determined by the custom serialization. The store cannot be scanned in reverse, so
+    /// this visits all the keys of the map, but only reads the last value.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::map_view::CustomMapView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut map: CustomMapView<_, u64, String> = CustomMapView::load(context).await.unwrap();
+    /// assert_eq!(map.last().await.unwrap(), None);
+    /// map.insert(&256, String::from("Bonjour"));
+    /// map.insert(&1, String::from("Hello"));
+    /// assert_eq!(
+    ///     map.last().await.unwrap(),
+    ///     Some((256, String::from("Bonjour")))
+    /// );
+    /// # })
+    /// ```
+    pub async fn last(&self) -> Result<Option<(I, V)>, ViewError> {
+        let mut last_key = None;
+        self.map
+            .for_each_key(
+                |key| {
+                    last_key = Some(key.to_vec());
+                    Ok(())
+                },
+                Vec::new(),
+            )
+            .await?;
+        let Some(key) = last_key else {
+            return Ok(None);
+        };
+        let index = I::from_custom_bytes(&key)?;
+        Ok(self.map.get(&key).await?.map(|value| (index, value)))
+    }
+
     /// Obtains the number of entries in the map
     /// ```rust
     /// # tokio_test::block_on(async {
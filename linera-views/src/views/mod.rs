@@ -11,7 +11,10 @@ pub use linera_views_derive::{
 use serde::Serialize;
 use thiserror::Error;
 
-use crate::{batch::Batch, common::HasherOutput};
+use crate::{
+    batch::{Batch, BatchStatistics},
+    common::HasherOutput,
+};
 
 #[cfg(test)]
 #[path = "unit_tests/views.rs"]
@@ -47,6 +50,12 @@ pub mod key_value_store_view;
 /// Wrapping a view to compute a hash.
 pub mod hashable_wrapper;
 
+/// Wrapping a view to version and upgrade its storage layout.
+pub mod versioned_view;
+
+/// Wrapping a view that is only present on some instances.
+pub mod optional_view;
+
 /// The minimum value for the view tags. Values in 0..MIN_VIEW_TAG are used for other purposes.
 pub const MIN_VIEW_TAG: u8 = 1;
 
@@ -158,6 +167,34 @@ pub enum ViewError {
     /// Some blobs were not found.
     #[error("Blobs not found: {0:?}")]
     BlobsNotFound(Vec<BlobId>),
+
+    /// A queue cannot grow beyond its maximum length.
+    #[error("The queue cannot hold more than {0} elements")]
+    QueueFull(usize),
+
+    /// The range extends beyond the end of the log.
+    #[error("The range {start}..{end} is out of bounds for a log of {count} entries")]
+    RangeOutOfBounds {
+        /// The start of the range.
+        start: usize,
+        /// The end of the range.
+        end: usize,
+        /// The number of entries in the log.
+        count: usize,
+    },
+
+    /// The store cannot hold more data.
+    #[error("The store would exceed its capacity of {0} bytes")]
+    StoreCapacityExceeded(usize),
+
+    /// The storage layout of a view has a version that cannot be loaded.
+    #[error("The stored view has layout version {found} but version {expected} is expected")]
+    UnsupportedViewVersion {
+        /// The version found in storage.
+        found: u32,
+        /// The version expected by the view.
+        expected: u32,
+    },
 }
 
 impl ViewError {
@@ -222,6 +259,10 @@ impl Hasher for sha3::Sha3_256 {
 pub trait RootView<C>: View<C> {
     /// Saves the root view to the database context
     async fn save(&mut self) -> Result<(), ViewError>;
+
+    /// Saves the root view to the database context and returns the statistics of the
+    /// written batch.
+    async fn save_with_statistics(&mut self) -> Result<BatchStatistics, ViewError>;
 }
 
 /// A [`View`] that also supports crypto hash
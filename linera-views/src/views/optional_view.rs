@@ -0,0 +1,261 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+
+use crate::{
+    batch::Batch,
+    common::from_bytes_option_or_default,
+    context::Context,
+    views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
+};
+
+/// Key tags to create the sub-keys of an `OptionalView` on top of the base key.
+#[repr(u8)]
+enum KeyTag {
+    /// The key recording that the inner view is present.
+    Presence = MIN_VIEW_TAG,
+    /// Prefix for the inner view.
+    Inner,
+}
+
+/// Returns the key recording whether the inner view of an [`OptionalView`] is present.
+fn presence_key<C: Context>(context: &C) -> Vec<u8> {
+    context.base_tag(KeyTag::Presence as u8)
+}
+
+/// Returns the context of the view wrapped by an [`OptionalView`] with the given context.
+fn inner_context<C: Context>(context: &C) -> C {
+    context.clone_with_base_key(context.base_tag(KeyTag::Inner as u8))
+}
+
+/// A view that may hold an inner view, e.g. a part of a larger view that only exists on
+/// some instances.
+///
+/// Whether the inner view is present is stored, so that a change of presence is reverted
+/// by [`View::rollback`], and saving an unchanged view writes nothing. An absent inner view
+/// has no data in storage.
+///
+/// The inner view is stored under a tag of the base key, so wrapping the field of an
+/// existing view in an `OptionalView` changes its storage layout.
+#[derive(Debug)]
+pub struct OptionalView<C, W> {
+    context: C,
+    /// Whether the inner view is present in storage.
+    stored_present: bool,
+    /// Whether the inner view is present, including the pending changes.
+    present: bool,
+    /// Whether the stored data is deleted before the pending changes are saved.
+    delete_storage_first: bool,
+    /// The inner view. It is only accessible while it is present.
+    inner: W,
+}
+
+impl<C, W> OptionalView<C, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: View<C>,
+{
+    /// Returns whether the inner view is present.
+    pub fn is_present(&self) -> bool {
+        self.present
+    }
+
+    /// Returns the inner view, if present.
+    pub fn get(&self) -> Option<&W> {
+        self.present.then_some(&self.inner)
+    }
+
+    /// Returns the inner view mutably, if present.
+    pub fn get_mut(&mut self) -> Option<&mut W> {
+        self.present.then_some(&mut self.inner)
+    }
+
+    /// Returns the inner view, inserting an empty one if it is absent.
+    pub fn get_or_insert(&mut self) -> &mut W {
+        if !self.present {
+            self.present = true;
+            self.delete_storage_first = true;
+            self.inner.clear();
+        }
+        &mut self.inner
+    }
+
+    /// Removes the inner view. Its data is deleted when the view is saved.
+    pub fn remove(&mut self) {
+        self.present = false;
+        self.delete_storage_first = self.stored_present;
+        self.inner.clear();
+    }
+}
+
+#[async_trait]
+impl<C, W> View<C> for OptionalView<C, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: View<C> + Send + Sync,
+{
+    const NUM_INIT_KEYS: usize = 1 + W::NUM_INIT_KEYS;
+
+    fn context(&self) -> &C {
+        &self.context
+    }
+
+    fn pre_load(context: &C) -> Result<Vec<Vec<u8>>, ViewError> {
+        let mut v = vec![presence_key(context)];
+        v.extend(W::pre_load(&inner_context(context))?);
+        Ok(v)
+    }
+
+    fn post_load(context: C, values: &[Option<Vec<u8>>]) -> Result<Self, ViewError> {
+        let stored_present =
+            from_bytes_option_or_default(values.first().ok_or(ViewError::PostLoadValuesError)?)?;
+        let inner = W::post_load(
+            inner_context(&context),
+            values.get(1..).ok_or(ViewError::PostLoadValuesError)?,
+        )?;
+        Ok(Self {
+            context,
+            stored_present,
+            present: stored_present,
+            delete_storage_first: false,
+            inner,
+        })
+    }
+
+    async fn load(context: C) -> Result<Self, ViewError> {
+        let keys = Self::pre_load(&context)?;
+        let values = context.read_multi_values_bytes(keys).await?;
+        Self::post_load(context, &values)
+    }
+
+    fn rollback(&mut self) {
+        self.present = self.stored_present;
+        self.delete_storage_first = false;
+        self.inner.rollback();
+    }
+
+    async fn has_pending_changes(&self) -> bool {
+        if self.delete_storage_first {
+            return true;
+        }
+        self.present && self.inner.has_pending_changes().await
+    }
+
+    fn flush(&mut self, batch: &mut Batch) -> Result<bool, ViewError> {
+        if self.delete_storage_first {
+            batch.delete_key_prefix(self.context.base_key());
+        }
+        let delete_view = if self.present {
+            self.inner.flush(batch)?;
+            if self.delete_storage_first || !self.stored_present {
+                batch.put_key_value(presence_key(&self.context), &true)?;
+            }
+            false
+        } else {
+            // The inner view was cleared when it was removed, and its data is deleted with
+            // the rest of the view: this only updates its state.
+            self.inner.flush(&mut Batch::new())?;
+            true
+        };
+        self.stored_present = self.present;
+        self.delete_storage_first = false;
+        Ok(delete_view)
+    }
+
+    fn clear(&mut self) {
+        self.remove();
+    }
+}
+
+impl<C, W> ClonableView<C> for OptionalView<C, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: ClonableView<C> + Send + Sync,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(OptionalView {
+            context: self.context.clone(),
+            stored_present: self.stored_present,
+            present: self.present,
+            delete_storage_first: self.delete_storage_first,
+            inner: self.inner.clone_unchecked()?,
+        })
+    }
+}
+
+#[async_trait]
+impl<C, W> HashableView<C> for OptionalView<C, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: HashableView<C> + Send + Sync,
+{
+    type Hasher = W::Hasher;
+
+    async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        let mut hasher = Self::Hasher::default();
+        if self.present {
+            hasher.update_with_bytes(&[1])?;
+            hasher.update_with_bytes(self.inner.hash_mut().await?.as_ref())?;
+        } else {
+            hasher.update_with_bytes(&[0])?;
+        }
+        Ok(hasher.finalize())
+    }
+
+    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        let mut hasher = Self::Hasher::default();
+        if self.present {
+            hasher.update_with_bytes(&[1])?;
+            hasher.update_with_bytes(self.inner.hash().await?.as_ref())?;
+        } else {
+            hasher.update_with_bytes(&[0])?;
+        }
+        Ok(hasher.finalize())
+    }
+}
+
+mod graphql {
+    use std::borrow::Cow;
+
+    use super::OptionalView;
+    use crate::{
+        context::Context,
+        views::{View, ViewError},
+    };
+
+    impl<C, W> async_graphql::OutputType for OptionalView<C, W>
+    where
+        C: Context + Send + Sync,
+        ViewError: From<C::Error>,
+        W: View<C> + async_graphql::OutputType + Send + Sync,
+    {
+        fn type_name() -> Cow<'static, str> {
+            W::type_name()
+        }
+
+        fn qualified_type_name() -> String {
+            W::type_name().into_owned()
+        }
+
+        fn create_type_info(registry: &mut async_graphql::registry::Registry) -> String {
+            W::create_type_info(registry);
+            Self::qualified_type_name()
+        }
+
+        async fn resolve(
+            &self,
+            ctx: &async_graphql::ContextSelectionSet<'_>,
+            field: &async_graphql::Positioned<async_graphql::parser::types::Field>,
+        ) -> async_graphql::ServerResult<async_graphql::Value> {
+            match self.get() {
+                Some(view) => view.resolve(ctx, field).await,
+                None => Ok(async_graphql::Value::Null),
+            }
+        }
+    }
+}
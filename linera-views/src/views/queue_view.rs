@@ -18,7 +18,7 @@ use {
 
 use crate::{
     batch::Batch,
-    common::{from_bytes_option_or_default, HasherOutput},
+    common::{from_bytes_option, from_bytes_option_or_default, HasherOutput},
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
     views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
@@ -42,6 +42,8 @@ enum KeyTag {
     Store = MIN_VIEW_TAG,
     /// Prefix for the indices of the log.
     Index,
+    /// Prefix for the storing of the maximum length.
+    MaxLength,
 }
 
 /// A view that supports a FIFO queue for values of type `T`.
@@ -52,6 +54,8 @@ pub struct QueueView<C, T> {
     front_delete_count: usize,
     delete_storage_first: bool,
     new_back_values: VecDeque<T>,
+    stored_max_length: Option<usize>,
+    max_length: Option<usize>,
 }
 
 #[async_trait]
@@ -61,25 +65,32 @@ where
     ViewError: From<C::Error>,
     T: Send + Sync + Serialize,
 {
-    const NUM_INIT_KEYS: usize = 1;
+    const NUM_INIT_KEYS: usize = 2;
 
     fn context(&self) -> &C {
         &self.context
     }
 
     fn pre_load(context: &C) -> Result<Vec<Vec<u8>>, ViewError> {
-        Ok(vec![context.base_tag(KeyTag::Store as u8)])
+        Ok(vec![
+            context.base_tag(KeyTag::Store as u8),
+            context.base_tag(KeyTag::MaxLength as u8),
+        ])
     }
 
     fn post_load(context: C, values: &[Option<Vec<u8>>]) -> Result<Self, ViewError> {
         let stored_indices =
             from_bytes_option_or_default(values.first().ok_or(ViewError::PostLoadValuesError)?)?;
+        let stored_max_length =
+            from_bytes_option(values.get(1).ok_or(ViewError::PostLoadValuesError)?)?;
         Ok(Self {
             context,
             stored_indices,
             front_delete_count: 0,
             delete_storage_first: false,
             new_back_values: VecDeque::new(),
+            stored_max_length,
+            max_length: stored_max_length,
         })
     }
 
@@ -93,6 +104,7 @@ where
         self.delete_storage_first = false;
         self.front_delete_count = 0;
         self.new_back_values.clear();
+        self.max_length = self.stored_max_length;
     }
 
     async fn has_pending_changes(&self) -> bool {
@@ -102,6 +114,9 @@ where
         if self.front_delete_count > 0 {
             return true;
         }
+        if self.max_length != self.stored_max_length {
+            return true;
+        }
         !self.new_back_values.is_empty()
     }
 
@@ -109,12 +124,25 @@ where
         let mut delete_view = false;
         if self.delete_storage_first {
             batch.delete_key_prefix(self.context.base_key());
+            self.stored_max_length = None;
             delete_view = true;
         }
+        if self.max_length != self.stored_max_length {
+            let key = self.context.base_tag(KeyTag::MaxLength as u8);
+            match self.max_length {
+                Some(max_length) => {
+                    batch.put_key_value(key, &max_length)?;
+                    delete_view = false;
+                }
+                None => batch.delete_key(key),
+            }
+            self.stored_max_length = self.max_length;
+        }
         if self.stored_count() == 0 {
             let key_prefix = self.context.base_tag(KeyTag::Index as u8);
             batch.delete_key_prefix(key_prefix);
-            self.stored_indices = Range::default();
+            let end = self.index_range().start;
+            self.stored_indices = end..end;
         } else if self.front_delete_count > 0 {
             let deletion_range = self.stored_indices.clone().take(self.front_delete_count);
             self.stored_indices.start += self.front_delete_count;
@@ -134,7 +162,7 @@ where
             }
             self.new_back_values.clear();
         }
-        if !self.delete_storage_first || !self.stored_indices.is_empty() {
+        if !delete_view || !self.stored_indices.is_empty() {
             let key = self.context.base_tag(KeyTag::Store as u8);
             batch.put_key_value(key, &self.stored_indices)?;
         }
@@ -146,6 +174,7 @@ where
     fn clear(&mut self) {
         self.delete_storage_first = true;
         self.new_back_values.clear();
+        self.max_length = None;
     }
 }
 
@@ -162,6 +191,8 @@ where
             front_delete_count: self.front_delete_count,
             delete_storage_first: self.delete_storage_first,
             new_back_values: self.new_back_values.clone(),
+            stored_max_length: self.stored_max_length,
+            max_length: self.max_length,
         })
     }
 }
@@ -252,6 +283,51 @@ where
         }
     }
 
+    /// Deletes the `count` front values, or all the values if there are fewer.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::queue_view::QueueView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut queue = QueueView::load(context).await.unwrap();
+    /// queue.push_back(34);
+    /// queue.push_back(37);
+    /// queue.push_back(42);
+    /// queue.delete_front_many(2);
+    /// assert_eq!(queue.elements().await.unwrap(), vec![42]);
+    /// # })
+    /// ```
+    pub fn delete_front_many(&mut self, count: usize) {
+        let stored_deleted = count.min(self.stored_count());
+        self.front_delete_count += stored_deleted;
+        let new_deleted = (count - stored_deleted).min(self.new_back_values.len());
+        self.new_back_values.drain(..new_deleted);
+    }
+
+    /// Deletes the front values whose position in storage is lower than `index`. See
+    /// [`QueueView::index_range`] for the positions of the values. To keep a tail of
+    /// the last `n` processed values, e.g. for auditing, prune until `n` positions before
+    /// the first unprocessed value.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::queue_view::QueueView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut queue = QueueView::load(context).await.unwrap();
+    /// queue.push_back(34);
+    /// queue.push_back(37);
+    /// queue.push_back(42);
+    /// queue.prune_front_until(2);
+    /// assert_eq!(queue.elements().await.unwrap(), vec![42]);
+    /// # })
+    /// ```
+    pub fn prune_front_until(&mut self, index: usize) {
+        let start = self.index_range().start;
+        self.delete_front_many(index.saturating_sub(start));
+    }
+
     /// Pushes a value to the end of the queue.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -269,6 +345,31 @@ where
         self.new_back_values.push_back(value);
     }
 
+    /// Pushes a value to the end of the queue, unless the queue already holds the
+    /// maximum number of elements.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::queue_view::QueueView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut queue = QueueView::load(context).await.unwrap();
+    /// queue.set_max_length(Some(1));
+    /// queue.try_push_back(34).unwrap();
+    /// assert!(queue.try_push_back(37).is_err());
+    /// assert_eq!(queue.elements().await.unwrap(), vec![34]);
+    /// # })
+    /// ```
+    pub fn try_push_back(&mut self, value: T) -> Result<(), ViewError> {
+        if let Some(max_length) = self.max_length {
+            if self.count() >= max_length {
+                return Err(ViewError::QueueFull(max_length));
+            }
+        }
+        self.push_back(value);
+        Ok(())
+    }
+
     /// Reads the size of the queue.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -285,6 +386,46 @@ where
         self.stored_count() + self.new_back_values.len()
     }
 
+    /// Returns the positions in storage of the values of the queue, once the staged
+    /// changes are saved. Positions only grow, also when all the values are deleted,
+    /// until the queue is cleared: they then start again from 0.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::queue_view::QueueView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut queue = QueueView::load(context).await.unwrap();
+    /// queue.push_back(34);
+    /// queue.push_back(37);
+    /// queue.push_back(42);
+    /// queue.delete_front();
+    /// assert_eq!(queue.index_range(), 0..2);
+    /// # })
+    /// ```
+    pub fn index_range(&self) -> Range<usize> {
+        let end = if self.delete_storage_first {
+            0
+        } else {
+            self.stored_indices.end
+        };
+        let start = end - self.stored_count();
+        start..end + self.new_back_values.len()
+    }
+
+    /// Returns the maximum number of elements accepted by [`QueueView::try_push_back`],
+    /// if any.
+    pub fn max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+
+    /// Sets the maximum number of elements accepted by [`QueueView::try_push_back`].
+    /// The bound is saved with the queue, but is not part of its hash. Values already in
+    /// the queue are kept.
+    pub fn set_max_length(&mut self, max_length: Option<usize>) {
+        self.max_length = max_length;
+    }
+
     /// Obtains the extra data.
     pub fn extra(&self) -> &C::Extra {
         self.context.extra()
@@ -500,5 +641,13 @@ mod graphql {
                 .read_front(count.unwrap_or_else(|| self.count()))
                 .await?)
         }
+
+        async fn start_index(&self) -> usize {
+            self.index_range().start
+        }
+
+        async fn end_index(&self) -> usize {
+            self.index_range().end
+        }
     }
 }
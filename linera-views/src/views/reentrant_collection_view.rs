@@ -5,7 +5,7 @@
 use std::sync::LazyLock;
 use std::{
     borrow::Borrow,
-    collections::{btree_map, BTreeMap},
+    collections::{btree_map, BTreeMap, BTreeSet},
     io::Write,
     marker::PhantomData,
     mem,
@@ -23,6 +23,7 @@ use {
 
 use crate::{
     batch::Batch,
+    collection_view::find_orphan_keys,
     common::{CustomSerialize, HasherOutput, Update},
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
@@ -81,6 +82,9 @@ pub struct ReentrantByteCollectionView<C, W> {
     updates: BTreeMap<Vec<u8>, Update<Arc<RwLock<W>>>>,
     /// Entries cached in memory that have the exact same state as in the persistent storage.
     cached_entries: Mutex<BTreeMap<Vec<u8>, Arc<RwLock<W>>>>,
+    /// Hashes of entries in the persistent storage, so that unchanged entries do not have to
+    /// be loaded again each time the hash of the collection is computed.
+    stored_hashes: Mutex<BTreeMap<Vec<u8>, Vec<u8>>>,
 }
 
 /// We need to find new base keys in order to implement the collection_view.
@@ -121,6 +125,7 @@ where
             delete_storage_first: false,
             updates: BTreeMap::new(),
             cached_entries: Mutex::new(BTreeMap::new()),
+            stored_hashes: Mutex::new(BTreeMap::new()),
         })
     }
 
@@ -142,6 +147,14 @@ where
 
     fn flush(&mut self, batch: &mut Batch) -> Result<bool, ViewError> {
         let mut delete_view = false;
+        let stored_hashes = self.stored_hashes.get_mut().unwrap();
+        if self.delete_storage_first {
+            stored_hashes.clear();
+        } else {
+            for index in self.updates.keys() {
+                stored_hashes.remove(index);
+            }
+        }
         if self.delete_storage_first {
             delete_view = true;
             batch.delete_key_prefix(self.context.base_key());
@@ -215,6 +228,7 @@ where
             delete_storage_first: self.delete_storage_first,
             updates: cloned_updates,
             cached_entries: Mutex::new(BTreeMap::new()),
+            stored_hashes: Mutex::new(BTreeMap::new()),
         })
     }
 }
@@ -437,6 +451,41 @@ where
         }
     }
 
+    /// Removes an entry and returns read-only access to its subview, including the
+    /// staged changes, for final reads. If absent then `None` is returned and nothing
+    /// is done.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::reentrant_collection_view::ReentrantByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: ReentrantByteCollectionView<_, RegisterView<_, String>> =
+    ///     ReentrantByteCollectionView::load(context).await.unwrap();
+    /// {
+    ///     let mut subview = view.try_load_entry_mut(&[0, 1]).await.unwrap();
+    ///     subview.set(String::from("Hello"));
+    /// }
+    /// let subview = view.take_entry(&[0, 1]).await.unwrap().unwrap();
+    /// assert_eq!(*subview.get(), String::from("Hello"));
+    /// assert!(view.keys().await.unwrap().is_empty());
+    /// # })
+    /// ```
+    pub async fn take_entry(
+        &mut self,
+        short_key: &[u8],
+    ) -> Result<Option<ReadGuardedView<W>>, ViewError> {
+        let Some(view) = self.try_load_view(short_key).await? else {
+            return Ok(None);
+        };
+        let guard = view
+            .try_read_arc()
+            .ok_or_else(|| ViewError::TryLockError(short_key.to_vec()))?;
+        self.remove_entry(short_key.to_vec());
+        Ok(Some(ReadGuardedView(guard)))
+    }
+
     /// Marks the entry so that it is removed in the next flush.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -484,7 +533,8 @@ where
     W: View<C> + Send + Sync + 'static,
 {
     /// Loads multiple entries for writing at once.
-    /// The entries in `short_keys` have to be all distinct.
+    /// The entries in `short_keys` have to be all distinct, otherwise an error is returned.
+    /// The returned guards own their entries, so that they can be moved to different tasks.
     /// ```rust
     /// # tokio_test::block_on(async {
     /// # use linera_views::context::{create_test_memory_context, MemoryContext};
@@ -510,6 +560,12 @@ where
         &mut self,
         short_keys: Vec<Vec<u8>>,
     ) -> Result<Vec<WriteGuardedView<W>>, ViewError> {
+        let mut distinct_keys = BTreeSet::new();
+        for short_key in &short_keys {
+            if !distinct_keys.insert(short_key) {
+                return Err(ViewError::TryLockError(short_key.clone()));
+            }
+        }
         let cached_entries = self.cached_entries.get_mut().unwrap();
         let mut short_keys_to_load = Vec::new();
         let mut keys = Vec::new();
@@ -866,6 +922,26 @@ where
         Ok(count)
     }
 
+    /// Returns the stored keys of sub-views whose entry is not listed in storage, for
+    /// instance after an interrupted write. If `repair` is set, these keys are deleted
+    /// directly from storage. Staged changes are ignored.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::reentrant_collection_view::ReentrantByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: ReentrantByteCollectionView<_, RegisterView<_, String>> =
+    ///     ReentrantByteCollectionView::load(context).await.unwrap();
+    /// view.try_load_entry_mut(&[0, 1]).await.unwrap();
+    /// assert!(view.verify_integrity(false).await.unwrap().is_empty());
+    /// # })
+    /// ```
+    pub async fn verify_integrity(&self, repair: bool) -> Result<Vec<Vec<u8>>, ViewError> {
+        find_orphan_keys(&self.context, repair).await
+    }
+
     /// Applies a function f on each index (aka key). Keys are visited in a
     /// lexicographic order. If the function returns false then the loop
     /// ends prematurely.
@@ -986,6 +1062,7 @@ where
         let count = keys.len() as u32;
         hasher.update_with_bcs_bytes(&count)?;
         let cached_entries = self.cached_entries.get_mut().unwrap();
+        let stored_hashes = self.stored_hashes.get_mut().unwrap();
         for key in keys {
             hasher.update_with_bytes(&key)?;
             let hash = if let Some(entry) = self.updates.get_mut(&key) {
@@ -996,16 +1073,21 @@ where
                     .try_write_arc()
                     .ok_or_else(|| ViewError::TryLockError(key))?;
                 view.hash_mut().await?
+            } else if let Some(hash) = stored_hashes.get(&key) {
+                hasher.write_all(hash)?;
+                continue;
             } else if let Some(view) = cached_entries.get_mut(&key) {
                 let mut view = view
                     .try_write_arc()
                     .ok_or_else(|| ViewError::TryLockError(key))?;
                 view.hash_mut().await?
             } else {
-                let key = self.context.base_tag_index(KeyTag::Subview as u8, &key);
-                let context = self.context.clone_with_base_key(key);
+                let subview_key = self.context.base_tag_index(KeyTag::Subview as u8, &key);
+                let context = self.context.clone_with_base_key(subview_key);
                 let mut view = W::load(context).await?;
-                view.hash_mut().await?
+                let hash = view.hash_mut().await?;
+                stored_hashes.insert(key, hash.as_ref().to_vec());
+                hash
             };
             hasher.write_all(hash.as_ref())?;
         }
@@ -1028,6 +1110,7 @@ where
         }
         for (key, cached_entry) in keys.into_iter().zip(cached_entries_result) {
             hasher.update_with_bytes(&key)?;
+            let stored_hash = self.stored_hashes.lock().unwrap().get(&key).cloned();
             let hash = if let Some(entry) = self.updates.get(&key) {
                 let Update::Set(view) = entry else {
                     unreachable!();
@@ -1036,16 +1119,24 @@ where
                     .try_read_arc()
                     .ok_or_else(|| ViewError::TryLockError(key))?;
                 view.hash().await?
+            } else if let Some(hash) = stored_hash {
+                hasher.write_all(&hash)?;
+                continue;
             } else if let Some(view) = cached_entry {
                 let view = view
                     .try_read_arc()
                     .ok_or_else(|| ViewError::TryLockError(key))?;
                 view.hash().await?
             } else {
-                let key = self.context.base_tag_index(KeyTag::Subview as u8, &key);
-                let context = self.context.clone_with_base_key(key);
+                let subview_key = self.context.base_tag_index(KeyTag::Subview as u8, &key);
+                let context = self.context.clone_with_base_key(subview_key);
                 let view = W::load(context).await?;
-                view.hash().await?
+                let hash = view.hash().await?;
+                self.stored_hashes
+                    .lock()
+                    .unwrap()
+                    .insert(key, hash.as_ref().to_vec());
+                hash
             };
             hasher.write_all(hash.as_ref())?;
         }
@@ -1242,6 +1333,37 @@ where
         Ok(())
     }
 
+    /// Removes an entry and returns read-only access to its subview for final reads.
+    /// If absent then `None` is returned and nothing is done.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::reentrant_collection_view::ReentrantCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: ReentrantCollectionView<_, u64, RegisterView<_, String>> =
+    ///     ReentrantCollectionView::load(context).await.unwrap();
+    /// {
+    ///     let mut subview = view.try_load_entry_mut(&23).await.unwrap();
+    ///     subview.set(String::from("Hello"));
+    /// }
+    /// let subview = view.take_entry(&23).await.unwrap().unwrap();
+    /// assert_eq!(*subview.get(), String::from("Hello"));
+    /// # })
+    /// ```
+    pub async fn take_entry<Q>(
+        &mut self,
+        index: &Q,
+    ) -> Result<Option<ReadGuardedView<W>>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = C::derive_short_key(index)?;
+        self.collection.take_entry(&short_key).await
+    }
+
     /// Marks the entry so that it is removed in the next flush.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -1479,6 +1601,25 @@ where
         self.collection.count().await
     }
 
+    /// Returns the stored keys of sub-views whose entry is not listed in storage. If
+    /// `repair` is set, these keys are deleted directly from storage.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::reentrant_collection_view::ReentrantCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: ReentrantCollectionView<_, u64, RegisterView<_, String>> =
+    ///     ReentrantCollectionView::load(context).await.unwrap();
+    /// view.try_load_entry_mut(&23).await.unwrap();
+    /// assert!(view.verify_integrity(false).await.unwrap().is_empty());
+    /// # })
+    /// ```
+    pub async fn verify_integrity(&self, repair: bool) -> Result<Vec<Vec<u8>>, ViewError> {
+        self.collection.verify_integrity(repair).await
+    }
+
     /// Applies a function f on each index. Indices are visited in an order
     /// determined by the serialization. If the function f returns false then
     /// the loop ends prematurely.
@@ -1760,6 +1901,37 @@ where
         Ok(())
     }
 
+    /// Removes an entry and returns read-only access to its subview for final reads.
+    /// If absent then `None` is returned and nothing is done.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::reentrant_collection_view::ReentrantCustomCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: ReentrantCustomCollectionView<_, u128, RegisterView<_, String>> =
+    ///     ReentrantCustomCollectionView::load(context).await.unwrap();
+    /// {
+    ///     let mut subview = view.try_load_entry_mut(&23).await.unwrap();
+    ///     subview.set(String::from("Hello"));
+    /// }
+    /// let subview = view.take_entry(&23).await.unwrap().unwrap();
+    /// assert_eq!(*subview.get(), String::from("Hello"));
+    /// # })
+    /// ```
+    pub async fn take_entry<Q>(
+        &mut self,
+        index: &Q,
+    ) -> Result<Option<ReadGuardedView<W>>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: CustomSerialize,
+    {
+        let short_key = index.to_custom_bytes()?;
+        self.collection.take_entry(&short_key).await
+    }
+
     /// Marks the entry so that it is removed in the next flush.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -1997,6 +2169,25 @@ where
         self.collection.count().await
     }
 
+    /// Returns the stored keys of sub-views whose entry is not listed in storage. If
+    /// `repair` is set, these keys are deleted directly from storage.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::{create_test_memory_context, MemoryContext};
+    /// # use linera_views::reentrant_collection_view::ReentrantCustomCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut view: ReentrantCustomCollectionView<_, u128, RegisterView<_, String>> =
+    ///     ReentrantCustomCollectionView::load(context).await.unwrap();
+    /// view.try_load_entry_mut(&23).await.unwrap();
+    /// assert!(view.verify_integrity(false).await.unwrap().is_empty());
+    /// # })
+    /// ```
+    pub async fn verify_integrity(&self, repair: bool) -> Result<Vec<Vec<u8>>, ViewError> {
+        self.collection.verify_integrity(repair).await
+    }
+
     /// Applies a function f on each index. Indices are visited in an order
     /// determined by the custom serialization. If the function f returns false
     /// then the loop ends prematurely.
@@ -166,6 +166,31 @@ where
         self.update = Some(Box::new(value));
     }
 
+    /// Sets the value in the register to `new` if the current value is `expected`.
+    /// Returns whether the value was set. If not, the register is left untouched.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut register = RegisterView::<_, u32>::load(context).await.unwrap();
+    /// assert!(register.compare_and_set(&0, 5));
+    /// assert!(!register.compare_and_set(&0, 7));
+    /// assert_eq!(*register.get(), 5);
+    /// # })
+    /// ```
+    pub fn compare_and_set(&mut self, expected: &T, new: T) -> bool
+    where
+        T: PartialEq,
+    {
+        if self.get() != expected {
+            return false;
+        }
+        self.set(new);
+        true
+    }
+
     /// Obtains the extra data.
     pub fn extra(&self) -> &C::Extra {
         self.context.extra()
@@ -200,6 +225,26 @@ where
         }
     }
 
+    /// Modifies the value in the register in place and returns the result of `f`.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut register: RegisterView<_, u32> = RegisterView::load(context).await.unwrap();
+    /// let previous = register.modify(|value| std::mem::replace(value, 5));
+    /// assert_eq!(previous, 0);
+    /// assert_eq!(*register.get(), 5);
+    /// # })
+    /// ```
+    pub fn modify<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        f(self.get_mut())
+    }
+
     fn compute_hash(&self) -> Result<<sha3::Sha3_256 as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = REGISTER_VIEW_HASH_RUNTIME.measure_latency();
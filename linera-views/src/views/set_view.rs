@@ -166,6 +166,41 @@ where
         }
     }
 
+    /// Inserts several values. The values already present are left unchanged.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::{context::create_test_memory_context, set_view::ByteSetView};
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut set = ByteSetView::load(context).await.unwrap();
+    /// set.insert_all(vec![vec![0, 1], vec![0, 2]]);
+    /// assert_eq!(set.keys().await.unwrap(), vec![vec![0, 1], vec![0, 2]]);
+    /// # })
+    /// ```
+    pub fn insert_all(&mut self, short_keys: impl IntoIterator<Item = Vec<u8>>) {
+        for short_key in short_keys {
+            self.insert(short_key);
+        }
+    }
+
+    /// Removes several values from the set. The absent values are ignored.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::{context::create_test_memory_context, set_view::ByteSetView};
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut set = ByteSetView::load(context).await.unwrap();
+    /// set.insert_all(vec![vec![0, 1], vec![0, 2]]);
+    /// set.remove_all(vec![vec![0, 1], vec![3]]);
+    /// assert_eq!(set.keys().await.unwrap(), vec![vec![0, 2]]);
+    /// # })
+    /// ```
+    pub fn remove_all(&mut self, short_keys: impl IntoIterator<Item = Vec<u8>>) {
+        for short_key in short_keys {
+            self.remove(short_key);
+        }
+    }
+
     /// Gets the extra data.
     pub fn extra(&self) -> &C::Extra {
         self.context.extra()
@@ -203,6 +238,46 @@ where
         let key = self.context.base_index(short_key);
         Ok(self.context.contains_key(&key).await?)
     }
+
+    /// Returns true if all the given indices exist in the set. The indices that are
+    /// not staged in the view are looked up in storage in a single batch.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::{context::create_test_memory_context, set_view::ByteSetView};
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut set = ByteSetView::load(context).await.unwrap();
+    /// set.insert(vec![0, 1]);
+    /// set.insert(vec![0, 2]);
+    /// assert_eq!(
+    ///     set.contains_all(vec![vec![0, 1], vec![0, 2]])
+    ///         .await
+    ///         .unwrap(),
+    ///     true
+    /// );
+    /// assert_eq!(
+    ///     set.contains_all(vec![vec![0, 1], vec![34]]).await.unwrap(),
+    ///     false
+    /// );
+    /// # })
+    /// ```
+    pub async fn contains_all(&self, short_keys: Vec<Vec<u8>>) -> Result<bool, ViewError> {
+        let mut keys = Vec::new();
+        for short_key in short_keys {
+            match self.updates.get(&short_key) {
+                Some(Update::Removed) => return Ok(false),
+                Some(Update::Set(())) => {}
+                None => {
+                    if self.delete_storage_first {
+                        return Ok(false);
+                    }
+                    keys.push(self.context.base_index(&short_key));
+                }
+            }
+        }
+        let tests = self.context.contains_keys(keys).await?;
+        Ok(tests.into_iter().all(|test| test))
+    }
 }
 
 impl<C> ByteSetView<C>
@@ -232,6 +307,63 @@ where
         Ok(keys)
     }
 
+    /// Returns at most `limit` keys of the set that come strictly after `cursor`, or
+    /// from the start of the set if `cursor` is `None`. The order is lexicographic, so
+    /// the last key of a page can be used as the cursor of the next one.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::{context::create_test_memory_context, set_view::ByteSetView};
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut set = ByteSetView::load(context).await.unwrap();
+    /// set.insert_all(vec![vec![0, 1], vec![0, 2], vec![3]]);
+    /// let page = set.keys_after(None, 2).await.unwrap();
+    /// assert_eq!(page, vec![vec![0, 1], vec![0, 2]]);
+    /// let page = set.keys_after(Some(&[0, 2]), 2).await.unwrap();
+    /// assert_eq!(page, vec![vec![3]]);
+    /// # })
+    /// ```
+    pub async fn keys_after(
+        &self,
+        cursor: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        let mut keys = Vec::new();
+        if limit == 0 {
+            return Ok(keys);
+        }
+        let Some(cursor) = cursor else {
+            self.for_each_key_while(|key| {
+                keys.push(key.to_vec());
+                Ok(keys.len() < limit)
+            })
+            .await?;
+            return Ok(keys);
+        };
+        // The keys after the cursor are visited by scanning the prefixes of the cursor from
+        // the longest to the shortest one, so that only the prefix containing the next
+        // `limit` keys is read. Each prefix adds the keys whose next byte is greater than
+        // the cursor's.
+        for prefix_len in (0..=cursor.len()).rev() {
+            let (prefix, rest) = cursor.split_at(prefix_len);
+            self.for_each_key_while_with_prefix(prefix, |suffix| {
+                let is_after = match rest.first() {
+                    None => !suffix.is_empty(),
+                    Some(byte) => suffix.first().is_some_and(|first| first > byte),
+                };
+                if is_after {
+                    keys.push([prefix, suffix].concat());
+                }
+                Ok(keys.len() < limit)
+            })
+            .await?;
+            if keys.len() == limit {
+                break;
+            }
+        }
+        Ok(keys)
+    }
+
     /// Returns the number of entries in the set.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -276,19 +408,37 @@ where
     /// assert_eq!(count, 2);
     /// # })
     /// ```
-    pub async fn for_each_key_while<F>(&self, mut f: F) -> Result<(), ViewError>
+    pub async fn for_each_key_while<F>(&self, f: F) -> Result<(), ViewError>
+    where
+        F: FnMut(&[u8]) -> Result<bool, ViewError> + Send,
+    {
+        self.for_each_key_while_with_prefix(&[], f).await
+    }
+
+    /// Applies a function f on each key starting with `prefix`, with the prefix removed.
+    /// Keys are visited in a lexicographic order. If the function returns false, then the
+    /// loop ends prematurely.
+    async fn for_each_key_while_with_prefix<F>(
+        &self,
+        prefix: &[u8],
+        mut f: F,
+    ) -> Result<(), ViewError>
     where
         F: FnMut(&[u8]) -> Result<bool, ViewError> + Send,
     {
-        let mut updates = self.updates.iter();
+        let mut updates = self
+            .updates
+            .range(prefix.to_vec()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| (&key[prefix.len()..], value));
         let mut update = updates.next();
         if !self.delete_storage_first {
-            let base = self.context.base_key();
+            let base = self.context.base_index(prefix);
             for index in self.context.find_keys_by_prefix(&base).await?.iterator() {
                 let index = index?;
                 loop {
                     match update {
-                        Some((key, value)) if key.as_slice() <= index => {
+                        Some((key, value)) if key <= index => {
                             if let Update::Set(_) = value {
                                 if !f(key)? {
                                     return Ok(());
@@ -497,6 +647,61 @@ where
         Ok(())
     }
 
+    /// Inserts several values. Nothing is staged if one of them fails to serialize.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::{context::create_test_memory_context, set_view::SetView};
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut set = SetView::<_, u32>::load(context).await.unwrap();
+    /// set.insert_all(&[34 as u32, 37]).unwrap();
+    /// assert_eq!(set.indices().await.unwrap(), vec![34, 37]);
+    /// # })
+    /// ```
+    pub fn insert_all<'a, Q>(
+        &mut self,
+        indices: impl IntoIterator<Item = &'a Q>,
+    ) -> Result<(), ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + 'a,
+    {
+        let short_keys = indices
+            .into_iter()
+            .map(|index| C::derive_short_key(index))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.set.insert_all(short_keys);
+        Ok(())
+    }
+
+    /// Removes several values. Nothing is staged if one of them fails to serialize.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::{context::create_test_memory_context, set_view::SetView};
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut set = SetView::<_, u32>::load(context).await.unwrap();
+    /// set.insert_all(&[34 as u32, 37]).unwrap();
+    /// set.remove_all(&[34 as u32, 42]).unwrap();
+    /// assert_eq!(set.indices().await.unwrap(), vec![37]);
+    /// # })
+    /// ```
+    pub fn remove_all<'a, Q>(
+        &mut self,
+        indices: impl IntoIterator<Item = &'a Q>,
+    ) -> Result<(), ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + 'a,
+    {
+        let short_keys = indices
+            .into_iter()
+            .map(|index| C::derive_short_key(index))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.set.remove_all(short_keys);
+        Ok(())
+    }
+
     /// Obtains the extra data.
     pub fn extra(&self) -> &C::Extra {
         self.set.extra()
@@ -529,6 +734,33 @@ where
         let short_key = C::derive_short_key(index)?;
         self.set.contains(&short_key).await
     }
+
+    /// Returns true if all the given indices exist in the set.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::{context::create_test_memory_context, set_view::SetView};
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut set: SetView<_, u32> = SetView::load(context).await.unwrap();
+    /// set.insert_all(&[34 as u32, 37]).unwrap();
+    /// assert_eq!(set.contains_all(&[34 as u32, 37]).await.unwrap(), true);
+    /// assert_eq!(set.contains_all(&[34 as u32, 45]).await.unwrap(), false);
+    /// # })
+    /// ```
+    pub async fn contains_all<'a, Q>(
+        &self,
+        indices: impl IntoIterator<Item = &'a Q>,
+    ) -> Result<bool, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + 'a,
+    {
+        let short_keys = indices
+            .into_iter()
+            .map(|index| C::derive_short_key(index))
+            .collect::<Result<_, _>>()?;
+        self.set.contains_all(short_keys).await
+    }
 }
 
 impl<C, I> SetView<C, I>
@@ -558,6 +790,37 @@ where
         Ok(indices)
     }
 
+    /// Returns at most `limit` indices of the set that come strictly after `cursor`, or
+    /// from the start of the set if `cursor` is `None`. The order is determined by
+    /// serialization.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::{context::create_test_memory_context, set_view::SetView};
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut set: SetView<_, u32> = SetView::load(context).await.unwrap();
+    /// set.insert_all(&[34 as u32, 37, 42]).unwrap();
+    /// assert_eq!(set.indices_after(None, 2).await.unwrap(), vec![34, 37]);
+    /// assert_eq!(
+    ///     set.indices_after(Some(&(37 as u32)), 2).await.unwrap(),
+    ///     vec![42]
+    /// );
+    /// # })
+    /// ```
+    pub async fn indices_after(
+        &self,
+        cursor: Option<&I>,
+        limit: usize,
+    ) -> Result<Vec<I>, ViewError> {
+        let cursor = cursor.map(|index| C::derive_short_key(index)).transpose()?;
+        self.set
+            .keys_after(cursor.as_deref(), limit)
+            .await?
+            .iter()
+            .map(|key| Ok(C::deserialize_value(key)?))
+            .collect()
+    }
+
     /// Returns the number of entries in the set.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -781,6 +1044,63 @@ where
         Ok(())
     }
 
+    /// Inserts several values. Nothing is staged if one of them fails to serialize.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::set_view::CustomSetView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut set = CustomSetView::<_, u128>::load(context).await.unwrap();
+    /// set.insert_all(&[34 as u128, 37]).unwrap();
+    /// assert_eq!(set.indices().await.unwrap(), vec![34, 37]);
+    /// # })
+    /// ```
+    pub fn insert_all<'a, Q>(
+        &mut self,
+        indices: impl IntoIterator<Item = &'a Q>,
+    ) -> Result<(), ViewError>
+    where
+        I: Borrow<Q>,
+        Q: CustomSerialize + 'a,
+    {
+        let short_keys = indices
+            .into_iter()
+            .map(|index| index.to_custom_bytes())
+            .collect::<Result<Vec<_>, _>>()?;
+        self.set.insert_all(short_keys);
+        Ok(())
+    }
+
+    /// Removes several values. Nothing is staged if one of them fails to serialize.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::set_view::CustomSetView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut set = CustomSetView::<_, u128>::load(context).await.unwrap();
+    /// set.insert_all(&[34 as u128, 37]).unwrap();
+    /// set.remove_all(&[34 as u128, 42]).unwrap();
+    /// assert_eq!(set.indices().await.unwrap(), vec![37]);
+    /// # })
+    /// ```
+    pub fn remove_all<'a, Q>(
+        &mut self,
+        indices: impl IntoIterator<Item = &'a Q>,
+    ) -> Result<(), ViewError>
+    where
+        I: Borrow<Q>,
+        Q: CustomSerialize + 'a,
+    {
+        let short_keys = indices
+            .into_iter()
+            .map(|index| index.to_custom_bytes())
+            .collect::<Result<Vec<_>, _>>()?;
+        self.set.remove_all(short_keys);
+        Ok(())
+    }
+
     /// Obtains the extra data.
     pub fn extra(&self) -> &C::Extra {
         self.set.extra()
@@ -814,6 +1134,34 @@ where
         let short_key = index.to_custom_bytes()?;
         self.set.contains(&short_key).await
     }
+
+    /// Returns true if all the given indices exist in the set.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::set_view::CustomSetView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut set = CustomSetView::<_, u128>::load(context).await.unwrap();
+    /// set.insert_all(&[34 as u128, 37]).unwrap();
+    /// assert_eq!(set.contains_all(&[34 as u128, 37]).await.unwrap(), true);
+    /// assert_eq!(set.contains_all(&[34 as u128, 42]).await.unwrap(), false);
+    /// # })
+    /// ```
+    pub async fn contains_all<'a, Q>(
+        &self,
+        indices: impl IntoIterator<Item = &'a Q>,
+    ) -> Result<bool, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: CustomSerialize + 'a,
+    {
+        let short_keys = indices
+            .into_iter()
+            .map(|index| index.to_custom_bytes())
+            .collect::<Result<_, _>>()?;
+        self.set.contains_all(short_keys).await
+    }
 }
 
 impl<C, I> CustomSetView<C, I>
@@ -846,6 +1194,38 @@ where
         Ok(indices)
     }
 
+    /// Returns at most `limit` indices of the set that come strictly after `cursor`, or
+    /// from the start of the set if `cursor` is `None`. The order is determined by the
+    /// custom serialization.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::create_test_memory_context;
+    /// # use linera_views::set_view::CustomSetView;
+    /// # use linera_views::views::View;
+    /// # let context = create_test_memory_context();
+    /// let mut set = CustomSetView::<_, u128>::load(context).await.unwrap();
+    /// set.insert_all(&[34 as u128, 37, 42]).unwrap();
+    /// assert_eq!(set.indices_after(None, 2).await.unwrap(), vec![34, 37]);
+    /// assert_eq!(
+    ///     set.indices_after(Some(&(37 as u128)), 2).await.unwrap(),
+    ///     vec![42]
+    /// );
+    /// # })
+    /// ```
+    pub async fn indices_after(
+        &self,
+        cursor: Option<&I>,
+        limit: usize,
+    ) -> Result<Vec<I>, ViewError> {
+        let cursor = cursor.map(|index| index.to_custom_bytes()).transpose()?;
+        self.set
+            .keys_after(cursor.as_deref(), limit)
+            .await?
+            .iter()
+            .map(|key| I::from_custom_bytes(key))
+            .collect()
+    }
+
     /// Returns the number of entries of the set.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -0,0 +1,259 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::{Deref, DerefMut};
+
+use async_trait::async_trait;
+use linera_base::ensure;
+use static_assertions as sa;
+
+use crate::{
+    batch::{Batch, WriteOperation},
+    common::from_bytes_option,
+    context::Context,
+    store::KeyIterable,
+    views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
+};
+
+/// Key tags to create the sub-keys of a `VersionedView` on top of the base key.
+#[repr(u8)]
+enum KeyTag {
+    /// Prefix for the inner view.
+    Inner = MIN_VIEW_TAG,
+}
+
+/// The tag of the key storing the version of the storage layout, on top of the base key.
+// No view uses tags below `MIN_VIEW_TAG` under its base key, so the version cannot collide
+// with the data of the inner view, nor with legacy data written before the view was wrapped.
+const VERSION_TAG: u8 = 0;
+sa::const_assert!(VERSION_TAG < MIN_VIEW_TAG);
+
+/// The upgrades of the storage layout of a view wrapped in a [`VersionedView`].
+///
+/// Layouts are numbered from 0. Version 0 designates the data that was written directly
+/// under the base key before the view was wrapped in a [`VersionedView`]. Later versions
+/// are stored under [`inner_context`].
+#[async_trait]
+pub trait Migration<C>: Sized {
+    /// The version of the storage layout of this view.
+    const VERSION: u32;
+
+    /// Creates the view from the data stored under `context` in the older layout `version`.
+    ///
+    /// The returned view must have the context [`inner_context`] and hold all its content as
+    /// pending changes, e.g. by being cleared before the data is inserted: the data of the
+    /// older layout is only read here, and all of it is deleted when the view is saved.
+    async fn migrate(context: &C, version: u32) -> Result<Self, ViewError>;
+}
+
+/// Returns the context of the view wrapped by a [`VersionedView`] with the given context.
+pub fn inner_context<C: Context>(context: &C) -> C {
+    context.clone_with_base_key(context.base_tag(KeyTag::Inner as u8))
+}
+
+/// Returns the key storing the version of the layout of a [`VersionedView`].
+fn version_key<C: Context>(context: &C) -> Vec<u8> {
+    let mut key = context.base_key();
+    key.push(VERSION_TAG);
+    key
+}
+
+/// A view that records the version of the storage layout of the wrapped view and
+/// upgrades older layouts when it is loaded.
+///
+/// The migrations are run in memory by [`View::load`], and the new layout replaces the
+/// older one when the view is saved. When the view is loaded as part of a larger view,
+/// through [`View::post_load`], a layout of a different version is rejected instead, and
+/// legacy data without a version is not detected.
+///
+/// The version is only written together with some content, so that loading and saving an
+/// empty view writes nothing.
+#[derive(Debug)]
+pub struct VersionedView<C, W> {
+    context: C,
+    stored_version: Option<u32>,
+    /// Whether the inner view was migrated from the stored layout and not saved yet.
+    migration_pending: bool,
+    inner: W,
+}
+
+impl<C, W> VersionedView<C, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: Migration<C>,
+{
+    /// Returns the version of the layout stored under `context`, or `None` if nothing
+    /// is stored there. Legacy data without a version is of version 0.
+    async fn read_stored_version(context: &C) -> Result<Option<u32>, ViewError> {
+        if let Some(version) = context.read_value(&version_key(context)).await? {
+            return Ok(Some(version));
+        }
+        let keys = context.find_keys_by_prefix(&context.base_key()).await?;
+        if keys.iterator().next().is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(0))
+        }
+    }
+}
+
+#[async_trait]
+impl<C, W> View<C> for VersionedView<C, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: View<C> + Migration<C> + Send + Sync,
+{
+    const NUM_INIT_KEYS: usize = 1 + W::NUM_INIT_KEYS;
+
+    fn context(&self) -> &C {
+        &self.context
+    }
+
+    fn pre_load(context: &C) -> Result<Vec<Vec<u8>>, ViewError> {
+        let mut v = vec![version_key(context)];
+        v.extend(W::pre_load(&inner_context(context))?);
+        Ok(v)
+    }
+
+    fn post_load(context: C, values: &[Option<Vec<u8>>]) -> Result<Self, ViewError> {
+        let stored_version =
+            from_bytes_option(values.first().ok_or(ViewError::PostLoadValuesError)?)?;
+        if let Some(version) = stored_version {
+            if version != W::VERSION {
+                return Err(ViewError::UnsupportedViewVersion {
+                    found: version,
+                    expected: W::VERSION,
+                });
+            }
+        }
+        let inner = W::post_load(
+            inner_context(&context),
+            values.get(1..).ok_or(ViewError::PostLoadValuesError)?,
+        )?;
+        Ok(Self {
+            context,
+            stored_version,
+            migration_pending: false,
+            inner,
+        })
+    }
+
+    async fn load(context: C) -> Result<Self, ViewError> {
+        match Self::read_stored_version(&context).await? {
+            Some(version) if version < W::VERSION => {
+                let inner = W::migrate(&context, version).await?;
+                Ok(Self {
+                    context,
+                    stored_version: Some(version),
+                    migration_pending: true,
+                    inner,
+                })
+            }
+            _ => {
+                let keys = Self::pre_load(&context)?;
+                let values = context.read_multi_values_bytes(keys).await?;
+                Self::post_load(context, &values)
+            }
+        }
+    }
+
+    /// Rolls back the changes of the inner view. A migration that was not saved yet is rolled
+    /// back too, and the view must then be loaded again.
+    fn rollback(&mut self) {
+        self.inner.rollback();
+        self.migration_pending = false;
+    }
+
+    async fn has_pending_changes(&self) -> bool {
+        self.migration_pending || self.inner.has_pending_changes().await
+    }
+
+    fn flush(&mut self, batch: &mut Batch) -> Result<bool, ViewError> {
+        if self.migration_pending {
+            // All the content of the migrated view is pending: the older layout is replaced.
+            batch.delete_key_prefix(self.context.base_key());
+        } else if let Some(version) = self.stored_version {
+            // The inner view of a rolled back migration does not match the stored layout.
+            ensure!(
+                version == W::VERSION,
+                ViewError::UnsupportedViewVersion {
+                    found: version,
+                    expected: W::VERSION,
+                }
+            );
+        }
+        let first_operation = batch.operations.len();
+        let delete_view = self.inner.flush(batch)?;
+        let writes_content = batch.operations[first_operation..]
+            .iter()
+            .any(|operation| matches!(operation, WriteOperation::Put { .. }));
+        if delete_view {
+            batch.delete_key_prefix(self.context.base_key());
+            self.stored_version = None;
+        } else if writes_content {
+            if self.stored_version != Some(W::VERSION) {
+                batch.put_key_value(version_key(&self.context), &W::VERSION)?;
+                self.stored_version = Some(W::VERSION);
+            }
+        } else if self.migration_pending {
+            // The older layout was deleted and nothing replaces it.
+            self.stored_version = None;
+        }
+        self.migration_pending = false;
+        Ok(delete_view)
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+}
+
+impl<C, W> ClonableView<C> for VersionedView<C, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: ClonableView<C> + Migration<C> + Send + Sync,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(VersionedView {
+            context: self.context.clone(),
+            stored_version: self.stored_version,
+            migration_pending: self.migration_pending,
+            inner: self.inner.clone_unchecked()?,
+        })
+    }
+}
+
+#[async_trait]
+impl<C, W> HashableView<C> for VersionedView<C, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: HashableView<C> + Migration<C> + Send + Sync,
+{
+    type Hasher = W::Hasher;
+
+    async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.inner.hash_mut().await
+    }
+
+    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.inner.hash().await
+    }
+}
+
+impl<C, W> Deref for VersionedView<C, W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        &self.inner
+    }
+}
+
+impl<C, W> DerefMut for VersionedView<C, W> {
+    fn deref_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+}
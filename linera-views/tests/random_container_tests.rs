@@ -12,9 +12,9 @@ use linera_views::{
     map_view::HashedByteMapView,
     queue_view::HashedQueueView,
     random::make_deterministic_rng,
-    reentrant_collection_view::HashedReentrantCollectionView,
+    reentrant_collection_view::{HashedReentrantCollectionView, ReentrantCollectionView},
     register_view::RegisterView,
-    views::{CryptoHashRootView, CryptoHashView, RootView, View, ViewError},
+    views::{CryptoHashRootView, CryptoHashView, HashableView, RootView, View, ViewError},
 };
 use rand::{distributions::Uniform, Rng, RngCore};
 
@@ -370,6 +370,34 @@ async fn run_map_view_mutability<R: RngCore + Clone>(rng: &mut R) -> Result<()>
                 let part_key_values = view.map.key_values_by_prefix(vec![u]).await?;
                 assert_eq!(part_state_vec, part_key_values);
             }
+            for _ in 0..3 {
+                let mut bounds = (0..2)
+                    .map(|_| {
+                        let len = rng.gen_range(0..4);
+                        rng.clone()
+                            .sample_iter(Uniform::from(0..5))
+                            .take(len)
+                            .collect::<Vec<u8>>()
+                    })
+                    .collect::<Vec<_>>();
+                bounds.sort();
+                let range = bounds[0].clone()..bounds[1].clone();
+                let range_state_vec = new_state_map
+                    .range(range.clone())
+                    .map(|(key, value)| (key.clone(), *value))
+                    .collect::<Vec<_>>();
+                let mut range_key_values = Vec::new();
+                view.map
+                    .for_each_key_value_in_range(range, |key, value| {
+                        range_key_values.push((key.to_vec(), *value));
+                        Ok(())
+                    })
+                    .await?;
+                assert_eq!(range_state_vec, range_key_values);
+            }
+            let limit = rng.gen_range(0..8);
+            let count_up_to = view.map.count_up_to(limit).await?;
+            assert_eq!(count_up_to, new_state_map.len().min(limit));
             let keys_vec = all_keys.iter().cloned().collect::<Vec<_>>();
             let values = view.map.multi_get(keys_vec.clone()).await?;
             for i in 0..keys_vec.len() {
@@ -530,7 +558,7 @@ async fn queue_view_mutability_check() -> Result<()> {
         let count_oper = rng.gen_range(0..25);
         let mut new_vector = vector.clone();
         for _ in 0..count_oper {
-            let choice = rng.gen_range(0..5);
+            let choice = rng.gen_range(0..7);
             let count = view.queue.count();
             if choice == 0 {
                 // inserting random stuff
@@ -576,6 +604,36 @@ async fn queue_view_mutability_check() -> Result<()> {
                 assert!(!view.has_pending_changes().await);
                 new_vector.clone_from(&vector);
             }
+            if choice == 5 {
+                // deleting entries in bulk, possibly more than available
+                let n_remove = rng.gen_range(0..=count + 2);
+                view.queue.delete_front_many(n_remove);
+                new_vector.drain(..n_remove.min(new_vector.len()));
+            }
+            if choice == 6 {
+                // inserting up to a maximum length
+                let max_length = count + rng.gen_range(0..3);
+                view.queue.set_max_length(Some(max_length));
+                let n_ins = rng.gen_range(0..5);
+                for _ in 0..n_ins {
+                    let val = rng.gen::<u8>();
+                    let result = view.queue.try_push_back(val);
+                    if new_vector.len() < max_length {
+                        assert!(result.is_ok());
+                        new_vector.push(val);
+                    } else {
+                        assert!(matches!(result, Err(ViewError::QueueFull(_))));
+                    }
+                }
+                view.queue.set_max_length(None);
+            }
+            assert_eq!(view.queue.count(), new_vector.len());
+            let n_read = rng.gen_range(0..=new_vector.len() + 2);
+            let front_values = view.queue.read_front(n_read).await?;
+            assert_eq!(front_values, new_vector[..n_read.min(new_vector.len())]);
+            let back_values = view.queue.read_back(n_read).await?;
+            let back_start = new_vector.len() - n_read.min(new_vector.len());
+            assert_eq!(back_values, new_vector[back_start..]);
             let front1 = view.queue.front().await?;
             let front2 = new_vector.first().cloned();
             assert_eq!(front1, front2);
@@ -621,6 +679,22 @@ where
         }
         Ok(map)
     }
+
+    /// Checks that the hash of the collection, which reuses the hashes of the unchanged stored
+    /// entries, is the same as the hash of a collection built from scratch with the same entries.
+    async fn check_hash_from_scratch(&mut self, map: &BTreeMap<u8, u32>) -> Result<()> {
+        let context = create_test_memory_context();
+        let mut scratch =
+            ReentrantCollectionView::<_, u8, RegisterView<_, u32>>::load(context).await?;
+        for (index, value) in map {
+            scratch.try_load_entry_mut(index).await?.set(*value);
+        }
+        let scratch_hash = scratch.hash().await?;
+        let collection: &mut ReentrantCollectionView<C, u8, RegisterView<C, u32>> = &mut self.v;
+        assert_eq!(collection.hash().await?, scratch_hash);
+        assert_eq!(collection.hash_mut().await?, scratch_hash);
+        Ok(())
+    }
 }
 
 #[tokio::test]
@@ -738,6 +812,7 @@ async fn reentrant_collection_view_check() -> Result<()> {
                 // Inequality could be a bug or a hash collision (unlikely)
                 assert_ne!(hash, new_hash);
             }
+            view.check_hash_from_scratch(&new_map).await?;
             // Checking the keys
             let key_values = view.key_values().await?;
             assert_eq!(key_values, new_map);
@@ -762,6 +837,7 @@ async fn reentrant_collection_view_check() -> Result<()> {
             map = new_map.clone();
             view.save().await?;
             assert!(!view.has_pending_changes().await);
+            view.check_hash_from_scratch(&map).await?;
         }
     }
     Ok(())
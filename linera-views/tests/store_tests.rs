@@ -5,14 +5,16 @@ use linera_views::{
     batch::Batch,
     context::{create_test_memory_context, Context as _},
     key_value_store_view::ViewContainer,
-    memory::MemoryStore,
+    lru_caching::{CacheStatistics, LruCachingMemoryStore},
+    memory::{MemoryStore, MemoryStoreError},
     random::make_deterministic_rng,
-    store::TestKeyValueStore as _,
+    store::{ReadableKeyValueStore as _, TestKeyValueStore as _, WritableKeyValueStore as _},
     test_utils::{
         big_read_multi_values, get_random_test_scenarios, run_big_write_read, run_reads,
         run_writes_from_blank, run_writes_from_state,
     },
     value_splitting::create_value_splitting_memory_store,
+    views::ViewError,
 };
 #[cfg(web)]
 use wasm_bindgen_test::wasm_bindgen_test;
@@ -124,6 +126,70 @@ async fn test_specific_reads_memory() {
     run_reads(store, key_values).await;
 }
 
+#[tokio::test]
+async fn test_lru_caching_statistics() {
+    let store = LruCachingMemoryStore::new_test_store().await.unwrap();
+    let mut batch = Batch::new();
+    batch.put_key_value_bytes(vec![0, 1], vec![1]);
+    store.write_batch(batch).await.unwrap();
+    // Written values are cached.
+    assert_eq!(
+        store.read_value_bytes(&[0, 1]).await.unwrap(),
+        Some(vec![1])
+    );
+    // The first read of a missing key goes to the inner store, then it is cached.
+    assert_eq!(store.read_value_bytes(&[0, 2]).await.unwrap(), None);
+    assert_eq!(store.read_value_bytes(&[0, 2]).await.unwrap(), None);
+    let values = store
+        .read_multi_values_bytes(vec![vec![0, 1], vec![0, 3]])
+        .await
+        .unwrap();
+    assert_eq!(values, vec![Some(vec![1]), None]);
+    assert_eq!(
+        store.cache_statistics(),
+        CacheStatistics { hits: 3, misses: 2 }
+    );
+}
+
+#[tokio::test]
+async fn test_memory_store_capacity() {
+    let store = MemoryStore::new_test_store().await.unwrap();
+    store.set_max_total_size(Some(10));
+    let mut batch = Batch::new();
+    batch.put_key_value_bytes(vec![0, 1], vec![1; 4]);
+    store.write_batch(batch).await.unwrap();
+    assert_eq!(store.total_size(), 6);
+    // A batch exceeding the capacity is rejected as a whole.
+    let mut batch = Batch::new();
+    batch.delete_key(vec![0, 1]);
+    batch.put_key_value_bytes(vec![0, 2], vec![2; 9]);
+    let error = store.write_batch(batch).await.unwrap_err();
+    assert!(matches!(error, MemoryStoreError::StoreCapacityExceeded(10)));
+    assert_eq!(
+        store.read_value_bytes(&[0, 1]).await.unwrap(),
+        Some(vec![1; 4])
+    );
+    assert_eq!(store.total_size(), 6);
+    // Overwriting a value only accounts for the size difference.
+    let mut batch = Batch::new();
+    batch.put_key_value_bytes(vec![0, 1], vec![1; 8]);
+    store.write_batch(batch).await.unwrap();
+    assert_eq!(store.total_size(), 10);
+    store.set_max_total_size(None);
+    let mut batch = Batch::new();
+    batch.put_key_value_bytes(vec![0, 2], vec![2; 9]);
+    store.write_batch(batch).await.unwrap();
+    assert_eq!(store.total_size(), 21);
+    // The error of a full store is typed once converted into a `ViewError`.
+    store.set_max_total_size(Some(21));
+    let mut batch = Batch::new();
+    batch.delete_key_prefix(vec![0]);
+    batch.put_key_value_bytes(vec![1; 30], Vec::new());
+    let error = ViewError::from(store.write_batch(batch).await.unwrap_err());
+    assert!(matches!(error, ViewError::StoreCapacityExceeded(21)));
+    assert_eq!(store.total_size(), 21);
+}
+
 #[tokio::test]
 async fn test_test_memory_writes_from_blank() {
     let key_value_store = create_value_splitting_memory_store();
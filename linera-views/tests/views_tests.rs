@@ -1,10 +1,11 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use anyhow::Result;
 use async_trait::async_trait;
+use linera_base::{crypto::CryptoHash, data_types::Amount, identifiers::Owner};
 #[cfg(with_dynamodb)]
 use linera_views::dynamo_db::DynamoDbStore;
 #[cfg(with_rocksdb)]
@@ -15,7 +16,7 @@ use linera_views::scylla_db::ScyllaDbStore;
 use linera_views::store::AdminKeyValueStore as _;
 use linera_views::{
     batch::{
-        Batch, WriteOperation,
+        Batch, BatchStatistics, WriteOperation,
         WriteOperation::{Delete, DeletePrefix, Put},
     },
     collection_view::HashedCollectionView,
@@ -23,19 +24,21 @@ use linera_views::{
     key_value_store_view::{KeyValueStoreView, ViewContainer},
     log_view::HashedLogView,
     lru_caching::{LruCachingMemoryStore, LruCachingStore},
-    map_view::{ByteMapView, HashedMapView},
+    map_view::{ByteMapView, CustomMapView, HashedMapView, MapView},
     memory::MemoryStore,
+    optional_view::OptionalView,
     queue_view::HashedQueueView,
     random::make_deterministic_rng,
     reentrant_collection_view::HashedReentrantCollectionView,
-    register_view::HashedRegisterView,
-    set_view::HashedSetView,
+    register_view::{HashedRegisterView, RegisterView},
+    set_view::{ByteSetView, HashedSetView},
     store::TestKeyValueStore as _,
     test_utils::{
         get_random_byte_vector, get_random_key_value_operations, get_random_key_values,
-        span_random_reordering_put_delete,
+        run_view_test_suite, span_random_reordering_put_delete,
     },
-    views::{CryptoHashRootView, HashableView, Hasher, RootView, View, ViewError},
+    versioned_view::{inner_context, Migration, VersionedView},
+    views::{ClonableView, CryptoHashRootView, HashableView, Hasher, RootView, View, ViewError},
 };
 use rand::{Rng, RngCore};
 
@@ -767,6 +770,23 @@ async fn test_views_in_dynamo_db() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_view_suite_in_memory() -> Result<()> {
+    run_view_test_suite(|| async { create_test_memory_context() }).await?;
+    Ok(())
+}
+
+#[cfg(with_rocksdb)]
+#[tokio::test]
+async fn test_view_suite_in_rocks_db() -> Result<()> {
+    run_view_test_suite(|| async {
+        let store = RocksDbStore::new_test_store().await.unwrap();
+        ViewContext::create_root_context(store, ()).await.unwrap()
+    })
+    .await?;
+    Ok(())
+}
+
 #[cfg(with_rocksdb)]
 #[cfg(test)]
 async fn test_store_rollback_kernel<S>(store: &mut S) -> Result<()>
@@ -848,6 +868,888 @@ async fn test_collection_removal() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_map_view_multi_get() -> Result<()> {
+    type MapViewType = HashedMapView<MemoryContext<()>, u8, u8>;
+
+    let context = create_test_memory_context();
+    let mut map = MapViewType::load(context.clone()).await?;
+    for index in 0..4 {
+        map.insert(&index, index)?;
+    }
+    let mut batch = Batch::new();
+    map.flush(&mut batch)?;
+    map.context().write_batch(batch).await?;
+
+    // Mix stored, overwritten, removed, staged and missing entries.
+    let mut map = MapViewType::load(context.clone()).await?;
+    map.insert(&1, 10)?;
+    map.remove(&2)?;
+    map.insert(&5, 50)?;
+    let values = map.multi_get(&[0, 1, 2, 3, 4, 5]).await?;
+    assert_eq!(
+        values,
+        vec![Some(0), Some(10), None, Some(3), None, Some(50)]
+    );
+    map.clear();
+    map.insert(&3, 30)?;
+    let values = map.multi_get(&[0, 3]).await?;
+    assert_eq!(values, vec![None, Some(30)]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_custom_map_view_ordered_iteration() -> Result<()> {
+    type MapViewType = CustomMapView<MemoryContext<()>, u64, u64>;
+
+    let mut rng = make_deterministic_rng();
+    let context = create_test_memory_context();
+    let mut expected = BTreeMap::new();
+    for _ in 0..10 {
+        let mut map = MapViewType::load(context.clone()).await?;
+        for _ in 0..20 {
+            // Mix small indices with indices that only differ in their high bytes.
+            let index = rng.gen_range(0..256u64) << (8 * rng.gen_range(0..8u32));
+            if rng.gen_bool(0.2) {
+                map.remove(&index)?;
+                expected.remove(&index);
+            } else {
+                let value = rng.gen::<u64>();
+                map.insert(&index, value)?;
+                expected.insert(index, value);
+            }
+        }
+        // Check both the staged updates and, after the save, the stored values.
+        for _ in 0..2 {
+            let expected_pairs = expected.clone().into_iter().collect::<Vec<_>>();
+            assert_eq!(map.index_values().await?, expected_pairs);
+            assert_eq!(map.first().await?, expected_pairs.first().cloned());
+            assert_eq!(map.last().await?, expected_pairs.last().cloned());
+            let start = rng.gen_range(0..256u64) << (8 * rng.gen_range(0..8u32));
+            let end = rng.gen_range(0..256u64) << (8 * rng.gen_range(0..8u32));
+            let expected_range = if start < end {
+                expected.range(start..end).map(|(k, v)| (*k, *v)).collect()
+            } else {
+                Vec::new()
+            };
+            assert_eq!(map.range(start..end).await?, expected_range);
+
+            let mut batch = Batch::new();
+            map.flush(&mut batch)?;
+            context.write_batch(batch).await?;
+            map = MapViewType::load(context.clone()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_view_bulk_operations_and_pagination() -> Result<()> {
+    type SetViewType = HashedSetView<MemoryContext<()>, u8>;
+
+    let context = create_test_memory_context();
+    let mut set = SetViewType::load(context.clone()).await?;
+    set.insert_all(&[0, 2, 4, 6])?;
+    let mut batch = Batch::new();
+    set.flush(&mut batch)?;
+    set.context().write_batch(batch).await?;
+
+    // Layer staged insertions and removals on top of the stored entries.
+    let mut set = SetViewType::load(context.clone()).await?;
+    set.remove_all(&[2, 3])?;
+    set.insert_all(&[1, 5])?;
+    assert_eq!(set.count().await?, 5);
+    assert!(set.contains_all(&[0, 1, 4, 5, 6]).await?);
+    assert!(!set.contains_all(&[0, 2]).await?);
+    assert!(!set.contains_all(&[3]).await?);
+    assert!(set.contains_all(&Vec::<u8>::new()).await?);
+
+    // Pages are contiguous and the last one is truncated.
+    assert_eq!(set.indices_after(None, 0).await?, Vec::<u8>::new());
+    assert_eq!(set.indices_after(None, 2).await?, vec![0, 1]);
+    assert_eq!(set.indices_after(Some(&1), 2).await?, vec![4, 5]);
+    assert_eq!(set.indices_after(Some(&5), 2).await?, vec![6]);
+    assert_eq!(set.indices_after(Some(&6), 2).await?, Vec::<u8>::new());
+    // A cursor does not need to be in the set.
+    assert_eq!(set.indices_after(Some(&2), 10).await?, vec![4, 5, 6]);
+
+    // Nothing stored is visible after a clear.
+    set.clear();
+    set.insert_all(&[3])?;
+    assert!(!set.contains_all(&[0, 3]).await?);
+    assert_eq!(set.indices_after(None, 10).await?, vec![3]);
+    assert_eq!(set.count().await?, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_byte_set_view_pagination_across_prefixes() -> Result<()> {
+    let mut rng = make_deterministic_rng();
+    let mut random_key = || {
+        let len = rng.gen_range(1..4);
+        (0..len).map(|_| rng.gen_range(0..4)).collect::<Vec<u8>>()
+    };
+    let context = create_test_memory_context();
+    let mut set = ByteSetView::<MemoryContext<()>>::load(context.clone()).await?;
+    let mut keys = BTreeSet::new();
+    for _ in 0..60 {
+        let key = random_key();
+        set.insert(key.clone());
+        keys.insert(key);
+    }
+    let mut batch = Batch::new();
+    set.flush(&mut batch)?;
+    set.context().write_batch(batch).await?;
+
+    // Layer staged insertions and removals on top of the stored keys.
+    let mut set = ByteSetView::<MemoryContext<()>>::load(context).await?;
+    for i in 0..20 {
+        let key = random_key();
+        if i % 2 == 0 {
+            set.insert(key.clone());
+            keys.insert(key);
+        } else {
+            set.remove(key.clone());
+            keys.remove(&key);
+        }
+    }
+    let keys = keys.into_iter().collect::<Vec<_>>();
+    assert_eq!(set.keys().await?, keys);
+
+    for limit in [1, 3, 10] {
+        let mut paged_keys = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = set.keys_after(cursor.as_deref(), limit).await?;
+            assert!(page.len() <= limit);
+            let Some(last) = page.last() else {
+                break;
+            };
+            cursor = Some(last.clone());
+            paged_keys.extend(page);
+        }
+        assert_eq!(paged_keys, keys);
+    }
+    // A cursor does not need to be in the set.
+    for cursor in [vec![], vec![0], vec![1, 2, 3, 3], vec![3, 3, 3, 3]] {
+        let expected = keys
+            .iter()
+            .filter(|key| **key > cursor)
+            .take(5)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(set.keys_after(Some(cursor.as_slice()), 5).await?, expected);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_queue_view_bulk_operations() -> Result<()> {
+    type QueueViewType = HashedQueueView<MemoryContext<()>, u64>;
+
+    let context = create_test_memory_context();
+    let mut queue = QueueViewType::load(context.clone()).await?;
+    for value in 0..3 {
+        queue.push_back(value);
+    }
+    let mut batch = Batch::new();
+    queue.flush(&mut batch)?;
+    queue.context().write_batch(batch).await?;
+
+    // Delete more elements than were stored before the new values were pushed.
+    let mut queue = QueueViewType::load(context.clone()).await?;
+    queue.push_back(3);
+    queue.push_back(4);
+    queue.delete_front_many(4);
+    assert_eq!(queue.count(), 1);
+    assert_eq!(queue.read_front(2).await?, vec![4]);
+    queue.rollback();
+    assert_eq!(queue.elements().await?, vec![0, 1, 2]);
+
+    queue.push_back(3);
+    queue.push_back(4);
+    queue.delete_front_many(4);
+    let mut batch = Batch::new();
+    queue.flush(&mut batch)?;
+    queue.context().write_batch(batch).await?;
+    let mut queue = QueueViewType::load(context.clone()).await?;
+    assert_eq!(queue.elements().await?, vec![4]);
+
+    // The maximum length includes the staged elements and is saved with the queue.
+    queue.set_max_length(Some(3));
+    assert!(queue.has_pending_changes().await);
+    queue.try_push_back(5)?;
+    queue.try_push_back(6)?;
+    assert!(matches!(
+        queue.try_push_back(7),
+        Err(ViewError::QueueFull(3))
+    ));
+    queue.rollback();
+    assert_eq!(queue.max_length(), None);
+    queue.set_max_length(Some(3));
+    let mut batch = Batch::new();
+    queue.flush(&mut batch)?;
+    queue.context().write_batch(batch).await?;
+    let mut queue = QueueViewType::load(context.clone()).await?;
+    assert_eq!(queue.max_length(), Some(3));
+    assert!(!queue.has_pending_changes().await);
+    queue.set_max_length(None);
+    queue.rollback();
+    assert_eq!(queue.max_length(), Some(3));
+    queue.delete_front_many(10);
+    assert_eq!(queue.count(), 0);
+    assert_eq!(queue.read_back(2).await?, Vec::<u64>::new());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_queue_view_prune_front_until() -> Result<()> {
+    type QueueViewType = HashedQueueView<MemoryContext<()>, u64>;
+
+    let context = create_test_memory_context();
+    let mut queue = QueueViewType::load(context.clone()).await?;
+    for value in 0..5 {
+        queue.push_back(value);
+    }
+    let mut batch = Batch::new();
+    queue.flush(&mut batch)?;
+    queue.context().write_batch(batch).await?;
+
+    // Pruning an index that was already pruned does nothing.
+    let mut queue = QueueViewType::load(context.clone()).await?;
+    queue.push_back(5);
+    assert_eq!(queue.index_range(), 0..6);
+    queue.prune_front_until(3);
+    queue.prune_front_until(1);
+    assert_eq!(queue.index_range(), 3..6);
+    let mut batch = Batch::new();
+    queue.flush(&mut batch)?;
+    queue.context().write_batch(batch).await?;
+
+    // The positions and the hash survive a reload.
+    let mut queue = QueueViewType::load(context.clone()).await?;
+    assert_eq!(queue.index_range(), 3..6);
+    assert_eq!(queue.elements().await?, vec![3, 4, 5]);
+    let mut fresh_queue = QueueViewType::load(create_test_memory_context()).await?;
+    for value in 3..6 {
+        fresh_queue.push_back(value);
+    }
+    assert_eq!(queue.hash().await?, fresh_queue.hash().await?);
+
+    // Positions keep growing once no stored value remains, also after a save.
+    queue.prune_front_until(10);
+    assert_eq!(queue.index_range(), 6..6);
+    let mut batch = Batch::new();
+    queue.flush(&mut batch)?;
+    queue.context().write_batch(batch).await?;
+    let mut queue = QueueViewType::load(context.clone()).await?;
+    assert_eq!(queue.index_range(), 6..6);
+    queue.push_back(6);
+    assert_eq!(queue.index_range(), 6..7);
+    let mut batch = Batch::new();
+    queue.flush(&mut batch)?;
+    queue.context().write_batch(batch).await?;
+    let mut queue = QueueViewType::load(context.clone()).await?;
+    assert_eq!(queue.index_range(), 6..7);
+    assert_eq!(queue.elements().await?, vec![6]);
+
+    // Clearing the queue starts the positions again from 0.
+    queue.clear();
+    queue.push_back(7);
+    assert_eq!(queue.index_range(), 0..1);
+    let mut batch = Batch::new();
+    queue.flush(&mut batch)?;
+    queue.context().write_batch(batch).await?;
+    let queue = QueueViewType::load(context).await?;
+    assert_eq!(queue.index_range(), 0..1);
+    assert_eq!(queue.elements().await?, vec![7]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_log_view_range_reads() -> Result<()> {
+    type LogViewType = HashedLogView<MemoryContext<()>, u32>;
+
+    let context = create_test_memory_context();
+    let mut log = LogViewType::load(context.clone()).await?;
+    for value in 0..4 {
+        log.push(value);
+    }
+    let mut batch = Batch::new();
+    log.flush(&mut batch)?;
+    log.context().write_batch(batch).await?;
+
+    // Ranges straddling the persisted and staged entries.
+    let mut log = LogViewType::load(context.clone()).await?;
+    log.push(4);
+    log.push(5);
+    assert_eq!(log.try_read(2..5).await?, vec![2, 3, 4]);
+    assert_eq!(log.try_read(3..=5).await?, vec![3, 4, 5]);
+    assert_eq!(log.try_read(..).await?, vec![0, 1, 2, 3, 4, 5]);
+    assert_eq!(log.try_read(6..6).await?, Vec::<u32>::new());
+    assert_eq!(log.read_last(3).await?, vec![3, 4, 5]);
+    assert_eq!(log.read_last(10).await?, vec![0, 1, 2, 3, 4, 5]);
+
+    // Out-of-bounds ranges are truncated by `read` and rejected by `try_read`.
+    assert_eq!(log.read(4..10).await?, vec![4, 5]);
+    assert!(matches!(
+        log.try_read(4..10).await,
+        Err(ViewError::RangeOutOfBounds {
+            start: 4,
+            end: 10,
+            count: 6
+        })
+    ));
+    assert!(matches!(
+        log.try_read(7..).await,
+        Err(ViewError::RangeOutOfBounds { .. })
+    ));
+
+    // Only the staged entries remain after clearing.
+    log.clear();
+    log.push(6);
+    assert_eq!(log.read_last(2).await?, vec![6]);
+    assert!(log.try_read(0..2).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_view_compare_and_set() -> Result<()> {
+    type RegisterViewType = HashedRegisterView<MemoryContext<()>, u32>;
+
+    let context = create_test_memory_context();
+    let mut register = RegisterViewType::load(context.clone()).await?;
+    register.set(5);
+    let mut batch = Batch::new();
+    register.flush(&mut batch)?;
+    register.context().write_batch(batch).await?;
+    let hash = register.hash().await?;
+
+    // A failed compare-and-set leaves the register untouched.
+    let mut register = RegisterViewType::load(context.clone()).await?;
+    assert!(!register.compare_and_set(&4, 7));
+    assert_eq!(*register.get(), 5);
+    assert!(!register.has_pending_changes().await);
+    assert_eq!(register.hash().await?, hash);
+
+    assert!(register.compare_and_set(&5, 7));
+    assert_eq!(*register.get(), 7);
+    assert_ne!(register.hash().await?, hash);
+    register.rollback();
+    assert_eq!(*register.get(), 5);
+    assert_eq!(register.hash().await?, hash);
+
+    assert_eq!(register.modify(|value| std::mem::replace(value, 8)), 5);
+    assert_eq!(*register.get(), 8);
+    register.rollback();
+    assert_eq!(*register.get(), 5);
+
+    register.clear();
+    assert!(register.compare_and_set(&0, 9));
+    let mut batch = Batch::new();
+    register.flush(&mut batch)?;
+    register.context().write_batch(batch).await?;
+    let register = RegisterViewType::load(context.clone()).await?;
+    assert_eq!(*register.get(), 9);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_key_value_store_view_compare_and_set() -> Result<()> {
+    let context = create_test_memory_context();
+    let mut view = KeyValueStoreView::load(context.clone()).await?;
+    view.insert(vec![0], vec![1]).await?;
+    let mut batch = Batch::new();
+    view.flush(&mut batch)?;
+    view.context().write_batch(batch).await?;
+    let hash = view.hash().await?;
+
+    assert!(!view.compare_and_set(vec![0], Some(&[2][..]), None).await?);
+    assert!(
+        !view
+            .compare_and_set(vec![1], Some(&[1][..]), Some(vec![3]))
+            .await?
+    );
+    assert_eq!(view.index_values().await?, vec![(vec![0], vec![1])]);
+    assert_eq!(view.hash().await?, hash);
+
+    assert!(view.compare_and_set(vec![0], Some(&[1][..]), None).await?);
+    assert!(view.compare_and_set(vec![1], None, Some(vec![3])).await?);
+    view.update(vec![1], |value| Some([value?, vec![4]].concat()))
+        .await?;
+    view.update(vec![2], |value| value).await?;
+    assert_eq!(view.index_values().await?, vec![(vec![1], vec![3, 4])]);
+    view.rollback();
+    assert_eq!(view.index_values().await?, vec![(vec![0], vec![1])]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reentrant_collection_view_concurrent_writes() -> Result<()> {
+    type EntryType = HashedRegisterView<MemoryContext<()>, u64>;
+    type CollectionViewType = HashedReentrantCollectionView<MemoryContext<()>, u64, EntryType>;
+
+    let context = create_test_memory_context();
+    let mut collection = CollectionViewType::load(context.clone()).await?;
+    collection.try_load_entry_mut(&0).await?.set(100);
+    let mut batch = Batch::new();
+    collection.flush(&mut batch)?;
+    collection.context().write_batch(batch).await?;
+
+    let indices = (0..16).collect::<Vec<u64>>();
+    assert!(matches!(
+        collection.try_load_entries_mut(&[1, 2, 1]).await,
+        Err(ViewError::TryLockError(_))
+    ));
+
+    // Each task increments its own entry, yielding so that the tasks interleave.
+    let entries = collection.try_load_entries_mut(&indices).await?;
+    let tasks = entries
+        .into_iter()
+        .zip(&indices)
+        .map(|(mut entry, index)| {
+            let index = *index;
+            tokio::spawn(async move {
+                for _ in 0..=index {
+                    *entry.get_mut() += 1;
+                    tokio::task::yield_now().await;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for task in tasks {
+        task.await?;
+    }
+    let expected = |index: u64| if index == 0 { 101 } else { index + 1 };
+    for index in &indices {
+        let entry = collection.try_load_entry(index).await?.unwrap();
+        assert_eq!(*entry.get(), expected(*index));
+    }
+
+    // The writes are staged: they are discarded by a rollback and persisted by a flush.
+    collection.rollback();
+    assert_eq!(*collection.try_load_entry(&0).await?.unwrap().get(), 100);
+    assert!(collection.try_load_entry(&1).await?.is_none());
+    for mut entry in collection.try_load_entries_mut(&indices).await? {
+        *entry.get_mut() += 1;
+    }
+    let mut batch = Batch::new();
+    collection.flush(&mut batch)?;
+    collection.context().write_batch(batch).await?;
+    let collection = CollectionViewType::load(context.clone()).await?;
+    for index in &indices {
+        let entry = collection.try_load_entry(index).await?.unwrap();
+        assert_eq!(*entry.get(), if *index == 0 { 101 } else { 1 });
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_collection_view_try_load_entries() -> Result<()> {
+    type EntryType = HashedRegisterView<MemoryContext<()>, u8>;
+    type CollectionViewType = HashedCollectionView<MemoryContext<()>, u8, EntryType>;
+
+    let context = create_test_memory_context();
+    let mut collection = CollectionViewType::load(context.clone()).await?;
+    for index in 0..3 {
+        collection.load_entry_mut(&index).await?.set(index);
+    }
+    let mut batch = Batch::new();
+    collection.flush(&mut batch)?;
+    collection.context().write_batch(batch).await?;
+
+    // Mix stored, modified, removed, staged and missing entries.
+    let mut collection = CollectionViewType::load(context.clone()).await?;
+    collection.load_entry_mut(&1).await?.set(10);
+    collection.remove_entry(&2)?;
+    collection.load_entry_mut(&4).await?.set(40);
+    let entries = collection.try_load_entries(&[0, 1, 2, 3, 4]).await?;
+    let values = entries
+        .iter()
+        .map(|entry| entry.as_ref().map(|entry| *entry.get()))
+        .collect::<Vec<_>>();
+    assert_eq!(values, vec![Some(0), Some(10), None, None, Some(40)]);
+    drop(entries);
+    assert!(collection.load_entry_mut(&3).await.is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_collection_view_remove_entry_deletes_prefix() -> Result<()> {
+    type EntryType = HashedLogView<MemoryContext<()>, u32>;
+    type CollectionViewType = HashedCollectionView<MemoryContext<()>, u8, EntryType>;
+
+    let save_entries = |indices: Vec<u8>| async move {
+        let context = create_test_memory_context();
+        let mut collection = CollectionViewType::load(context.clone()).await?;
+        for index in indices {
+            let log = collection.load_entry_mut(&index).await?;
+            for value in 0..10 {
+                log.push(value);
+            }
+        }
+        let mut batch = Batch::new();
+        collection.flush(&mut batch)?;
+        collection.context().write_batch(batch).await?;
+        Ok::<_, anyhow::Error>(context)
+    };
+    let context = save_entries(vec![1, 2, 3]).await?;
+
+    // Remove an entry with staged writes.
+    let mut collection = CollectionViewType::load(context.clone()).await?;
+    collection.load_entry_mut(&2).await?.push(10);
+    collection.remove_entry(&2)?;
+    let mut batch = Batch::new();
+    collection.flush(&mut batch)?;
+    let prefix_deletions = batch
+        .operations
+        .iter()
+        .filter(|operation| matches!(operation, DeletePrefix { .. }))
+        .count();
+    assert_eq!(prefix_deletions, 1);
+    assert!(!batch
+        .operations
+        .iter()
+        .any(|operation| matches!(operation, Put { .. })));
+    collection.context().write_batch(batch).await?;
+
+    // The storage is the same as if the entry had never been written, and the sibling
+    // entries are untouched.
+    let expected_context = save_entries(vec![1, 3]).await?;
+    assert_eq!(
+        context.find_key_values_by_prefix(&[]).await?,
+        expected_context.find_key_values_by_prefix(&[]).await?
+    );
+    let collection = CollectionViewType::load(context).await?;
+    assert_eq!(collection.indices().await?, vec![1, 3]);
+    let log = collection.try_load_entry(&3).await?.unwrap();
+    assert_eq!(log.read(..).await?, (0..10).collect::<Vec<_>>());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_collection_view_take_entry() -> Result<()> {
+    type EntryType = HashedRegisterView<MemoryContext<()>, u8>;
+    type CollectionViewType = HashedCollectionView<MemoryContext<()>, u8, EntryType>;
+    type ReentrantCollectionViewType =
+        HashedReentrantCollectionView<MemoryContext<()>, u8, EntryType>;
+
+    let context = create_test_memory_context();
+    let mut collection = CollectionViewType::load(context.clone()).await?;
+    for index in 0..3 {
+        collection.load_entry_mut(&index).await?.set(index);
+    }
+    let mut batch = Batch::new();
+    collection.flush(&mut batch)?;
+    collection.context().write_batch(batch).await?;
+
+    // Take stored, modified, staged, removed and missing entries.
+    let mut collection = CollectionViewType::load(context.clone()).await?;
+    collection.load_entry_mut(&1).await?.set(10);
+    collection.load_entry_mut(&4).await?.set(40);
+    collection.remove_entry(&2)?;
+    let values = [
+        collection.take_entry(&0).await?.map(|entry| *entry.get()),
+        collection.take_entry(&1).await?.map(|entry| *entry.get()),
+        collection.take_entry(&2).await?.map(|entry| *entry.get()),
+        collection.take_entry(&3).await?.map(|entry| *entry.get()),
+        collection.take_entry(&4).await?.map(|entry| *entry.get()),
+    ];
+    assert_eq!(values, [Some(0), Some(10), None, None, Some(40)]);
+    assert_eq!(collection.indices().await?, Vec::<u8>::new());
+
+    // A rollback restores the stored entries.
+    collection.rollback();
+    assert_eq!(collection.indices().await?, vec![0, 1, 2]);
+    assert_eq!(*collection.load_entry_or_insert(&1).await?.get(), 1);
+
+    // The removal of a taken entry is flushed.
+    let entry = collection.take_entry(&1).await?.unwrap();
+    let mut batch = Batch::new();
+    collection.flush(&mut batch)?;
+    collection.context().write_batch(batch).await?;
+    assert_eq!(*entry.get(), 1);
+    let collection = CollectionViewType::load(context.clone()).await?;
+    assert_eq!(collection.indices().await?, vec![0, 2]);
+
+    // The reentrant collection hands out a read guard instead.
+    let context = create_test_memory_context();
+    let mut collection = ReentrantCollectionViewType::load(context.clone()).await?;
+    collection.try_load_entry_mut(&0).await?.set(0);
+    collection.try_load_entry_mut(&1).await?.set(1);
+    let mut batch = Batch::new();
+    collection.flush(&mut batch)?;
+    collection.context().write_batch(batch).await?;
+    let mut collection = ReentrantCollectionViewType::load(context.clone()).await?;
+    collection.try_load_entry_mut(&0).await?.set(20);
+    let entry = collection.take_entry(&0).await?.unwrap();
+    assert_eq!(*entry.get(), 20);
+    drop(entry);
+    assert!(collection.take_entry(&0).await?.is_none());
+    assert_eq!(collection.indices().await?, vec![1]);
+    collection.rollback();
+    assert_eq!(collection.indices().await?, vec![0, 1]);
+    assert_eq!(*collection.try_load_entry(&0).await?.unwrap().get(), 0);
+
+    Ok(())
+}
+
+#[derive(RootView)]
+pub struct SaveStatisticsView<C> {
+    pub register: RegisterView<C, u64>,
+    pub map: MapView<C, u8, u64>,
+}
+
+#[tokio::test]
+async fn test_save_statistics() -> Result<()> {
+    let context = create_test_memory_context();
+    let mut view = SaveStatisticsView::load(context.clone()).await?;
+    view.register.set(7);
+    for index in 0..3 {
+        view.map.insert(&index, u64::from(index))?;
+    }
+    let insertions = view.save_with_statistics().await?;
+    let stored_bytes = context
+        .find_key_values_by_prefix(&[])
+        .await?
+        .iter()
+        .map(|(key, value)| key.len() + value.len())
+        .sum();
+    assert_eq!(
+        insertions,
+        BatchStatistics {
+            puts: 4,
+            deletes: 0,
+            bytes: stored_bytes,
+        }
+    );
+
+    view.map.remove(&1)?;
+    let deletions = view.save_with_statistics().await?;
+    assert_eq!((deletions.puts, deletions.deletes), (0, 1));
+    assert_eq!(
+        view.save_with_statistics().await?,
+        BatchStatistics::default()
+    );
+
+    #[cfg(with_metrics)]
+    {
+        use linera_views::metrics::{SAVE_VIEW_BYTES, SAVE_VIEW_OPERATIONS};
+        let name = "SaveStatisticsView";
+        let puts = SAVE_VIEW_OPERATIONS.with_label_values(&[name, "put"]).get();
+        let deletes = SAVE_VIEW_OPERATIONS
+            .with_label_values(&[name, "delete"])
+            .get();
+        let bytes = SAVE_VIEW_BYTES.with_label_values(&[name]).get();
+        assert_eq!((puts, deletes), (4, 1));
+        assert_eq!(bytes as usize, insertions.bytes + deletions.bytes);
+    }
+
+    Ok(())
+}
+
+#[derive(CryptoHashRootView, ClonableView)]
+pub struct OptionalFieldsView<C> {
+    pub register: HashedRegisterView<C, u64>,
+    #[view(skip)]
+    pub cache: Vec<u64>,
+    pub map: OptionalView<C, HashedMapView<C, u8, u64>>,
+}
+
+#[tokio::test]
+async fn test_optional_and_skipped_fields() -> Result<()> {
+    let context = create_test_memory_context();
+    let mut view = OptionalFieldsView::load(context.clone()).await?;
+    assert!(view.map.get().is_none());
+    let absent_hash = view.hash().await?;
+
+    // Skipped fields are neither stored nor hashed.
+    view.register.set(7);
+    view.cache.push(7);
+    view.save().await?;
+    let mut view = OptionalFieldsView::load(context.clone()).await?;
+    assert!(view.cache.is_empty());
+    view.register.set(0);
+    assert_eq!(view.hash().await?, absent_hash);
+
+    // Present sub-views are stored, and their presence is hashed.
+    assert_eq!(view.map.get_or_insert().count().await?, 0);
+    assert_ne!(view.hash().await?, absent_hash);
+    view.map.get_or_insert().insert(&1, 10)?;
+    let present_hash = view.hash().await?;
+    view.save().await?;
+    let mut view = OptionalFieldsView::load(context.clone()).await?;
+    let map = view.map.get().expect("the map should be present");
+    assert_eq!(map.get(&1).await?, Some(10));
+    assert_eq!(view.hash().await?, present_hash);
+    assert!(!view.has_pending_changes().await);
+    assert_eq!(
+        view.save_with_statistics().await?,
+        BatchStatistics::default()
+    );
+
+    // The content of a present sub-view is rolled back and cloned.
+    view.map.get_mut().unwrap().insert(&2, 20)?;
+    assert!(view.has_pending_changes().await);
+    let clone = view.clone_unchecked()?;
+    assert_eq!(clone.map.get().unwrap().get(&2).await?, Some(20));
+    view.rollback();
+    assert!(!view.has_pending_changes().await);
+    assert_eq!(view.hash().await?, present_hash);
+
+    // Removing a sub-view is rolled back too.
+    view.map.clear();
+    assert!(view.map.get().is_none());
+    assert!(view.has_pending_changes().await);
+    view.rollback();
+    assert!(!view.has_pending_changes().await);
+    assert_eq!(view.map.get().unwrap().get(&1).await?, Some(10));
+
+    // Clearing a sub-view that is absent in storage writes nothing.
+    view.map.remove();
+    view.save().await?;
+    view.map.clear();
+    assert!(!view.has_pending_changes().await);
+    assert_eq!(
+        view.save_with_statistics().await?,
+        BatchStatistics::default()
+    );
+
+    // Removing a sub-view deletes its content, and inserting it again starts empty.
+    let mut view = OptionalFieldsView::load(context.clone()).await?;
+    assert!(view.map.get().is_none());
+    assert_eq!(view.hash().await?, absent_hash);
+    assert_eq!(view.map.get_or_insert().get(&1).await?, None);
+    view.rollback();
+    assert!(view.map.get().is_none());
+
+    Ok(())
+}
+
+/// The owner of the balance of the legacy layout in `BalancesView`.
+fn legacy_owner() -> Owner {
+    Owner(CryptoHash::from([0; 4]))
+}
+
+/// A layout that replaced a single `RegisterView<C, Amount>` balance.
+#[derive(View)]
+pub struct BalancesView<C> {
+    pub balances: MapView<C, Owner, Amount>,
+}
+
+#[async_trait]
+impl Migration<MemoryContext<()>> for BalancesView<MemoryContext<()>> {
+    const VERSION: u32 = 1;
+
+    async fn migrate(context: &MemoryContext<()>, version: u32) -> Result<Self, ViewError> {
+        assert_eq!(version, 0);
+        let legacy_balance = RegisterView::<_, Amount>::load(context.clone()).await?;
+        let mut balances = BalancesView::load(inner_context(context)).await?;
+        balances.clear();
+        balances
+            .balances
+            .insert(&legacy_owner(), *legacy_balance.get())?;
+        Ok(balances)
+    }
+}
+
+#[tokio::test]
+async fn test_versioned_view_migration() -> Result<()> {
+    type VersionedBalancesView = VersionedView<MemoryContext<()>, BalancesView<MemoryContext<()>>>;
+
+    let context = create_test_memory_context().clone_with_base_key(vec![0]);
+    let mut legacy_balance = RegisterView::<_, Amount>::load(context.clone()).await?;
+    legacy_balance.set(Amount::from_tokens(5));
+    let mut batch = Batch::new();
+    legacy_balance.flush(&mut batch)?;
+    context.write_batch(batch).await?;
+
+    // Loading migrates the legacy data in memory only.
+    let mut view = VersionedBalancesView::load(context.clone()).await?;
+    assert_eq!(
+        view.balances.get(&legacy_owner()).await?,
+        Some(Amount::from_tokens(5))
+    );
+    assert!(view.has_pending_changes().await);
+    assert!(context.contains_key(&context.base_key()).await?);
+
+    // Saving replaces the legacy layout.
+    let mut batch = Batch::new();
+    view.flush(&mut batch)?;
+    context.write_batch(batch).await?;
+    assert!(!context.contains_key(&context.base_key()).await?);
+    let mut view = VersionedBalancesView::load(context.clone()).await?;
+    assert!(!view.has_pending_changes().await);
+    assert_eq!(
+        view.balances.get(&legacy_owner()).await?,
+        Some(Amount::from_tokens(5))
+    );
+
+    // Later loads do not migrate again.
+    view.balances
+        .insert(&legacy_owner(), Amount::from_tokens(7))?;
+    let mut batch = Batch::new();
+    view.flush(&mut batch)?;
+    context.write_batch(batch).await?;
+    let view = VersionedBalancesView::load(context.clone()).await?;
+    assert!(!view.has_pending_changes().await);
+    assert_eq!(
+        view.balances.get(&legacy_owner()).await?,
+        Some(Amount::from_tokens(7))
+    );
+
+    // An empty view writes nothing, and the version is only written with some content.
+    let new_context = create_test_memory_context().clone_with_base_key(vec![0]);
+    let mut view = VersionedBalancesView::load(new_context.clone()).await?;
+    assert!(!view.has_pending_changes().await);
+    let mut batch = Batch::new();
+    view.flush(&mut batch)?;
+    assert!(batch.operations.is_empty());
+    view.balances
+        .insert(&legacy_owner(), Amount::from_tokens(1))?;
+    let mut batch = Batch::new();
+    view.flush(&mut batch)?;
+    new_context.write_batch(batch).await?;
+    let view = VersionedBalancesView::load(new_context.clone()).await?;
+    assert!(!view.has_pending_changes().await);
+
+    // A layout from a future version cannot be loaded.
+    let mut batch = Batch::new();
+    let mut version_key = new_context.base_key();
+    version_key.push(0);
+    batch.put_key_value(version_key, &2u32)?;
+    new_context.write_batch(batch).await?;
+    let result = VersionedBalancesView::load(new_context).await;
+    assert!(matches!(
+        result,
+        Err(ViewError::UnsupportedViewVersion {
+            found: 2,
+            expected: 1
+        })
+    ));
+
+    // Legacy data is not mistaken for a version, whatever its keys.
+    let legacy_context = create_test_memory_context().clone_with_base_key(vec![0]);
+    let mut batch = Batch::new();
+    batch.put_key_value(legacy_context.base_tag(2), &2u32)?;
+    legacy_context.write_batch(batch).await?;
+    let view = VersionedBalancesView::load(legacy_context).await?;
+    assert!(view.has_pending_changes().await);
+
+    Ok(())
+}
+
 async fn test_removal_api_first_second_condition(
     first_condition: bool,
     second_condition: bool,
@@ -1205,3 +2107,51 @@ async fn check_large_write_memory() -> Result<()> {
     let mut store = MemoryTestStorage::new().await;
     check_large_write(&mut store, vector).await
 }
+
+#[tokio::test]
+async fn test_collection_verify_integrity() -> Result<()> {
+    type EntryType = HashedLogView<MemoryContext<()>, u32>;
+    type CollectionViewType = HashedCollectionView<MemoryContext<()>, u8, EntryType>;
+
+    let context = create_test_memory_context();
+    let mut collection = CollectionViewType::load(context.clone()).await?;
+    for index in [1, 2, 3] {
+        let log = collection.load_entry_mut(&index).await?;
+        for value in 0..10 {
+            log.push(value);
+        }
+    }
+    let mut batch = Batch::new();
+    collection.flush(&mut batch)?;
+    context.write_batch(batch).await?;
+    let collection = CollectionViewType::load(context.clone()).await?;
+    assert!(collection.verify_integrity(false).await?.is_empty());
+
+    // Unlist the entry 2, leaving the keys of its log behind.
+    let mut index_key = context.base_key();
+    index_key.extend([1, 2]);
+    let mut batch = Batch::new();
+    batch.delete_key(index_key);
+    context.write_batch(batch).await?;
+    let mut subview_prefix = context.base_key();
+    subview_prefix.extend([2, 2]);
+    let orphan_keys = context.find_keys_by_prefix(&subview_prefix).await?;
+    assert!(!orphan_keys.is_empty());
+
+    let collection = CollectionViewType::load(context.clone()).await?;
+    let reported_keys = collection.verify_integrity(false).await?;
+    assert_eq!(reported_keys.len(), orphan_keys.len());
+    assert!(reported_keys
+        .iter()
+        .all(|key| key.starts_with(&subview_prefix)));
+    assert_eq!(collection.verify_integrity(true).await?, reported_keys);
+    assert!(collection.verify_integrity(false).await?.is_empty());
+
+    // The listed entries are untouched.
+    let collection = CollectionViewType::load(context).await?;
+    assert_eq!(collection.indices().await?, vec![1, 3]);
+    let log = collection.try_load_entry(&3).await?.unwrap();
+    assert_eq!(log.read(..).await?, (0..10).collect::<Vec<_>>());
+
+    Ok(())
+}